@@ -12,6 +12,7 @@ use anyhow::bail;
 use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AllowedOrigins;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::InterfaceDriver;
 use holochain_conductor_api::{AdminInterfaceConfig, AppInfo};
@@ -93,6 +94,14 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    #[structopt(long, value_delimiter = ",")]
+    /// Web origins which may connect to the interface, e.g.
+    /// `http://localhost:8888`.
+    /// Defaults to allowing any origin.
+    pub allowed_origins: Vec<String>,
+    #[structopt(long)]
+    /// The only app which connections to the interface may access.
+    pub installed_app_id: Option<String>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -374,7 +383,7 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     let resp = cmd
         .command(AdminRequest::AddAdminInterfaces(vec![
             AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
+                driver: InterfaceDriver::websocket(port),
             },
         ]))
         .await?;
@@ -540,7 +549,16 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            driver: None,
+            allowed_origins: if args.allowed_origins.is_empty() {
+                AllowedOrigins::Any
+            } else {
+                args.allowed_origins.into_iter().collect()
+            },
+            installed_app_id: args.installed_app_id,
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
@@ -46,6 +46,8 @@ pub async fn run(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                allowed_origins: Vec::new(),
+                installed_app_id: None,
             },
         )
         .await?;
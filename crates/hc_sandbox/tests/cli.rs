@@ -1,6 +1,7 @@
 use assert_cmd::prelude::*;
 use holochain_conductor_api::AppRequest;
 use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::ExternalApiWireError;
 use holochain_websocket::{self as ws, WebsocketConfig, WebsocketReceiver, WebsocketSender};
 use matches::assert_matches;
 use once_cell::sync::Lazy;
@@ -73,7 +74,12 @@ async fn call_app_interface(port: u16) {
     };
     let response = app_tx.request(request);
     let r: AppResponse = check_timeout(response).await;
-    assert_matches!(r, AppResponse::AppInfo(None));
+    // The connection hasn't been authenticated with a token from the
+    // admin interface, so the request is refused.
+    assert_matches!(
+        r,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
 }
 
 async fn check_timeout<T>(response: impl Future<Output = Result<T, ws::WebsocketError>>) -> T {
@@ -198,3 +198,46 @@ pub fn update_cap_grant(
         chain_top_ordering: ChainTopOrdering::default(),
     })
 }
+
+/// List the capability grants on the local source chain.
+///
+/// Each [`CapGrantInfo`] holds the grant along with the hash of the action that committed it,
+/// which is what [`update_cap_grant`] and [`delete_cap_grant`] expect, so this is the starting
+/// point for reviewing and revoking access that has been delegated to other agents.
+///
+/// The [`CapGrantQuery`] filters the grants by tag and by the zome function they give access to.
+/// By default only live grants are listed, i.e. grants that have not been revoked by an update
+/// or a delete. Setting `include_revoked` also lists revoked grants along with the time they
+/// were revoked.
+///
+/// Grants committed earlier in the current zome call are included.
+///
+/// ```ignore
+/// // All the live grants which let other agents call `foo` in the `bar` zome.
+/// let grants = capability_grants(CapGrantQuery::new().function(("bar".into(), "foo".into())))?;
+/// ```
+pub fn capability_grants(query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+    HDK.with(|h| h.borrow().capability_grants(query))
+}
+
+/// List the capability claims on the local source chain.
+///
+/// The [`CapClaimQuery`] filters the claims by tag and by the agent who issued the grant.
+/// Claims that have been deleted are never listed.
+///
+/// There is no guarantee that a listed claim is still usable. See [`create_cap_claim`].
+pub fn capability_claims(query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+    HDK.with(|h| h.borrow().capability_claims(query))
+}
+
+/// Get the capability which authorized the current zome call.
+///
+/// The returned [`CapabilityInfo`] holds the agent who made the call and the grant on the local
+/// source chain which authorized it. The grant is `None` when the chain author made the call,
+/// as the author never needs a grant to call their own externs.
+///
+/// This is useful for auditing who is exercising delegated access, e.g. by recording the grant's
+/// action hash alongside any entries committed on behalf of a remote caller.
+pub fn capability_info() -> ExternResult<CapabilityInfo> {
+    HDK.with(|h| h.borrow().capability_info(()))
+}
@@ -294,6 +294,32 @@ pub fn get_details<H: Into<AnyDhtHash>>(
         .unwrap())
 }
 
+/// Get the metadata held by the DHT for an entry or action hash,
+/// without fetching the records themselves.
+///
+/// The returned [`MetadataSet`] holds the hashes and timestamps of:
+/// - the actions which created the entry, or the action itself,
+///   split into valid and invalid actions
+/// - any updates and deletes referencing the hash
+/// - any links on the hash as a base which haven't been deleted
+///
+/// along with the [`EntryDhtStatus`] for an entry hash, or the
+/// [`ValidationStatus`] for an action hash.
+///
+/// This is cheaper than [`get_details`] when only the shape of the CRUD
+/// history is needed, e.g. to check whether an entry has been updated.
+/// If nothing is known about the hash, the sets will be empty.
+pub fn get_meta<H: Into<AnyDhtHash>>(hash: H, options: GetOptions) -> ExternResult<MetadataSet> {
+    Ok(HDK
+        .with(|h| {
+            h.borrow()
+                .get_meta(vec![GetInput::new(hash.into(), options)])
+        })?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
 /// Implements a whole lot of sane defaults for a struct or enum that should behave as an entry.
 /// All the entry def fields are available as dedicated methods on the type and matching From impls
 /// are provided for each. This allows for both Foo::entry_def() and EntryDef::from(Foo::new())
@@ -36,6 +36,11 @@ pub trait HdkT: HdiT {
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+    fn get_meta(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<MetadataSet>>;
+    // Capability
+    fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>>;
+    fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>>;
+    fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapabilityInfo>;
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -52,6 +57,7 @@ pub trait HdkT: HdiT {
         &self,
         get_links_input: Vec<GetLinksInput>,
     ) -> ExternResult<Vec<LinkDetails>>;
+    fn count_links(&self, query: LinkQuery) -> ExternResult<usize>;
     // P2P
     fn block_agent(&self, block_agent_input: BlockAgentInput) -> ExternResult<()>;
     fn unblock_agent(&self, unblock_agent_input: BlockAgentInput) -> ExternResult<()>;
@@ -108,6 +114,14 @@ mockall::mock! {
         fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
         fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
         fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+        fn get_meta(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<MetadataSet>>;
+        // Capability
+        fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>>;
+        fn capability_grants(
+            &self,
+            cap_grant_query: CapGrantQuery,
+        ) -> ExternResult<Vec<CapGrantInfo>>;
+        fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapabilityInfo>;
         // CounterSigning
         fn accept_countersigning_preflight_request(
             &self,
@@ -124,6 +138,7 @@ mockall::mock! {
             &self,
             get_links_input: Vec<GetLinksInput>,
         ) -> ExternResult<Vec<LinkDetails>>;
+        fn count_links(&self, query: LinkQuery) -> ExternResult<usize>;
         // P2P
         fn block_agent(&self, block_agent_input: BlockAgentInput) -> ExternResult<()>;
         fn unblock_agent(&self, unblock_agent_input: BlockAgentInput) -> ExternResult<()>;
@@ -301,6 +316,19 @@ impl HdkT for ErrHdk {
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
+    fn get_meta(&self, _: Vec<GetInput>) -> ExternResult<Vec<MetadataSet>> {
+        Self::err()
+    }
+    // Capability
+    fn capability_claims(&self, _: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+        Self::err()
+    }
+    fn capability_grants(&self, _: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+        Self::err()
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapabilityInfo> {
+        Self::err()
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -327,6 +355,9 @@ impl HdkT for ErrHdk {
     fn get_link_details(&self, _: Vec<GetLinksInput>) -> ExternResult<Vec<LinkDetails>> {
         Self::err()
     }
+    fn count_links(&self, _: LinkQuery) -> ExternResult<usize> {
+        Self::err()
+    }
     // P2P
     fn block_agent(&self, _: BlockAgentInput) -> ExternResult<()> {
         Self::err()
@@ -498,6 +529,19 @@ impl HdkT for HostHdk {
     fn get_details(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         host_call::<Vec<GetInput>, Vec<Option<Details>>>(__hc__get_details_1, get_inputs)
     }
+    fn get_meta(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<MetadataSet>> {
+        host_call::<Vec<GetInput>, Vec<MetadataSet>>(__hc__get_meta_1, get_inputs)
+    }
+    // Capability
+    fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+        host_call::<CapClaimQuery, Vec<CapClaim>>(__hc__capability_claims_1, cap_claim_query)
+    }
+    fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+        host_call::<CapGrantQuery, Vec<CapGrantInfo>>(__hc__capability_grants_1, cap_grant_query)
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapabilityInfo> {
+        host_call::<(), CapabilityInfo>(__hc__capability_info_1, ())
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -529,6 +573,9 @@ impl HdkT for HostHdk {
     ) -> ExternResult<Vec<LinkDetails>> {
         host_call::<Vec<GetLinksInput>, Vec<LinkDetails>>(__hc__get_link_details_1, get_links_input)
     }
+    fn count_links(&self, query: LinkQuery) -> ExternResult<usize> {
+        host_call::<LinkQuery, usize>(__hc__count_links_1, query)
+    }
     fn block_agent(&self, block_agent_input: BlockAgentInput) -> ExternResult<()> {
         host_call::<BlockAgentInput, ()>(__hc__block_agent_1, block_agent_input)
     }
@@ -156,6 +156,54 @@ pub fn get_links(
         .unwrap())
 }
 
+/// Returns the links described by a [ `GetLinksInput` ], which can also select links by when and
+/// by whom they were created, and return them a page at a time.
+///
+/// Links are returned oldest first. The filters are applied by the authorities for the base, so
+/// only the matching links are sent over the network.
+///
+/// e.g. to get the links created by `agent` in the last hour, 20 at a time:
+/// ```ignore
+/// let input = GetLinksInput::new(base, LinkTypes::Comment.try_into_filter()?, None)
+///     .author(agent)
+///     .after((sys_time()? - Duration::from_secs(60 * 60))?)
+///     .limit(20);
+/// let page = get_links_filtered(input.clone())?;
+/// // The next page starts after the last link of this one.
+/// let next_page = match page.last() {
+///     Some(last) => get_links_filtered(input.cursor(last.into()))?,
+///     None => vec![],
+/// };
+/// ```
+///
+/// See [ `get_links` ].
+pub fn get_links_filtered(input: GetLinksInput) -> ExternResult<Vec<Link>> {
+    Ok(HDK
+        .with(|h| h.borrow().get_links(vec![input]))?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
+/// Returns the number of live links described by a [ `LinkQuery` ], without fetching them.
+///
+/// Deleted links are not counted. The authorities for the base count the links from their own
+/// databases and only send back the hashes of the matching links, and any links the current
+/// agent has created which haven't reached the authorities yet are added to the count.
+///
+/// e.g. to count the comments on a post which start with a `#` tag:
+/// ```ignore
+/// let count = count_links(
+///     LinkQuery::new(post_hash, LinkTypes::Comment.try_into_filter()?)
+///         .tag_prefix(LinkTag::new("#")),
+/// )?;
+/// ```
+///
+/// See [ `get_links_filtered` ].
+pub fn count_links(query: LinkQuery) -> ExternResult<usize> {
+    HDK.with(|h| h.borrow().count_links(query))
+}
+
 /// Get all link creates and deletes that reference a base hash, optionally filtered by type or tag.
 ///
 /// Type can be filtered by providing a variant of the link types, or a range of them. To get links of
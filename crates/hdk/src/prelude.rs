@@ -1,3 +1,6 @@
+pub use crate::capability::capability_claims;
+pub use crate::capability::capability_grants;
+pub use crate::capability::capability_info;
 pub use crate::capability::create_cap_claim;
 pub use crate::capability::create_cap_grant;
 pub use crate::capability::delete_cap_grant;
@@ -20,6 +23,7 @@ pub use crate::entry::delete;
 pub use crate::entry::delete_entry;
 pub use crate::entry::get;
 pub use crate::entry::get_details;
+pub use crate::entry::get_meta;
 pub use crate::entry::must_get_action;
 pub use crate::entry::must_get_entry;
 pub use crate::entry::must_get_valid_record;
@@ -40,10 +44,12 @@ pub use crate::info::agent_info;
 pub use crate::info::call_info;
 pub use crate::info::dna_info;
 pub use crate::info::zome_info;
+pub use crate::link::count_links;
 pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
 pub use crate::link::get_links;
+pub use crate::link::get_links_filtered;
 pub use crate::link::LinkTypeFilterExt;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
@@ -127,8 +133,10 @@ macro_rules! holochain_externs {
             capability_info:1,
             get:1,
             get_details:1,
+            get_meta:1,
             get_links:1,
             get_link_details:1,
+            count_links:1,
             get_agent_activity:1,
             must_get_entry:1,
             must_get_valid_record:1,
@@ -136,7 +136,23 @@ pub fn schedule(scheduled_fn: &str) -> ExternResult<()> {
     HDK.with(|h| h.borrow().schedule(String::from(scheduled_fn)))
 }
 
-/// @todo Not implemented
+/// Suspend the current zome call for a while.
+///
+/// The conductor caps each sleep at its configured maximum, 10 seconds unless
+/// set otherwise, so `sleep` may return early. Sleeping does not block other
+/// zome calls, including ones writing to the same source chain, which makes it
+/// suitable for simple backoff, e.g. while polling for data to reach the DHT.
+///
+/// Only zome calls may sleep. Calling `sleep` from a callback such as `init`
+/// or `validate` returns an error.
+///
+/// ```ignore
+/// let mut wait = std::time::Duration::from_millis(100);
+/// while get(hash.clone(), GetOptions::default())?.is_none() {
+///     sleep(wait)?;
+///     wait *= 2;
+/// }
+/// ```
 pub fn sleep(wake_after: std::time::Duration) -> ExternResult<()> {
     HDK.with(|h| h.borrow().sleep(wake_after))
 }
@@ -34,6 +34,9 @@ pub type NetIdHash = HoloHash<hash_type::NetId>;
 /// The hash of some wasm bytecode
 pub type WasmHash = HoloHash<hash_type::Wasm>;
 
+/// The hash of an app bundle
+pub type AppHash = HoloHash<hash_type::App>;
+
 /// The hash of some external data that can't or doesn't exist on the DHT.
 pub type ExternalHash = HoloHash<hash_type::External>;
 
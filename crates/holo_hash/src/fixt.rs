@@ -9,6 +9,8 @@ use crate::AnyDhtHash;
 use crate::AnyDhtHashB64;
 use crate::AnyLinkableHash;
 use crate::AnyLinkableHashB64;
+use crate::AppHash;
+use crate::AppHashB64;
 use crate::DhtOpHash;
 use crate::DhtOpHashB64;
 use crate::DnaHash;
@@ -154,6 +156,15 @@ fixturator!(
     constructor fn new(WasmHash);
 );
 
+fixturator!(
+    AppHash;
+    constructor fn from_raw_32(ThirtyTwoHashBytes);
+);
+fixturator!(
+    AppHashB64;
+    constructor fn new(AppHash);
+);
+
 fixturator!(
     AnyDhtHash;
     constructor fn from_raw_32_and_type(ThirtyTwoHashBytes, HashTypeAnyDht);
@@ -16,7 +16,7 @@ use std::convert::TryInto;
 // hCgk 5124 <Buffer 84 28 24>
 // hCkk 5252 <Buffer 84 29 24> * ACTION
 // hCok 5380 <Buffer 84 2a 24> * WASM
-// hCsk 5508 <Buffer 84 2b 24>
+// hCsk 5508 <Buffer 84 2b 24> * APP
 // hCwk 5636 <Buffer 84 2c 24>
 // hC0k 5764 <Buffer 84 2d 24> * DNA
 // hC4k 5892 <Buffer 84 2e 24>
@@ -47,6 +47,7 @@ pub(crate) const DNA_PREFIX: &[u8] = &[0x84, 0x2d, 0x24]; // uhC0k [132, 45, 36]
 pub(crate) const NET_ID_PREFIX: &[u8] = &[0x84, 0x22, 0x24]; // uhCIk [132, 34, 36]
 pub(crate) const ACTION_PREFIX: &[u8] = &[0x84, 0x29, 0x24]; // uhCkk [132, 41, 36]
 pub(crate) const WASM_PREFIX: &[u8] = &[0x84, 0x2a, 0x24]; // uhCok [132, 42, 36]
+pub(crate) const APP_PREFIX: &[u8] = &[0x84, 0x2b, 0x24]; // uhCsk [132, 43, 36]
 pub(crate) const EXTERNAL_PREFIX: &[u8] = &[0x84, 0x2f, 0x24]; // uhC8k [132, 47, 36]
 
 /// A PrimitiveHashType is one with a multihash prefix.
@@ -170,6 +171,7 @@ primitive_hash_type!(Action, ActionHash, ActionVisitor, ACTION_PREFIX);
 primitive_hash_type!(NetId, NetIdHash, NetIdVisitor, NET_ID_PREFIX);
 primitive_hash_type!(Wasm, WasmHash, WasmVisitor, WASM_PREFIX);
 primitive_hash_type!(External, ExternalHash, ExternalVisitor, EXTERNAL_PREFIX);
+primitive_hash_type!(App, AppHash, AppVisitor, APP_PREFIX);
 
 // DhtOps are mostly hashes
 impl HashTypeSync for DhtOp {}
@@ -194,6 +196,8 @@ impl HashTypeAsync for External {}
 
 impl HashTypeAsync for NetId {}
 impl HashTypeAsync for Wasm {}
+// An AppHash is a hash of a whole app bundle, including all of its DNAs
+impl HashTypeAsync for App {}
 
 impl From<AgentPubKey> for EntryHash {
     fn from(hash: AgentPubKey) -> EntryHash {
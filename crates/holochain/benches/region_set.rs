@@ -0,0 +1,117 @@
+//! Compare computing the region set for gossip with a database query per
+//! region against reading it from the space's [`DhtRegionTree`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ::fixt::prelude::*;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use holochain::conductor::kitsune_host_impl::query_region_set::*;
+use holochain_p2p::dht::prelude::*;
+use holochain_p2p::dht_arc::DhtArcSet;
+use holochain_state::prelude::*;
+use holochain_state::test_utils::test_dht_db;
+use holochain_types::fixt::*;
+use tokio::runtime::Builder;
+use tokio::runtime::Runtime;
+
+criterion_group!(benches, region_set);
+
+criterion_main!(benches);
+
+/// How far back in time the ops are spread
+const HISTORY: Duration = Duration::from_secs(60 * 60 * 24 * 30);
+
+fn ops(n: usize) -> Vec<DhtOpHashed> {
+    let now = Timestamp::now();
+    (0..n)
+        .map(|i| {
+            let mut create = fixt!(Create);
+            create.timestamp = (now - HISTORY.mul_f64(i as f64 / n as f64)).unwrap();
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+                fixt!(Signature),
+                Action::Create(create),
+            ))
+        })
+        .collect()
+}
+
+fn region_set(bench: &mut Criterion) {
+    let mut group = bench.benchmark_group("region_set");
+    group.sample_size(
+        std::env::var_os("BENCH_SAMPLE_SIZE")
+            .and_then(|s| s.to_string_lossy().parse::<usize>().ok())
+            .unwrap_or(100),
+    );
+    let runtime = rt();
+    let topo = Topology::standard((Timestamp::now() - HISTORY).unwrap(), Duration::ZERO);
+    let strat = ArqStrat::default();
+    let arcset = Arc::new(DhtArcSet::Full);
+
+    for n in [1_000, 10_000, 100_000] {
+        let db = test_dht_db();
+        db.test_commit(|txn| {
+            for op in ops(n) {
+                insert_op(txn, &op).unwrap();
+            }
+            StateMutationResult::Ok(())
+        })
+        .unwrap();
+        let region_tree = DhtRegionTree::default();
+
+        group.bench_with_input(BenchmarkId::new("database", n), &n, |b, _| {
+            b.iter(|| {
+                runtime.block_on(query_region_set_from_db(
+                    db.to_db(),
+                    topo.clone(),
+                    &strat,
+                    arcset.clone(),
+                ))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("tree_cold", n), &n, |b, _| {
+            b.iter(|| {
+                runtime.block_on(query_region_set(
+                    db.to_db(),
+                    &DhtRegionTree::default(),
+                    topo.clone(),
+                    &strat,
+                    arcset.clone(),
+                ))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("tree_warm", n), &n, |b, _| {
+            b.iter(|| {
+                runtime.block_on(query_region_set(
+                    db.to_db(),
+                    &region_tree,
+                    topo.clone(),
+                    &strat,
+                    arcset.clone(),
+                ))
+            })
+        });
+        let mut new_ops = ops(1_000).into_iter();
+        group.bench_with_input(BenchmarkId::new("tree_update", n), &n, |b, _| {
+            b.iter(|| {
+                if let Some(op) = new_ops.next() {
+                    db.test_commit(|txn| insert_op(txn, &op).unwrap());
+                }
+                runtime.block_on(query_region_set(
+                    db.to_db(),
+                    &region_tree,
+                    topo.clone(),
+                    &strat,
+                    arcset.clone(),
+                ))
+            })
+        });
+    }
+}
+
+pub fn rt() -> Runtime {
+    Builder::new_multi_thread().enable_all().build().unwrap()
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "hc-chc-server",
+    about = "A reference Chain Head Coordinator server, for local testing only."
+)]
+struct Opt {
+    /// Bind to this interface
+    #[structopt(short, long, default_value = "127.0.0.1:0")]
+    interface: String,
+
+    /// Path to the SQLite database in which chains are stored.
+    /// If not given, an in-memory database is used.
+    #[structopt(short, long)]
+    database: Option<PathBuf>,
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    holochain_trace::test_run().ok();
+    let opt = Opt::from_args();
+
+    use std::net::ToSocketAddrs;
+    let addr = opt
+        .interface
+        .as_str()
+        .to_socket_addrs()
+        .expect("invalid interface")
+        .next()
+        .expect("interface did not resolve to an address");
+
+    match holochain::conductor::chc::run_chc_server(addr, opt.database.as_deref()).await {
+        Ok((driver, addr, _shutdown)) => {
+            println!("http://{}", addr);
+            driver.await;
+        }
+        Err(err) => eprintln!("{}", err),
+    }
+}
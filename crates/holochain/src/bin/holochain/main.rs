@@ -6,7 +6,9 @@ use holochain::conductor::Conductor;
 use holochain::conductor::ConductorHandle;
 use holochain_conductor_api::conductor::ConductorConfigError;
 use holochain_conductor_api::config::conductor::KeystoreConfig;
+use holochain_conductor_api::config::conductor::LogFormat;
 use holochain_trace::Output;
+use holochain_trace::RotatingFile;
 use holochain_util::tokio_helper;
 #[cfg(unix)]
 use sd_notify::{notify, NotifyState};
@@ -27,6 +29,9 @@ struct Opt {
     - Log: Output logs to stdout with spans (human readable)
     - Compact: Same as Log but with less information
     - Json: Output logs as structured json (machine readable)
+    - OpenTel: Same as Log but also exports spans to the OpenTelemetry collector
+      at OTEL_EXPORTER_OTLP_ENDPOINT (http://localhost:4317 by default)
+    The format set in the logger section of the conductor config takes precedence.
     ",
         default_value = "Log"
     )]
@@ -60,6 +65,15 @@ struct Opt {
         help = "Display version information such as git revision and HDK version"
     )]
     build_info: bool,
+
+    #[structopt(
+        long,
+        help = "Encrypt the databases which were created before database encryption
+    was enabled, or with the fixed key of older versions, with keys from the
+    configured keystore, and then exit.
+    No conductor may be running on these databases at the same time."
+    )]
+    encrypt_databases: bool,
 }
 
 fn main() {
@@ -86,9 +100,14 @@ async fn async_main() {
         std::env::set_var("CUSTOM_FILTER", t);
     }
 
-    holochain_trace::init_fmt(opt.structured.clone()).expect("Failed to start contextual logging");
+    init_logging(&opt, &config).expect("Failed to start contextual logging");
     debug!("holochain_trace initialized");
 
+    if opt.encrypt_databases {
+        encrypt_databases(&opt, config).await;
+        return;
+    }
+
     kitsune_p2p_types::metrics::init_sys_info_poll();
 
     let conductor = conductor_handle_from_config(&opt, config).await;
@@ -113,9 +132,35 @@ async fn async_main() {
         .unwrap_or_else(|e| tracing::error!("Could not handle termination signal: {:?}", e));
     tracing::info!("Gracefully shutting down conductor...");
     let shutdown_result = conductor.shutdown().await;
+    // Send any spans which are still waiting to be exported.
+    let _ = tokio::task::spawn_blocking(holochain_trace::shutdown_open).await;
     handle_shutdown(shutdown_result);
 }
 
+/// Start logging as set by the conductor config's logger, if any,
+/// or else by the command line and environment.
+fn init_logging(
+    opt: &Opt,
+    config: &ConductorConfig,
+) -> Result<(), holochain_trace::errors::TracingError> {
+    let logger = match &config.logger {
+        Some(logger) => logger,
+        None => return holochain_trace::init_fmt(opt.structured.clone()),
+    };
+    let output = match logger.format {
+        Some(LogFormat::Log) => Output::Log,
+        Some(LogFormat::Compact) => Output::Compact,
+        Some(LogFormat::Json) => Output::Json,
+        None => opt.structured.clone(),
+    };
+    let file = logger.file.as_ref().map(|file| RotatingFile {
+        path: file.path.clone(),
+        max_file_size: file.max_file_size_bytes,
+        max_files: file.max_files,
+    });
+    holochain_trace::init_fmt_with(output, Some(&logger.filter_directives()), file)
+}
+
 fn get_conductor_config(opt: &Opt) -> ConductorConfig {
     let config_path = opt.config_path.clone();
     let config_path_default = config_path.is_none();
@@ -137,9 +182,9 @@ fn get_conductor_config(opt: &Opt) -> ConductorConfig {
     config
 }
 
-async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> ConductorHandle {
-    // read the passphrase to prepare for usage
-    let passphrase = match &config.keystore {
+/// Read the keystore passphrase, if the configured keystore needs one.
+fn read_passphrase(opt: &Opt, config: &ConductorConfig) -> Option<sodoken::BufRead> {
+    match &config.keystore {
         KeystoreConfig::DangerTestKeystore => None,
         KeystoreConfig::LairServer { .. } | KeystoreConfig::LairServerInProc { .. } => {
             if opt.piped {
@@ -148,7 +193,43 @@ async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> Con
 
             Some(holochain_util::pw::pw_get().unwrap())
         }
-    };
+    }
+}
+
+#[cfg(feature = "sqlite-encrypted")]
+async fn encrypt_databases(opt: &Opt, config: ConductorConfig) {
+    let passphrase = read_passphrase(opt, &config);
+    match Conductor::builder()
+        .config(config)
+        .passphrase(passphrase)
+        .encrypt_databases()
+        .await
+    {
+        Ok(paths) => {
+            for path in paths.iter() {
+                println!("Encrypted {}", path.display());
+            }
+            println!("Encrypted {} databases.", paths.len());
+        }
+        Err(err) => {
+            println!("Couldn't encrypt databases: {:?}", err);
+            std::process::exit(ERROR_CODE);
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite-encrypted"))]
+async fn encrypt_databases(_opt: &Opt, _config: ConductorConfig) {
+    println!(
+        "This conductor was built without database encryption. Build it with the \
+        `sqlite-encrypted` feature to encrypt databases."
+    );
+    std::process::exit(ERROR_CODE);
+}
+
+async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> ConductorHandle {
+    // read the passphrase to prepare for usage
+    let passphrase = read_passphrase(opt, &config);
 
     // Check if database is present
     // In interactive mode give the user a chance to create it, otherwise create it automatically
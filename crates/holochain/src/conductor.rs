@@ -14,6 +14,7 @@
 // TODO: clean up allow(missing_docs) once parent is fully documented
 
 pub mod api;
+pub mod app_auth_token_store;
 mod cell;
 #[cfg(feature = "chc")]
 pub mod chc;
@@ -29,6 +30,7 @@ pub mod interactive;
 pub mod interface;
 pub mod kitsune_host_impl;
 pub mod manager;
+pub mod metrics;
 pub mod p2p_agent_store;
 pub mod paths;
 #[allow(missing_docs)]
@@ -217,6 +217,9 @@ pub trait CellConductorReadHandleT: Send + Sync {
 
     /// Expose is_blocked functionality to zomes.
     async fn is_blocked(&self, input: BlockTargetId, timestamp: Timestamp) -> DatabaseResult<bool>;
+
+    /// The longest time a zome call may sleep for at once.
+    fn max_zome_sleep(&self) -> std::time::Duration;
 }
 
 #[async_trait]
@@ -280,4 +283,8 @@ impl CellConductorReadHandleT for CellConductorApi {
     async fn is_blocked(&self, input: BlockTargetId, timestamp: Timestamp) -> DatabaseResult<bool> {
         self.conductor_handle.is_blocked(input, timestamp).await
     }
+
+    fn max_zome_sleep(&self) -> std::time::Duration {
+        self.conductor_handle.get_config().max_zome_sleep()
+    }
 }
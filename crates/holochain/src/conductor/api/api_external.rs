@@ -1,5 +1,6 @@
 use crate::conductor::interface::error::InterfaceResult;
 use holochain_serialized_bytes::prelude::*;
+use holochain_types::signal::Signal;
 
 mod admin_interface;
 mod app_interface;
@@ -18,4 +19,15 @@ pub trait InterfaceApi: 'static + Send + Sync + Clone {
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
     ) -> InterfaceResult<Self::ApiResponse>;
+
+    /// Get an instance of this API for a single client connection, so that
+    /// state set by requests on that connection isn't shared with others.
+    fn for_connection(&self) -> Self {
+        self.clone()
+    }
+
+    /// Should this signal be sent to the client of this connection?
+    fn allows_signal(&self, _signal: &Signal) -> bool {
+        true
+    }
 }
@@ -81,21 +81,30 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 // network seed and properties from the register call will override any in the bundle
                 let dna = match source {
                     DnaSource::Hash(ref hash) => {
-                        if !modifiers.has_some_option_set() {
-                            return Err(ConductorApiError::DnaReadError(
-                                "DnaSource::Hash requires `properties` or `network_seed` or `origin_time` to create a derived Dna"
-                                    .to_string(),
-                            ));
+                        if let Some(dna_file) = self.conductor_handle.get_dna_file(hash) {
+                            if !modifiers.has_some_option_set() {
+                                return Err(ConductorApiError::DnaReadError(
+                                    "DnaSource::Hash requires `properties` or `network_seed` or `origin_time` to create a derived Dna"
+                                        .to_string(),
+                                ));
+                            }
+                            dna_file.update_modifiers(modifiers)
+                        } else {
+                            // Not registered locally, so try to fetch it from the app store
+                            let bundle = self
+                                .conductor_handle
+                                .get_dna_bundle_from_app_store(hash)
+                                .await?
+                                .ok_or_else(|| {
+                                    ConductorApiError::DnaReadError(format!(
+                                        "Unable to create derived Dna: {} not registered",
+                                        hash
+                                    ))
+                                })?;
+                            let (dna_file, _original_hash) =
+                                bundle.into_dna_file(modifiers).await?;
+                            dna_file
                         }
-                        self.conductor_handle
-                            .get_dna_file(hash)
-                            .ok_or_else(|| {
-                                ConductorApiError::DnaReadError(format!(
-                                    "Unable to create derived Dna: {} not registered",
-                                    hash
-                                ))
-                            })?
-                            .update_modifiers(modifiers)
                     }
                     DnaSource::Path(ref path) => {
                         let bundle = Bundle::read_from_file(path).await?;
@@ -220,14 +229,30 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppDisabled)
             }
-            AttachAppInterface { port } => {
-                let port = port.unwrap_or(0);
+            AttachAppInterface {
+                port,
+                driver,
+                allowed_origins,
+                installed_app_id,
+            } => {
+                let driver = match (port, driver) {
+                    (port, None) => InterfaceDriver::websocket(port.unwrap_or(0)),
+                    (None, Some(driver)) => driver,
+                    (Some(_), Some(_)) => {
+                        return Err(ConductorApiError::other(
+                            "Attach an app interface with either a port or a driver",
+                        ))
+                    }
+                };
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(either::Either::Left(port))
+                    .add_app_interface(driver, None, allowed_origins, installed_app_id)
                     .await?;
-                Ok(AdminResponse::AppInterfaceAttached { port })
+                // Unix domain sockets don't have a port.
+                Ok(AdminResponse::AppInterfaceAttached {
+                    port: port.unwrap_or(0),
+                })
             }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
@@ -288,9 +313,39 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::CloneCellDeleted)
             }
+            MigrateCell(payload) => Ok(AdminResponse::CellMigrated(
+                self.conductor_handle.clone().migrate_cell(*payload).await?,
+            )),
             StorageInfo => Ok(AdminResponse::StorageInfo(
                 self.conductor_handle.storage_info().await?,
             )),
+            ListCountersigningSessions => Ok(AdminResponse::CountersigningSessionsListed(
+                self.conductor_handle.list_countersigning_sessions().await?,
+            )),
+            ResolveCountersigningSession { cell_id, force } => {
+                Ok(AdminResponse::CountersigningSessionResolved(
+                    self.conductor_handle
+                        .resolve_countersigning_session(&cell_id, force)
+                        .await?,
+                ))
+            }
+            IssueAppAuthenticationToken(payload) => {
+                Ok(AdminResponse::AppAuthenticationTokenIssued(
+                    self.conductor_handle
+                        .issue_app_authentication_token(payload)
+                        .await?,
+                ))
+            }
+            RevokeAppAuthenticationToken(token) => {
+                self.conductor_handle
+                    .revoke_app_authentication_token(&token);
+                Ok(AdminResponse::AppAuthenticationTokenRevoked)
+            }
+            SetLogFilter { filter } => {
+                holochain_trace::set_filter(&filter).map_err(ConductorApiError::other)?;
+                info!(%filter, "Log filter changed");
+                Ok(AdminResponse::LogFilterSet)
+            }
         }
     }
 }
@@ -1,14 +1,23 @@
 use super::InterfaceApi;
+use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
 use crate::conductor::ConductorHandle;
 
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
+use holochain_types::signal::Signal;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 pub use holochain_conductor_api::*;
 
@@ -41,12 +50,227 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+    /// The only app which connections to the interface may authenticate for
+    interface_app_id: Option<InstalledAppId>,
+    /// Which app this connection may make requests on behalf of
+    auth: Arc<RwLock<ConnectionAuth>>,
+    /// The signal subscriptions set on this connection, by app
+    signal_subscriptions: Arc<RwLock<HashMap<InstalledAppId, AppSignalSubscription>>>,
+}
+
+/// Which apps a connection may make requests on behalf of
+enum ConnectionAuth {
+    /// The API is used in process rather than by a client connection,
+    /// so any app may be accessed
+    Unrestricted,
+    /// A client connection which hasn't authenticated yet
+    Pending,
+    /// A client connection which has authenticated for a single app
+    App {
+        installed_app_id: InstalledAppId,
+        /// The cells of the app, as of the last time they were looked up
+        cells: HashSet<CellId>,
+    },
+}
+
+/// A signal subscription, along with the cells it applies to
+struct AppSignalSubscription {
+    cells: HashSet<CellId>,
+    filters: SignalFilterSet,
 }
 
 impl RealAppInterfaceApi {
     /// Create a new instance from a shared Conductor reference
+    ///
+    /// The instance may access every app. Instances for client connections
+    /// are created with [`InterfaceApi::for_connection`], and must be
+    /// authenticated before they can be used.
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            interface_app_id: None,
+            auth: Arc::new(RwLock::new(ConnectionAuth::Unrestricted)),
+            signal_subscriptions: Default::default(),
+        }
+    }
+
+    /// Only allow connections to authenticate for this app
+    pub fn restrict_to_app(mut self, installed_app_id: InstalledAppId) -> Self {
+        self.interface_app_id = Some(installed_app_id);
+        self
+    }
+
+    /// Restrict this connection to the app which the token was issued for
+    async fn authenticate(
+        &self,
+        token: AppAuthenticationToken,
+    ) -> ConductorApiResult<InstalledAppId> {
+        let authenticated = matches!(&*self.auth.read(), ConnectionAuth::App { .. });
+        if authenticated {
+            return Err(ConductorApiError::AppAuthenticationFailed(
+                "This connection is already authenticated".to_string(),
+            ));
+        }
+        let installed_app_id = self
+            .conductor_handle
+            .authenticate_app_token(&token)
+            .ok_or_else(|| {
+                ConductorApiError::AppAuthenticationFailed(
+                    "The token is invalid or has expired".to_string(),
+                )
+            })?;
+        if let Some(interface_app_id) = &self.interface_app_id {
+            if interface_app_id != &installed_app_id {
+                return Err(ConductorApiError::AppAuthenticationFailed(format!(
+                    "This app interface only serves app {}",
+                    interface_app_id
+                )));
+            }
+        }
+        let cells = self.app_cells(&installed_app_id).await?;
+        *self.auth.write() = ConnectionAuth::App {
+            installed_app_id: installed_app_id.clone(),
+            cells,
+        };
+        Ok(installed_app_id)
+    }
+
+    /// Check that this connection may make the request
+    async fn check_request(&self, request: &AppRequest) -> ConductorApiResult<()> {
+        if let AppRequest::Authenticate { .. } = request {
+            return Ok(());
+        }
+        let pending = matches!(&*self.auth.read(), ConnectionAuth::Pending);
+        if pending {
+            return Err(not_authenticated());
+        }
+        match request {
+            AppRequest::Authenticate { .. } | AppRequest::ListWasmHostFunctions => Ok(()),
+            AppRequest::AppInfo { installed_app_id } => self.check_app(installed_app_id),
+            AppRequest::CallZome(call) => self.check_cell(&call.cell_id).await,
+            AppRequest::CreateCloneCell(payload) => self.check_app(&payload.app_id),
+            AppRequest::ProvisionRole(payload) => self.check_app(&payload.app_id),
+            AppRequest::DisableCloneCell(payload) | AppRequest::EnableCloneCell(payload) => {
+                self.check_app(&payload.app_id)
+            }
+            AppRequest::NetworkInfo(payload) => {
+                for dna_hash in &payload.dnas {
+                    self.check_cell(&CellId::new(
+                        dna_hash.clone(),
+                        payload.agent_pub_key.clone(),
+                    ))
+                    .await?;
+                }
+                Ok(())
+            }
+            AppRequest::SetSignalSubscription(subscription) => {
+                self.check_app(&subscription.installed_app_id)
+            }
+        }
+    }
+
+    fn check_app(&self, installed_app_id: &InstalledAppId) -> ConductorApiResult<()> {
+        match &*self.auth.read() {
+            ConnectionAuth::Unrestricted => Ok(()),
+            ConnectionAuth::Pending => Err(not_authenticated()),
+            ConnectionAuth::App {
+                installed_app_id: authenticated,
+                ..
+            } => {
+                if authenticated == installed_app_id {
+                    Ok(())
+                } else {
+                    Err(ConductorApiError::AppAuthenticationFailed(format!(
+                        "This connection is not authenticated for app {}",
+                        installed_app_id
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Check the cell belongs to the app this connection is authenticated
+    /// for, looking the app's cells up again if the cell isn't known yet,
+    /// since clone cells may have been added to the app by other connections.
+    async fn check_cell(&self, cell_id: &CellId) -> ConductorApiResult<()> {
+        let installed_app_id = match &*self.auth.read() {
+            ConnectionAuth::Unrestricted => return Ok(()),
+            ConnectionAuth::Pending => return Err(not_authenticated()),
+            ConnectionAuth::App {
+                installed_app_id,
+                cells,
+            } => {
+                if cells.contains(cell_id) {
+                    return Ok(());
+                }
+                installed_app_id.clone()
+            }
+        };
+        let cells = self.app_cells(&installed_app_id).await?;
+        let allowed = cells.contains(cell_id);
+        if let ConnectionAuth::App { cells: known, .. } = &mut *self.auth.write() {
+            *known = cells;
+        }
+        if allowed {
+            Ok(())
+        } else {
+            Err(ConductorApiError::AppAuthenticationFailed(format!(
+                "Cell {:?} is not part of app {}",
+                cell_id, installed_app_id
+            )))
+        }
+    }
+
+    /// Record a cell which was added to the app this connection is
+    /// authenticated for, so that its signals are sent.
+    fn add_cell(&self, cell_id: &CellId) {
+        if let ConnectionAuth::App { cells, .. } = &mut *self.auth.write() {
+            cells.insert(cell_id.clone());
+        }
+    }
+
+    async fn app_cells(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorApiResult<HashSet<CellId>> {
+        let app_info = self
+            .conductor_handle
+            .get_app_info(installed_app_id)
+            .await?
+            .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+        Ok(app_info
+            .cell_info
+            .into_values()
+            .flatten()
+            .filter_map(|info| match info {
+                CellInfo::Provisioned(cell) => Some(cell.cell_id),
+                CellInfo::Cloned(cell) => Some(cell.cell_id),
+                CellInfo::Stem(_) => None,
+            })
+            .collect())
+    }
+
+    async fn set_signal_subscription(
+        &self,
+        subscription: SignalSubscription,
+    ) -> ConductorApiResult<()> {
+        let SignalSubscription {
+            installed_app_id,
+            filters,
+        } = subscription;
+        let mut cells = self.app_cells(&installed_app_id).await?;
+        if let ConnectionAuth::App { cells: known, .. } = &mut *self.auth.write() {
+            known.extend(cells.iter().cloned());
+        }
+        match &filters {
+            SignalFilterSet::Include(f) | SignalFilterSet::Exclude(f) => {
+                cells.extend(f.keys().cloned())
+            }
+        }
+        self.signal_subscriptions
+            .write()
+            .insert(installed_app_id, AppSignalSubscription { cells, filters });
+        Ok(())
     }
 }
 
@@ -57,7 +281,11 @@ impl AppInterfaceApi for RealAppInterfaceApi {
         &self,
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse> {
+        self.check_request(&request).await?;
         match request {
+            AppRequest::Authenticate { token } => {
+                Ok(AppResponse::Authenticated(self.authenticate(token).await?))
+            }
             AppRequest::AppInfo { installed_app_id } => Ok(AppResponse::AppInfo(
                 self.conductor_handle
                     .get_app_info(&installed_app_id)
@@ -91,6 +319,7 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     .clone()
                     .create_clone_cell(*payload)
                     .await?;
+                self.add_cell(&clone_cell.cell_id);
                 Ok(AppResponse::CloneCellCreated(clone_cell))
             }
             AppRequest::DisableCloneCell(payload) => {
@@ -106,8 +335,18 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     .clone()
                     .enable_clone_cell(&payload)
                     .await?;
+                self.add_cell(&enabled_cell.cell_id);
                 Ok(AppResponse::CloneCellEnabled(enabled_cell))
             }
+            AppRequest::ProvisionRole(payload) => {
+                let provisioned_cell = self
+                    .conductor_handle
+                    .clone()
+                    .provision_deferred_role(*payload)
+                    .await?;
+                self.add_cell(&provisioned_cell.cell_id);
+                Ok(AppResponse::RoleProvisioned(provisioned_cell))
+            }
             AppRequest::NetworkInfo(payload) => {
                 let info = self.conductor_handle.network_info(&payload).await?;
                 Ok(AppResponse::NetworkInfo(info))
@@ -115,6 +354,10 @@ impl AppInterfaceApi for RealAppInterfaceApi {
             AppRequest::ListWasmHostFunctions => Ok(AppResponse::ListWasmHostFunctions(
                 self.conductor_handle.list_wasm_host_functions().await?,
             )),
+            AppRequest::SetSignalSubscription(subscription) => {
+                self.set_signal_subscription(*subscription).await?;
+                Ok(AppResponse::SignalSubscriptionSet)
+            }
         }
     }
 }
@@ -138,4 +381,49 @@ impl InterfaceApi for RealAppInterfaceApi {
             Err(e) => Ok(AppResponse::Error(SerializationError::from(e).into())),
         }
     }
+
+    fn for_connection(&self) -> Self {
+        Self {
+            conductor_handle: self.conductor_handle.clone(),
+            interface_app_id: self.interface_app_id.clone(),
+            auth: Arc::new(RwLock::new(ConnectionAuth::Pending)),
+            signal_subscriptions: Default::default(),
+        }
+    }
+
+    fn allows_signal(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::App {
+                cell_id,
+                zome_name,
+                signal,
+            } => {
+                match &*self.auth.read() {
+                    ConnectionAuth::Unrestricted => (),
+                    ConnectionAuth::Pending => return false,
+                    ConnectionAuth::App { cells, .. } => {
+                        if !cells.contains(cell_id) {
+                            return false;
+                        }
+                    }
+                }
+                let subscriptions = self.signal_subscriptions.read();
+                let mut governing = subscriptions
+                    .values()
+                    .filter(|s| s.cells.contains(cell_id))
+                    .peekable();
+                // Signals from cells which aren't part of any subscribed app
+                // are always sent
+                governing.peek().is_none()
+                    || governing.any(|s| s.filters.allows(cell_id, zome_name, signal))
+            }
+            Signal::System(_) => !matches!(&*self.auth.read(), ConnectionAuth::Pending),
+        }
+    }
+}
+
+fn not_authenticated() -> ConductorApiError {
+    ConductorApiError::AppAuthenticationFailed(
+        "This connection must be authenticated with an AppRequest::Authenticate first".to_string(),
+    )
 }
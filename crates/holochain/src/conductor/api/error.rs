@@ -68,6 +68,10 @@ pub enum ConductorApiError {
     #[error("The Dna file path provided was invalid")]
     DnaReadError(String),
 
+    /// The connection to an app interface isn't authenticated for the request
+    #[error("App authentication failed: {0}")]
+    AppAuthenticationFailed(String),
+
     /// KeystoreError
     #[error("KeystoreError: {0}")]
     KeystoreError(#[from] holochain_keystore::KeystoreError),
@@ -150,6 +154,9 @@ impl From<ConductorApiError> for ExternalApiWireError {
     fn from(err: ConductorApiError) -> Self {
         match err {
             ConductorApiError::DnaReadError(e) => ExternalApiWireError::DnaReadError(e),
+            ConductorApiError::AppAuthenticationFailed(e) => {
+                ExternalApiWireError::AppAuthenticationFailed(e)
+            }
             e => ExternalApiWireError::internal(e),
         }
     }
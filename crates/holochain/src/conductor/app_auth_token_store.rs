@@ -0,0 +1,133 @@
+//! The tokens which clients use to authenticate their connections to
+//! app interfaces.
+//!
+//! Tokens are only kept in memory, so they all become invalid when the
+//! conductor restarts.
+
+use holochain_conductor_api::AppAuthenticationToken;
+use holochain_types::prelude::InstalledAppId;
+use holochain_zome_types::Timestamp;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The number of random bytes in a token.
+const TOKEN_LEN: usize = 32;
+
+/// The app authentication tokens issued by a conductor which are still valid
+#[derive(Default)]
+pub struct AppAuthTokenStore {
+    issued: HashMap<AppAuthenticationToken, IssuedToken>,
+}
+
+struct IssuedToken {
+    installed_app_id: InstalledAppId,
+    expires_at: Option<Timestamp>,
+    single_use: bool,
+}
+
+impl IssuedToken {
+    fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= now)
+            .unwrap_or(false)
+    }
+}
+
+impl AppAuthTokenStore {
+    /// Issue a new token for an app, which expires after `expiry` if given.
+    /// Returns the token and when it expires.
+    pub fn issue_token(
+        &mut self,
+        installed_app_id: InstalledAppId,
+        expiry: Option<Duration>,
+        single_use: bool,
+    ) -> (AppAuthenticationToken, Option<Timestamp>) {
+        let now = Timestamp::now();
+        self.remove_expired(now);
+        // A token so far in the future that it overflows never expires.
+        let expires_at = expiry.and_then(|expiry| (now + expiry).ok());
+        let token = rand::random::<[u8; TOKEN_LEN]>().to_vec();
+        self.issued.insert(
+            token.clone(),
+            IssuedToken {
+                installed_app_id,
+                expires_at,
+                single_use,
+            },
+        );
+        (token, expires_at)
+    }
+
+    /// Get the app a token was issued for, if the token is valid.
+    /// Single use tokens are removed once they have been used.
+    pub fn authenticate_token(&mut self, token: &[u8]) -> Option<InstalledAppId> {
+        self.remove_expired(Timestamp::now());
+        let issued = self.issued.get(token)?;
+        if issued.single_use {
+            self.issued
+                .remove(token)
+                .map(|issued| issued.installed_app_id)
+        } else {
+            Some(issued.installed_app_id.clone())
+        }
+    }
+
+    /// Make a token invalid.
+    pub fn revoke_token(&mut self, token: &[u8]) {
+        self.issued.remove(token);
+    }
+
+    /// Make all the tokens issued for an app invalid.
+    pub fn revoke_app_tokens(&mut self, installed_app_id: &InstalledAppId) {
+        self.issued
+            .retain(|_, issued| &issued.installed_app_id != installed_app_id);
+    }
+
+    fn remove_expired(&mut self, now: Timestamp) {
+        self.issued.retain(|_, issued| !issued.is_expired(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_use_token_is_removed_after_use() {
+        let mut store = AppAuthTokenStore::default();
+        let (token, _) = store.issue_token("app".to_string(), None, true);
+        assert_eq!(store.authenticate_token(&token), Some("app".to_string()));
+        assert_eq!(store.authenticate_token(&token), None);
+    }
+
+    #[test]
+    fn reusable_token_can_be_revoked() {
+        let mut store = AppAuthTokenStore::default();
+        let (token, expires_at) = store.issue_token("app".to_string(), None, false);
+        assert!(expires_at.is_none());
+        assert!(store.authenticate_token(&token).is_some());
+        assert!(store.authenticate_token(&token).is_some());
+        store.revoke_token(&token);
+        assert_eq!(store.authenticate_token(&token), None);
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let mut store = AppAuthTokenStore::default();
+        let (token, expires_at) =
+            store.issue_token("app".to_string(), Some(Duration::from_millis(1)), false);
+        assert!(expires_at.is_some());
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(store.authenticate_token(&token), None);
+    }
+
+    #[test]
+    fn revoking_app_tokens_leaves_other_apps_tokens() {
+        let mut store = AppAuthTokenStore::default();
+        let (token_a, _) = store.issue_token("a".to_string(), None, false);
+        let (token_b, _) = store.issue_token("b".to_string(), None, false);
+        store.revoke_app_tokens(&"a".to_string());
+        assert_eq!(store.authenticate_token(&token_a), None);
+        assert_eq!(store.authenticate_token(&token_b), Some("b".to_string()));
+    }
+}
@@ -19,6 +19,8 @@ use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::countersigning_workflow::countersigning_success;
 use crate::core::workflow::countersigning_workflow::incoming_countersigning;
+use crate::core::workflow::countersigning_workflow::resolve_countersigning_session;
+use crate::core::workflow::countersigning_workflow::resume_countersigning_session;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
 use crate::core::workflow::CallZomeWorkflowArgs;
@@ -41,6 +43,7 @@ use holochain_state::host_fn_workspace::SourceChainWorkspace;
 use holochain_state::nonce::fresh_nonce;
 use holochain_state::prelude::*;
 use holochain_state::schedule::live_scheduled_fns;
+use holochain_trace::OpenSpanExt;
 use holochain_types::db_cache::DhtDbQueryCache;
 use holochain_types::prelude::*;
 use rusqlite::OptionalExtension;
@@ -370,6 +373,7 @@ impl Cell {
             | QueryAgentInfoSignedNearBasis { .. }
             | QueryPeerDensity { .. }
             | Publish { .. }
+            | PublishWarrant { .. }
             | FetchOpData { .. } => {
                 // These events are aggregated over a set of cells, so need to be handled at the conductor level.
                 unreachable!()
@@ -386,6 +390,7 @@ impl Cell {
                 payload,
                 nonce,
                 expires_at,
+                trace_context,
                 ..
             } => {
                 async {
@@ -398,7 +403,10 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("call_remote"))
+                .instrument(continue_remote_trace(
+                    debug_span!("call_remote"),
+                    trace_context,
+                ))
                 .await;
             }
 
@@ -407,6 +415,7 @@ impl Cell {
                 respond,
                 dht_hash,
                 options,
+                trace_context,
                 ..
             } => {
                 async {
@@ -416,7 +425,10 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("cell_handle_get"))
+                .instrument(continue_remote_trace(
+                    debug_span!("cell_handle_get"),
+                    trace_context,
+                ))
                 .await;
             }
 
@@ -425,6 +437,7 @@ impl Cell {
                 respond,
                 dht_hash,
                 options,
+                trace_context,
                 ..
             } => {
                 async {
@@ -434,7 +447,10 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("cell_handle_get_meta"))
+                .instrument(continue_remote_trace(
+                    debug_span!("cell_handle_get_meta"),
+                    trace_context,
+                ))
                 .await;
             }
 
@@ -443,6 +459,7 @@ impl Cell {
                 respond,
                 link_key,
                 options,
+                trace_context,
                 ..
             } => {
                 async {
@@ -452,7 +469,32 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("cell_handle_get_links"))
+                .instrument(continue_remote_trace(
+                    debug_span!("cell_handle_get_links"),
+                    trace_context,
+                ))
+                .await;
+            }
+
+            CountLinks {
+                span_context: _,
+                respond,
+                link_key,
+                create_link_hashes,
+                trace_context,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_count_links(link_key, create_link_hashes)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(continue_remote_trace(
+                    debug_span!("cell_handle_count_links"),
+                    trace_context,
+                ))
                 .await;
             }
 
@@ -462,6 +504,7 @@ impl Cell {
                 agent,
                 query,
                 options,
+                trace_context,
                 ..
             } => {
                 async {
@@ -471,7 +514,10 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("cell_handle_get_agent_activity"))
+                .instrument(continue_remote_trace(
+                    debug_span!("cell_handle_get_agent_activity"),
+                    trace_context,
+                ))
                 .await;
             }
 
@@ -480,6 +526,7 @@ impl Cell {
                 respond,
                 author,
                 filter,
+                trace_context,
                 ..
             } => {
                 async {
@@ -489,7 +536,10 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("cell_handle_must_get_agent_activity"))
+                .instrument(continue_remote_trace(
+                    debug_span!("cell_handle_must_get_agent_activity"),
+                    trace_context,
+                ))
                 .await;
             }
 
@@ -636,14 +686,17 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let db = self.space.dht_db.clone();
+        authority::handle_get_meta(db.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -663,6 +716,19 @@ impl Cell {
             .map_err(Into::into)
     }
 
+    #[instrument(skip(self))]
+    /// a remote node is asking how many live links we hold on a base
+    async fn handle_count_links(
+        &self,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+    ) -> CellResult<CountLinksResponse> {
+        let db = self.space.dht_db.clone();
+        authority::handle_count_links(db.into(), link_key, create_link_hashes)
+            .await
+            .map_err(Into::into)
+    }
+
     #[instrument(skip(self, options))]
     async fn handle_get_agent_activity(
         &self,
@@ -951,6 +1017,37 @@ impl Cell {
         Ok(())
     }
 
+    /// Publish this cell's committed action for its countersigning session
+    /// to the entry authorities again, if it has an unresolved session.
+    pub(crate) async fn resume_countersigning_session(&self) -> CellResult<()> {
+        Ok(resume_countersigning_session(
+            &self.space,
+            &self.holochain_p2p_cell,
+            self.id.agent_pubkey().clone(),
+        )
+        .await
+        .map_err(Box::new)?)
+    }
+
+    /// Try to resolve this cell's countersigning session, or force it to be
+    /// resolved one way or the other.
+    /// Returns how the session was resolved, if it was.
+    pub(crate) async fn resolve_countersigning_session(
+        &self,
+        force: Option<CountersigningSessionResolution>,
+    ) -> CellResult<Option<CountersigningSessionResolution>> {
+        Ok(resolve_countersigning_session(
+            &self.space,
+            &self.holochain_p2p_cell,
+            self.id.agent_pubkey().clone(),
+            force,
+            self.queue_triggers.clone(),
+            self.conductor_api.signal_broadcaster(),
+        )
+        .await
+        .map_err(Box::new)?)
+    }
+
     /// Instantiate a Ribosome for use by this Cell's workflows
     pub(crate) fn get_ribosome(&self) -> CellResult<RealRibosome> {
         Ok(self
@@ -992,3 +1089,10 @@ impl std::fmt::Debug for Cell {
         f.debug_struct("Cell").field("id", &self.id()).finish()
     }
 }
+
+/// Make the span handling a request from a remote node part of
+/// the remote node's trace.
+pub(crate) fn continue_remote_trace(span: Span, trace_context: Vec<u8>) -> Span {
+    span.set_from_bytes(trace_context);
+    span
+}
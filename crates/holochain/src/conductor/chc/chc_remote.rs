@@ -2,6 +2,20 @@
 //!
 //! **NOTE** this API is not set in stone. Do not design a CHC against this API yet,
 //! as it will change!
+//!
+//! Every cell gets its own chain on the remote service, addressed by the path
+//! `/{dna_hash}/{agent_pubkey}` relative to the base URL given as the CHC namespace.
+//! All request and response bodies are msgpack-encoded.
+//!
+//! - `GET /head`: the hash of the last action, as `Option<ActionHash>`
+//! - `POST /add_actions`: append a `Vec<SignedActionHashed>`. Responds with status 409
+//!   and `(Option<u32>, String)` (the current head seq and the reason) if the actions
+//!   don't extend the current head.
+//! - `POST /add_entries`: store a `Vec<EntryHashed>`
+//! - `POST /get_actions_since_hash`: given an `Option<ActionHash>`, respond with the
+//!   `Vec<SignedActionHashed>` following it
+//! - `POST /get_entries`: given a `Vec<EntryHash>`, respond with a `HashMap<EntryHash, Entry>`.
+//!   Responds with status 404 and the `Vec<EntryHash>` which were not found if any are missing.
 
 use std::collections::{HashMap, HashSet};
 
@@ -10,7 +24,7 @@ use holo_hash::{ActionHash, EntryHash};
 use holochain_serialized_bytes::{decode, encode};
 use holochain_types::chc::{ChainHeadCoordinator, ChcError, ChcResult};
 use holochain_zome_types::prelude::*;
-use reqwest::Url;
+use reqwest::{StatusCode, Url};
 
 /// An HTTP client which can talk to a remote CHC implementation
 pub struct ChcRemote {
@@ -29,8 +43,14 @@ impl ChainHeadCoordinator for ChcRemote {
 
     async fn add_actions(&self, actions: Vec<Self::Item>) -> ChcResult<()> {
         let body = encode(&actions)?;
-        let _response = self.actions.post("/add_actions", body).await?;
-        Ok(())
+        match self.actions.post("/add_actions", body).await {
+            Ok(_) => Ok(()),
+            Err(ChcRemoteError::Status(StatusCode::CONFLICT, body)) => {
+                let (seq, reason): (Option<u32>, String) = decode(&body)?;
+                Err(ChcError::InvalidChain(seq, reason))
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 
     async fn add_entries(&self, entries: Vec<EntryHashed>) -> ChcResult<()> {
@@ -47,52 +67,259 @@ impl ChainHeadCoordinator for ChcRemote {
 
     async fn get_entries(
         &self,
-        _hashes: HashSet<&EntryHash>,
+        hashes: HashSet<&EntryHash>,
     ) -> ChcResult<HashMap<EntryHash, Entry>> {
-        todo!()
+        let hashes: Vec<&EntryHash> = hashes.into_iter().collect();
+        let body = encode(&hashes)?;
+        match self.entries.post("/get_entries", body).await {
+            Ok(response) => Ok(decode(&response)?),
+            Err(ChcRemoteError::Status(StatusCode::NOT_FOUND, body)) => {
+                let missing: Vec<EntryHash> = decode(&body)?;
+                Err(ChcError::MissingEntries(missing.into_iter().collect()))
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 }
 
 impl ChcRemote {
-    /// Constructor
-    pub fn new(_namespace: &str, _cell_id: &CellId) -> Self {
-        todo!("Implement remote CHC client")
+    /// Constructor. The namespace is the base URL of the remote CHC service.
+    pub fn new(namespace: &str, cell_id: &CellId) -> ChcResult<Self> {
+        let base_url = Url::parse(namespace)
+            .map_err(|e| ChcError::InvalidNamespace(namespace.to_string(), e.to_string()))?;
+        let client = ChcRemoteClient::new(base_url, cell_id);
+        Ok(Self {
+            actions: client.clone(),
+            entries: client,
+        })
     }
 }
 
 /// Client for a single CHC server
+#[derive(Clone)]
 pub struct ChcRemoteClient {
-    base_url: url::Url,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+/// A failed request to the remote CHC, before interpretation
+enum ChcRemoteError {
+    /// The service could not be reached, or the response could not be read
+    Unreachable(reqwest::Error),
+    /// The service responded with a non-success status code
+    Status(StatusCode, Bytes),
+}
+
+impl From<ChcRemoteError> for ChcError {
+    fn from(e: ChcRemoteError) -> Self {
+        match e {
+            ChcRemoteError::Unreachable(e) => ChcError::ServiceUnreachable(e.to_string()),
+            ChcRemoteError::Status(status, body) => ChcError::UnexpectedResponse(
+                status.as_u16(),
+                String::from_utf8_lossy(&body).into_owned(),
+            ),
+        }
+    }
 }
 
 impl ChcRemoteClient {
+    /// Constructor, for the chain of the given cell
+    pub fn new(base_url: Url, cell_id: &CellId) -> Self {
+        let base_url = format!(
+            "{}/{}/{}",
+            base_url.as_str().trim_end_matches('/'),
+            cell_id.dna_hash(),
+            cell_id.agent_pubkey()
+        );
+        Self {
+            base_url,
+            client: reqwest::Client::new(),
+        }
+    }
+
     fn url(&self, path: &str) -> Url {
-        assert!(path.chars().nth(0) == Some('/'));
+        assert!(path.starts_with('/'));
         Url::parse(&format!("{}{}", self.base_url, path)).expect("invalid URL")
     }
 
-    async fn get(&self, path: &str) -> ChcResult<Bytes> {
-        let bytes = reqwest::get(self.url(path))
-            .await
-            .map_err(extract_string)?
-            .bytes()
+    async fn get(&self, path: &str) -> Result<Bytes, ChcRemoteError> {
+        let response = self
+            .client
+            .get(self.url(path))
+            .send()
             .await
-            .map_err(extract_string)?;
-        Ok(bytes)
+            .map_err(ChcRemoteError::Unreachable)?;
+        Self::extract_body(response).await
     }
 
-    async fn post(&self, path: &str, body: Vec<u8>) -> ChcResult<Bytes> {
-        let client = reqwest::Client::new();
-        let response = client
+    async fn post(&self, path: &str, body: Vec<u8>) -> Result<Bytes, ChcRemoteError> {
+        let response = self
+            .client
             .post(self.url(path))
             .body(body)
             .send()
             .await
-            .map_err(extract_string)?;
-        Ok(response.bytes().await.map_err(extract_string)?)
+            .map_err(ChcRemoteError::Unreachable)?;
+        Self::extract_body(response).await
+    }
+
+    async fn extract_body(response: reqwest::Response) -> Result<Bytes, ChcRemoteError> {
+        let status = response.status();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(ChcRemoteError::Unreachable)?;
+        if status.is_success() {
+            Ok(bytes)
+        } else {
+            Err(ChcRemoteError::Status(status, bytes))
+        }
     }
 }
 
-fn extract_string(e: reqwest::Error) -> ChcError {
-    ChcError::ServiceUnreachable(e.to_string())
+#[cfg(test)]
+mod tests {
+    use holo_hash::HasHash;
+    use holochain_conductor_api::conductor::ConductorConfig;
+    use holochain_types::chc::ChainHeadCoordinatorExt;
+
+    use crate::conductor::chc::run_chc_server;
+    use crate::sweettest::*;
+
+    use super::*;
+
+    use ::fixt::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remote_chc_multi_conductor() {
+        use holochain::test_utils::inline_zomes::{simple_crud_zome, AppString};
+
+        let (driver, addr, shutdown) = run_chc_server(([127, 0, 0, 1], 0), None).await.unwrap();
+        tokio::spawn(driver);
+        let url = format!("http://{}", addr);
+
+        let config = ConductorConfig {
+            chc_namespace: Some(url.clone()),
+            ..Default::default()
+        };
+        let mut conductors =
+            SweetConductorBatch::from_configs([config.clone(), config.clone()]).await;
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+        let (agent, _) = SweetAgents::alice_and_bob();
+
+        let (c0,) = conductors[0]
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let cell_id = c0.cell_id().clone();
+        let chc = ChcRemote::new(&url, &cell_id).unwrap();
+
+        let records = chc.get_record_data(None).await.unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            chc.head().await.unwrap().as_ref(),
+            Some(records[2].action_address())
+        );
+
+        // Genesis on another device for the same agent conflicts with the CHC
+        let install_result = conductors[1]
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await;
+        assert_eq!(
+            format!("{:?}", install_result),
+            r#"Err(ConductorError(GenesisFailed { errors: [ConductorApiError(WorkflowError(SourceChainError(ChcHeadMoved("genesis", InvalidChain(Some(2), "Action is not the first, so needs previous action")))))] }))"#
+        );
+
+        conductors[1]
+            .raw_handle()
+            .chc_sync(cell_id.clone(), None)
+            .await
+            .unwrap();
+        conductors[1]
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await
+            .unwrap();
+        let c1: SweetCell = conductors[1].get_sweet_cell(cell_id.clone()).unwrap();
+
+        let _: ActionHash = conductors[0]
+            .call(
+                &c0.zome(SweetInlineZomes::COORDINATOR),
+                "create_string",
+                AppString::new("zero"),
+            )
+            .await;
+
+        // The second device is now behind, so its write is rejected
+        let stale: Result<ActionHash, _> = conductors[1]
+            .call_fallible(
+                &c1.zome(SweetInlineZomes::COORDINATOR),
+                "create_string",
+                AppString::new("one"),
+            )
+            .await;
+        assert!(format!("{:?}", stale).contains("ChcHeadMoved"));
+
+        conductors[1]
+            .raw_handle()
+            .chc_sync(cell_id.clone(), None)
+            .await
+            .unwrap();
+
+        let dump0 = conductors[0]
+            .dump_full_cell_state(&cell_id, None)
+            .await
+            .unwrap();
+        let dump1 = conductors[1]
+            .dump_full_cell_state(&cell_id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            dump0.source_chain_dump.records,
+            dump1.source_chain_dump.records
+        );
+        assert_eq!(chc.get_record_data(None).await.unwrap().len(), 5);
+
+        shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remote_chc_missing_entries() {
+        let (driver, addr, shutdown) = run_chc_server(([127, 0, 0, 1], 0), None).await.unwrap();
+        tokio::spawn(driver);
+
+        let chc = ChcRemote::new(&format!("http://{}", addr), &fixt!(CellId)).unwrap();
+        assert_eq!(chc.head().await.unwrap(), None);
+
+        let present = EntryHashed::from_content_sync(fixt!(Entry));
+        let missing = fixt!(EntryHash);
+        chc.add_entries(vec![present.clone()]).await.unwrap();
+
+        let entries = chc
+            .get_entries([present.as_hash()].into_iter().collect())
+            .await
+            .unwrap();
+        assert_eq!(entries.get(present.as_hash()), Some(present.as_content()));
+
+        match chc
+            .get_entries([present.as_hash(), &missing].into_iter().collect())
+            .await
+        {
+            Err(ChcError::MissingEntries(hashes)) => {
+                assert_eq!(hashes, [missing].into_iter().collect::<HashSet<_>>())
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        shutdown();
+    }
+
+    #[test]
+    fn invalid_namespace_is_an_error() {
+        assert!(matches!(
+            ChcRemote::new("not a url", &fixt!(CellId)),
+            Err(ChcError::InvalidNamespace(_, _))
+        ));
+    }
 }
@@ -0,0 +1,351 @@
+//! A reference implementation of a remote CHC server, backed by SQLite.
+//!
+//! This speaks the HTTP API described in [`super::chc_remote`], and exists so that
+//! multi-device source chain coordination can be exercised locally and in tests.
+//! It is not intended for production use: in particular it performs no
+//! authentication, so anybody who can reach it can write to any chain.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use holochain_serialized_bytes::{decode, encode};
+use holochain_types::prelude::*;
+use rusqlite::{named_params, Connection, OptionalExtension};
+use warp::{http::StatusCode, hyper::body::Bytes, Filter};
+
+use crate::core::validate_chain;
+
+/// No single request body may be larger than this.
+const SIZE_LIMIT: u64 = 16 * 1024 * 1024;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS Action (
+    cell TEXT NOT NULL,
+    seq  INTEGER NOT NULL,
+    hash BLOB NOT NULL,
+    blob BLOB NOT NULL,
+    PRIMARY KEY (cell, seq)
+);
+CREATE UNIQUE INDEX IF NOT EXISTS Action_hash_idx ON Action (cell, hash);
+CREATE TABLE IF NOT EXISTS Entry (
+    cell TEXT NOT NULL,
+    hash BLOB NOT NULL,
+    blob BLOB NOT NULL,
+    PRIMARY KEY (cell, hash)
+);
+";
+
+/// Future which drives the server until shutdown
+pub type ChcServerDriver = futures::future::BoxFuture<'static, ()>;
+
+/// Call this to gracefully shut down the server
+pub type ChcServerShutdown = Box<dyn FnOnce() + 'static + Send + Sync>;
+
+/// Run a CHC server bound to the given address.
+///
+/// If `db_path` is `None`, the chains are kept in an in-memory database
+/// and are lost when the server shuts down.
+pub async fn run_chc_server(
+    addr: impl Into<SocketAddr> + 'static,
+    db_path: Option<&Path>,
+) -> Result<(ChcServerDriver, SocketAddr, ChcServerShutdown), String> {
+    let store = ChcStore::open(db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+
+    let head = warp::get()
+        .and(warp::path!(String / String / "head"))
+        .and(with_store(store.clone()))
+        .map(|dna, agent, store: ChcStore| {
+            respond(
+                store
+                    .head(&cell_key(dna, agent))
+                    .map(|head| ok_reply(&head.map(|(hash, _)| hash))),
+            )
+        });
+    let add_actions = post_route("add_actions", store.clone(), |store, cell, body| {
+        let actions: Vec<SignedActionHashed> = decode(&body)?;
+        match store.add_actions(&cell, actions)? {
+            Ok(()) => Ok(ok_reply(&())),
+            Err(conflict) => Ok(reply_with_status(&conflict, StatusCode::CONFLICT)),
+        }
+    });
+    let add_entries = post_route("add_entries", store.clone(), |store, cell, body| {
+        let entries: Vec<EntryHashed> = decode(&body)?;
+        store.add_entries(&cell, entries)?;
+        Ok(ok_reply(&()))
+    });
+    let get_actions_since_hash = post_route(
+        "get_actions_since_hash",
+        store.clone(),
+        |store, cell, body| {
+            let hash: Option<ActionHash> = decode(&body)?;
+            Ok(ok_reply(&store.get_actions_since_hash(&cell, hash)?))
+        },
+    );
+    let get_entries = post_route("get_entries", store, |store, cell, body| {
+        let hashes: Vec<EntryHash> = decode(&body)?;
+        match store.get_entries(&cell, hashes)? {
+            Ok(entries) => Ok(ok_reply(&entries)),
+            Err(missing) => Ok(reply_with_status(&missing, StatusCode::NOT_FOUND)),
+        }
+    });
+
+    let routes = head
+        .or(add_actions)
+        .or(add_entries)
+        .or(get_actions_since_hash)
+        .or(get_entries);
+
+    let (s, r) = tokio::sync::oneshot::channel();
+    let shutdown = Box::new(move || {
+        let _ = s.send(());
+    });
+
+    match warp::serve(routes).try_bind_with_graceful_shutdown(addr, async move {
+        let _ = r.await;
+    }) {
+        Ok((addr, server)) => {
+            let driver = futures::future::FutureExt::boxed(server);
+            Ok((driver, addr, shutdown))
+        }
+        Err(e) => Err(format!("Failed to bind socket: {:?}", e)),
+    }
+}
+
+type Reply = warp::reply::WithStatus<Vec<u8>>;
+
+fn post_route<F>(
+    name: &'static str,
+    store: ChcStore,
+    handler: F,
+) -> impl Filter<Extract = (Reply,), Error = warp::Rejection> + Clone
+where
+    F: Fn(ChcStore, String, Bytes) -> ChcServerResult<Reply> + Clone + Send + Sync + 'static,
+{
+    warp::post()
+        .and(warp::path!(String / String / String).and_then(
+            move |dna, agent, path: String| async move {
+                if path == name {
+                    Ok(cell_key(dna, agent))
+                } else {
+                    Err(warp::reject::not_found())
+                }
+            },
+        ))
+        .and(warp::body::content_length_limit(SIZE_LIMIT))
+        .and(warp::body::bytes())
+        .and(with_store(store))
+        .map(move |cell, body, store| respond(handler(store, cell, body)))
+}
+
+fn with_store(
+    store: ChcStore,
+) -> impl Filter<Extract = (ChcStore,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || store.clone())
+}
+
+fn cell_key(dna: String, agent: String) -> String {
+    format!("{}/{}", dna, agent)
+}
+
+fn ok_reply<T: serde::Serialize + std::fmt::Debug>(data: &T) -> Reply {
+    reply_with_status(data, StatusCode::OK)
+}
+
+fn reply_with_status<T: serde::Serialize + std::fmt::Debug>(data: &T, status: StatusCode) -> Reply {
+    match encode(data) {
+        Ok(body) => warp::reply::with_status(body, status),
+        Err(e) => internal_error(e.into()),
+    }
+}
+
+fn internal_error(e: ChcServerError) -> Reply {
+    tracing::error!(?e, "CHC server error");
+    warp::reply::with_status(
+        e.to_string().into_bytes(),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+fn respond(result: ChcServerResult<Reply>) -> Reply {
+    match result {
+        Ok(reply) => reply,
+        Err(e @ ChcServerError::Serialization(_)) => {
+            warp::reply::with_status(e.to_string().into_bytes(), StatusCode::BAD_REQUEST)
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Errors produced while handling a CHC server request
+#[derive(Debug, thiserror::Error)]
+pub enum ChcServerError {
+    /// The request body could not be decoded, or the response encoded
+    #[error(transparent)]
+    Serialization(#[from] SerializedBytesError),
+
+    /// The database could not be accessed
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+}
+
+/// Result type for [`ChcServerError`]
+pub type ChcServerResult<T> = Result<T, ChcServerError>;
+
+/// The SQLite-backed storage behind the CHC server.
+///
+/// Each cell's chain is stored under a key made of its DNA hash and agent key.
+#[derive(Clone)]
+pub struct ChcStore(Arc<parking_lot::Mutex<Connection>>);
+
+impl ChcStore {
+    /// Open (or create) the database at the given path, or an in-memory
+    /// database if no path is given.
+    pub fn open(path: Option<&Path>) -> rusqlite::Result<Self> {
+        let conn = match path {
+            Some(path) => Connection::open(path)?,
+            None => Connection::open_in_memory()?,
+        };
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self(Arc::new(parking_lot::Mutex::new(conn))))
+    }
+
+    /// The hash and sequence number of the last action in the chain.
+    pub fn head(&self, cell: &str) -> ChcServerResult<Option<(ActionHash, u32)>> {
+        head(&self.0.lock(), cell)
+    }
+
+    /// Append actions to the chain. If the actions do not form a valid
+    /// continuation of the stored chain, nothing is written and the current
+    /// head sequence number is returned along with the reason.
+    pub fn add_actions(
+        &self,
+        cell: &str,
+        actions: Vec<SignedActionHashed>,
+    ) -> ChcServerResult<Result<(), (Option<u32>, String)>> {
+        let mut conn = self.0.lock();
+        let txn = conn.transaction()?;
+        let head = head(&txn, cell)?;
+        if let Err(e) = validate_chain(actions.iter(), &head) {
+            return Ok(Err((head.map(|(_, seq)| seq), e.to_string())));
+        }
+        for action in actions {
+            txn.execute(
+                "INSERT INTO Action (cell, seq, hash, blob) VALUES (:cell, :seq, :hash, :blob)",
+                named_params! {
+                    ":cell": cell,
+                    ":seq": action.seq(),
+                    ":hash": action.as_hash().get_raw_39(),
+                    ":blob": encode(&action)?,
+                },
+            )?;
+        }
+        txn.commit()?;
+        Ok(Ok(()))
+    }
+
+    /// Store entries. Entries which are already present are ignored.
+    pub fn add_entries(&self, cell: &str, entries: Vec<EntryHashed>) -> ChcServerResult<()> {
+        let mut conn = self.0.lock();
+        let txn = conn.transaction()?;
+        for entry in entries {
+            txn.execute(
+                "INSERT OR IGNORE INTO Entry (cell, hash, blob) VALUES (:cell, :hash, :blob)",
+                named_params! {
+                    ":cell": cell,
+                    ":hash": entry.as_hash().get_raw_39(),
+                    ":blob": encode(entry.as_content())?,
+                },
+            )?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Get all actions after the given hash. If the hash is not found, or not
+    /// given, the entire chain is returned.
+    pub fn get_actions_since_hash(
+        &self,
+        cell: &str,
+        hash: Option<ActionHash>,
+    ) -> ChcServerResult<Vec<SignedActionHashed>> {
+        let conn = self.0.lock();
+        let since_seq: Option<u32> = match hash {
+            Some(hash) => conn
+                .query_row(
+                    "SELECT seq FROM Action WHERE cell = :cell AND hash = :hash",
+                    named_params! {
+                        ":cell": cell,
+                        ":hash": hash.get_raw_39(),
+                    },
+                    |row| row.get(0),
+                )
+                .optional()?,
+            None => None,
+        };
+        let mut stmt = conn.prepare(
+            "SELECT blob FROM Action WHERE cell = :cell AND seq > :seq ORDER BY seq ASC",
+        )?;
+        let blobs = stmt
+            .query_map(
+                named_params! {
+                    ":cell": cell,
+                    ":seq": since_seq.map(i64::from).unwrap_or(-1),
+                },
+                |row| row.get::<_, Vec<u8>>(0),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(blobs
+            .into_iter()
+            .map(|blob| decode(&blob))
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// Get the entries with the given hashes, or the list of hashes which
+    /// could not be found.
+    pub fn get_entries(
+        &self,
+        cell: &str,
+        hashes: Vec<EntryHash>,
+    ) -> ChcServerResult<Result<HashMap<EntryHash, Entry>, Vec<EntryHash>>> {
+        let conn = self.0.lock();
+        let mut stmt =
+            conn.prepare("SELECT blob FROM Entry WHERE cell = :cell AND hash = :hash")?;
+        let mut entries = HashMap::new();
+        let mut missing = vec![];
+        for hash in hashes {
+            let blob: Option<Vec<u8>> = stmt
+                .query_row(
+                    named_params! {
+                        ":cell": cell,
+                        ":hash": hash.get_raw_39(),
+                    },
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match blob {
+                Some(blob) => {
+                    entries.insert(hash, decode(&blob)?);
+                }
+                None => missing.push(hash),
+            }
+        }
+        if missing.is_empty() {
+            Ok(Ok(entries))
+        } else {
+            Ok(Err(missing))
+        }
+    }
+}
+
+fn head(conn: &Connection, cell: &str) -> ChcServerResult<Option<(ActionHash, u32)>> {
+    Ok(conn
+        .query_row(
+            "SELECT hash, seq FROM Action WHERE cell = :cell ORDER BY seq DESC LIMIT 1",
+            named_params! { ":cell": cell },
+            |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .map(|(hash, seq)| (ActionHash::from_raw_39_panicky(hash), seq)))
+}
@@ -32,11 +32,13 @@
 pub use self::share::RwShare;
 use super::api::RealAppInterfaceApi;
 use super::api::ZomeCall;
+use super::app_auth_token_store::AppAuthTokenStore;
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::interface::error::InterfaceResult;
+use super::interface::websocket::listener_port;
 use super::interface::websocket::spawn_admin_interface_tasks;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
@@ -67,6 +69,8 @@ use crate::core::queue_consumer::QueueConsumerMap;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CHANNEL_BOUND;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CONCURRENT_LIMIT;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsHostAccess;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
 use crate::core::ribosome::RibosomeT;
 use crate::core::workflow::ZomeCallResult;
 use crate::{
@@ -79,13 +83,17 @@ use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
 use holochain_conductor_api::conductor::KeystoreConfig;
+use holochain_conductor_api::AllowedOrigins;
+use holochain_conductor_api::AppAuthenticationTokenIssued;
 use holochain_conductor_api::AppInfo;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::ClonedCell;
 use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::IntegrationStateDump;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::ProvisionedCell;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::lair_keystore::spawn_lair_keystore_in_proc;
 use holochain_keystore::test_keystore::spawn_test_keystore;
@@ -104,6 +112,7 @@ use holochain_state::prelude::StateQueryResult;
 use holochain_state::prelude::*;
 use holochain_state::source_chain;
 use holochain_types::prelude::{test_keystore, wasm, *};
+use holochain_websocket::WebsocketConfig;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use rusqlite::Transaction;
@@ -133,6 +142,7 @@ pub use app_impls::*;
 pub use app_status_impls::*;
 pub use cell_impls::*;
 pub use clone_cell_impls::*;
+pub use countersigning_impls::*;
 pub use dna_impls::*;
 pub use interface_impls::*;
 pub use misc_impls::*;
@@ -143,6 +153,8 @@ pub use state_impls::*;
 
 mod graft_records_onto_source_chain;
 
+mod migrate_cell;
+
 /// A list of Cells which failed to start, and why
 pub type CellStartupErrors = Vec<(CellId, CellError)>;
 
@@ -201,6 +213,9 @@ pub struct Conductor {
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
+    /// The tokens issued for authenticating connections to app interfaces
+    app_auth_token_store: RwShare<AppAuthTokenStore>,
+
     /// The interface to the task manager
     task_manager: TaskManagerClient,
 
@@ -234,8 +249,6 @@ impl Conductor {
 
 /// Methods related to conductor startup/shutdown
 mod startup_shutdown_impls {
-    use kitsune_p2p_types::box_fut_plain;
-
     use crate::conductor::manager::{spawn_task_outcome_handler, OutcomeReceiver, OutcomeSender};
 
     use super::*;
@@ -260,6 +273,7 @@ mod startup_shutdown_impls {
                 config,
                 shutting_down: Arc::new(AtomicBool::new(false)),
                 app_interfaces: RwShare::new(HashMap::new()),
+                app_auth_token_store: RwShare::new(AppAuthTokenStore::default()),
                 task_manager: TaskManagerClient::new(outcome_sender),
                 // Must be initialized later, since it requires an Arc<Conductor>
                 outcomes_task: RwShare::new(None),
@@ -313,6 +327,7 @@ mod startup_shutdown_impls {
             self: Arc<Self>,
             outcome_rx: OutcomeReceiver,
             admin_configs: Vec<AdminInterfaceConfig>,
+            service_dnas: ConductorServiceDnas,
         ) -> ConductorResult<CellStartupErrors> {
             self.load_dnas().await?;
 
@@ -325,20 +340,7 @@ mod startup_shutdown_impls {
                 *lock = Some(task);
             });
 
-            self.services.share_mut(|services| {
-                let mut dpki = MockDpkiService::new();
-                dpki.expect_is_key_valid()
-                    .returning(|_, _| box_fut_plain(Ok(true)));
-                dpki.expect_key_mutation()
-                    .returning(|_, _| box_fut_plain(Ok(())));
-
-                let app_store = MockAppStoreService::new();
-
-                *services = Some(ConductorServices {
-                    dpki: Arc::new(dpki),
-                    app_store: Arc::new(app_store),
-                });
-            });
+            self.clone().initialize_services(service_dnas).await?;
 
             self.clone().add_admin_interfaces(admin_configs).await?;
             self.clone().startup_app_interfaces().await?;
@@ -349,6 +351,140 @@ mod startup_shutdown_impls {
 
             self.process_app_status_fx(AppStatusFx::SpinUp, None).await
         }
+
+        /// Set up the conductor services.
+        ///
+        /// If DPKI or the app store are configured, either through the conductor
+        /// config or by passing the DNA directly, their apps are installed and
+        /// enabled the first time the conductor starts up. Their cells are spun up
+        /// along with all other apps.
+        async fn initialize_services(
+            self: Arc<Self>,
+            service_dnas: ConductorServiceDnas,
+        ) -> ConductorResult<()> {
+            // Genesis of the DPKI cell must not itself depend on DPKI
+            self.services.share_mut(|services| {
+                *services = Some(ConductorServices {
+                    dpki: None,
+                    app_store: None,
+                });
+            });
+
+            let ConductorServiceDnas { dpki, app_store } = service_dnas;
+            let dpki_dna = match dpki {
+                Some(dna) => Some(dna),
+                None => match &self.config.dpki {
+                    Some(config) => {
+                        Some(load_service_dna(&config.dna_path, config.network_seed.clone()).await?)
+                    }
+                    None => None,
+                },
+            };
+            let app_store_dna = match app_store {
+                Some(dna) => Some(dna),
+                None => match &self.config.app_store {
+                    Some(config) => {
+                        Some(load_service_dna(&config.dna_path, config.network_seed.clone()).await?)
+                    }
+                    None => None,
+                },
+            };
+
+            let mut cell_ids = ConductorServiceCells {
+                dpki: None,
+                app_store: None,
+            };
+
+            if let Some(dna) = dpki_dna {
+                cell_ids.dpki = Some(
+                    self.clone()
+                        .install_service_app(DPKI_APP_ID, DPKI_ROLE_NAME, dna)
+                        .await?,
+                );
+                // Set DPKI up before installing the app store, so that
+                // the app store's agent key gets registered with it
+                self.services.share_mut(|services| {
+                    *services = Some(ConductorServices::builtin(
+                        self.clone(),
+                        ConductorServiceCells {
+                            dpki: cell_ids.dpki.clone(),
+                            app_store: None,
+                        },
+                    ));
+                });
+            }
+
+            if let Some(dna) = app_store_dna {
+                cell_ids.app_store = Some(
+                    self.clone()
+                        .install_service_app(APP_STORE_APP_ID, APP_STORE_ROLE_NAME, dna)
+                        .await?,
+                );
+            }
+
+            self.services.share_mut(|services| {
+                *services = Some(ConductorServices::builtin(self.clone(), cell_ids));
+            });
+
+            Ok(())
+        }
+
+        /// Install the app for a conductor service with a fresh agent key, unless
+        /// it is already installed. Returns the CellId of the service cell.
+        async fn install_service_app(
+            self: Arc<Self>,
+            installed_app_id: &str,
+            role_name: &str,
+            dna: DnaFile,
+        ) -> ConductorResult<CellId> {
+            let dna_hash = dna.dna_hash().clone();
+            if !self.list_dnas().contains(&dna_hash) {
+                self.register_dna(dna).await?;
+            }
+
+            let installed_app_id: InstalledAppId = installed_app_id.into();
+            let state = self.get_state().await?;
+            if let Ok(app) = state.get_app(&installed_app_id) {
+                return app
+                    .all_cells()
+                    .find(|cell_id| *cell_id.dna_hash() == dna_hash)
+                    .cloned()
+                    .ok_or_else(|| {
+                        ConductorError::other(format!(
+                            "The installed app {} does not contain a cell for the configured DNA {}",
+                            installed_app_id, dna_hash
+                        ))
+                    });
+            }
+
+            let agent_key = self.keystore().new_sign_keypair_random().await?;
+            let cell_id = CellId::new(dna_hash, agent_key);
+            self.clone()
+                .install_app_legacy(
+                    installed_app_id.clone(),
+                    vec![(InstalledCell::new(cell_id.clone(), role_name.into()), None)],
+                )
+                .await?;
+            // The cell is spun up along with all other enabled apps
+            let _ = self
+                .transition_app_status(installed_app_id, AppStatusTransition::Enable)
+                .await?;
+            Ok(cell_id)
+        }
+    }
+
+    /// Load the DNA for a conductor service from a bundle file
+    async fn load_service_dna(
+        path: &std::path::Path,
+        network_seed: Option<String>,
+    ) -> ConductorResult<DnaFile> {
+        let bundle = DnaBundle::read_from_file(path).await?;
+        let modifiers = DnaModifiersOpt {
+            network_seed,
+            ..DnaModifiersOpt::none()
+        };
+        let (dna, _) = bundle.into_dna_file(modifiers).await?;
+        Ok(dna)
     }
 }
 
@@ -372,22 +508,17 @@ mod interface_impls {
                 let admin_api = admin_api.clone();
                 let tm = tm.clone();
                 async move {
-                    match driver {
-                        InterfaceDriver::Websocket { port } => {
-                            let (listener_handle, listener) =
-                                spawn_websocket_listener(port).await?;
-                            let port = listener_handle.local_addr().port().unwrap_or(port);
-                            spawn_admin_interface_tasks(
-                                tm.clone(),
-                                listener_handle,
-                                listener,
-                                admin_api.clone(),
-                                port,
-                            );
-
-                            InterfaceResult::Ok(port)
-                        }
-                    }
+                    let (listener_handle, listener) =
+                        spawn_websocket_listener(&driver, WebsocketConfig::default()).await?;
+                    let port = listener_port(&driver, &listener_handle);
+                    spawn_admin_interface_tasks(
+                        tm.clone(),
+                        listener_handle,
+                        listener,
+                        admin_api.clone(),
+                    );
+
+                    InterfaceResult::Ok(port)
                 }
             };
 
@@ -401,27 +532,44 @@ mod interface_impls {
             // Exit if the admin interfaces fail to be created
             let ports = ports.map_err(Box::new)?;
 
-            for p in ports {
+            for p in ports.into_iter().flatten() {
                 self.add_admin_port(p);
             }
             Ok(())
         }
 
         /// Spawn a new app interface task, register it with the TaskManager,
-        /// and modify the conductor accordingly, based on the config passed in
-        /// which is just a networking port number (or 0 to auto-select one).
-        /// Returns the given or auto-chosen port number if giving an Ok Result
+        /// and modify the conductor accordingly, based on the driver passed in.
+        /// A websocket port of 0 lets the OS choose a free port.
+        /// Returns the given or auto-chosen port number if giving an Ok Result,
+        /// or `None` for interfaces which don't listen on a port.
+        ///
+        /// `interface_id` is only given when restarting an interface from the state.
+        ///
+        /// Only browsers from the `allowed_origins` can connect to the interface,
+        /// and if `installed_app_id` is set then connections can only access that app.
         pub async fn add_app_interface(
             self: Arc<Self>,
-            port: either::Either<u16, AppInterfaceId>,
-        ) -> ConductorResult<u16> {
-            let interface_id = match port {
-                either::Either::Left(port) => AppInterfaceId::new(port),
-                either::Either::Right(id) => id,
+            driver: InterfaceDriver,
+            interface_id: Option<AppInterfaceId>,
+            allowed_origins: AllowedOrigins,
+            installed_app_id: Option<InstalledAppId>,
+        ) -> ConductorResult<Option<u16>> {
+            let interface_id = match interface_id {
+                None => {
+                    if let Some(installed_app_id) = &installed_app_id {
+                        // Check the app exists
+                        self.get_state().await?.get_app(installed_app_id)?;
+                    }
+                    AppInterfaceId::new(driver.port().unwrap_or(0))
+                }
+                Some(id) => id,
             };
-            let port = interface_id.port();
-            tracing::debug!("Attaching interface {}", port);
-            let app_api = RealAppInterfaceApi::new(self.clone());
+            tracing::debug!("Attaching interface {:?}", driver);
+            let mut app_api = RealAppInterfaceApi::new(self.clone());
+            if let Some(installed_app_id) = &installed_app_id {
+                app_api = app_api.restrict_to_app(installed_app_id.clone());
+            }
             // This receiver is thrown away because we can produce infinite new
             // receivers from the Sender
             let (signal_tx, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
@@ -429,9 +577,15 @@ mod interface_impls {
             let tm = self.task_manager();
 
             // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
-            let port = spawn_app_interface_task(tm.clone(), port, app_api, signal_tx.clone())
-                .await
-                .map_err(Box::new)?;
+            let port = spawn_app_interface_task(
+                tm.clone(),
+                &driver,
+                allowed_origins.clone(),
+                app_api,
+                signal_tx.clone(),
+            )
+            .await
+            .map_err(Box::new)?;
             let interface = AppInterfaceRuntime::Websocket { signal_tx };
 
             self.app_interfaces.share_mut(|app_interfaces| {
@@ -444,13 +598,20 @@ mod interface_impls {
                 app_interfaces.insert(interface_id.clone(), interface);
                 Ok(())
             })?;
-            let config = AppInterfaceConfig::websocket(port);
+            // Store the port that was actually bound
+            let driver = match (driver, port) {
+                (InterfaceDriver::Websocket { tls, .. }, Some(port)) => {
+                    InterfaceDriver::Websocket { port, tls }
+                }
+                (driver, _) => driver,
+            };
+            let config = AppInterfaceConfig::websocket(driver, allowed_origins, installed_app_id);
             self.update_state(|mut state| {
                 state.app_interfaces.insert(interface_id, config);
                 Ok(state)
             })
             .await?;
-            tracing::debug!("App interface added at port: {}", port);
+            tracing::debug!("App interface added at port: {:?}", port);
             Ok(port)
         }
 
@@ -467,17 +628,67 @@ mod interface_impls {
                 .await?
                 .app_interfaces
                 .values()
-                .map(|config| config.driver.port())
+                .filter_map(|config| config.driver.port())
                 .collect())
         }
 
+        /// Issue a token for authenticating a connection to an app interface
+        pub async fn issue_app_authentication_token(
+            &self,
+            payload: IssueAppAuthenticationTokenPayload,
+        ) -> ConductorResult<AppAuthenticationTokenIssued> {
+            let IssueAppAuthenticationTokenPayload {
+                installed_app_id,
+                expiry_seconds,
+                single_use,
+            } = payload;
+            // Check the app exists
+            self.get_state().await?.get_app(&installed_app_id)?;
+            let expiry =
+                (expiry_seconds > 0).then_some(std::time::Duration::from_secs(expiry_seconds));
+            let (token, expires_at) = self
+                .app_auth_token_store
+                .share_mut(|store| store.issue_token(installed_app_id, expiry, single_use));
+            Ok(AppAuthenticationTokenIssued { token, expires_at })
+        }
+
+        /// Get the app which a token was issued for, if the token is still valid.
+        /// Single use tokens can't be used again.
+        pub fn authenticate_app_token(&self, token: &[u8]) -> Option<InstalledAppId> {
+            self.app_auth_token_store
+                .share_mut(|store| store.authenticate_token(token))
+        }
+
+        /// Make a token invalid for authenticating any more connections
+        pub fn revoke_app_authentication_token(&self, token: &[u8]) {
+            self.app_auth_token_store
+                .share_mut(|store| store.revoke_token(token));
+        }
+
         /// Start all app interfaces currently in state.
         /// This should only be run at conductor initialization.
         #[allow(irrefutable_let_patterns)]
         pub(crate) async fn startup_app_interfaces(self: Arc<Self>) -> ConductorResult<()> {
-            for id in self.get_state().await?.app_interfaces.keys().cloned() {
+            for (id, config) in self.get_state().await?.app_interfaces {
                 tracing::debug!("Starting up app interface: {:?}", id);
-                let _ = self.clone().add_app_interface(either::Right(id)).await?;
+                // Websockets listen on the port originally asked for again,
+                // which may be 0 to let the OS choose a free port.
+                let driver = match config.driver {
+                    InterfaceDriver::Websocket { tls, .. } => InterfaceDriver::Websocket {
+                        port: id.port(),
+                        tls,
+                    },
+                    driver => driver,
+                };
+                let _ = self
+                    .clone()
+                    .add_app_interface(
+                        driver,
+                        Some(id),
+                        config.allowed_origins,
+                        config.installed_app_id,
+                    )
+                    .await?;
             }
             Ok(())
         }
@@ -517,7 +728,8 @@ mod dna_impls {
             app: &InstalledApp,
         ) -> ConductorResult<HashMap<CellId, DnaDefHashed>> {
             let mut dna_defs = HashMap::new();
-            for cell_id in app.all_cells() {
+            let deferred_cells = app.deferred_cells().map(|(_, cell_id)| cell_id);
+            for cell_id in app.all_cells().chain(deferred_cells) {
                 let ribosome = self.get_ribosome(cell_id.dna_hash())?;
                 let dna_def = ribosome.dna_def();
                 dna_defs.insert(cell_id.to_owned(), dna_def.to_owned());
@@ -730,6 +942,8 @@ mod dna_impls {
         /// Install a [`DnaFile`](holochain_types::dna::DnaFile) in this Conductor
         pub async fn register_dna(&self, dna: DnaFile) -> ConductorResult<()> {
             let ribosome = RealRibosome::new(dna)?;
+            // Refuse DNAs which declare rate limits that can't be enforced
+            ribosome.run_rate_limits(RateLimitsHostAccess, RateLimitsInvocation)?;
             let entry_defs = self.register_dna_wasm(ribosome.clone()).await?;
             self.register_dna_entry_defs(entry_defs);
             self.add_ribosome_to_store(ribosome);
@@ -744,7 +958,9 @@ mod network_impls {
     use holochain_p2p::HolochainP2pSender;
     use holochain_sqlite::stats::{get_size_on_disk, get_used_size};
     use holochain_zome_types::block::Block;
+    use holochain_zome_types::block::BlockTarget;
     use holochain_zome_types::block::BlockTargetId;
+    use holochain_zome_types::block::CellBlockReason;
     use kitsune_p2p::KitsuneAgent;
     use kitsune_p2p::KitsuneBinType;
     use rusqlite::params;
@@ -752,6 +968,7 @@ mod network_impls {
     use crate::conductor::api::error::{
         zome_call_response_to_conductor_api_result, ConductorApiError,
     };
+    use crate::core::validation::{check_warrant, WarrantCheck};
 
     use super::*;
 
@@ -798,6 +1015,85 @@ mod network_impls {
             .await?)
         }
 
+        /// Handle a warrant published to us by another agent.
+        ///
+        /// The warranted action is validated locally, and the warrant is only
+        /// held if the action turns out to be invalid. A warrant against a valid
+        /// action is dropped and its author is blocked instead.
+        pub async fn handle_publish_warrant(
+            &self,
+            dna_hash: &DnaHash,
+            warrant: SignedWarrant,
+        ) -> ConductorResult<()> {
+            if !warrant.is_valid().await {
+                tracing::warn!(?warrant, "Ignoring a warrant with invalid signatures");
+                return Ok(());
+            }
+            let network = self.holochain_p2p.to_dna(dna_hash.clone(), None);
+            match check_warrant(self, network, &warrant).await? {
+                WarrantCheck::Justified => self.hold_warrant(dna_hash, warrant).await,
+                WarrantCheck::Unjustified => {
+                    tracing::warn!(?warrant, "Blocking the author of a false warrant");
+                    let author = warrant.warrant().author.clone();
+                    self.block_agent_forever(
+                        dna_hash,
+                        author,
+                        CellBlockReason::FalseWarrant(warrant.action_hash()),
+                    )
+                    .await
+                }
+                WarrantCheck::Undecided => {
+                    tracing::info!(
+                        ?warrant,
+                        "Dropping a warrant whose action can't be validated yet"
+                    );
+                    Ok(())
+                }
+            }
+        }
+
+        /// Hold a warrant and block the warranted agent.
+        ///
+        /// The warrant must already be known to be justified, either because
+        /// our own validation issued it or because it has been checked by
+        /// [`Conductor::handle_publish_warrant`].
+        pub(crate) async fn hold_warrant(
+            &self,
+            dna_hash: &DnaHash,
+            warrant: SignedWarrant,
+        ) -> ConductorResult<()> {
+            let warrantee = warrant.warrant().warrantee().clone();
+            let action_hash = warrant.action_hash();
+            self.spaces
+                .handle_publish_warrant(dna_hash, warrant)
+                .await?;
+            self.block_agent_forever(dna_hash, warrantee, CellBlockReason::Warrant(action_hash))
+                .await
+        }
+
+        async fn block_agent_forever(
+            &self,
+            dna_hash: &DnaHash,
+            agent: AgentPubKey,
+            reason: CellBlockReason,
+        ) -> ConductorResult<()> {
+            // Never block our own agents.
+            if self
+                .running_cell_ids(None)
+                .iter()
+                .any(|cell_id| cell_id.agent_pubkey() == &agent)
+            {
+                return Ok(());
+            }
+            self.block(Block::new(
+                BlockTarget::Cell(CellId::new(dna_hash.clone(), agent), reason),
+                InclusiveTimestampInterval::try_new(Timestamp::MIN, Timestamp::MAX)
+                    .map_err(ConductorError::other)?,
+            ))
+            .await?;
+            Ok(())
+        }
+
         /// Block some target.
         pub async fn block(&self, input: Block) -> DatabaseResult<()> {
             self.spaces.block(input).await
@@ -1146,6 +1442,7 @@ mod network_impls {
                 | Get { .. }
                 | GetMeta { .. }
                 | GetLinks { .. }
+                | CountLinks { .. }
                 | GetAgentActivity { .. }
                 | MustGetAgentActivity { .. }
                 | ValidationReceiptReceived { .. } => {
@@ -1154,12 +1451,29 @@ mod network_impls {
                     let cell = self.cell_by_id(&cell_id).await?;
                     cell.handle_holochain_p2p_event(event).await?;
                 }
+                PublishWarrant {
+                    dna_hash,
+                    respond,
+                    warrant,
+                    ..
+                } => {
+                    async {
+                        let res = self
+                            .handle_publish_warrant(&dna_hash, warrant)
+                            .await
+                            .map_err(holochain_p2p::HolochainP2pError::other);
+                        respond.respond(Ok(async move { res }.boxed().into()));
+                    }
+                    .instrument(debug_span!("handle_publish_warrant"))
+                    .await;
+                }
                 Publish {
                     dna_hash,
                     respond,
                     request_validation_receipt,
                     countersigning_session,
                     ops,
+                    trace_context,
                     ..
                 } => {
                     async {
@@ -1175,7 +1489,10 @@ mod network_impls {
                             .map_err(holochain_p2p::HolochainP2pError::other);
                         respond.respond(Ok(async move { res }.boxed().into()));
                     }
-                    .instrument(debug_span!("handle_publish"))
+                    .instrument(crate::conductor::cell::continue_remote_trace(
+                        debug_span!("handle_publish"),
+                        trace_context,
+                    ))
                     .await;
                 }
                 FetchOpData {
@@ -1223,9 +1540,19 @@ mod network_impls {
         }
 
         /// Invoke a zome function on a Cell
+        #[instrument(skip_all, level = "debug", fields(zome_name = %call.zome_name, fn_name = %call.fn_name))]
         pub async fn call_zome(&self, call: ZomeCall) -> ConductorApiResult<ZomeCallResult> {
             let cell = self.cell_by_id(&call.cell_id).await?;
-            Ok(cell.call_zome(call, None).await?)
+            let (zome_name, fn_name) = (call.zome_name.clone(), call.fn_name.clone());
+            let start = std::time::Instant::now();
+            let result = cell.call_zome(call, None).await;
+            crate::conductor::metrics::observe_zome_call(
+                cell.id(),
+                &zome_name,
+                &fn_name,
+                start.elapsed(),
+            );
+            Ok(result?)
         }
 
         pub(crate) async fn call_zome_with_workspace(
@@ -1321,7 +1648,12 @@ mod app_impls {
             } = payload;
 
             let bundle = {
-                let original_bundle = source.resolve().await?;
+                let original_bundle = match source {
+                    AppBundleSource::Hash(app_hash) => {
+                        self.get_app_bundle_from_app_store(app_hash).await?
+                    }
+                    source => source.resolve().await?,
+                };
                 if let Some(network_seed) = network_seed {
                     let mut manifest = original_bundle.manifest().to_owned();
                     manifest.set_network_seed(network_seed);
@@ -1336,17 +1668,45 @@ mod app_impls {
             let installed_app_id =
                 installed_app_id.unwrap_or_else(|| manifest.app_name().to_owned());
 
-            let local_dnas = self
+            let mut local_dnas = self
                 .ribosome_store()
                 .share_ref(|store| bundle.get_all_dnas_from_store(store));
+
+            // Any DNAs referred to by hash which aren't registered yet may be
+            // available from the app store
+            let missing_dnas: Vec<DnaHash> = manifest
+                .app_roles()
+                .into_iter()
+                .flat_map(|role| role.dna.installed_hash)
+                .map(DnaHash::from)
+                .filter(|hash| !local_dnas.contains_key(hash))
+                .collect();
+            for dna_hash in missing_dnas {
+                if let Some(bundle) = self.get_dna_bundle_from_app_store(&dna_hash).await? {
+                    let (dna, _) = bundle.into_dna_file(DnaModifiersOpt::none()).await?;
+                    local_dnas.insert(dna_hash, dna);
+                }
+            }
+            // Cells provisioned by other apps, which roles may bind to instead
+            // of creating their own
+            let state = self.get_state().await?;
+            let existing_cells: HashSet<CellId> = state
+                .installed_apps()
+                .values()
+                .flat_map(|app| app.provisioned_cells().map(|(_, cell_id)| cell_id.clone()))
+                .collect();
             let ops = bundle
-                .resolve_cells(&local_dnas, agent_key.clone(), membrane_proofs)
+                .resolve_cells(
+                    &local_dnas,
+                    agent_key.clone(),
+                    membrane_proofs,
+                    &existing_cells,
+                )
                 .await?;
 
             let cells_to_create = ops.cells_to_create();
 
             // check if cells_to_create contains a cell identical to an existing one
-            let state = self.get_state().await?;
             let all_cells: HashSet<_> = state
                 .installed_apps()
                 .values()
@@ -1376,15 +1736,42 @@ mod app_impls {
             Ok(stopped_app)
         }
 
+        /// Fetch an app bundle from the app store
+        async fn get_app_bundle_from_app_store(
+            &self,
+            app_hash: AppHash,
+        ) -> ConductorResult<AppBundle> {
+            self.app_store_service()
+                .ok_or(ConductorError::AppStoreMissing)?
+                .get_app_bundle(app_hash.clone())
+                .await?
+                .ok_or(ConductorError::AppBundleNotFound(app_hash))
+        }
+
+        /// Fetch a DNA bundle from the app store, if the conductor has one and
+        /// the DNA has been published to it
+        pub(crate) async fn get_dna_bundle_from_app_store(
+            &self,
+            dna_hash: &DnaHash,
+        ) -> ConductorResult<Option<DnaBundle>> {
+            match self.app_store_service() {
+                Some(app_store) => Ok(app_store.get_dna_bundle(dna_hash.clone()).await?),
+                None => Ok(None),
+            }
+        }
+
         /// Uninstall an app
         #[tracing::instrument(skip(self))]
         pub async fn uninstall_app(
             self: Arc<Self>,
             installed_app_id: &InstalledAppId,
         ) -> ConductorResult<()> {
+            self.check_app_not_protected(installed_app_id).await?;
             let self_clone = self.clone();
             let app = self.remove_app_from_db(installed_app_id).await?;
             tracing::debug!(msg = "Removed app from db.", app = ?app);
+            self.app_auth_token_store
+                .share_mut(|store| store.revoke_app_tokens(installed_app_id));
 
             // Remove cells which may now be dangling due to the removed app
             self_clone
@@ -1393,6 +1780,116 @@ mod app_impls {
             Ok(())
         }
 
+        /// Provision the cell of a role whose provisioning was deferred when
+        /// the app was installed, and start it if the app is running.
+        #[tracing::instrument(skip(self, payload))]
+        pub async fn provision_deferred_role(
+            self: Arc<Self>,
+            payload: ProvisionRolePayload,
+        ) -> ConductorResult<ProvisionedCell> {
+            let ProvisionRolePayload {
+                app_id,
+                role_name,
+                membrane_proof,
+            } = payload;
+            // Mark the role first, so that genesis is only run once even if the
+            // role is requested to be provisioned again in the meantime
+            let (_, (cell_id, already_exists)) = self
+                .update_state_prime({
+                    let app_id = app_id.clone();
+                    let role_name = role_name.clone();
+                    move |mut state| {
+                        let cell_id = state
+                            .get_app_mut(&app_id)?
+                            .start_provisioning_deferred_role(&role_name)?;
+                        // A role which creates its cell only if it doesn't exist may find
+                        // that another app has created the cell in the meantime
+                        let already_exists = state
+                            .installed_apps()
+                            .values()
+                            .any(|app| app.all_cells().any(|c| *c == cell_id));
+                        Ok((state, (cell_id, already_exists)))
+                    }
+                })
+                .await?;
+            if !already_exists {
+                let genesis = crate::conductor::conductor::genesis_cells(
+                    self.clone(),
+                    vec![(cell_id.clone(), membrane_proof)],
+                )
+                .await;
+                if let Err(e) = genesis {
+                    self.update_state({
+                        let app_id = app_id.clone();
+                        let role_name = role_name.clone();
+                        move |mut state| {
+                            state
+                                .get_app_mut(&app_id)?
+                                .abort_provisioning_deferred_role(&role_name)?;
+                            Ok(state)
+                        }
+                    })
+                    .await?;
+                    return Err(e);
+                }
+            }
+
+            self.update_state({
+                let app_id = app_id.clone();
+                move |mut state| {
+                    state
+                        .get_app_mut(&app_id)?
+                        .provision_deferred_role(&role_name)?;
+                    Ok(state)
+                }
+            })
+            .await?;
+            self.create_and_add_initialized_cells_for_running_apps(Some(&app_id))
+                .await?;
+
+            let ribosome = self.get_ribosome(cell_id.dna_hash())?;
+            let dna_def = ribosome.dna_def();
+            Ok(ProvisionedCell {
+                cell_id,
+                dna_modifiers: dna_def.modifiers.clone(),
+                name: dna_def.name.clone(),
+            })
+        }
+
+        /// Migrate the agent of a role's cell to a new version of its DNA.
+        ///
+        /// The agent's chain on the old DNA is closed and a chain which refers
+        /// back to it is opened on the new DNA. The old cell keeps running so
+        /// that its data stays available, but no more actions can be written to it.
+        #[tracing::instrument(skip(self, payload))]
+        pub async fn migrate_cell(
+            self: Arc<Self>,
+            payload: MigrateCellPayload,
+        ) -> ConductorResult<CellId> {
+            migrate_cell::migrate_cell(self, payload).await
+        }
+
+        /// Apps which contain cells used by the conductor services must not be
+        /// disabled or uninstalled, since the conductor relies on them
+        pub(crate) async fn check_app_not_protected(
+            &self,
+            installed_app_id: &InstalledAppId,
+        ) -> ConductorResult<()> {
+            let protected: HashSet<CellId> = self.services.share_ref(|services| {
+                services
+                    .as_ref()
+                    .map(|s| s.protected_cell_ids().into_iter().cloned().collect())
+                    .unwrap_or_default()
+            });
+            let state = self.get_state().await?;
+            let app = state.get_app(installed_app_id)?;
+            if app.all_cells().any(|cell_id| protected.contains(cell_id)) {
+                Err(ConductorError::AppProtected(installed_app_id.clone()))
+            } else {
+                Ok(())
+            }
+        }
+
         /// List active AppIds
         pub async fn list_running_apps(&self) -> ConductorResult<Vec<InstalledAppId>> {
             let state = self.get_state().await?;
@@ -1742,6 +2239,9 @@ mod app_status_impls {
             app_id: InstalledAppId,
             reason: DisabledAppReason,
         ) -> ConductorResult<InstalledApp> {
+            if let DisabledAppReason::User = reason {
+                self.check_app_not_protected(&app_id).await?;
+            }
             let (app, delta) = self
                 .transition_app_status(app_id.clone(), AppStatusTransition::Disable(reason))
                 .await?;
@@ -2030,6 +2530,47 @@ mod state_impls {
     }
 }
 
+/// Methods related to the countersigning sessions of this conductor's agents
+mod countersigning_impls {
+    use super::*;
+    use holochain_state::countersigning::countersigning_session;
+
+    impl Conductor {
+        /// List the unresolved countersigning sessions of all running cells.
+        pub(crate) async fn list_countersigning_sessions(
+            &self,
+        ) -> ConductorResult<Vec<(CellId, CountersigningSessionState)>> {
+            let mut sessions = Vec::new();
+            for cell_id in self.running_cell_ids(None) {
+                let space = self.get_or_create_space(cell_id.dna_hash())?;
+                let author = cell_id.agent_pubkey().clone();
+                if let Some(session) = space
+                    .authored_db
+                    .async_reader(move |txn| countersigning_session(&txn, &author))
+                    .await?
+                {
+                    sessions.push((cell_id, session));
+                }
+            }
+            Ok(sessions)
+        }
+
+        /// Resolve the countersigning session of a cell, either by checking
+        /// the other signers' agent activity or by forcing a resolution.
+        pub(crate) async fn resolve_countersigning_session(
+            &self,
+            cell_id: &CellId,
+            force: Option<CountersigningSessionResolution>,
+        ) -> ConductorResult<Option<CountersigningSessionResolution>> {
+            Ok(self
+                .cell_by_id(cell_id)
+                .await?
+                .resolve_countersigning_session(force)
+                .await?)
+        }
+    }
+}
+
 /// Methods related to zome function scheduling
 mod scheduler_impls {
     use super::*;
@@ -2368,6 +2909,18 @@ mod accessor_impls {
         pub fn task_manager(&self) -> TaskManagerClient {
             self.task_manager.clone()
         }
+
+        /// Get the DPKI service, if the conductor is running one
+        pub fn dpki_service(&self) -> Option<Arc<dyn DpkiService>> {
+            self.services
+                .share_ref(|services| services.as_ref().and_then(|s| s.dpki.clone()))
+        }
+
+        /// Get the app store service, if the conductor is running one
+        pub fn app_store_service(&self) -> Option<Arc<dyn AppStoreService>> {
+            self.services
+                .share_ref(|services| services.as_ref().and_then(|s| s.app_store.clone()))
+        }
     }
 }
 
@@ -2549,8 +3102,10 @@ impl Conductor {
 
         let tasks = app_cells.difference(&on_cells).map(|cell_id| {
             let handle = self.clone();
-            let chc = handle.chc(cell_id);
             async move {
+                let chc = handle
+                    .chc(cell_id)
+                    .map_err(|e| (cell_id.clone(), CellError::from(Box::new(e))))?;
                 let holochain_p2p_cell =
                     handle.holochain_p2p.to_dna(cell_id.dna_hash().clone(), chc);
 
@@ -2811,6 +3366,25 @@ pub(crate) async fn genesis_cells(
     conductor: ConductorHandle,
     cell_ids_with_proofs: Vec<(CellId, Option<MembraneProof>)>,
 ) -> ConductorResult<()> {
+    // Any new agent keys must be registered with DPKI before genesis can succeed
+    if let Some(dpki) = conductor.dpki_service() {
+        let dpki_dnas: HashSet<DnaHash> = dpki
+            .cell_ids()
+            .into_iter()
+            .map(|cell_id| cell_id.dna_hash().clone())
+            .collect();
+        let agents: HashSet<AgentPubKey> = cell_ids_with_proofs
+            .iter()
+            .filter(|(cell_id, _)| !dpki_dnas.contains(cell_id.dna_hash()))
+            .map(|(cell_id, _)| cell_id.agent_pubkey().clone())
+            .collect();
+        for agent in agents {
+            if let KeyState::NotFound = dpki.key_state(agent.clone()).await? {
+                dpki.register_key(agent).await?;
+            }
+        }
+    }
+
     let cells_tasks = cell_ids_with_proofs.into_iter().map(|(cell_id, proof)| {
         let space = conductor
             .get_or_create_space(cell_id.dna_hash())
@@ -2821,7 +3395,7 @@ pub(crate) async fn genesis_cells(
             let dht_db = space.dht_db;
             let dht_db_cache = space.dht_query_cache;
             let conductor = conductor.clone();
-            let chc = conductor.chc(&cell_id);
+            let chc = conductor.chc(&cell_id).map_err(Box::new)?;
             let cell_id_inner = cell_id.clone();
             let ribosome = conductor
                 .get_ribosome(cell_id.dna_hash())
@@ -22,6 +22,9 @@ pub struct ConductorBuilder {
     pub state: Option<ConductorState>,
     /// Skip printing setup info to stdout
     pub no_print_setup: bool,
+    /// Optional DNA overrides for the conductor services, taking precedence
+    /// over the DNAs in the conductor config
+    pub service_dnas: ConductorServiceDnas,
 }
 
 impl ConductorBuilder {
@@ -50,60 +53,61 @@ impl ConductorBuilder {
         self
     }
 
+    /// Use this DNA as the DPKI service, rather than loading it from the path
+    /// given in the DPKI config.
+    pub fn with_dpki_dna(mut self, dna: DnaFile) -> Self {
+        self.service_dnas.dpki = Some(dna);
+        self
+    }
+
+    /// Use this DNA as the app store, rather than loading it from the path
+    /// given in the app store config.
+    pub fn with_app_store_dna(mut self, dna: DnaFile) -> Self {
+        self.service_dnas.app_store = Some(dna);
+        self
+    }
+
+    /// Encrypt the databases in the configured environment path which were
+    /// created before database encryption was enabled, with the keys the
+    /// conductor would use. Returns the paths of the encrypted databases.
+    ///
+    /// No conductor may be running on these databases at the same time.
+    #[cfg(feature = "sqlite-encrypted")]
+    pub async fn encrypt_databases(self) -> ConductorResult<Vec<std::path::PathBuf>> {
+        let keystore = match self.keystore {
+            Some(keystore) => keystore,
+            None => spawn_configured_keystore(&self.config, self.passphrase).await?,
+        };
+        let key = database_key(&keystore)
+            .await?
+            .expect("database encryption is enabled");
+        let root: std::path::PathBuf = self.config.environment_path.clone().into();
+        Ok(tokio::task::spawn_blocking(move || {
+            holochain_sqlite::encryption::encrypt_databases_in_dir(&root, &key)
+        })
+        .await??)
+    }
+
     /// Initialize a "production" Conductor
     pub async fn build(self) -> ConductorResult<ConductorHandle> {
         tracing::info!(?self.config);
 
-        let keystore = if let Some(keystore) = self.keystore {
-            keystore
-        } else {
-            pub(crate) fn warn_no_encryption() {
-                #[cfg(not(feature = "sqlite-encrypted"))]
-                {
-                    const MSG: &str = "WARNING: running without local db encryption";
-                    eprintln!("{}", MSG);
-                    println!("{}", MSG);
-                    tracing::warn!("{}", MSG);
-                }
-            }
-            let get_passphrase = || -> ConductorResult<sodoken::BufRead> {
-                match self.passphrase {
-                    None => Err(
-                        one_err::OneErr::new("passphrase required for lair keystore api").into(),
-                    ),
-                    Some(p) => Ok(p),
-                }
-            };
-            match &self.config.keystore {
-                KeystoreConfig::DangerTestKeystore => spawn_test_keystore().await?,
-                KeystoreConfig::LairServer { connection_url } => {
-                    warn_no_encryption();
-                    let passphrase = get_passphrase()?;
-                    spawn_lair_keystore(connection_url.clone(), passphrase).await?
-                }
-                KeystoreConfig::LairServerInProc { lair_root } => {
-                    warn_no_encryption();
-                    let mut keystore_config_path = lair_root.clone().unwrap_or_else(|| {
-                        let mut p: std::path::PathBuf = self.config.environment_path.clone().into();
-                        p.push("keystore");
-                        p
-                    });
-                    keystore_config_path.push("lair-keystore-config.yaml");
-                    let passphrase = get_passphrase()?;
-                    spawn_lair_keystore_in_proc(keystore_config_path, passphrase).await?
-                }
-            }
+        let keystore = match self.keystore {
+            Some(keystore) => keystore,
+            None => spawn_configured_keystore(&self.config, self.passphrase).await?,
         };
 
         let Self {
             ribosome_store,
             config,
+            service_dnas,
             ..
         } = self;
 
         let ribosome_store = RwShare::new(ribosome_store);
 
-        let spaces = Spaces::new(&config)?;
+        let db_key = database_key(&keystore).await?;
+        let spaces = Spaces::new(&config, db_key)?;
         let tag = spaces.get_state().await?.tag().clone();
 
         let tag_ed: Arc<str> = format!("{}_ed", tag.0).into_boxed_str().into();
@@ -183,6 +187,7 @@ impl ConductorBuilder {
             p2p_evt,
             post_commit_receiver,
             outcome_rx,
+            service_dnas,
             self.no_print_setup,
         )
         .await
@@ -230,6 +235,7 @@ impl ConductorBuilder {
         p2p_evt: holochain_p2p::event::HolochainP2pEventReceiver,
         post_commit_receiver: tokio::sync::mpsc::Receiver<PostCommitArgs>,
         outcome_receiver: OutcomeReceiver,
+        service_dnas: ConductorServiceDnas,
         no_print_setup: bool,
     ) -> ConductorResult<ConductorHandle> {
         conductor
@@ -245,10 +251,16 @@ impl ConductorBuilder {
             Self::spawn_post_commit(conductor2, post_commit_receiver, stop).map(Ok)
         });
 
+        if let Some(config) = conductor_config.metrics.clone() {
+            let address =
+                crate::conductor::metrics::spawn_metrics_server(conductor.clone(), config)?;
+            info!(%address, "Serving metrics");
+        }
+
         let configs = conductor_config.admin_interfaces.unwrap_or_default();
         let cell_startup_errors = conductor
             .clone()
-            .initialize_conductor(outcome_receiver, configs)
+            .initialize_conductor(outcome_receiver, configs, service_dnas)
             .await?;
 
         // TODO: This should probably be emitted over the admin interface
@@ -301,7 +313,8 @@ impl ConductorBuilder {
         let keystore = self.keystore.unwrap_or_else(test_keystore);
         self.config.environment_path = env_path.to_path_buf().into();
 
-        let spaces = Spaces::new(&self.config)?;
+        let db_key = database_key(&keystore).await?;
+        let spaces = Spaces::new(&self.config, db_key)?;
         let tag = spaces.get_state().await?.tag().clone();
 
         let tag_ed: Arc<str> = format!("{}_ed", tag.0).into_boxed_str().into();
@@ -365,8 +378,72 @@ impl ConductorBuilder {
             p2p_evt,
             post_commit_receiver,
             outcome_rx,
+            self.service_dnas,
             self.no_print_setup,
         )
         .await
     }
 }
+
+/// Connect to the keystore given in the conductor config,
+/// spawning it first if it runs in process.
+async fn spawn_configured_keystore(
+    config: &ConductorConfig,
+    passphrase: Option<sodoken::BufRead>,
+) -> ConductorResult<MetaLairClient> {
+    fn warn_no_encryption() {
+        #[cfg(not(feature = "sqlite-encrypted"))]
+        {
+            const MSG: &str = "WARNING: running without local db encryption";
+            eprintln!("{}", MSG);
+            println!("{}", MSG);
+            tracing::warn!("{}", MSG);
+        }
+    }
+    let get_passphrase = || -> ConductorResult<sodoken::BufRead> {
+        match passphrase {
+            None => Err(one_err::OneErr::new("passphrase required for lair keystore api").into()),
+            Some(p) => Ok(p),
+        }
+    };
+    Ok(match &config.keystore {
+        KeystoreConfig::DangerTestKeystore => spawn_test_keystore().await?,
+        KeystoreConfig::LairServer { connection_url } => {
+            warn_no_encryption();
+            let passphrase = get_passphrase()?;
+            spawn_lair_keystore(connection_url.clone(), passphrase).await?
+        }
+        KeystoreConfig::LairServerInProc { lair_root } => {
+            warn_no_encryption();
+            let mut keystore_config_path = lair_root.clone().unwrap_or_else(|| {
+                let mut p: std::path::PathBuf = config.environment_path.clone().into();
+                p.push("keystore");
+                p
+            });
+            keystore_config_path.push("lair-keystore-config.yaml");
+            let passphrase = get_passphrase()?;
+            spawn_lair_keystore_in_proc(keystore_config_path, passphrase).await?
+        }
+    })
+}
+
+/// The lair tag of the seed which the database encryption key is derived from.
+#[cfg(feature = "sqlite-encrypted")]
+const DATABASE_KEY_TAG: &str = "HC_DB_KEY";
+
+/// The key which each of the conductor's databases has its own key derived from,
+/// or `None` if the conductor is built without database encryption.
+async fn database_key(keystore: &MetaLairClient) -> ConductorResult<Option<DbKey>> {
+    #[cfg(feature = "sqlite-encrypted")]
+    {
+        let key = keystore
+            .get_or_create_database_key(DATABASE_KEY_TAG.into())
+            .await?;
+        Ok(Some(DbKey::from_bytes(key)))
+    }
+    #[cfg(not(feature = "sqlite-encrypted"))]
+    {
+        let _ = keystore;
+        Ok(None)
+    }
+}
@@ -3,13 +3,15 @@ use holochain_p2p::ChcImpl;
 use super::*;
 
 impl Conductor {
+    /// The CHC of the given cell, if one is configured.
+    /// Fails if the configured CHC namespace is invalid.
     #[allow(unused_variables)]
-    pub(crate) fn chc(&self, cell_id: &CellId) -> Option<ChcImpl> {
+    pub(crate) fn chc(&self, cell_id: &CellId) -> ConductorResult<Option<ChcImpl>> {
         cfg_if::cfg_if! {
             if #[cfg(feature = "chc")] {
-                crate::conductor::chc::build_chc(self.config.chc_namespace.as_ref(), cell_id)
+                Ok(crate::conductor::chc::build_chc(self.config.chc_namespace.as_ref(), cell_id)?)
             } else {
-                None
+                Ok(None)
             }
         }
     }
@@ -21,7 +23,7 @@ impl Conductor {
         cell_id: CellId,
         enable_app: Option<InstalledAppId>,
     ) -> ConductorApiResult<()> {
-        if let Some(chc) = self.chc(&cell_id) {
+        if let Some(chc) = self.chc(&cell_id)? {
             let db = self.get_authored_db(cell_id.dna_hash())?;
             let author = cell_id.agent_pubkey().clone();
             let top_hash = db
@@ -29,17 +31,7 @@ impl Conductor {
                     SourceChainResult::Ok(chain_head_db(&txn, Arc::new(author))?.map(|h| h.action))
                 })
                 .await?;
-            let actions = chc.get_actions_since_hash(top_hash).await?;
-            let entry_hashes: HashSet<&EntryHash> = actions
-                .iter()
-                .filter_map(|a| a.hashed.entry_hash())
-                .collect();
-            dbg!(&actions, &entry_hashes);
-            let entries = chc.get_entries(entry_hashes).await?;
-            dbg!(&entries);
-            let records = records_from_actions_and_entries(actions, entries)?;
-            dbg!(&records);
-
+            let records = chc.get_record_data(top_hash).await?;
             self.clone()
                 .graft_records_onto_source_chain(cell_id, true, records)
                 .await?;
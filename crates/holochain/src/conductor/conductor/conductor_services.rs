@@ -13,40 +13,58 @@ mod app_store_service;
 pub use app_store_service::*;
 
 use super::ConductorHandle;
+use holochain_types::prelude::DnaFile;
 use holochain_zome_types::CellId;
 
 /// The set of all Conductor Services available to the conductor
 #[derive(Clone)]
 pub struct ConductorServices {
-    /// The DPKI service
-    pub dpki: Arc<dyn DpkiService>,
-    /// The AppStore service
-    pub app_store: Arc<dyn AppStoreService>,
+    /// The DPKI service, if the conductor is configured to use one
+    pub dpki: Option<Arc<dyn DpkiService>>,
+    /// The AppStore service, if the conductor is configured to use one
+    pub app_store: Option<Arc<dyn AppStoreService>>,
 }
 
 impl ConductorServices {
     /// Construct services from the default built-in implementations
     pub fn builtin(conductor: ConductorHandle, cell_ids: ConductorServiceCells) -> Self {
         Self {
-            dpki: DeepkeyBuiltin::new(conductor.clone(), cell_ids.dpki),
-            app_store: AppStoreBuiltin::new(conductor, cell_ids.app_store),
+            dpki: cell_ids.dpki.map(|cell_id| {
+                DeepkeyBuiltin::new(conductor.clone(), cell_id) as Arc<dyn DpkiService>
+            }),
+            app_store: cell_ids.app_store.map(|cell_id| {
+                AppStoreBuiltin::new(conductor, cell_id) as Arc<dyn AppStoreService>
+            }),
         }
     }
 
     /// Get the list of any CellIds which may be protected due to being in use by ConductorServices
     pub fn protected_cell_ids(&self) -> HashSet<&CellId> {
-        self.dpki
-            .cell_ids()
-            .union(&self.app_store.cell_ids())
-            .copied()
-            .collect()
+        let mut cell_ids = HashSet::new();
+        if let Some(dpki) = &self.dpki {
+            cell_ids.extend(dpki.cell_ids());
+        }
+        if let Some(app_store) = &self.app_store {
+            cell_ids.extend(app_store.cell_ids());
+        }
+        cell_ids
     }
 }
 
 /// Initialized for ConductorService: just the CellIds that are used for each service
 pub struct ConductorServiceCells {
-    /// The CellId to use for DPKI
-    pub dpki: CellId,
-    /// The CellId to use for the AppStore
-    pub app_store: CellId,
+    /// The CellId to use for DPKI, if any
+    pub dpki: Option<CellId>,
+    /// The CellId to use for the AppStore, if any
+    pub app_store: Option<CellId>,
+}
+
+/// DNAs to run the built-in services with, taking precedence over any DNAs
+/// specified in the conductor config
+#[derive(Clone, Default)]
+pub struct ConductorServiceDnas {
+    /// The DNA to use for DPKI
+    pub dpki: Option<DnaFile>,
+    /// The DNA to use for the AppStore
+    pub app_store: Option<DnaFile>,
 }
@@ -4,6 +4,12 @@ use holochain_types::prelude::*;
 
 use crate::conductor::ConductorHandle;
 
+/// The InstalledAppId under which the conductor installs the app store cell
+pub const APP_STORE_APP_ID: &str = "__APP_STORE__";
+
+/// The role name of the app store cell within the app store app
+pub const APP_STORE_ROLE_NAME: &str = "app_store";
+
 /// Interface for the AppStore service
 #[async_trait::async_trait]
 #[mockall::automock]
@@ -21,42 +27,100 @@ pub trait AppStoreService: Send + Sync {
 
 /// The errors which can be produced by the AppStoreService
 #[derive(thiserror::Error, Debug)]
-pub enum AppStoreServiceError {}
+#[allow(missing_docs)]
+pub enum AppStoreServiceError {
+    #[error("App store DNA could not be called: {0}")]
+    ZomeCallFailed(String),
+
+    #[error("The app store returned DNA {1} when DNA {0} was requested")]
+    DnaHashMismatch(DnaHash, DnaHash),
+
+    #[error("The app store returned app {1} when app {0} was requested")]
+    AppHashMismatch(AppHash, AppHash),
+
+    #[error(transparent)]
+    DnaError(#[from] DnaError),
+
+    #[error(transparent)]
+    AppBundleError(#[from] AppBundleError),
+}
 /// Alias
 pub type AppStoreServiceResult<T> = Result<T, AppStoreServiceError>;
 
-/// This doesn't exist yet. We need to define it.
-pub enum AppHash {}
-
 /// The built-in implementation of the app store service, which runs a DNA
+/// implementing the zome functions defined in [`holochain_zome_types::app_store`]
 pub struct AppStoreBuiltin {
-    _conductor: ConductorHandle,
-    _cell_id: CellId,
+    conductor: ConductorHandle,
+    cell_id: CellId,
 }
 
 impl AppStoreBuiltin {
     /// Constructor
     pub fn new(conductor: ConductorHandle, cell_id: CellId) -> Arc<Self> {
-        Arc::new(Self {
-            _conductor: conductor,
-            _cell_id: cell_id,
-        })
+        Arc::new(Self { conductor, cell_id })
+    }
+
+    async fn call<I, O>(&self, fn_name: &str, payload: I) -> AppStoreServiceResult<O>
+    where
+        I: serde::Serialize + std::fmt::Debug,
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let provenance = self.cell_id.agent_pubkey().clone();
+        self.conductor
+            .easy_call_zome(
+                &provenance,
+                None,
+                self.cell_id.clone(),
+                APP_STORE_ZOME_NAME,
+                fn_name,
+                payload,
+            )
+            .await
+            .map_err(|e| AppStoreServiceError::ZomeCallFailed(e.to_string()))
     }
 }
 
 #[async_trait::async_trait]
 #[allow(clippy::needless_lifetimes)]
 impl AppStoreService for AppStoreBuiltin {
-    async fn get_dna_bundle(&self, _dna_hash: DnaHash) -> AppStoreServiceResult<Option<DnaBundle>> {
-        todo!("placeholder")
+    async fn get_dna_bundle(&self, dna_hash: DnaHash) -> AppStoreServiceResult<Option<DnaBundle>> {
+        let bytes: Option<Bytes> = self
+            .call(APP_STORE_GET_DNA_BUNDLE_FN, dna_hash.clone())
+            .await?;
+        let bytes = match bytes {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        // The bundle is decoded twice, since building the DnaFile to check
+        // the hash consumes the bundle.
+        let (_, actual) = DnaBundle::decode(&bytes)?
+            .into_dna_file(DnaModifiersOpt::none())
+            .await?;
+        if actual != dna_hash {
+            return Err(AppStoreServiceError::DnaHashMismatch(dna_hash, actual));
+        }
+        Ok(Some(DnaBundle::decode(&bytes)?))
     }
 
-    async fn get_app_bundle(&self, _app_hash: AppHash) -> AppStoreServiceResult<Option<AppBundle>> {
-        todo!("placeholder")
+    async fn get_app_bundle(&self, app_hash: AppHash) -> AppStoreServiceResult<Option<AppBundle>> {
+        let bytes: Option<Bytes> = self
+            .call(APP_STORE_GET_APP_BUNDLE_FN, app_hash.clone())
+            .await?;
+        let bundle = match bytes {
+            Some(bytes) => AppBundle::decode(&bytes)?,
+            None => return Ok(None),
+        };
+
+        let actual = bundle.app_hash().await;
+        if actual != app_hash {
+            return Err(AppStoreServiceError::AppHashMismatch(app_hash, actual));
+        }
+        Ok(Some(bundle))
     }
 
     fn cell_ids<'a>(&'a self) -> std::collections::HashSet<&'a CellId> {
-        [&self._cell_id].into_iter().collect()
+        [&self.cell_id].into_iter().collect()
     }
 }
 
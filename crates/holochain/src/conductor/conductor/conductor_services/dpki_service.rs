@@ -1,10 +1,16 @@
 use std::sync::Arc;
 
 use holo_hash::AgentPubKey;
-use holochain_zome_types::{CellId, Timestamp};
+use holochain_zome_types::prelude::*;
 
 use crate::conductor::ConductorHandle;
 
+/// The InstalledAppId under which the conductor installs the DPKI cell
+pub const DPKI_APP_ID: &str = "__DPKI__";
+
+/// The role name of the DPKI cell within the DPKI app
+pub const DPKI_ROLE_NAME: &str = "dpki";
+
 /// Interface for the DPKI service
 #[async_trait::async_trait]
 #[mockall::automock]
@@ -14,6 +20,9 @@ pub trait DpkiService: Send + Sync {
     async fn is_key_valid(&self, key: AgentPubKey, timestamp: Timestamp)
         -> DpkiServiceResult<bool>;
 
+    /// Get the full lifecycle state of a key, as recorded by DPKI
+    async fn key_state(&self, key: AgentPubKey) -> DpkiServiceResult<KeyState>;
+
     /// Defines the different ways that keys can be created and destroyed:
     /// If an old key is specified, it will be destroyed
     /// If a new key is specified, it will be registered
@@ -63,7 +72,10 @@ pub trait DpkiServiceExt: DpkiService {
     }
 }
 
+impl<T: DpkiService + ?Sized> DpkiServiceExt for T {}
+
 /// The built-in implementation of the DPKI service contract, which runs a DNA
+/// implementing the zome functions defined in [`holochain_zome_types::dpki`]
 pub struct DeepkeyBuiltin {
     conductor: ConductorHandle,
     cell_id: CellId,
@@ -74,10 +86,27 @@ impl DeepkeyBuiltin {
     pub fn new(conductor: ConductorHandle, cell_id: CellId) -> Arc<Self> {
         Arc::new(Self { conductor, cell_id })
     }
+
+    async fn call<I, O>(&self, fn_name: &str, payload: I) -> DpkiServiceResult<O>
+    where
+        I: serde::Serialize + std::fmt::Debug,
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let provenance = self.cell_id.agent_pubkey().clone();
+        self.conductor
+            .easy_call_zome(
+                &provenance,
+                None,
+                self.cell_id.clone(),
+                DPKI_ZOME_NAME,
+                fn_name,
+                payload,
+            )
+            .await
+            .map_err(|e| DpkiServiceError::ZomeCallFailed(e.to_string()))
+    }
 }
 
-#[allow(unreachable_code)]
-#[allow(unused_variables)]
 #[allow(clippy::needless_lifetimes)]
 #[async_trait::async_trait]
 impl DpkiService for DeepkeyBuiltin {
@@ -86,26 +115,11 @@ impl DpkiService for DeepkeyBuiltin {
         key: AgentPubKey,
         timestamp: Timestamp,
     ) -> DpkiServiceResult<bool> {
-        let keystore = self.conductor.keystore();
-        let cell_id = self.cell_id.clone();
-        let zome_name: String = "TODO: depends on dna implementation".to_string();
-        let fn_name: String = "TODO: depends on dna implementation".to_string();
-        let payload = "TODO: depends on dna implementation".to_string();
-        let cap_secret = None;
-        let provenance = cell_id.agent_pubkey().clone();
-        let is_valid: bool = self
-            .conductor
-            .easy_call_zome(
-                &provenance,
-                cap_secret,
-                cell_id,
-                zome_name,
-                fn_name,
-                payload,
-            )
-            .await
-            .map_err(|e| DpkiServiceError::ZomeCallFailed(e.to_string()))?;
-        Ok(is_valid)
+        Ok(self.key_state(key).await?.is_valid_at(timestamp))
+    }
+
+    async fn key_state(&self, key: AgentPubKey) -> DpkiServiceResult<KeyState> {
+        self.call(DPKI_KEY_STATE_FN, key).await
     }
 
     async fn key_mutation(
@@ -113,7 +127,15 @@ impl DpkiService for DeepkeyBuiltin {
         old_key: Option<AgentPubKey>,
         new_key: Option<AgentPubKey>,
     ) -> DpkiServiceResult<()> {
-        todo!()
+        match (old_key, new_key) {
+            (None, None) => Ok(()),
+            (None, Some(new_key)) => self.call(DPKI_REGISTER_KEY_FN, new_key).await,
+            (Some(old_key), Some(new_key)) => {
+                self.call(DPKI_UPDATE_KEY_FN, UpdateKeyInput { old_key, new_key })
+                    .await
+            }
+            (Some(old_key), None) => self.call(DPKI_REVOKE_KEY_FN, old_key).await,
+        }
     }
 
     fn cell_ids<'a>(&'a self) -> std::collections::HashSet<&'a CellId> {
@@ -127,6 +149,14 @@ pub fn mock_dpki() -> MockDpkiService {
     let mut dpki = MockDpkiService::new();
     dpki.expect_is_key_valid()
         .returning(|_, _| async move { Ok(true) }.boxed());
+    dpki.expect_key_state().returning(|_| {
+        async move {
+            Ok(KeyState::Valid {
+                registered_at: Timestamp::MIN,
+            })
+        }
+        .boxed()
+    });
     dpki.expect_cell_ids()
         .return_const(std::collections::HashSet::new());
     dpki
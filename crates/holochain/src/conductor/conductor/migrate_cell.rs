@@ -0,0 +1,147 @@
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+
+use super::*;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::workflow::migrate_agent_workflow::check_migrate_agent;
+use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentWorkflowArgs;
+
+pub(crate) async fn migrate_cell(
+    handle: ConductorHandle,
+    payload: MigrateCellPayload,
+) -> ConductorResult<CellId> {
+    let MigrateCellPayload {
+        app_id,
+        role_name,
+        new_dna_hash,
+        membrane_proof,
+    } = payload;
+
+    let state = handle.get_state().await?;
+    let old_cell_id = state
+        .get_app(&app_id)?
+        .role(&role_name)?
+        .provisioned_cell()
+        .cloned()
+        .ok_or_else(|| AppError::RoleNotProvisioned(role_name.clone()))?;
+    if *old_cell_id.dna_hash() == new_dna_hash {
+        return Err(ConductorError::CellMigrationError(format!(
+            "Role '{}' is already using DNA {}",
+            role_name, new_dna_hash
+        )));
+    }
+    let new_cell_id = CellId::new(new_dna_hash, old_cell_id.agent_pubkey().clone());
+
+    let old_ribosome = handle.get_ribosome(old_cell_id.dna_hash())?;
+    let new_ribosome = handle.get_ribosome(new_cell_id.dna_hash())?;
+
+    let old_workspace = cell_workspace(&handle, &old_cell_id, &old_ribosome).await?;
+    // A migration which failed after closing the old chain is resumed
+    // from the point where it stopped.
+    let already_closed = match old_workspace.source_chain().closed_for_dna().await? {
+        None => false,
+        Some(dna_hash) if dna_hash == *new_cell_id.dna_hash() => true,
+        Some(dna_hash) => {
+            return Err(ConductorError::CellMigrationError(format!(
+                "The chain of cell {:?} has already been closed for DNA {}",
+                old_cell_id, dna_hash
+            )))
+        }
+    };
+
+    // Every zome on both sides must agree to the migration before either
+    // chain is changed. Genesis of the new cell has no effect if it already ran.
+    if !already_closed {
+        check_migration(old_workspace.clone(), &old_ribosome, MigrateAgent::Close)?;
+    }
+    genesis_cells(handle.clone(), vec![(new_cell_id.clone(), membrane_proof)]).await?;
+    let new_workspace = cell_workspace(&handle, &new_cell_id, &new_ribosome).await?;
+    let already_opened = !new_workspace
+        .source_chain()
+        .query(ChainQueryFilter::new().action_type(ActionType::OpenChain))
+        .await?
+        .is_empty();
+    if !already_opened {
+        check_migration(new_workspace.clone(), &new_ribosome, MigrateAgent::Open)?;
+    }
+
+    if !already_closed {
+        migrate_agent_workflow(
+            old_workspace,
+            handle
+                .holochain_p2p()
+                .to_dna(old_cell_id.dna_hash().clone(), handle.chc(&old_cell_id)?),
+            MigrateAgentWorkflowArgs::new(
+                old_ribosome,
+                handle.clone(),
+                MigrateAgent::Close,
+                new_cell_id.dna_hash().clone(),
+            ),
+        )
+        .await?;
+    }
+    if !already_opened {
+        migrate_agent_workflow(
+            new_workspace,
+            handle
+                .holochain_p2p()
+                .to_dna(new_cell_id.dna_hash().clone(), handle.chc(&new_cell_id)?),
+            MigrateAgentWorkflowArgs::new(
+                new_ribosome,
+                handle.clone(),
+                MigrateAgent::Open,
+                old_cell_id.dna_hash().clone(),
+            ),
+        )
+        .await?;
+    }
+
+    handle
+        .update_state({
+            let app_id = app_id.clone();
+            let new_cell_id = new_cell_id.clone();
+            move |mut state| {
+                state
+                    .get_app_mut(&app_id)?
+                    .migrate_role(&role_name, new_cell_id)?;
+                Ok(state)
+            }
+        })
+        .await?;
+    handle
+        .create_and_add_initialized_cells_for_running_apps(Some(&app_id))
+        .await?;
+
+    Ok(new_cell_id)
+}
+
+async fn cell_workspace(
+    handle: &ConductorHandle,
+    cell_id: &CellId,
+    ribosome: &RealRibosome,
+) -> ConductorResult<SourceChainWorkspace> {
+    handle
+        .get_or_create_space(cell_id.dna_hash())?
+        .source_chain_workspace(
+            handle.keystore().clone(),
+            cell_id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await
+}
+
+fn check_migration(
+    workspace: SourceChainWorkspace,
+    ribosome: &RealRibosome,
+    migrate_agent: MigrateAgent,
+) -> ConductorResult<()> {
+    match check_migrate_agent(workspace, ribosome, migrate_agent.clone())? {
+        MigrateAgentResult::Pass => Ok(()),
+        MigrateAgentResult::Fail(zome_name, reason) => {
+            Err(ConductorError::CellMigrationError(format!(
+                "Zome '{}' refused to {:?} the chain: {}",
+                zome_name, migrate_agent, reason
+            )))
+        }
+    }
+}
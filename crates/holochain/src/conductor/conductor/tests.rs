@@ -33,10 +33,13 @@ async fn can_update_state() {
         tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
 
     let (outcome_tx, _outcome_rx) = futures::channel::mpsc::channel(8);
-    let spaces = Spaces::new(&ConductorConfig {
-        environment_path: db_dir.path().to_path_buf().into(),
-        ..Default::default()
-    })
+    let spaces = Spaces::new(
+        &ConductorConfig {
+            environment_path: db_dir.path().to_path_buf().into(),
+            ..Default::default()
+        },
+        None,
+    )
     .unwrap();
     let conductor = Conductor::new(
         Default::default(),
@@ -84,10 +87,13 @@ async fn app_ids_are_unique() {
         tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
 
     let (outcome_tx, _outcome_rx) = futures::channel::mpsc::channel(8);
-    let spaces = Spaces::new(&ConductorConfig {
-        environment_path: db_dir.path().to_path_buf().into(),
-        ..Default::default()
-    })
+    let spaces = Spaces::new(
+        &ConductorConfig {
+            environment_path: db_dir.path().to_path_buf().into(),
+            ..Default::default()
+        },
+        None,
+    )
     .unwrap();
     let conductor = Conductor::new(
         Default::default(),
@@ -221,18 +227,8 @@ async fn common_genesis_test_app(
 ) -> ConductorApiResult<SweetApp> {
     let hardcoded_zome = InlineIntegrityZome::new_unique(Vec::new(), 0);
 
-    // Just a strong reminder that we need to be careful once we start using existing Cells:
-    // When a Cell panics or fails validation in general, we want to disable all Apps touching that Cell.
-    // However, if the panic/failure happens during Genesis, we want to completely
-    // destroy the app which is attempting to Create that Cell, but *NOT* any other apps
-    // which might be touching that Cell.
-    //
-    // It probably works out to be the same either way, since if we are creating a Cell,
-    // no other app could be possibly referencing it, but just in case we have some kind of complex
-    // behavior like installing two apps which reference each others' Cells at the same time,
-    // we need to be aware of this distinction.
-    holochain_types::app::we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-    );
+    // Genesis only runs for the cells an app creates, never for existing cells
+    // it uses, so a failure here can only affect the app being installed.
 
     // Create one DNA which always works, and another from a zome that gets passed in
     let (dna_hardcoded, _, _) = mk_dna(("hardcoded", hardcoded_zome)).await;
@@ -1171,3 +1167,50 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dpki_app_is_installed_and_protected() {
+    use crate::test_utils::inline_zomes::dpki_zome;
+
+    let (dpki_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(dpki_zome()).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let mut conductor =
+        SweetConductor::from_builder(Conductor::builder().with_dpki_dna(dpki_dna.clone())).await;
+
+    let dpki_cell_id = conductor
+        .get_state()
+        .await
+        .unwrap()
+        .get_app(&DPKI_APP_ID.to_string())
+        .unwrap()
+        .all_cells()
+        .next()
+        .unwrap()
+        .clone();
+    assert_eq!(dpki_cell_id.dna_hash(), dpki_dna.dna_hash());
+    assert!(conductor.live_cell_ids().contains(&dpki_cell_id));
+
+    // Installing an app registers its agent with DPKI
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let agent = app.agent().clone();
+    assert_matches!(
+        conductor.dpki_service().unwrap().key_state(agent).await,
+        Ok(KeyState::Valid { .. })
+    );
+
+    // The DPKI app can't be disabled or uninstalled by the user
+    assert_matches!(
+        conductor
+            .raw_handle()
+            .disable_app(DPKI_APP_ID.into(), DisabledAppReason::User)
+            .await,
+        Err(ConductorError::AppProtected(_))
+    );
+    assert_matches!(
+        conductor
+            .raw_handle()
+            .uninstall_app(&DPKI_APP_ID.into())
+            .await,
+        Err(ConductorError::AppProtected(_))
+    );
+}
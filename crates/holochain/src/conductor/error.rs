@@ -34,6 +34,9 @@ pub enum ConductorError {
     #[error("Cell was referenced, but is currently disabled. CellId: {0:?}")]
     CellDisabled(CellId),
 
+    #[error("Error while migrating cell: {0}")]
+    CellMigrationError(String),
+
     #[error("Cell was referenced, but is missing from the conductor. CellId: {0:?}")]
     CellMissing(CellId),
 
@@ -92,6 +95,23 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error(
+        "The app {0} is in use by the conductor services, so it cannot be disabled or uninstalled"
+    )]
+    AppProtected(InstalledAppId),
+
+    #[error(transparent)]
+    DpkiError(#[from] crate::conductor::conductor::DpkiServiceError),
+
+    #[error("Tried to install from the app store, but the conductor has no app store")]
+    AppStoreMissing,
+
+    #[error("The app bundle {0} was not found in the app store")]
+    AppBundleNotFound(AppHash),
+
+    #[error(transparent)]
+    AppStoreError(#[from] crate::conductor::conductor::AppStoreServiceError),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -125,6 +145,9 @@ pub enum ConductorError {
     #[error(transparent)]
     RibosomeError(#[from] crate::core::ribosome::error::RibosomeError),
 
+    #[error(transparent)]
+    ChcError(#[from] holochain_types::chc::ChcError),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -2,8 +2,8 @@
 //! and dispatch them to the appropriate handlers within Holochain.
 //! They also allow emitting responses and one-way Signals.
 //!
-//! Currently the only InterfaceDriver is a Websocket-based one, whose
-//! implementation can be found in the `websocket` module here.
+//! All InterfaceDrivers are Websocket-based, served over TCP (optionally with TLS)
+//! or a unix domain socket. The implementation can be found in the `websocket` module here.
 
 use crate::conductor::api::*;
 use error::InterfaceError;
@@ -1,7 +1,7 @@
 //! Module for establishing Websocket-based Interfaces,
 //! i.e. those configured with `InterfaceDriver::Websocket`
+//! or `InterfaceDriver::UnixSocket`
 
-use super::error::InterfaceError;
 use super::error::InterfaceResult;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskResult;
@@ -9,8 +9,10 @@ use crate::conductor::manager::TaskManagerClient;
 use futures::FutureExt;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_types::signal::Signal;
+use holochain_websocket::AllowedOrigins;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::ListenerStream;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
@@ -32,21 +34,57 @@ use url2::url2;
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 const MAX_CONNECTIONS: isize = 400;
 
-/// Create a WebsocketListener to be used in interfaces
+/// Create a WebsocketListener to be used in interfaces,
+/// with the TLS or socket file settings of the driver applied to `config`
 pub async fn spawn_websocket_listener(
-    port: u16,
-) -> InterfaceResult<(
-    ListenerHandle,
-    impl futures::stream::Stream<Item = ListenerItem>,
-)> {
-    trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
-    trace!("LISTENING AT: {}", listener.0.local_addr());
-    Ok(listener)
+    driver: &InterfaceDriver,
+    mut config: WebsocketConfig,
+) -> InterfaceResult<(ListenerHandle, ListenerStream)> {
+    let (handle, stream) = match driver {
+        InterfaceDriver::Websocket { port, tls } => {
+            if let Some(tls) = tls {
+                config = config.tls_server(holochain_websocket::tls::server_config(
+                    &tls.cert_path,
+                    &tls.key_path,
+                    tls.client_ca_path.as_deref(),
+                )?);
+            }
+            let (handle, stream) = WebsocketListener::bind_with_handle(
+                url2!("{}://127.0.0.1:{}", config.scheme, port),
+                Arc::new(config),
+            )
+            .await?;
+            (handle, Box::pin(stream) as ListenerStream)
+        }
+        #[cfg(unix)]
+        InterfaceDriver::UnixSocket { path, mode } => {
+            if let Some(mode) = mode {
+                config = config.unix_socket_mode(*mode);
+            }
+            let (handle, stream) =
+                WebsocketListener::bind_unix_with_handle(path, Arc::new(config)).await?;
+            (handle, Box::pin(stream) as ListenerStream)
+        }
+        #[cfg(not(unix))]
+        InterfaceDriver::UnixSocket { .. } => {
+            return Err(super::error::InterfaceError::Other(
+                "Unix domain socket interfaces are only supported on unix platforms".into(),
+            ))
+        }
+    };
+    trace!("LISTENING AT: {}", handle.local_addr());
+    Ok((handle, stream))
+}
+
+/// The port a listener spawned for this driver is bound to.
+/// Unix domain sockets don't have a port.
+pub fn listener_port(driver: &InterfaceDriver, handle: &ListenerHandle) -> Option<u16> {
+    match driver {
+        InterfaceDriver::Websocket { port, .. } => {
+            Some(handle.local_addr().port().unwrap_or(*port))
+        }
+        InterfaceDriver::UnixSocket { .. } => None,
+    }
 }
 
 /// Create an Admin Interface, which only receives AdminRequest messages
@@ -56,14 +94,14 @@ pub fn spawn_admin_interface_tasks<A: InterfaceApi>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
-    port: u16,
 ) {
+    let local_addr = handle.local_addr().clone();
     // Task that will kill the listener and all child connections.
     tm.add_conductor_task_ignored("admin interface websocket closer", |stop| {
         handle.close_on(stop.map(|_| true)).map(Ok)
     });
 
-    tm.add_conductor_task_ignored(&format!("admin interface, port {}", port), |_stop| {
+    tm.add_conductor_task_ignored(&format!("admin interface, {}", local_addr), |_stop| {
         async move {
             let num_connections = Arc::new(AtomicIsize::new(0));
             futures::pin_mut!(listener);
@@ -93,24 +131,22 @@ pub fn spawn_admin_interface_tasks<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
+/// from Cells via a broadcast channel.
+/// Returns the port the interface is bound to, if it listens on one.
 pub async fn spawn_app_interface_task<A: InterfaceApi>(
     tm: TaskManagerClient,
-    port: u16,
+    driver: &InterfaceDriver,
+    allowed_origins: AllowedOrigins,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
-) -> InterfaceResult<u16> {
+) -> InterfaceResult<Option<u16>> {
     trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+    let (handle, mut listener) = spawn_websocket_listener(
+        driver,
+        WebsocketConfig::default().allowed_origins(allowed_origins),
     )
     .await?;
-    trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let port = listener_port(driver, &handle);
     // Task that will kill the listener and all child connections.
     tm.add_conductor_task_ignored("app interface websocket closer", |stop| {
         handle.close_on(stop.map(|_| true)).map(Ok)
@@ -123,14 +159,14 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                     Ok((tx_to_iface, rx_from_iface)) => {
                         let rx_from_cell = signal_broadcaster.subscribe();
                         spawn_recv_incoming_msgs_and_outgoing_signals(
-                            api.clone(),
+                            api.for_connection(),
                             rx_from_iface,
                             rx_from_cell,
                             tx_to_iface,
                         );
                     }
                     Err(err) => {
-                        warn!("Admin socket connection failed: {}", err);
+                        warn!("App socket connection failed: {}", err);
                     }
                 }
             }
@@ -184,9 +220,15 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
+    let signal_api = api.clone();
     tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
+        // Filter before serializing, so unwanted signals cost as little as possible
+        let allowed = signal_api.allows_signal(&signal);
         async move {
+            if !allowed {
+                return;
+            }
             trace!(msg = "Sending signal!", ?signal);
             if let Err(err) = async move {
                 let bytes = SerializedBytes::try_from(signal)?;
@@ -806,7 +848,12 @@ pub mod test {
         holochain_trace::test_run().ok();
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            driver: None,
+            allowed_origins: AllowedOrigins::Any,
+            installed_app_id: None,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -1,7 +1,7 @@
 //! Implementation of the Kitsune Host API
 
 mod query_region_op_hashes;
-mod query_region_set;
+pub mod query_region_set;
 mod query_size_limited_regions;
 
 use std::sync::Arc;
@@ -169,9 +169,15 @@ impl KitsuneHost for KitsuneHostImpl {
         async move {
             let topology = self.get_topology(space.clone()).await?;
             let db = self.spaces.dht_db(&dna_hash)?;
-            let region_set =
-                query_region_set::query_region_set(db, topology.clone(), &self.strat, dht_arc_set)
-                    .await?;
+            let region_tree = self.spaces.dht_region_tree(&dna_hash)?;
+            let region_set = query_region_set::query_region_set(
+                db,
+                &region_tree,
+                topology.clone(),
+                &self.strat,
+                dht_arc_set,
+            )
+            .await?;
             Ok(region_set)
         }
         .boxed()
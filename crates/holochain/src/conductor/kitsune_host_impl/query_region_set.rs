@@ -3,7 +3,10 @@ use std::sync::{
     Arc,
 };
 
-use holochain_p2p::{dht::prelude::*, dht_arc::DhtArcSet};
+use holochain_p2p::{
+    dht::{prelude::*, Loc},
+    dht_arc::DhtArcSet,
+};
 use holochain_sqlite::prelude::*;
 use rusqlite::named_params;
 
@@ -12,14 +15,50 @@ use crate::conductor::error::ConductorResult;
 static LAST_LOG_MS: AtomicI64 = AtomicI64::new(0);
 const LOG_RATE_MS: i64 = 1000;
 
-/// The network module needs info about various groupings ("regions") of ops
+/// The network module needs info about various groupings ("regions") of ops.
+///
+/// The data is read from the space's [`DhtRegionTree`], which is brought up to
+/// date with the database first.
 pub async fn query_region_set(
+    db: DbWrite<DbKindDht>,
+    region_tree: &DhtRegionTree,
+    topology: Topology,
+    strat: &ArqStrat,
+    dht_arc_set: Arc<DhtArcSet>,
+) -> ConductorResult<RegionSetLtcs> {
+    let coords = region_coords(&topology, strat, &dht_arc_set);
+    Ok(region_tree.region_set(&db, &topology, coords).await?)
+}
+
+/// The same as [`query_region_set`], but computing each region with a query
+/// against the database rather than with a [`DhtRegionTree`].
+pub async fn query_region_set_from_db(
     db: DbWrite<DbKindDht>,
     topology: Topology,
     strat: &ArqStrat,
     dht_arc_set: Arc<DhtArcSet>,
 ) -> ConductorResult<RegionSetLtcs> {
-    let (arq_set, rounded) = ArqBoundsSet::from_dht_arc_set_rounded(&topology, strat, &dht_arc_set);
+    let coords = region_coords(&topology, strat, &dht_arc_set);
+
+    let region_set = db
+        .async_reader(move |txn| {
+            let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
+            let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
+            let regions = coords
+                .into_region_set(|(_, coords)| query_region_data(&mut stmt, &topology, coords))?;
+            DatabaseResult::Ok(regions)
+        })
+        .await?;
+
+    Ok(region_set)
+}
+
+fn region_coords(
+    topology: &Topology,
+    strat: &ArqStrat,
+    dht_arc_set: &DhtArcSet,
+) -> RegionCoordSetLtcs {
+    let (arq_set, rounded) = ArqBoundsSet::from_dht_arc_set_rounded(topology, strat, dht_arc_set);
     if rounded {
         // If an arq was rounded, emit a warning, but throttle it to once every LOG_RATE_MS
         // so we don't get slammed.
@@ -46,20 +85,99 @@ pub async fn query_region_set(
         }
     }
 
-    let times = TelescopingTimes::historical(&topology);
-    let coords = RegionCoordSetLtcs::new(times, arq_set);
+    let times = TelescopingTimes::historical(topology);
+    RegionCoordSetLtcs::new(times, arq_set)
+}
 
-    let region_set = db
-        .async_reader(move |txn| {
-            let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
-            let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
-            let regions = coords
-                .into_region_set(|(_, coords)| query_region_data(&mut stmt, &topology, coords))?;
-            DatabaseResult::Ok(regions)
-        })
-        .await?;
+/// An in-memory [`RegionTree`] of all the ops in a space's DHT database, so
+/// that gossip doesn't have to query the database for every region it compares.
+///
+/// The tree is built from the database the first time it is queried, and from
+/// then on only the ops added to the database since it was last synced are read.
+#[derive(Clone, Default)]
+pub struct DhtRegionTree(Arc<tokio::sync::Mutex<Option<SyncedRegionTree>>>);
 
-    Ok(region_set)
+struct SyncedRegionTree {
+    tree: RegionTree,
+    /// The rowid of the last op read from the database. Ops are never removed
+    /// from the DHT database, so every op with a greater rowid is new.
+    last_rowid: i64,
+}
+
+impl DhtRegionTree {
+    /// Add the ops which have been added to the database since the last sync.
+    /// Does nothing if the tree hasn't been built yet.
+    pub async fn sync(&self, db: &DbRead<DbKindDht>) -> DatabaseResult<()> {
+        if let Some(synced) = self.0.lock().await.as_mut() {
+            synced.sync(db).await?;
+        }
+        Ok(())
+    }
+
+    /// Generate the data for each coord in the set, first building the tree
+    /// if this is the first query or the topology has changed.
+    pub async fn region_set(
+        &self,
+        db: &DbRead<DbKindDht>,
+        topology: &Topology,
+        coords: RegionCoordSetLtcs,
+    ) -> DatabaseResult<RegionSetLtcs> {
+        let mut lock = self.0.lock().await;
+        if !matches!(&*lock, Some(synced) if synced.tree.topo() == topology) {
+            *lock = Some(SyncedRegionTree {
+                tree: RegionTree::new(topology.clone()),
+                last_rowid: 0,
+            });
+        }
+        let synced = lock.as_mut().expect("the tree was just built");
+        synced.sync(db).await?;
+        Ok(synced.tree.region_set(coords))
+    }
+}
+
+impl SyncedRegionTree {
+    async fn sync(&mut self, db: &DbRead<DbKindDht>) -> DatabaseResult<()> {
+        let last_rowid = self.last_rowid;
+        let ops = db
+            .async_reader(move |txn| {
+                let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION_DATA_SINCE;
+                let mut stmt = txn.prepare_cached(sql)?;
+                let ops = stmt
+                    .query_map(named_params! { ":rowid": last_rowid }, |row| {
+                        let action_size: i64 = row.get("action_size")?;
+                        let entry_size: i64 = row.get("entry_size")?;
+                        Ok((
+                            row.get::<_, i64>("rowid")?,
+                            Loc::from(row.get::<_, u32>("storage_center_loc")?),
+                            row.get::<_, Timestamp>("authored_timestamp")?,
+                            RegionData {
+                                hash: RegionHash::from_vec(row.get("hash")?)
+                                    .expect("region hash must be 32 bytes"),
+                                size: u32::try_from(action_size + entry_size).unwrap_or(u32::MAX),
+                                count: 1,
+                            },
+                        ))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                DatabaseResult::Ok(ops)
+            })
+            .await?;
+        let topo = self.tree.topo().clone();
+        for (rowid, loc, timestamp, data) in ops {
+            self.last_rowid = rowid;
+            // The database queries leave out ops from before the time origin,
+            // whereas the topology would put them in the first time quantum.
+            if timestamp < topo.time_origin {
+                continue;
+            }
+            let coords = SpacetimeQuantumCoords {
+                space: topo.space_quantum(loc),
+                time: topo.time_quantum(timestamp),
+            };
+            self.tree.add(coords, data);
+        }
+        Ok(())
+    }
 }
 
 pub(super) fn query_region_data(
@@ -113,9 +231,17 @@ mod tests {
         let strat = ArqStrat::default();
         let arcset = Arc::new(DhtArcSet::Full);
 
-        let regions_empty = query_region_set(db.to_db(), topo.clone(), &strat, arcset.clone())
-            .await
-            .unwrap();
+        let region_tree = DhtRegionTree::default();
+
+        let regions_empty = query_region_set(
+            db.to_db(),
+            &region_tree,
+            topo.clone(),
+            &strat,
+            arcset.clone(),
+        )
+        .await
+        .unwrap();
         {
             let sum: RegionData = regions_empty.regions().map(|r| r.data).sum();
             assert_eq!(sum.count, 0);
@@ -143,6 +269,7 @@ mod tests {
             .map(|op| {
                 holochain_p2p::WireDhtOpData {
                     op_data: op.into_content(),
+                    trace_context: Vec::new(),
                 }
                 .encode()
                 .unwrap()
@@ -158,9 +285,19 @@ mod tests {
         })
         .unwrap();
 
-        let regions = query_region_set(db.to_db(), topo, &strat, arcset)
+        let regions = query_region_set(
+            db.to_db(),
+            &region_tree,
+            topo.clone(),
+            &strat,
+            arcset.clone(),
+        )
+        .await
+        .unwrap();
+        let regions_from_db = query_region_set_from_db(db.to_db(), topo, &strat, arcset)
             .await
             .unwrap();
+        assert_eq!(regions, regions_from_db);
 
         let diff = regions.diff(regions_empty).unwrap();
         {
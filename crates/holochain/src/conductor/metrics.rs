@@ -0,0 +1,227 @@
+//! Serves the conductor's metrics over HTTP in the Prometheus text format,
+//! for Prometheus or any other OpenMetrics compatible scraper.
+//!
+//! Metrics are registered with the default [`prometheus`] registry by the
+//! crates which record them, e.g. database permit wait times in
+//! `holochain_sqlite` and gossip rounds in `kitsune_p2p`. The gauges which
+//! describe the state of each DNA's databases are refreshed when scraped.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use super::Conductor;
+use super::ConductorHandle;
+use futures::FutureExt;
+use holochain_conductor_api::config::conductor::MetricsConfig;
+use holochain_sqlite::db::AsP2pStateTxExt;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::prelude::*;
+use hyper::service::make_service_fn;
+use hyper::service::service_fn;
+use hyper::Body;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use once_cell::sync::Lazy;
+use prometheus::HistogramVec;
+use prometheus::IntGaugeVec;
+use prometheus::TextEncoder;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+static ZOME_CALL_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    prometheus::register_histogram_vec!(
+        "holochain_zome_call_duration_seconds",
+        "Duration of zome calls made through the conductor",
+        &["dna", "zome", "fn_name"]
+    )
+    .expect("Metric names are unique")
+});
+
+static PEER_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    prometheus::register_int_gauge_vec!(
+        "holochain_peer_count",
+        "Number of peers in the peer store of each DNA",
+        &["dna"]
+    )
+    .expect("Metric names are unique")
+});
+
+static OP_QUEUE_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    prometheus::register_int_gauge_vec!(
+        "holochain_op_queue_depth",
+        "Number of DHT ops waiting for each workflow of each DNA",
+        &["dna", "queue"]
+    )
+    .expect("Metric names are unique")
+});
+
+/// Record how long a zome call took.
+pub(crate) fn observe_zome_call(
+    cell_id: &CellId,
+    zome_name: &ZomeName,
+    fn_name: &FunctionName,
+    duration: Duration,
+) {
+    ZOME_CALL_DURATION
+        .with_label_values(&[
+            &cell_id.dna_hash().to_string(),
+            &zome_name.to_string(),
+            fn_name.as_ref(),
+        ])
+        .observe(duration.as_secs_f64());
+}
+
+/// Refresh the gauges which are read from the databases of every running DNA,
+/// then encode all registered metrics in the Prometheus text format.
+pub async fn gather(conductor: &Conductor) -> ConductorResult<String> {
+    let spaces = conductor.spaces.get_from_spaces(|space| {
+        (
+            space.dna_hash.clone(),
+            space.dht_db.clone(),
+            space.p2p_agents_db.clone(),
+        )
+    });
+
+    // Drop the series of DNAs which are no longer running.
+    PEER_COUNT.reset();
+    OP_QUEUE_DEPTH.reset();
+    for (dna_hash, dht_db, p2p_agents_db) in spaces {
+        let dna = dna_hash.to_string();
+        let peers = p2p_agents_db
+            .async_reader(|txn| txn.p2p_count_agents())
+            .await?;
+        PEER_COUNT.with_label_values(&[&dna]).set(peers.into());
+        let depths = dht_db.async_reader(|txn| op_queue_depths(&txn)).await?;
+        for (queue, depth) in depths {
+            OP_QUEUE_DEPTH.with_label_values(&[&dna, queue]).set(depth);
+        }
+    }
+
+    TextEncoder::new()
+        .encode_to_string(&prometheus::gather())
+        .map_err(ConductorError::other)
+}
+
+/// The number of ops which haven't been integrated yet, by the workflow
+/// they are waiting for.
+fn op_queue_depths(
+    txn: &Transaction,
+) -> Result<[(&'static str, i64); 3], holochain_sqlite::error::DatabaseError> {
+    Ok(txn.query_row(
+        "
+        SELECT
+        COUNT(CASE WHEN validation_stage IS NULL OR validation_stage = 0 THEN 1 END),
+        COUNT(CASE WHEN validation_stage = 1 OR validation_stage = 2 THEN 1 END),
+        COUNT(CASE WHEN validation_stage = 3 THEN 1 END)
+        FROM DhtOp
+        WHERE when_integrated IS NULL
+        ",
+        [],
+        |row| {
+            Ok([
+                ("sys_validation", row.get(0)?),
+                ("app_validation", row.get(1)?),
+                ("integration", row.get(2)?),
+            ])
+        },
+    )?)
+}
+
+/// Serve `GET /metrics` on the configured address until the conductor shuts down.
+/// Returns the address which is being served on.
+pub(crate) fn spawn_metrics_server(
+    conductor: ConductorHandle,
+    config: MetricsConfig,
+) -> ConductorResult<SocketAddr> {
+    let tm = conductor.task_manager();
+    let make_service = make_service_fn(move |_| {
+        let conductor = conductor.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_request(conductor.clone(), request)
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&config.bind_address)
+        .map_err(ConductorError::other)?
+        .serve(make_service);
+    let address = server.local_addr();
+    tm.add_conductor_task_ignored(&format!("metrics server, {}", address), move |stop| {
+        server
+            .with_graceful_shutdown(stop.map(|_| ()))
+            .map(|result| result.map_err(|e| Box::new(ConductorError::other(e)).into()))
+    });
+    Ok(address)
+}
+
+async fn handle_request(
+    conductor: ConductorHandle,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => match gather(&conductor).await {
+            Ok(metrics) => Response::builder()
+                .header(hyper::header::CONTENT_TYPE, prometheus::TEXT_FORMAT)
+                .body(Body::from(metrics)),
+            Err(e) => {
+                tracing::error!(?e, "Failed to gather metrics");
+                Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::empty())
+            }
+        },
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty()),
+    };
+    Ok(response.expect("Responses are valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweettest::*;
+    use crate::test_utils::inline_zomes::simple_create_read_zome;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn metrics_are_served() {
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let (dna_file, _, _) =
+            SweetDnaFile::unique_from_inline_zomes(("zome", simple_create_read_zome())).await;
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let _: ActionHash = conductor.call(&cell.zome("zome"), "create", ()).await;
+
+        let address = spawn_metrics_server(
+            conductor.raw_handle(),
+            MetricsConfig {
+                bind_address: ([127, 0, 0, 1], 0).into(),
+            },
+        )
+        .unwrap();
+
+        let response = reqwest::get(format!("http://{}/metrics", address))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body = response.text().await.unwrap();
+        let dna = cell.cell_id().dna_hash().to_string();
+        assert!(body.contains(&format!(
+            "holochain_zome_call_duration_seconds_count{{dna=\"{}\",fn_name=\"create\",zome=\"zome\"}} 1",
+            dna
+        )));
+        assert!(body.contains(&format!("holochain_peer_count{{dna=\"{}\"}}", dna)));
+        assert!(body.contains(&format!(
+            "holochain_op_queue_depth{{dna=\"{}\",queue=\"integration\"}}",
+            dna
+        )));
+        assert!(body.contains("holochain_db_permit_wait_seconds"));
+
+        let response = reqwest::get(format!("http://{}/other", address))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
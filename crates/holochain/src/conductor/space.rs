@@ -6,6 +6,7 @@ use std::{collections::HashMap, sync::Arc, time::Duration};
 use super::{
     conductor::RwShare,
     error::ConductorResult,
+    kitsune_host_impl::query_region_set::DhtRegionTree,
     p2p_agent_store::{self, P2pBatch},
 };
 use crate::conductor::{error::ConductorError, state::ConductorState};
@@ -42,7 +43,7 @@ use holochain_sqlite::{
         DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents, DbKindP2pMetrics,
         DbKindWasm, DbWrite, ReadAccess,
     },
-    prelude::{DatabaseError, DatabaseResult},
+    prelude::{DatabaseError, DatabaseResult, DbKey},
 };
 use holochain_state::{
     host_fn_workspace::SourceChainWorkspace,
@@ -53,6 +54,7 @@ use holochain_state::{
 };
 use holochain_types::db::AsP2pStateTxExt;
 use holochain_types::prelude::CellId;
+use holochain_types::prelude::SignedWarrant;
 use holochain_types::{
     db_cache::DhtDbQueryCache,
     dht_op::{DhtOp, DhtOpType},
@@ -86,6 +88,9 @@ pub struct Spaces {
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
     pub(crate) wasm_db: DbWrite<DbKindWasm>,
     network_config: KitsuneP2pConfig,
+    /// The key which each database's own key is derived from,
+    /// if databases are encrypted.
+    db_key: Option<DbKey>,
 }
 
 #[derive(Clone)]
@@ -124,6 +129,9 @@ pub struct Space {
     /// A cache for slow database queries.
     pub dht_query_cache: DhtDbQueryCache,
 
+    /// The regions of ops in the dht database, for gossip.
+    pub dht_region_tree: DhtRegionTree,
+
     /// Countersigning workspace that is shared across this cell.
     pub countersigning_workspace: CountersigningWorkspace,
 
@@ -148,17 +156,26 @@ pub struct TestSpace {
 
 impl Spaces {
     /// Create a new empty set of [`DnaHash`] spaces.
-    pub fn new(config: &ConductorConfig) -> ConductorResult<Self> {
+    /// If a key is given all databases are encrypted with keys derived from it.
+    pub fn new(config: &ConductorConfig, db_key: Option<DbKey>) -> ConductorResult<Self> {
         let root_db_dir = config.environment_path.clone();
         let db_sync_strategy = config.db_sync_strategy;
         let db_sync_level = match db_sync_strategy {
             DbSyncStrategy::Fast => DbSyncLevel::Off,
             DbSyncStrategy::Resilient => DbSyncLevel::Normal,
         };
-        let conductor_db =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindConductor, db_sync_level)?;
-        let wasm_db =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindWasm, db_sync_level)?;
+        let conductor_db = DbWrite::open_with_sync_level(
+            root_db_dir.as_ref(),
+            DbKindConductor,
+            db_sync_level,
+            db_key.as_ref(),
+        )?;
+        let wasm_db = DbWrite::open_with_sync_level(
+            root_db_dir.as_ref(),
+            DbKindWasm,
+            db_sync_level,
+            db_key.as_ref(),
+        )?;
         Ok(Spaces {
             map: RwShare::new(HashMap::new()),
             db_dir: Arc::new(root_db_dir),
@@ -167,6 +184,7 @@ impl Spaces {
             conductor_db,
             wasm_db,
             network_config: config.network.clone().unwrap_or_default(),
+            db_key,
         })
     }
 
@@ -353,6 +371,7 @@ impl Spaces {
                             Arc::new(dna_hash.clone()),
                             &self.db_dir,
                             self.db_sync_strategy,
+                            self.db_key.as_ref(),
                         )?;
 
                         let r = f(&space);
@@ -378,6 +397,11 @@ impl Spaces {
         self.get_or_create_space_ref(dna_hash, |space| space.dht_db.clone())
     }
 
+    /// Get the region tree of the dht database (this will create the space if it doesn't already exist).
+    pub fn dht_region_tree(&self, dna_hash: &DnaHash) -> DatabaseResult<DhtRegionTree> {
+        self.get_or_create_space_ref(dna_hash, |space| space.dht_region_tree.clone())
+    }
+
     /// Get the peer database (this will create the space if it doesn't already exist).
     pub fn p2p_agents_db(&self, dna_hash: &DnaHash) -> DatabaseResult<DbWrite<DbKindP2pAgents>> {
         self.get_or_create_space_ref(dna_hash, |space| space.p2p_agents_db.clone())
@@ -736,6 +760,21 @@ impl Spaces {
         Ok(())
     }
 
+    /// we are receiving a warrant, either from the network or from our own
+    /// validation, so hold on to it for anyone asking about the warrantee
+    pub async fn handle_publish_warrant(
+        &self,
+        dna_hash: &DnaHash,
+        warrant: SignedWarrant,
+    ) -> ConductorResult<()> {
+        let space = self.get_or_create_space(dna_hash)?;
+        space
+            .dht_db
+            .async_commit(move |txn| mutations::insert_warrant(txn, warrant))
+            .await?;
+        Ok(())
+    }
+
     /// Get the recent_threshold based on the kitsune network config
     pub fn recent_threshold(&self) -> Duration {
         self.network_config
@@ -749,6 +788,7 @@ impl Space {
         dna_hash: Arc<DnaHash>,
         root_db_dir: &DatabaseRootPath,
         db_sync_strategy: DbSyncStrategy,
+        db_key: Option<&DbKey>,
     ) -> DatabaseResult<Self> {
         let space = dna_hash.to_kitsune();
         let db_sync_level = match db_sync_strategy {
@@ -759,29 +799,38 @@ impl Space {
             root_db_dir.as_ref(),
             DbKindCache(dna_hash.clone()),
             db_sync_level,
+            db_key,
         )?;
         let authored_db = DbWrite::open_with_sync_level(
             root_db_dir.as_ref(),
             DbKindAuthored(dna_hash.clone()),
             DbSyncLevel::Normal,
+            db_key,
         )?;
         let dht_db = DbWrite::open_with_sync_level(
             root_db_dir.as_ref(),
             DbKindDht(dna_hash.clone()),
             db_sync_level,
+            db_key,
         )?;
         let p2p_agents_db = DbWrite::open_with_sync_level(
             root_db_dir.as_ref(),
             DbKindP2pAgents(space.clone()),
             db_sync_level,
+            db_key,
         )?;
         let p2p_metrics_db = DbWrite::open_with_sync_level(
             root_db_dir.as_ref(),
             DbKindP2pMetrics(space),
             db_sync_level,
+            db_key,
+        )?;
+        let conductor_db: DbWrite<DbKindConductor> = DbWrite::open_with_sync_level(
+            root_db_dir.as_ref(),
+            DbKindConductor,
+            db_sync_level,
+            db_key,
         )?;
-        let conductor_db: DbWrite<DbKindConductor> =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindConductor, db_sync_level)?;
 
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         tokio::spawn(p2p_agent_store::p2p_put_all_batch(
@@ -794,6 +843,7 @@ impl Space {
         let incoming_op_hashes = IncomingOpHashes::default();
         let incoming_ops_batch = IncomingOpsBatch::default();
         let dht_query_cache = DhtDbQueryCache::new(dht_db.clone().into());
+        let dht_region_tree = DhtRegionTree::default();
         let r = Self {
             dna_hash,
             cache_db: cache,
@@ -806,6 +856,7 @@ impl Space {
             incoming_op_hashes,
             incoming_ops_batch,
             dht_query_cache,
+            dht_region_tree,
             conductor_db,
         };
         Ok(r)
@@ -866,10 +917,13 @@ impl TestSpaces {
             .prefix("holochain-test-environments")
             .tempdir()
             .unwrap();
-        let spaces = Spaces::new(&ConductorConfig {
-            environment_path: temp_dir.path().to_path_buf().into(),
-            ..Default::default()
-        })
+        let spaces = Spaces::new(
+            &ConductorConfig {
+                environment_path: temp_dir.path().to_path_buf().into(),
+                ..Default::default()
+            },
+            None,
+        )
         .unwrap();
         spaces.map.share_mut(|map| {
             map.extend(
@@ -899,6 +953,7 @@ impl TestSpace {
                 Arc::new(dna_hash),
                 &temp_dir.path().to_path_buf().into(),
                 Default::default(),
+                None,
             )
             .unwrap(),
             _temp_dir: temp_dir,
@@ -3,6 +3,7 @@
 
 use holochain_conductor_api::config::InterfaceDriver;
 use holochain_conductor_api::signal_subscription::SignalSubscription;
+use holochain_conductor_api::AllowedOrigins;
 use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
@@ -198,10 +199,8 @@ impl ConductorState {
 /// Here, interfaces are user facing and make available zome functions to
 /// GUIs, browser based web UIs, local native UIs, other local applications and scripts.
 /// We currently have:
-/// * websockets
-///
-/// We will also soon develop
-/// * Unix domain sockets
+/// * websockets, optionally over TLS
+/// * websockets over Unix domain sockets
 ///
 /// The cells (referenced by ID) that are to be made available via that interface should be listed.
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -212,14 +211,29 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// The web origins which may connect to the interface
+    #[serde(default)]
+    pub allowed_origins: AllowedOrigins,
+
+    /// The only app which connections to the interface may access, if set
+    #[serde(default)]
+    pub installed_app_id: Option<InstalledAppId>,
 }
 
 impl AppInterfaceConfig {
-    /// Create config for a websocket interface
-    pub fn websocket(port: u16) -> Self {
+    /// Create config for a websocket interface,
+    /// served over TCP or a unix domain socket according to the driver
+    pub fn websocket(
+        driver: InterfaceDriver,
+        allowed_origins: AllowedOrigins,
+        installed_app_id: Option<InstalledAppId>,
+    ) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver,
+            allowed_origins,
+            installed_app_id,
         }
     }
 }
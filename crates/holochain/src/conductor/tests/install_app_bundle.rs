@@ -3,10 +3,12 @@ use std::{collections::HashMap, path::PathBuf};
 use crate::{conductor::error::ConductorError, sweettest::*};
 use fixt::prelude::strum_macros;
 use holo_hash::{AgentPubKey, DnaHash};
+use holochain_conductor_api::{CellInfo, ProvisionedCell, StemCell};
 use holochain_types::prelude::{
-    mapvec, AppBundle, AppBundleError, AppBundleSource, AppManifestCurrentBuilder,
+    mapvec, AppBundle, AppBundleError, AppBundleSource, AppError, AppManifestCurrentBuilder,
     AppManifestError, AppRoleDnaManifest, AppRoleManifest, CellProvisioning,
     CreateCloneCellPayload, DnaBundle, DnaFile, DnaLocation, InstallAppPayload,
+    ProvisionRolePayload, YamlProperties,
 };
 use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::{CellId, DnaModifiersOpt, Timestamp};
@@ -116,7 +118,375 @@ async fn clone_only_provisioning_creates_no_cell_and_allows_cloning() {
 
         assert_eq!(app.all_cells().count(), 1);
     }
-    // TODO: test that the cell can't be provisioned later
+    // The base cell of a clone only role can't be provisioned later
+    assert_matches!(
+        conductor
+            .clone()
+            .provision_deferred_role(ProvisionRolePayload {
+                app_id: "app_1".into(),
+                role_name: "name".into(),
+                membrane_proof: None,
+            })
+            .await
+            .unwrap_err(),
+        ConductorError::AppError(AppError::RoleNotDeferred(_))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deferred_provisioning_creates_cell_when_requested() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+
+    let (main_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let (optional_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let role = |name: &str, dna: &DnaFile, deferred: bool| AppRoleManifest {
+        name: name.into(),
+        dna: AppRoleDnaManifest {
+            location: Some(DnaLocation::Bundled(PathBuf::from(format!(
+                "{}",
+                dna.dna_hash()
+            )))),
+            modifiers: DnaModifiersOpt::none(),
+            installed_hash: Some(DnaHash::with_data_sync(dna.dna_def()).into()),
+            clone_limit: 0,
+        },
+        provisioning: Some(CellProvisioning::Create { deferred }),
+    };
+    let manifest = AppManifestCurrentBuilder::default()
+        .name("test_app".into())
+        .description(None)
+        .roles(vec![
+            role("main", &main_dna, false),
+            role("optional", &optional_dna, true),
+        ])
+        .build()
+        .unwrap();
+    let mut resources = Vec::new();
+    for dna in [&main_dna, &optional_dna] {
+        resources.push((
+            PathBuf::from(format!("{}", dna.dna_hash())),
+            DnaBundle::from_dna_file(dna.clone()).await.unwrap(),
+        ));
+    }
+    let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+        .await
+        .unwrap();
+
+    conductor
+        .clone()
+        .install_app_bundle(InstallAppPayload {
+            agent_key: agent.clone(),
+            source: AppBundleSource::Bundle(bundle),
+            installed_app_id: Some("app".into()),
+            network_seed: None,
+            membrane_proofs: HashMap::new(),
+        })
+        .await
+        .unwrap();
+    conductor
+        .clone()
+        .enable_app("app".to_string())
+        .await
+        .unwrap();
+
+    let main_cell_id = CellId::new(main_dna.dna_hash().clone(), agent.clone());
+    let optional_cell_id = CellId::new(optional_dna.dna_hash().clone(), agent.clone());
+    assert!(conductor.running_cell_ids(None).contains(&main_cell_id));
+    assert!(!conductor.running_cell_ids(None).contains(&optional_cell_id));
+
+    let app_info = conductor
+        .get_app_info(&"app".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(
+        app_info.cell_info["optional"].as_slice(),
+        [CellInfo::Stem(StemCell { original_dna_hash, .. })] if original_dna_hash == optional_dna.dna_hash()
+    );
+
+    let provisioned = conductor
+        .clone()
+        .provision_deferred_role(ProvisionRolePayload {
+            app_id: "app".into(),
+            role_name: "optional".into(),
+            membrane_proof: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(provisioned.cell_id, optional_cell_id);
+    assert!(conductor.running_cell_ids(None).contains(&optional_cell_id));
+
+    let app_info = conductor
+        .get_app_info(&"app".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(
+        app_info.cell_info["optional"].as_slice(),
+        [CellInfo::Provisioned(ProvisionedCell { cell_id, .. })] if *cell_id == optional_cell_id
+    );
+
+    // A role can only be provisioned once
+    assert_matches!(
+        conductor
+            .clone()
+            .provision_deferred_role(ProvisionRolePayload {
+                app_id: "app".into(),
+                role_name: "optional".into(),
+                membrane_proof: None,
+            })
+            .await
+            .unwrap_err(),
+        ConductorError::AppError(AppError::RoleNotDeferred(_))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn use_existing_provisioning_shares_cell_between_apps() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let cell_id = CellId::new(dna.dna_hash().clone(), agent.clone());
+
+    async fn make_payload(
+        agent_key: AgentPubKey,
+        dna: &DnaFile,
+        app_id: &str,
+        provisioning: CellProvisioning,
+    ) -> InstallAppPayload {
+        let path = PathBuf::from(format!("{}", dna.dna_hash()));
+        let roles = vec![AppRoleManifest {
+            name: "profiles".into(),
+            dna: AppRoleDnaManifest {
+                location: Some(DnaLocation::Bundled(path.clone())),
+                modifiers: DnaModifiersOpt::none(),
+                installed_hash: Some(DnaHash::with_data_sync(dna.dna_def()).into()),
+                clone_limit: 0,
+            },
+            provisioning: Some(provisioning),
+        }];
+        let manifest = AppManifestCurrentBuilder::default()
+            .name(app_id.into())
+            .description(None)
+            .roles(roles)
+            .build()
+            .unwrap();
+        let resources = vec![(path, DnaBundle::from_dna_file(dna.clone()).await.unwrap())];
+        let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+            .await
+            .unwrap();
+        InstallAppPayload {
+            agent_key,
+            source: AppBundleSource::Bundle(bundle),
+            installed_app_id: Some(app_id.into()),
+            network_seed: None,
+            membrane_proofs: HashMap::new(),
+        }
+    }
+
+    // There is no cell to use yet
+    assert_matches!(
+        conductor
+            .clone()
+            .install_app_bundle(
+                make_payload(
+                    agent.clone(),
+                    &dna,
+                    "app_b",
+                    CellProvisioning::UseExisting { deferred: false },
+                )
+                .await
+            )
+            .await
+            .unwrap_err(),
+        ConductorError::AppBundleError(AppBundleError::CellResolutionFailure(_, _))
+    );
+
+    for (app_id, provisioning) in [
+        ("app_a", CellProvisioning::Create { deferred: false }),
+        ("app_b", CellProvisioning::UseExisting { deferred: false }),
+        (
+            "app_c",
+            CellProvisioning::CreateIfNotExists { deferred: false },
+        ),
+    ] {
+        let app = conductor
+            .clone()
+            .install_app_bundle(make_payload(agent.clone(), &dna, app_id, provisioning).await)
+            .await
+            .unwrap();
+        assert_eq!(app.all_cells().collect::<Vec<_>>(), vec![&cell_id]);
+        conductor
+            .clone()
+            .enable_app(app_id.to_string())
+            .await
+            .unwrap();
+    }
+    assert!(conductor.running_cell_ids(None).contains(&cell_id));
+
+    // The cell is still used by the other apps after the app which created it
+    // is uninstalled
+    conductor
+        .clone()
+        .uninstall_app(&"app_a".to_string())
+        .await
+        .unwrap();
+    assert!(conductor.running_cell_ids(None).contains(&cell_id));
+
+    conductor
+        .clone()
+        .uninstall_app(&"app_b".to_string())
+        .await
+        .unwrap();
+    assert!(conductor.running_cell_ids(None).contains(&cell_id));
+
+    conductor
+        .clone()
+        .uninstall_app(&"app_c".to_string())
+        .await
+        .unwrap();
+    assert!(!conductor.running_cell_ids(None).contains(&cell_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn use_existing_provisioning_finds_cells_with_a_network_seed() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let network_seed = "network seed".to_string();
+    let seeded_dna = dna.clone().with_network_seed(network_seed.clone()).await;
+    let seeded_cell_id = CellId::new(seeded_dna.dna_hash().clone(), agent.clone());
+
+    async fn make_payload(
+        agent_key: AgentPubKey,
+        dna: &DnaFile,
+        app_id: &str,
+        provisioning: CellProvisioning,
+        modifiers: DnaModifiersOpt<YamlProperties>,
+        network_seed: Option<String>,
+    ) -> InstallAppPayload {
+        let path = PathBuf::from(format!("{}", dna.dna_hash()));
+        let roles = vec![AppRoleManifest {
+            name: "profiles".into(),
+            dna: AppRoleDnaManifest {
+                location: Some(DnaLocation::Bundled(path.clone())),
+                modifiers,
+                installed_hash: Some(DnaHash::with_data_sync(dna.dna_def()).into()),
+                clone_limit: 0,
+            },
+            provisioning: Some(provisioning),
+        }];
+        let manifest = AppManifestCurrentBuilder::default()
+            .name(app_id.into())
+            .description(None)
+            .roles(roles)
+            .build()
+            .unwrap();
+        let resources = vec![(path, DnaBundle::from_dna_file(dna.clone()).await.unwrap())];
+        let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+            .await
+            .unwrap();
+        InstallAppPayload {
+            agent_key,
+            source: AppBundleSource::Bundle(bundle),
+            installed_app_id: Some(app_id.into()),
+            network_seed,
+            membrane_proofs: HashMap::new(),
+        }
+    }
+
+    // The first app creates its cell with a network seed
+    let app = conductor
+        .clone()
+        .install_app_bundle(
+            make_payload(
+                agent.clone(),
+                &dna,
+                "app_a",
+                CellProvisioning::Create { deferred: false },
+                DnaModifiersOpt::none(),
+                Some(network_seed.clone()),
+            )
+            .await,
+        )
+        .await
+        .unwrap();
+    assert_eq!(app.all_cells().collect::<Vec<_>>(), vec![&seeded_cell_id]);
+
+    // Without the network seed there is no cell to use
+    assert_matches!(
+        conductor
+            .clone()
+            .install_app_bundle(
+                make_payload(
+                    agent.clone(),
+                    &dna,
+                    "app_b",
+                    CellProvisioning::UseExisting { deferred: false },
+                    DnaModifiersOpt::none(),
+                    None,
+                )
+                .await
+            )
+            .await
+            .unwrap_err(),
+        ConductorError::AppBundleError(AppBundleError::CellResolutionFailure(_, _))
+    );
+
+    // With it, both kinds of role bind to the first app's cell
+    for (app_id, provisioning, modifiers, network_seed) in [
+        (
+            "app_b",
+            CellProvisioning::UseExisting { deferred: false },
+            DnaModifiersOpt::none().with_network_seed(network_seed.clone()),
+            None,
+        ),
+        (
+            "app_c",
+            CellProvisioning::CreateIfNotExists { deferred: false },
+            DnaModifiersOpt::none(),
+            Some(network_seed.clone()),
+        ),
+    ] {
+        let app = conductor
+            .clone()
+            .install_app_bundle(
+                make_payload(
+                    agent.clone(),
+                    &dna,
+                    app_id,
+                    provisioning,
+                    modifiers,
+                    network_seed,
+                )
+                .await,
+            )
+            .await
+            .unwrap();
+        assert_eq!(app.all_cells().collect::<Vec<_>>(), vec![&seeded_cell_id]);
+    }
+
+    // A role which may create its cell creates a new one without the network seed
+    let app = conductor
+        .clone()
+        .install_app_bundle(
+            make_payload(
+                agent.clone(),
+                &dna,
+                "app_d",
+                CellProvisioning::CreateIfNotExists { deferred: false },
+                DnaModifiersOpt::none(),
+                None,
+            )
+            .await,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        app.all_cells().collect::<Vec<_>>(),
+        vec![&CellId::new(dna.dna_hash().clone(), agent.clone())]
+    );
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -657,3 +1027,86 @@ impl TestCase {
         (installed_hash, case)
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn install_app_by_hash_from_app_store() {
+    use crate::conductor::Conductor;
+    use crate::test_utils::inline_zomes::app_store_zome;
+    use holochain_zome_types::bytes::Bytes;
+
+    let (app_store_zomes, registry) = app_store_zome();
+    let (app_store_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(app_store_zomes).await;
+    let conductor =
+        SweetConductor::from_builder(Conductor::builder().with_app_store_dna(app_store_dna)).await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+
+    // The app bundle refers to its DNA by hash only, so the DNA must be
+    // fetched from the app store too
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let roles = vec![AppRoleManifest {
+        name: "name".into(),
+        dna: AppRoleDnaManifest {
+            location: Some(DnaLocation::Bundled(PathBuf::from("missing.dna"))),
+            modifiers: DnaModifiersOpt::none(),
+            installed_hash: Some(dna.dna_hash().clone().into()),
+            clone_limit: 0,
+        },
+        provisioning: Some(CellProvisioning::Create { deferred: false }),
+    }];
+    let manifest = AppManifestCurrentBuilder::default()
+        .name("test_app".into())
+        .description(None)
+        .roles(roles)
+        .build()
+        .unwrap();
+    let bundle = AppBundle::new(manifest.into(), vec![], PathBuf::from("."))
+        .await
+        .unwrap();
+    let app_hash = bundle.app_hash().await;
+
+    let payload = || InstallAppPayload {
+        agent_key: agent.clone(),
+        source: AppBundleSource::Hash(app_hash.clone()),
+        installed_app_id: Some("app_1".into()),
+        network_seed: None,
+        membrane_proofs: HashMap::new(),
+    };
+
+    // Nothing has been published yet
+    assert_matches!(
+        conductor.clone().install_app_bundle(payload()).await,
+        Err(ConductorError::AppBundleNotFound(hash)) if hash == app_hash
+    );
+
+    let dna_bytes = DnaBundle::from_dna_file(dna.clone())
+        .await
+        .unwrap()
+        .encode()
+        .unwrap();
+    {
+        let mut published = registry.lock();
+        published
+            .apps
+            .insert(app_hash.clone(), Bytes::from(bundle.encode().unwrap()));
+        published
+            .dnas
+            .insert(dna.dna_hash().clone(), Bytes::from(dna_bytes));
+    }
+
+    let app = conductor
+        .clone()
+        .install_app_bundle(payload())
+        .await
+        .unwrap();
+    assert_eq!(
+        app.all_cells().collect::<Vec<_>>(),
+        vec![&CellId::new(dna.dna_hash().clone(), agent.clone())]
+    );
+
+    // A conductor without an app store can't install by hash
+    let conductor = SweetConductor::from_standard_config().await;
+    assert_matches!(
+        conductor.clone().install_app_bundle(payload()).await,
+        Err(ConductorError::AppStoreMissing)
+    );
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use crate::core::workflow::migrate_agent_workflow::{
+    migrate_agent_workflow, MigrateAgentWorkflowArgs,
+};
+use crate::{conductor::error::ConductorError, core::ribosome::RibosomeT, sweettest::*};
+use holo_hash::{ActionHash, AgentPubKey, DnaHash};
+use holochain_types::prelude::{AppError, DnaFile, MigrateCellPayload, RoleName};
+use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::{Action, ActionType, CellId, ChainQueryFilter, MigrateAgent};
+use matches::assert_matches;
+
+async fn chain_head(conductor: &SweetConductor, cell_id: &CellId) -> Action {
+    conductor
+        .get_or_create_space(cell_id.dna_hash())
+        .unwrap()
+        .source_chain(conductor.keystore(), cell_id.agent_pubkey().clone())
+        .await
+        .unwrap()
+        .query(ChainQueryFilter::new().descending())
+        .await
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap()
+        .action()
+        .clone()
+}
+
+async fn setup(wasms: Vec<TestWasm>) -> (SweetConductor, AgentPubKey, DnaFile, DnaFile) {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (old_dna, _, _) = SweetDnaFile::unique_from_test_wasms(wasms.clone()).await;
+    let (new_dna, _, _) = SweetDnaFile::unique_from_test_wasms(wasms).await;
+    conductor.register_dna(new_dna.clone()).await.unwrap();
+    let role_name: RoleName = "role".into();
+    conductor
+        .setup_app_for_agent("app", agent.clone(), [&(role_name, old_dna.clone())])
+        .await
+        .unwrap();
+    (conductor, agent, old_dna, new_dna)
+}
+
+fn payload(new_dna_hash: &DnaHash) -> MigrateCellPayload {
+    MigrateCellPayload {
+        app_id: "app".into(),
+        role_name: "role".into(),
+        new_dna_hash: new_dna_hash.clone(),
+        membrane_proof: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrated_cell_closes_old_chain_and_opens_new_one() {
+    let (conductor, agent, old_dna, new_dna) =
+        setup(vec![TestWasm::MigrateAgentPass, TestWasm::Create]).await;
+    let old_cell_id = CellId::new(old_dna.dna_hash().clone(), agent.clone());
+    let new_cell_id = CellId::new(new_dna.dna_hash().clone(), agent.clone());
+
+    let migrated = conductor
+        .clone()
+        .migrate_cell(payload(new_dna.dna_hash()))
+        .await
+        .unwrap();
+    assert_eq!(migrated, new_cell_id);
+
+    // The old cell keeps running alongside the new one
+    let running = conductor.running_cell_ids(None);
+    assert!(running.contains(&old_cell_id));
+    assert!(running.contains(&new_cell_id));
+
+    assert_matches!(
+        chain_head(&conductor, &old_cell_id).await,
+        Action::CloseChain(close) if close.new_dna_hash == *new_dna.dna_hash()
+    );
+    assert_matches!(
+        chain_head(&conductor, &new_cell_id).await,
+        Action::OpenChain(open) if open.prev_dna_hash == *old_dna.dna_hash()
+    );
+
+    // Nothing more can be written to the closed chain
+    let zome = SweetZome::new(old_cell_id, TestWasm::Create.coordinator_zome_name());
+    let result: Result<ActionHash, _> = conductor.call_fallible(&zome, "create_entry", ()).await;
+    assert!(result.is_err());
+
+    let zome = SweetZome::new(new_cell_id, TestWasm::Create.coordinator_zome_name());
+    let result: Result<ActionHash, _> = conductor.call_fallible(&zome, "create_entry", ()).await;
+    assert!(result.is_ok());
+
+    // A role can't be migrated to the DNA it already uses
+    assert_matches!(
+        conductor
+            .clone()
+            .migrate_cell(payload(new_dna.dna_hash()))
+            .await
+            .unwrap_err(),
+        ConductorError::CellMigrationError(_)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failing_migrate_agent_callback_leaves_chains_untouched() {
+    let (conductor, agent, old_dna, new_dna) =
+        setup(vec![TestWasm::MigrateAgentFail, TestWasm::Create]).await;
+    let old_cell_id = CellId::new(old_dna.dna_hash().clone(), agent.clone());
+    let head_before = chain_head(&conductor, &old_cell_id).await;
+
+    assert_matches!(
+        conductor
+            .clone()
+            .migrate_cell(payload(new_dna.dna_hash()))
+            .await
+            .unwrap_err(),
+        ConductorError::CellMigrationError(_)
+    );
+    assert_eq!(chain_head(&conductor, &old_cell_id).await, head_before);
+    assert!(!conductor
+        .running_cell_ids(None)
+        .contains(&CellId::new(new_dna.dna_hash().clone(), agent)));
+
+    // Only provisioned roles can be migrated
+    assert_matches!(
+        conductor
+            .clone()
+            .migrate_cell(MigrateCellPayload {
+                role_name: "missing".into(),
+                ..payload(new_dna.dna_hash())
+            })
+            .await
+            .unwrap_err(),
+        ConductorError::AppError(AppError::RoleNameMissing(_))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migration_which_failed_after_closing_old_chain_can_be_retried() {
+    let (conductor, agent, old_dna, new_dna) =
+        setup(vec![TestWasm::MigrateAgentPass, TestWasm::Create]).await;
+    let old_cell_id = CellId::new(old_dna.dna_hash().clone(), agent.clone());
+    let new_cell_id = CellId::new(new_dna.dna_hash().clone(), agent.clone());
+
+    // Close the old chain without opening the new one, as if the migration
+    // had stopped right after its close step
+    let ribosome = conductor.get_ribosome(old_dna.dna_hash()).unwrap();
+    let workspace = conductor
+        .get_or_create_space(old_dna.dna_hash())
+        .unwrap()
+        .source_chain_workspace(
+            conductor.keystore(),
+            agent.clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await
+        .unwrap();
+    let network = conductor
+        .holochain_p2p()
+        .to_dna(old_dna.dna_hash().clone(), None);
+    migrate_agent_workflow(
+        workspace,
+        network,
+        MigrateAgentWorkflowArgs::new(
+            ribosome,
+            conductor.raw_handle(),
+            MigrateAgent::Close,
+            new_dna.dna_hash().clone(),
+        ),
+    )
+    .await
+    .unwrap();
+
+    let migrated = conductor
+        .clone()
+        .migrate_cell(payload(new_dna.dna_hash()))
+        .await
+        .unwrap();
+    assert_eq!(migrated, new_cell_id);
+    assert_matches!(
+        chain_head(&conductor, &new_cell_id).await,
+        Action::OpenChain(open) if open.prev_dna_hash == *old_dna.dna_hash()
+    );
+
+    // The old chain was only closed once
+    let closes = conductor
+        .get_or_create_space(old_dna.dna_hash())
+        .unwrap()
+        .source_chain(conductor.keystore(), agent)
+        .await
+        .unwrap()
+        .query(ChainQueryFilter::new().action_type(ActionType::CloseChain))
+        .await
+        .unwrap();
+    assert_eq!(closes.len(), 1);
+}
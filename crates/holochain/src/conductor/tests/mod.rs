@@ -1,6 +1,7 @@
 mod app_info;
 mod cell_cloning;
 mod install_app_bundle;
+mod migrate_cell;
 mod network_info;
 mod request_dna_def;
 mod signed_zome_call;
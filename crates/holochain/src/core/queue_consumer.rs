@@ -85,6 +85,7 @@ pub async fn spawn_queue_consumer_tasks(
         dht_db,
         cache_db: cache,
         dht_query_cache,
+        dht_region_tree,
         ..
     } = space;
 
@@ -119,6 +120,7 @@ pub async fn spawn_queue_consumer_tasks(
             dna_hash.clone(),
             dht_db.clone(),
             dht_query_cache.clone(),
+            dht_region_tree.clone(),
             conductor.task_manager(),
             tx_receipt.clone(),
             network.clone(),
@@ -177,6 +179,7 @@ pub async fn spawn_queue_consumer_tasks(
             conductor.task_manager(),
             network.clone(),
             tx_sys.clone(),
+            conductor.clone(),
         )
     });
 
@@ -6,21 +6,32 @@ use crate::core::workflow::countersigning_workflow::countersigning_workflow;
 use tracing::*;
 
 /// Spawn the QueueConsumer for countersigning workflow
-#[instrument(skip(space, tm, dna_network, trigger_sys))]
+#[instrument(skip(space, tm, dna_network, trigger_sys, conductor))]
 pub(crate) fn spawn_countersigning_consumer(
     space: Space,
     tm: TaskManagerClient,
     dna_network: HolochainP2pDna,
     trigger_sys: TriggerSender,
+    conductor: ConductorHandle,
 ) -> TriggerSender {
-    let (tx, rx) = TriggerSender::new();
+    // Loop so that sessions which expire without a response from the
+    // authorities get resolved.
+    let (tx, rx) =
+        TriggerSender::new_with_loop(Duration::from_secs(10)..Duration::from_secs(60), true);
 
     super::queue_consumer_dna_bound(
         "countersigning_consumer",
         space.dna_hash.clone(),
         tm,
         (tx.clone(), rx),
-        move || countersigning_workflow(space.clone(), dna_network.clone(), trigger_sys.clone()),
+        move || {
+            countersigning_workflow(
+                space.clone(),
+                dna_network.clone(),
+                trigger_sys.clone(),
+                conductor.clone(),
+            )
+        },
     );
 
     tx
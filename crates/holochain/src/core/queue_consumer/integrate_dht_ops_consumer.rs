@@ -1,6 +1,7 @@
 //! The workflow and queue consumer for DhtOp integration
 
 use super::*;
+use crate::conductor::kitsune_host_impl::query_region_set::DhtRegionTree;
 use crate::conductor::manager::TaskManagerClient;
 use crate::core::workflow::integrate_dht_ops_workflow::integrate_dht_ops_workflow;
 use holochain_types::db_cache::DhtDbQueryCache;
@@ -8,11 +9,12 @@ use holochain_types::db_cache::DhtDbQueryCache;
 use tracing::*;
 
 /// Spawn the QueueConsumer for DhtOpIntegration workflow
-#[instrument(skip(env, trigger_receipt, tm, network, dht_query_cache))]
+#[instrument(skip(env, trigger_receipt, tm, network, dht_query_cache, dht_region_tree))]
 pub fn spawn_integrate_dht_ops_consumer(
     dna_hash: Arc<DnaHash>,
     env: DbWrite<DbKindDht>,
     dht_query_cache: DhtDbQueryCache,
+    dht_region_tree: DhtRegionTree,
     tm: TaskManagerClient,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
@@ -28,6 +30,7 @@ pub fn spawn_integrate_dht_ops_consumer(
             integrate_dht_ops_workflow(
                 env.clone(),
                 dht_query_cache.clone(),
+                dht_region_tree.clone(),
                 trigger_receipt.clone(),
                 network.clone(),
             )
@@ -23,8 +23,10 @@ use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::guest_callback::CallIterator;
 use derive_more::Constructor;
 use error::RibosomeResult;
@@ -32,7 +34,9 @@ use guest_callback::entry_defs::EntryDefsHostAccess;
 use guest_callback::init::InitHostAccess;
 use guest_callback::migrate_agent::MigrateAgentHostAccess;
 use guest_callback::post_commit::PostCommitHostAccess;
+use guest_callback::rate_limits::RateLimitsHostAccess;
 use guest_callback::validate::ValidateHostAccess;
+use guest_callback::weigh::WeighHostAccess;
 use holo_hash::AgentPubKey;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
@@ -102,7 +106,9 @@ pub enum HostContext {
     Init(InitHostAccess),
     MigrateAgent(MigrateAgentHostAccess),
     PostCommit(PostCommitHostAccess), // MAYBE: add emit_signal access here?
+    RateLimits(RateLimitsHostAccess),
     Validate(ValidateHostAccess),
+    Weigh(WeighHostAccess),
     ZomeCall(ZomeCallHostAccess),
 }
 
@@ -116,6 +122,8 @@ impl From<&HostContext> for HostFnAccess {
             HostContext::EntryDefs(access) => access.into(),
             HostContext::MigrateAgent(access) => access.into(),
             HostContext::PostCommit(access) => access.into(),
+            HostContext::RateLimits(access) => access.into(),
+            HostContext::Weigh(access) => access.into(),
         }
     }
 }
@@ -676,6 +684,21 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
         invocation: PostCommitInvocation,
     ) -> RibosomeResult<()>;
 
+    /// Weigh an action for rate limiting. Zomes which don't define a
+    /// `weigh` callback give every action the default weight.
+    fn run_weigh(
+        &self,
+        access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<RateWeight>;
+
+    /// Collect the rate limits declared by all integrity zomes.
+    fn run_rate_limits(
+        &self,
+        access: RateLimitsHostAccess,
+        invocation: RateLimitsInvocation,
+    ) -> RibosomeResult<Vec<RateLimit>>;
+
     /// Helper function for running a validation callback. Calls
     /// private fn `do_callback!` under the hood.
     fn run_validate(
@@ -695,9 +718,17 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     fn zome_types(&self) -> &Arc<GlobalZomeTypes>;
 }
 
-/// Placeholder for weighing. Currently produces zero weight.
-pub fn weigh_placeholder() -> EntryRateWeight {
-    EntryRateWeight::default()
+/// Weigh an unweighed action in the integrity zome which defines its type.
+/// Actions with no such zome, e.g. those for cap grants, get the default weight.
+pub fn weigh_action(
+    ribosome: &impl RibosomeT,
+    zome_index: Option<ZomeIndex>,
+    input: WeighInput,
+) -> RibosomeResult<RateWeight> {
+    match zome_index.and_then(|zome_index| ribosome.get_integrity_zome(&zome_index)) {
+        Some(zome) => ribosome.run_weigh(WeighHostAccess, WeighInvocation::new(zome, input)),
+        None => Ok(RateWeight::default()),
+    }
 }
 
 #[cfg(test)]
@@ -39,6 +39,10 @@ pub enum RibosomeError {
     #[error("An error with entry defs in zome '{0}': {1}")]
     EntryDefs(ZomeName, String),
 
+    /// a zome declared a rate limit which can't be enforced
+    #[error("Invalid rate limit in zome '{0}': {1}")]
+    RateLimits(ZomeName, String),
+
     /// a mandatory dependency for a record doesn't exist
     /// for example a remove link ribosome call needs to find the add link in order to infer the
     /// correct base and this dependent relationship exists before even subconscious validation
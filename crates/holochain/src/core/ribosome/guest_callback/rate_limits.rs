@@ -0,0 +1,153 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct RateLimitsInvocation;
+
+impl RateLimitsInvocation {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[derive(Clone, Constructor, Debug)]
+pub struct RateLimitsHostAccess;
+
+impl From<RateLimitsHostAccess> for HostContext {
+    fn from(rate_limits_host_access: RateLimitsHostAccess) -> Self {
+        Self::RateLimits(rate_limits_host_access)
+    }
+}
+
+impl From<&RateLimitsHostAccess> for HostFnAccess {
+    fn from(_: &RateLimitsHostAccess) -> Self {
+        Self::none()
+    }
+}
+
+impl Invocation for RateLimitsInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::AllIntegrity
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["rate_limits".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(())
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
+
+/// The longest a full bucket may take to drain.
+/// Checking a rate limit walks back over the author's actions for this long.
+pub const MAX_RATE_LIMIT_DRAIN_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+/// How many seconds a full bucket takes to drain completely.
+/// Declared limits always drain, see [`check_declared_rate_limit`].
+pub fn full_drain_seconds(limit: &RateLimit) -> i64 {
+    let drain = limit.drain_per_second.max(1) as i64;
+    (limit.capacity as i64 + drain - 1) / drain
+}
+
+/// Check a limit declared by a `rate_limits` callback can be enforced
+/// without walking an unbounded part of an author's chain: the bucket
+/// must drain, and not take longer than [`MAX_RATE_LIMIT_DRAIN_SECONDS`]
+/// to empty.
+pub fn check_declared_rate_limit(limit: &RateLimit) -> Result<(), String> {
+    if limit.drain_per_second == 0 {
+        Err(format!("bucket {} never drains", limit.bucket_id))
+    } else if full_drain_seconds(limit) > MAX_RATE_LIMIT_DRAIN_SECONDS {
+        Err(format!(
+            "bucket {} takes {} seconds to drain, more than the maximum of {}",
+            limit.bucket_id,
+            full_drain_seconds(limit),
+            MAX_RATE_LIMIT_DRAIN_SECONDS
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Combine the limits declared by every integrity zome into one limit per
+/// bucket. If several zomes limit the same bucket, the strictest values win.
+pub fn merge_rate_limits(limits: impl IntoIterator<Item = RateLimit>) -> Vec<RateLimit> {
+    let mut merged: BTreeMap<RateBucketId, RateLimit> = BTreeMap::new();
+    for limit in limits {
+        merged
+            .entry(limit.bucket_id)
+            .and_modify(|existing| {
+                existing.capacity = existing.capacity.min(limit.capacity);
+                existing.drain_per_second = existing.drain_per_second.min(limit.drain_per_second);
+            })
+            .or_insert(limit);
+    }
+    merged.into_values().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::check_declared_rate_limit;
+    use super::merge_rate_limits;
+    use super::MAX_RATE_LIMIT_DRAIN_SECONDS;
+    use holochain_types::prelude::*;
+
+    #[test]
+    fn strictest_rate_limit_wins() {
+        let limits = merge_rate_limits([
+            RateLimit {
+                bucket_id: 1,
+                capacity: 100,
+                drain_per_second: 1,
+            },
+            RateLimit {
+                bucket_id: 0,
+                capacity: 10,
+                drain_per_second: 10,
+            },
+            RateLimit {
+                bucket_id: 1,
+                capacity: 200,
+                drain_per_second: 2,
+            },
+        ]);
+        assert_eq!(
+            limits,
+            vec![
+                RateLimit {
+                    bucket_id: 0,
+                    capacity: 10,
+                    drain_per_second: 10,
+                },
+                RateLimit {
+                    bucket_id: 1,
+                    capacity: 100,
+                    drain_per_second: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rate_limits_must_drain_in_time() {
+        let limit = |capacity, drain_per_second| RateLimit {
+            bucket_id: 0,
+            capacity,
+            drain_per_second,
+        };
+        assert!(check_declared_rate_limit(&limit(100, 1)).is_ok());
+        assert!(check_declared_rate_limit(&limit(100, 0)).is_err());
+        let max = MAX_RATE_LIMIT_DRAIN_SECONDS as RateBucketCapacity;
+        assert!(check_declared_rate_limit(&limit(max, 1)).is_ok());
+        assert!(check_declared_rate_limit(&limit(max + 1, 1)).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+
+/// Weigh an unweighed action in the integrity zome which defines its type.
+#[derive(Clone, Debug, Constructor)]
+pub struct WeighInvocation {
+    zome: IntegrityZome,
+    input: WeighInput,
+}
+
+#[derive(Clone, Constructor, Debug)]
+pub struct WeighHostAccess;
+
+impl From<WeighHostAccess> for HostContext {
+    fn from(weigh_host_access: WeighHostAccess) -> Self {
+        Self::Weigh(weigh_host_access)
+    }
+}
+
+impl From<&WeighHostAccess> for HostFnAccess {
+    fn from(_: &WeighHostAccess) -> Self {
+        // App validation weighs actions again to check the weight their
+        // author stamped on them, so weighing must be a pure function of
+        // the action.
+        Self::none()
+    }
+}
+
+impl Invocation for WeighInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::OneIntegrity(self.zome.clone())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["weigh".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(self.input)
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
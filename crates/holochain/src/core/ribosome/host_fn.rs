@@ -80,14 +80,14 @@ host_fn_api_impls! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the capability claims on the local source chain.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List the capability grants on the local source chain.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<zt::capability::CapGrantInfo>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability which authorized the current zome call.
+    fn capability_info (()) -> zt::capability::CapabilityInfo;
 
     // The EntryDefId determines how a create is handled on the host side.
     // CapGrant and CapClaim are handled natively.
@@ -147,11 +147,17 @@ host_fn_api_impls! {
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
 
+    // Get the metadata for an entry or action without the record itself.
+    fn get_meta (Vec<zt::entry::GetInput>) -> Vec<zt::metadata::MetadataSet>;
+
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
 
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
+    // Count the live links matching a query without fetching them.
+    fn count_links (zt::link::LinkQuery) -> usize;
+
     // Hash data on the host.
     fn hash (zt::hash::HashInput) -> zt::hash::HashOutput;
 
@@ -181,7 +187,7 @@ host_fn_api_impls! {
     // @todo
     fn schedule (String) -> ();
 
-    // @todo
+    // Suspend the zome call, up to the conductor's maximum sleep.
     fn sleep (core::time::Duration) -> ();
 
     // @todo
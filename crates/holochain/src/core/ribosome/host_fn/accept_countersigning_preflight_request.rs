@@ -121,6 +121,7 @@ pub mod wasm_test {
     use crate::test_utils::consistency_10s;
     use hdk::prelude::*;
     use holochain_state::source_chain::SourceChainError;
+    use holochain_types::countersigning::CountersigningSessionResolution;
     use holochain_zome_types::zome_io::ZomeCallUnsigned;
     use holochain_wasm_test_utils::TestWasm;
     use holochain_wasmer_host::prelude::*;
@@ -363,6 +364,115 @@ pub mod wasm_test {
         ).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "slow_tests")]
+    async fn resolve_stuck_session() {
+        holochain_trace::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            alice_pubkey,
+            bob,
+            bob_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::CounterSigning).await;
+        let handle = conductor.raw_handle();
+
+        let preflight_request: PreflightRequest = conductor
+            .call(
+                &alice,
+                "generate_countersigning_preflight_request",
+                vec![
+                    (alice_pubkey.clone(), vec![Role(0)]),
+                    (bob_pubkey.clone(), vec![]),
+                ],
+            )
+            .await;
+        let mut responses = vec![];
+        for zome in [&alice, &bob] {
+            let acceptance: PreflightRequestAcceptance = conductor
+                .call(
+                    zome,
+                    "accept_countersigning_preflight_request",
+                    preflight_request.clone(),
+                )
+                .await;
+            match acceptance {
+                PreflightRequestAcceptance::Accepted(response) => responses.push(response),
+                _ => unreachable!(),
+            }
+        }
+
+        // Only Alice commits, so the session can't complete.
+        let countersigned_action_hash_alice: ActionHash = conductor
+            .call(&alice, "create_a_countersigned_thing", responses)
+            .await;
+
+        let mut sessions = handle.list_countersigning_sessions().await.unwrap();
+        sessions.sort_by_key(|(cell_id, _)| cell_id.agent_pubkey() != &alice_pubkey);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(
+            sessions[0].1.action_hash,
+            Some(countersigned_action_hash_alice.clone())
+        );
+        assert_eq!(sessions[1].1.action_hash, None);
+
+        // Alice's chain is locked while the session is in progress.
+        let result: Result<ActionHash, _> =
+            conductor.call_fallible(&alice, "create_a_thing", ()).await;
+        match result {
+            Err(ConductorApiError::CellError(CellError::WorkflowError(workflow_error))) => {
+                assert!(matches!(
+                    *workflow_error,
+                    WorkflowError::SourceChainError(SourceChainError::ChainLocked)
+                ))
+            }
+            something_else => panic!("{:?}", something_else),
+        }
+
+        // Bob's agent activity says nothing about the session yet.
+        assert_eq!(
+            handle
+                .resolve_countersigning_session(alice.cell_id(), None)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // Alice gives up on the session.
+        assert_eq!(
+            handle
+                .resolve_countersigning_session(
+                    alice.cell_id(),
+                    Some(CountersigningSessionResolution::Abandoned),
+                )
+                .await
+                .unwrap(),
+            Some(CountersigningSessionResolution::Abandoned)
+        );
+        let sessions = handle.list_countersigning_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].0, *bob.cell_id());
+
+        // The countersigned action is gone and Alice can commit again.
+        let alice_activity: AgentActivity = conductor
+            .call(
+                &alice,
+                "get_agent_activity",
+                GetAgentActivityInput {
+                    agent_pubkey: alice_pubkey.clone(),
+                    chain_query_filter: ChainQueryFilter::new(),
+                    activity_request: ActivityRequest::Full,
+                },
+            )
+            .await;
+        assert!(!alice_activity
+            .valid_activity
+            .iter()
+            .any(|(_, hash)| *hash == countersigned_action_hash_alice));
+        let _: ActionHash = conductor.call(&alice, "create_a_thing", ()).await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[cfg(feature = "slow_tests")]
     async fn unlock_invalid_session() {
@@ -1,14 +1,179 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
 /// return the access info used for this call
 /// also return who is originated the call (pubkey)
 pub fn capability_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+) -> Result<CapabilityInfo, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            bindings: Permission::Allow,
+            ..
+        } => {
+            let check_function = (
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+            );
+            let auth = call_context.auth();
+            tokio_helper::block_forever_on(async move {
+                let workspace = call_context.host_context.workspace();
+                let source_chain = workspace
+                    .source_chain()
+                    .as_ref()
+                    .expect("Must have source chain if bindings access is given");
+                let (provenance, cap_secret) = match auth {
+                    InvocationAuth::Cap(provenance, cap_secret) => (provenance, cap_secret),
+                    InvocationAuth::LocalCallback => {
+                        return Ok(CapabilityInfo {
+                            provenance: source_chain.agent_pubkey().clone(),
+                            grant: None,
+                        })
+                    }
+                };
+                let cap_grant = source_chain
+                    .valid_cap_grant(check_function, provenance.clone(), cap_secret)
+                    .await
+                    .map_err(|e| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(e.to_string())).into()
+                    })?
+                    // As in `call_info`, the host must never run a call
+                    // without a valid grant so this is a critical bug.
+                    .expect(
+                        "The host is using an unauthorized cap_secret, which should never happen",
+                    );
+                let grant = match cap_grant {
+                    CapGrant::ChainAuthor(_) => None,
+                    CapGrant::RemoteAgent(zome_call_cap_grant) => Some(
+                        source_chain
+                            .cap_grants(CapGrantQuery::new().include_revoked(true))
+                            .await
+                            .map_err(|e| -> RuntimeError {
+                                wasm_error!(WasmErrorInner::Host(e.to_string())).into()
+                            })?
+                            .into_iter()
+                            .find(|info| info.cap_grant == zome_call_cap_grant)
+                            .ok_or_else(|| -> RuntimeError {
+                                wasm_error!(WasmErrorInner::Host(
+                                    "The capability grant for this call is not on the chain"
+                                        .to_string()
+                                ))
+                                .into()
+                            })?,
+                    ),
+                };
+                Ok(CapabilityInfo { provenance, grant })
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_info".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sweettest::*;
+    use holochain_types::inline_zome::InlineZomeSet;
+    use holochain_types::prelude::*;
+
+    fn capability_zome() -> InlineZomeSet {
+        SweetInlineZomes::new(vec![], 0)
+            .function("grant_whoami", |api, secret: CapSecret| {
+                let functions = GrantedFunctions::Listed(
+                    [(SweetInlineZomes::COORDINATOR.into(), "whoami".into())]
+                        .into_iter()
+                        .collect(),
+                );
+                let hash = api.create(CreateInput::new(
+                    EntryDefLocation::CapGrant,
+                    EntryVisibility::Private,
+                    Entry::CapGrant(ZomeCallCapGrant::new(
+                        "whoami".into(),
+                        secret.into(),
+                        functions,
+                    )),
+                    ChainTopOrdering::default(),
+                ))?;
+                Ok(hash)
+            })
+            .function("whoami", |api, ()| Ok(api.capability_info(())?))
+            .function(
+                "call_whoami",
+                |api, (agent, secret): (AgentPubKey, CapSecret)| {
+                    Ok(api
+                        .call(vec![Call::new(
+                            CallTarget::NetworkAgent(agent),
+                            SweetInlineZomes::COORDINATOR.into(),
+                            "whoami".into(),
+                            Some(secret),
+                            ExternIO::encode(()).unwrap(),
+                        )])?
+                        .pop()
+                        .unwrap())
+                },
+            )
+            .function("grants", |api, query: CapGrantQuery| {
+                Ok(api.capability_grants(query)?)
+            })
+            .0
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn capability_info_names_the_authorizing_grant() {
+        holochain_trace::test_run().ok();
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(capability_zome()).await;
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let ((alice,), (bob,)) = conductors
+            .setup_app("app", &[dna_file])
+            .await
+            .unwrap()
+            .into_tuples();
+        conductors.exchange_peer_info().await;
+        let alice_zome = alice.zome(SweetInlineZomes::COORDINATOR);
+        let bob_zome = bob.zome(SweetInlineZomes::COORDINATOR);
+
+        // The chain author needs no grant.
+        let info: CapabilityInfo = conductors[0].call(&alice_zome, "whoami", ()).await;
+        assert_eq!(info.provenance, *alice.agent_pubkey());
+        assert_eq!(info.grant, None);
+
+        let secret = CapSecret::from([1; CAP_SECRET_BYTES]);
+        let grant_hash: ActionHash = conductors[0]
+            .call(&alice_zome, "grant_whoami", secret)
+            .await;
+        let grants: Vec<CapGrantInfo> = conductors[0]
+            .call(&alice_zome, "grants", CapGrantQuery::new().tag("whoami"))
+            .await;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].action_hash, grant_hash);
+
+        let response: ZomeCallResponse = conductors[1]
+            .call(
+                &bob_zome,
+                "call_whoami",
+                (alice.agent_pubkey().clone(), secret),
+            )
+            .await;
+        let info: CapabilityInfo = match response {
+            ZomeCallResponse::Ok(io) => io.decode().unwrap(),
+            other => panic!("unexpected response {:?}", other),
+        };
+        assert_eq!(info.provenance, *bob.agent_pubkey());
+        assert_eq!(info.grant, Some(grants[0].clone()));
+    }
 }
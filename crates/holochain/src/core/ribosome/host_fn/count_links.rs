@@ -0,0 +1,146 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetLinksOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn count_links<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    query: LinkQuery,
+) -> Result<usize, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let count = tokio_helper::block_forever_on(async move {
+                Cascade::from_workspace_and_network(
+                    &call_context.host_context.workspace(),
+                    call_context.host_context.network().to_owned(),
+                )
+                .dht_count_links(WireLinkKey::from(query), GetLinksOptions::default())
+                .await
+            })
+            .map_err(|cascade_error| {
+                wasm_error!(WasmErrorInner::Host(cascade_error.to_string()))
+            })?;
+            Ok(count)
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "count_links".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sweettest::*;
+    use crate::test_utils::consistency_10s;
+    use holochain_types::inline_zome::InlineZomeSet;
+    use holochain_types::prelude::*;
+
+    fn count_zome() -> InlineZomeSet {
+        SweetInlineZomes::new(vec![], 1)
+            .function(
+                "create_link",
+                |api, (base, target, tag): (AnyLinkableHash, AnyLinkableHash, Vec<u8>)| {
+                    let hash = api.create_link(CreateLinkInput::new(
+                        base,
+                        target,
+                        ZomeIndex(0),
+                        LinkType::new(0),
+                        tag.into(),
+                        ChainTopOrdering::default(),
+                    ))?;
+                    Ok(hash)
+                },
+            )
+            .function("delete_link", |api, address: ActionHash| {
+                let hash =
+                    api.delete_link(DeleteLinkInput::new(address, ChainTopOrdering::default()))?;
+                Ok(hash)
+            })
+            .function("count_links", |api, query: LinkQuery| {
+                Ok(api.count_links(query)?)
+            })
+            .0
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn count_links_from_authorities() {
+        holochain_trace::test_run().ok();
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(count_zome()).await;
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let ((alice,), (bob,)) = conductors
+            .setup_app("app", &[dna_file])
+            .await
+            .unwrap()
+            .into_tuples();
+        conductors.exchange_peer_info().await;
+        let alice_zome = alice.zome(SweetInlineZomes::COORDINATOR);
+        let bob_zome = bob.zome(SweetInlineZomes::COORDINATOR);
+
+        let base = AnyLinkableHash::from(EntryHash::from_raw_36(vec![1; 36]));
+        let target = AnyLinkableHash::from(EntryHash::from_raw_36(vec![2; 36]));
+        let mut links = Vec::new();
+        for tag in [b"a1".to_vec(), b"a2".to_vec(), b"b1".to_vec()] {
+            let link: ActionHash = conductors[0]
+                .call(
+                    &alice_zome,
+                    "create_link",
+                    (base.clone(), target.clone(), tag),
+                )
+                .await;
+            links.push(link);
+        }
+        let _: ActionHash = conductors[0]
+            .call(&alice_zome, "delete_link", links[1].clone())
+            .await;
+        let _: ActionHash = conductors[1]
+            .call(
+                &bob_zome,
+                "create_link",
+                (base.clone(), target, b"a3".to_vec()),
+            )
+            .await;
+
+        consistency_10s([&alice, &bob]).await;
+
+        let all = LinkQuery::new(base.clone(), LinkTypeFilter::single_dep(0.into()));
+        let count: usize = conductors[1]
+            .call(&bob_zome, "count_links", all.clone())
+            .await;
+        assert_eq!(count, 3);
+
+        let count: usize = conductors[1]
+            .call(
+                &bob_zome,
+                "count_links",
+                all.clone().tag_prefix(b"a".to_vec().into()),
+            )
+            .await;
+        assert_eq!(count, 2);
+
+        let count: usize = conductors[0]
+            .call(
+                &alice_zome,
+                "count_links",
+                all.author(alice.agent_pubkey().clone()),
+            )
+            .await;
+        assert_eq!(count, 2);
+    }
+}
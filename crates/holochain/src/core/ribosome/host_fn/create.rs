@@ -1,4 +1,4 @@
-use crate::core::ribosome::weigh_placeholder;
+use crate::core::ribosome::weigh_action;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
@@ -11,7 +11,7 @@ use std::sync::Arc;
 /// create record
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -27,9 +27,9 @@ pub fn create<'a>(
                 chain_top_ordering,
             } = input;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
+            // Every party rebuilds the session's actions from the session
+            // data alone, so they carry the default weight.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
                     call_context
@@ -38,7 +38,7 @@ pub fn create<'a>(
                         .source_chain()
                         .as_ref()
                         .expect("Must have source chain if write_workspace access is given")
-                        .put_countersigned(entry, chain_top_ordering, weight)
+                        .put_countersigned(entry, chain_top_ordering, Default::default())
                         .await
                         .map_err(|source_chain_error| -> RuntimeError {
                             wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
@@ -49,17 +49,17 @@ pub fn create<'a>(
                     let entry_hash = EntryHash::with_data_sync(&entry);
 
                     // extract the entry defs for a zome
-                    let entry_type = match entry_location {
+                    let (entry_type, zome_index) = match entry_location {
                         EntryDefLocation::App(AppEntryDefLocation {
                             zome_index,
                             entry_def_index,
                         }) => {
                             let app_entry_def =
                                 AppEntryDef::new(entry_def_index, zome_index, entry_visibility);
-                            EntryType::App(app_entry_def)
+                            (EntryType::App(app_entry_def), Some(zome_index))
                         }
-                        EntryDefLocation::CapGrant => EntryType::CapGrant,
-                        EntryDefLocation::CapClaim => EntryType::CapClaim,
+                        EntryDefLocation::CapGrant => (EntryType::CapGrant, None),
+                        EntryDefLocation::CapClaim => (EntryType::CapClaim, None),
                     };
 
                     // build an action for the entry being committed
@@ -67,6 +67,27 @@ pub fn create<'a>(
                         entry_type,
                         entry_hash,
                     };
+                    let action = call_context
+                        .host_context
+                        .workspace_write()
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given")
+                        .next_action(action_builder)
+                        .map_err(|source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        })?;
+
+                    // weigh the action for rate limiting
+                    let weight = weigh_action(
+                        ribosome.as_ref(),
+                        zome_index,
+                        WeighInput::Create(action.clone(), entry.clone()),
+                    )
+                    .map_err(|ribosome_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                    })?
+                    .with_entry(&entry);
 
                     // return the hash of the committed entry
                     // note that validation is handled by the workflow
@@ -80,7 +101,11 @@ pub fn create<'a>(
                             .source_chain()
                             .as_ref()
                             .expect("Must have source chain if write_workspace access is given")
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                            .put_with_action(
+                                action.weighed(weight).into(),
+                                Some(entry),
+                                chain_top_ordering,
+                            )
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
@@ -0,0 +1,209 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::future::join_all;
+use holochain_cascade::Cascade;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_meta<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<GetInput>,
+) -> Result<Vec<MetadataSet>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let results: Vec<Result<MetadataSet, _>> = tokio_helper::block_forever_on(async move {
+                join_all(inputs.into_iter().map(|input| async {
+                    let GetInput {
+                        any_dht_hash,
+                        get_options,
+                    } = input;
+                    Cascade::from_workspace_and_network(
+                        &call_context.host_context.workspace(),
+                        call_context.host_context.network().to_owned(),
+                    )
+                    .get_meta(any_dht_hash, get_options)
+                    .await
+                }))
+                .await
+            });
+            let results: Result<Vec<_>, _> = results
+                .into_iter()
+                .map(|result| {
+                    result.map_err(|cascade_error| {
+                        wasm_error!(WasmErrorInner::Host(cascade_error.to_string()))
+                    })
+                })
+                .collect();
+            Ok(results?)
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_meta".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sweettest::*;
+    use crate::test_utils::consistency_10s;
+    use crate::test_utils::inline_zomes::AppString;
+    use holochain_types::inline_zome::InlineZomeSet;
+    use holochain_types::prelude::*;
+
+    fn meta_zome() -> InlineZomeSet {
+        SweetInlineZomes::new(vec![EntryDef::from_id("string")], 1)
+            .function("create_string", |api, s: AppString| {
+                let entry = Entry::app(s.try_into().unwrap()).unwrap();
+                let hash = api.create(CreateInput::new(
+                    InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                    EntryVisibility::Public,
+                    entry,
+                    ChainTopOrdering::default(),
+                ))?;
+                Ok(hash)
+            })
+            .function(
+                "update_string",
+                |api, (original_action_address, s): (ActionHash, AppString)| {
+                    let hash = api.update(UpdateInput {
+                        original_action_address,
+                        entry: Entry::app(s.try_into().unwrap()).unwrap(),
+                        chain_top_ordering: ChainTopOrdering::default(),
+                    })?;
+                    Ok(hash)
+                },
+            )
+            .function("delete", |api, action_hash: ActionHash| {
+                let hash =
+                    api.delete(DeleteInput::new(action_hash, ChainTopOrdering::default()))?;
+                Ok(hash)
+            })
+            .function(
+                "create_link",
+                |api, (base, target): (AnyLinkableHash, AnyLinkableHash)| {
+                    let hash = api.create_link(CreateLinkInput::new(
+                        base,
+                        target,
+                        ZomeIndex(0),
+                        LinkType::new(0),
+                        ().into(),
+                        ChainTopOrdering::default(),
+                    ))?;
+                    Ok(hash)
+                },
+            )
+            .function("delete_link", |api, address: ActionHash| {
+                let hash =
+                    api.delete_link(DeleteLinkInput::new(address, ChainTopOrdering::default()))?;
+                Ok(hash)
+            })
+            .function("get_meta", |api, hash: AnyDhtHash| {
+                Ok(api
+                    .get_meta(vec![GetInput::new(hash, GetOptions::default())])?
+                    .pop()
+                    .unwrap())
+            })
+            .0
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_meta_from_authorities() {
+        holochain_trace::test_run().ok();
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(meta_zome()).await;
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let ((alice,), (bob,)) = conductors
+            .setup_app("app", &[dna_file])
+            .await
+            .unwrap()
+            .into_tuples();
+        conductors.exchange_peer_info().await;
+        let alice_zome = alice.zome(SweetInlineZomes::COORDINATOR);
+
+        let create: ActionHash = conductors[0]
+            .call(&alice_zome, "create_string", AppString::new("one"))
+            .await;
+        let entry_hash = EntryHash::with_data_sync(
+            &Entry::app(AppString::new("one").try_into().unwrap()).unwrap(),
+        );
+        let update: ActionHash = conductors[0]
+            .call(
+                &alice_zome,
+                "update_string",
+                (create.clone(), AppString::new("two")),
+            )
+            .await;
+        let link: ActionHash = conductors[0]
+            .call(
+                &alice_zome,
+                "create_link",
+                (
+                    AnyLinkableHash::from(create.clone()),
+                    AnyLinkableHash::from(update.clone()),
+                ),
+            )
+            .await;
+        let deleted_link: ActionHash = conductors[0]
+            .call(
+                &alice_zome,
+                "create_link",
+                (
+                    AnyLinkableHash::from(create.clone()),
+                    AnyLinkableHash::from(entry_hash.clone()),
+                ),
+            )
+            .await;
+        let _: ActionHash = conductors[0]
+            .call(&alice_zome, "delete_link", deleted_link)
+            .await;
+        let delete: ActionHash = conductors[0]
+            .call(&alice_zome, "delete", create.clone())
+            .await;
+
+        consistency_10s([&alice, &bob]).await;
+
+        let hashes = |meta: &std::collections::BTreeSet<TimedActionHash>| {
+            meta.iter()
+                .map(|t| t.action_hash.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let meta: MetadataSet = conductors[1]
+            .call(
+                &bob.zome(SweetInlineZomes::COORDINATOR),
+                "get_meta",
+                AnyDhtHash::from(create.clone()),
+            )
+            .await;
+        assert_eq!(hashes(&meta.actions), vec![create.clone()]);
+        assert_eq!(hashes(&meta.updates), vec![update]);
+        assert_eq!(hashes(&meta.deletes), vec![delete]);
+        assert_eq!(hashes(&meta.links), vec![link]);
+        assert_eq!(meta.validation_status, Some(ValidationStatus::Valid));
+        assert_eq!(meta.entry_dht_status, None);
+
+        let meta: MetadataSet = conductors[1]
+            .call(
+                &bob.zome(SweetInlineZomes::COORDINATOR),
+                "get_meta",
+                AnyDhtHash::from(entry_hash),
+            )
+            .await;
+        assert_eq!(hashes(&meta.actions), vec![create]);
+        assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Dead));
+        assert_eq!(meta.validation_status, None);
+    }
+}
@@ -1,12 +1,105 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use crate::core::ribosome::ZomeCallHostAccess;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
+/// Suspend the zome call for the given duration, cut short to the maximum
+/// set in the conductor config.
+///
+/// Zome calls run on a blocking thread of their own and nothing in the
+/// workspace is locked between host calls, so a sleeping call holds up
+/// neither the async executor nor other calls writing to the same chain.
+/// Only zome calls may sleep. Callbacks run while the conductor is waiting
+/// on them, e.g. `init` holds the cell's init lock.
 pub fn sleep(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    call_context: Arc<CallContext>,
+    input: core::time::Duration,
 ) -> Result<(), RuntimeError> {
-    unimplemented!()
+    match call_context.host_context() {
+        HostContext::ZomeCall(ZomeCallHostAccess {
+            call_zome_handle, ..
+        }) => {
+            let wake_after = input.min(call_zome_handle.max_zome_sleep());
+            tokio_helper::block_forever_on(tokio::time::sleep(wake_after));
+            Ok(())
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "sleep".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sweettest::*;
+    use crate::test_utils::inline_zomes::AppString;
+    use holochain_types::inline_zome::InlineZomeSet;
+    use holochain_types::prelude::*;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    fn sleep_zome() -> InlineZomeSet {
+        SweetInlineZomes::new(vec![EntryDef::from_id("string")], 0)
+            .function("sleep", |api, wake_after: Duration| {
+                api.sleep(wake_after)?;
+                Ok(())
+            })
+            .function("create_string", |api, s: AppString| {
+                let entry = Entry::app(s.try_into().unwrap()).unwrap();
+                let hash = api.create(CreateInput::new(
+                    InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                    EntryVisibility::Public,
+                    entry,
+                    ChainTopOrdering::default(),
+                ))?;
+                Ok(hash)
+            })
+            .0
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sleep_is_bounded_and_does_not_block_the_chain() {
+        holochain_trace::test_run().ok();
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(sleep_zome()).await;
+        let mut config = SweetConductorConfig::standard().no_networking();
+        config.max_zome_sleep_ms = Some(1_000);
+        let mut conductor = SweetConductor::from_config(config).await;
+        let app = conductor.setup_app("app", [&dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome(SweetInlineZomes::COORDINATOR);
+
+        // A short sleep runs to completion.
+        let start = Instant::now();
+        conductor
+            .call::<_, (), _>(&zome, "sleep", Duration::from_millis(200))
+            .await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        // A long sleep is cut short to the configured maximum, and the chain
+        // can be written to by other calls in the meantime.
+        let start = Instant::now();
+        let sleeper = conductor.call::<_, (), _>(&zome, "sleep", Duration::from_secs(60));
+        let writer = async {
+            let _: ActionHash = conductor
+                .call(&zome, "create_string", AppString::new("awake"))
+                .await;
+            start.elapsed()
+        };
+        let ((), written_after) = futures::future::join(sleeper, writer).await;
+        let slept = start.elapsed();
+        assert!(written_after < Duration::from_millis(1_000));
+        assert!(slept >= Duration::from_millis(1_000));
+        assert!(slept < Duration::from_secs(30));
+    }
 }
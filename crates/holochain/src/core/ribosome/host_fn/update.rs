@@ -1,5 +1,5 @@
 use super::delete::get_original_entry_data;
-use crate::core::ribosome::weigh_placeholder;
+use crate::core::ribosome::weigh_action;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
@@ -11,7 +11,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn update<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: UpdateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,9 +30,9 @@ pub fn update<'a>(
             let (original_entry_address, entry_type) =
                 get_original_entry_data(call_context.clone(), original_action_address.clone())?;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
+            // Every party rebuilds the session's actions from the session
+            // data alone, so they carry the default weight.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
                     call_context
@@ -41,7 +41,7 @@ pub fn update<'a>(
                         .source_chain()
                         .as_ref()
                         .expect("Must have source chain if write_workspace access is given")
-                        .put_countersigned(entry, chain_top_ordering, weight)
+                        .put_countersigned(entry, chain_top_ordering, Default::default())
                         .await
                         .map_err(|source_chain_error| -> RuntimeError {
                             wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
@@ -52,6 +52,10 @@ pub fn update<'a>(
                     let entry_hash = EntryHash::with_data_sync(&entry);
 
                     // build an action for the entry being updated
+                    let zome_index = match &entry_type {
+                        EntryType::App(app_entry_def) => Some(app_entry_def.zome_index()),
+                        _ => None,
+                    };
                     let action_builder = builder::Update {
                         original_entry_address,
                         original_action_address,
@@ -59,6 +63,25 @@ pub fn update<'a>(
                         entry_hash,
                     };
                     let workspace = call_context.host_context.workspace_write();
+                    let action = workspace
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given")
+                        .next_action(action_builder)
+                        .map_err(|source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        })?;
+
+                    // weigh the action for rate limiting
+                    let weight = weigh_action(
+                        ribosome.as_ref(),
+                        zome_index,
+                        WeighInput::Update(action.clone(), entry.clone()),
+                    )
+                    .map_err(|ribosome_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                    })?
+                    .with_entry(&entry);
 
                     // return the hash of the updated entry
                     // note that validation is handled by the workflow
@@ -71,7 +94,11 @@ pub fn update<'a>(
                             .expect("Must have source chain if write_workspace access is given");
                         // push the action and the entry into the source chain
                         let action_hash = source_chain
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                            .put_with_action(
+                                action.weighed(weight).into(),
+                                Some(entry),
+                                chain_top_ordering,
+                            )
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
@@ -2,7 +2,13 @@ use super::guest_callback::entry_defs::EntryDefsHostAccess;
 use super::guest_callback::init::InitHostAccess;
 use super::guest_callback::migrate_agent::MigrateAgentHostAccess;
 use super::guest_callback::post_commit::PostCommitHostAccess;
+use super::guest_callback::rate_limits::check_declared_rate_limit;
+use super::guest_callback::rate_limits::merge_rate_limits;
+use super::guest_callback::rate_limits::RateLimitsHostAccess;
+use super::guest_callback::rate_limits::RateLimitsInvocation;
 use super::guest_callback::validate::ValidateHostAccess;
+use super::guest_callback::weigh::WeighHostAccess;
+use super::guest_callback::weigh::WeighInvocation;
 use super::host_fn::get_agent_activity::get_agent_activity;
 use super::host_fn::HostFnApi;
 use super::HostContext;
@@ -30,6 +36,7 @@ use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::count_links::count_links;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
@@ -41,6 +48,7 @@ use crate::core::ribosome::host_fn::get::get;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
+use crate::core::ribosome::host_fn::get_meta::get_meta;
 use crate::core::ribosome::host_fn::hash::hash;
 use crate::core::ribosome::host_fn::must_get_action::must_get_action;
 use crate::core::ribosome::host_fn::must_get_agent_activity::must_get_agent_activity;
@@ -609,8 +617,10 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__hc__capability_info_1", capability_info)
             .with_host_function(&mut ns, "__hc__get_1", get)
             .with_host_function(&mut ns, "__hc__get_details_1", get_details)
+            .with_host_function(&mut ns, "__hc__get_meta_1", get_meta)
             .with_host_function(&mut ns, "__hc__get_links_1", get_links)
             .with_host_function(&mut ns, "__hc__get_link_details_1", get_link_details)
+            .with_host_function(&mut ns, "__hc__count_links_1", count_links)
             .with_host_function(&mut ns, "__hc__get_agent_activity_1", get_agent_activity)
             .with_host_function(&mut ns, "__hc__must_get_entry_1", must_get_entry)
             .with_host_function(&mut ns, "__hc__must_get_action_1", must_get_action)
@@ -946,6 +956,41 @@ impl RibosomeT for RealRibosome {
         }
     }
 
+    fn run_weigh(
+        &self,
+        host_access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<RateWeight> {
+        match self.call_iterator(host_access.into(), invocation).next() {
+            Ok(Some((_zome, extern_io))) => Ok(extern_io.decode()?),
+            Ok(None) => Ok(RateWeight::default()),
+            Err((_zome, ribosome_error)) => Err(ribosome_error),
+        }
+    }
+
+    fn run_rate_limits(
+        &self,
+        host_access: RateLimitsHostAccess,
+        invocation: RateLimitsInvocation,
+    ) -> RibosomeResult<Vec<RateLimit>> {
+        let mut limits = Vec::new();
+        let mut call_iterator = self.call_iterator(host_access.into(), invocation);
+        loop {
+            match call_iterator.next() {
+                Ok(Some((zome, extern_io))) => {
+                    for limit in extern_io.decode::<Vec<RateLimit>>()? {
+                        check_declared_rate_limit(&limit)
+                            .map_err(|e| RibosomeError::RateLimits(zome.zome_name().clone(), e))?;
+                        limits.push(limit);
+                    }
+                }
+                Ok(None) => break,
+                Err((_zome, ribosome_error)) => return Err(ribosome_error),
+            }
+        }
+        Ok(merge_rate_limits(limits))
+    }
+
     fn run_genesis_self_check(
         &self,
         host_access: GenesisSelfCheckHostAccess,
@@ -1098,6 +1143,7 @@ pub mod wasm_test {
                 "__hc__capability_claims_1",
                 "__hc__capability_grants_1",
                 "__hc__capability_info_1",
+                "__hc__count_links_1",
                 "__hc__create_1",
                 "__hc__create_link_1",
                 "__hc__create_x25519_keypair_1",
@@ -1110,6 +1156,7 @@ pub mod wasm_test {
                 "__hc__get_details_1",
                 "__hc__get_link_details_1",
                 "__hc__get_links_1",
+                "__hc__get_meta_1",
                 "__hc__hash_1",
                 "__hc__must_get_action_1",
                 "__hc__must_get_agent_activity_1",
@@ -2,6 +2,7 @@
 //! This module contains all the checks we run for sys validation
 
 use super::queue_consumer::TriggerSender;
+use super::ribosome::guest_callback::rate_limits::full_drain_seconds;
 use super::ribosome::RibosomeT;
 use super::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
 use super::workflow::sys_validation_workflow::SysValidationWorkspace;
@@ -52,10 +53,33 @@ pub async fn verify_action_signature(sig: &Signature, action: &Action) -> SysVal
 }
 
 /// Verify the author key was valid at the time
-/// of signing with dpki
-/// TODO: This is just a stub until we have dpki.
-pub async fn author_key_is_valid(_author: &AgentPubKey) -> SysValidationResult<()> {
-    Ok(())
+/// of signing with dpki.
+///
+/// If the conductor isn't running DPKI, or the action belongs to the DPKI DNA
+/// itself, every key is considered valid.
+///
+/// DPKI learns about keys over the DHT like anything else, so a key it hasn't
+/// heard of yet is a missing dependency rather than an invalid key. Only keys
+/// which DPKI positively knows were not valid at the time are rejected.
+pub async fn author_key_is_valid(
+    action: &Action,
+    dna_hash: &DnaHash,
+    conductor: &Conductor,
+) -> SysValidationResult<()> {
+    let dpki = match conductor.dpki_service() {
+        Some(dpki) => dpki,
+        None => return Ok(()),
+    };
+    if dpki.cell_ids().iter().any(|id| id.dna_hash() == dna_hash) {
+        return Ok(());
+    }
+    let author = action.author().clone();
+    let timestamp = action.timestamp();
+    match dpki.key_state(author.clone()).await? {
+        KeyState::NotFound => Err(ValidationOutcome::DepMissingFromDht(author.into()).into()),
+        state if state.is_valid_at(timestamp) => Ok(()),
+        _ => Err(ValidationOutcome::DpkiAgentInvalid(author, timestamp).into()),
+    }
 }
 
 /// Verify the countersigning session contains the specified action.
@@ -427,6 +451,119 @@ pub fn check_tag_size(tag: &LinkTag) -> SysValidationResult<()> {
     }
 }
 
+/// Check the rate bytes stamped on a new entry action match the size of its entry.
+/// Countersigned entries always carry the default weight.
+pub fn check_entry_rate_bytes(
+    action: &NewEntryActionRef<'_>,
+    entry: &Entry,
+) -> SysValidationResult<()> {
+    if let Entry::CounterSign(_, _) = entry {
+        return Ok(());
+    }
+    let stamped = match action {
+        NewEntryActionRef::Create(Create { weight, .. })
+        | NewEntryActionRef::Update(Update { weight, .. }) => weight.rate_bytes,
+    };
+    let expected = rate_bytes(entry);
+    if stamped == expected {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::RateBytes(stamped, expected).into())
+    }
+}
+
+/// Check that an action doesn't overflow its author's bucket for any of the
+/// DNA's rate limits.
+///
+/// Buckets are leaky: each of the author's actions adds its cost to the bucket
+/// named by its weight, and the bucket drains at a constant rate in between.
+/// The author's previous actions are found by walking back along their chain,
+/// only as far as is needed for the bucket to have drained completely.
+/// Declared buckets always drain within a week, which bounds the walk in time,
+/// and the walk stops after [`MAX_RATE_LIMIT_WALK`] actions. If it stops there,
+/// the bucket is taken to have been full at the oldest action walked, so a
+/// long chain can't hide how much its author has spent.
+pub async fn check_rate_limit(
+    action: &Action,
+    rate_limits: &[RateLimit],
+    cascade: &Cascade,
+) -> SysValidationResult<()> {
+    let bucket_id = action.rate_data().bucket_id;
+    let cost = rate_cost(action);
+    let limit = match rate_limits.iter().find(|l| l.bucket_id == bucket_id) {
+        // A weightless action can't overflow a bucket that its predecessors didn't.
+        Some(limit) if cost > 0 => limit,
+        _ => return Ok(()),
+    };
+
+    // How long a full bucket takes to drain. Anything authored before that
+    // can't contribute to the current level.
+    let window_micros = full_drain_seconds(limit) * 1_000_000;
+
+    let mut prior = Vec::new();
+    let mut walked = 0;
+    let mut truncated_at = None;
+    let mut prev_action_hash = action.prev_action().cloned();
+    while let Some(hash) = prev_action_hash {
+        let (prev_action, _) = cascade
+            .retrieve_action(hash.clone(), Default::default())
+            .await?
+            .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.into()))?;
+        let prev_action = prev_action.action();
+        if action.timestamp().as_micros() - prev_action.timestamp().as_micros() > window_micros {
+            break;
+        }
+        walked += 1;
+        if walked == MAX_RATE_LIMIT_WALK {
+            truncated_at = Some(prev_action.timestamp());
+            break;
+        }
+        if prev_action.rate_data().bucket_id == bucket_id {
+            prior.push((prev_action.timestamp(), rate_cost(prev_action)));
+        }
+        prev_action_hash = prev_action.prev_action().cloned();
+    }
+
+    // Fill the bucket from the oldest action to this one, starting from full
+    // if the walk was cut short.
+    let mut level: u64 = if truncated_at.is_some() {
+        limit.capacity as u64
+    } else {
+        0
+    };
+    let mut last_timestamp: Option<Timestamp> = truncated_at;
+    for (timestamp, cost) in prior
+        .into_iter()
+        .rev()
+        .chain(std::iter::once((action.timestamp(), cost)))
+    {
+        if let Some(last_timestamp) = last_timestamp {
+            let elapsed = (timestamp.as_micros() - last_timestamp.as_micros()).max(0) as u128;
+            let drained = elapsed * limit.drain_per_second as u128 / 1_000_000;
+            level = level.saturating_sub(drained.min(u64::MAX as u128) as u64);
+        }
+        level += cost as u64;
+        last_timestamp = Some(timestamp);
+    }
+
+    if level > limit.capacity as u64 {
+        Err(ValidationOutcome::RateLimitExceeded(bucket_id, level, limit.capacity).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// The most actions [`check_rate_limit`] walks back along an author's chain.
+pub const MAX_RATE_LIMIT_WALK: usize = 10_000;
+
+/// How much an action fills its rate limit bucket.
+fn rate_cost(action: &Action) -> RateBucketCapacity {
+    match action.entry_rate_data() {
+        Some(weight) => weight.cost(),
+        None => action.rate_data().cost(),
+    }
+}
+
 /// Check a Update's entry type is the same for
 /// original and new entry.
 pub fn check_update_reference(
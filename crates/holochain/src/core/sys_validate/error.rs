@@ -2,6 +2,7 @@ use std::convert::TryFrom;
 
 use super::SourceChainError;
 use crate::conductor::api::error::ConductorApiError;
+use crate::conductor::conductor::DpkiServiceError;
 use crate::conductor::entry_def_store::error::EntryDefStoreError;
 use crate::core::validation::OutcomeOrError;
 use crate::core::workflow::error::WorkflowError;
@@ -33,6 +34,8 @@ pub enum SysValidationError {
     #[error(transparent)]
     DatabaseError(#[from] DatabaseError),
     #[error(transparent)]
+    DpkiServiceError(#[from] DpkiServiceError),
+    #[error(transparent)]
     EntryDefStoreError(#[from] EntryDefStoreError),
     #[error(transparent)]
     KeystoreError(#[from] KeystoreError),
@@ -107,6 +110,8 @@ pub enum ValidationOutcome {
     CounterSigningError(#[from] CounterSigningError),
     #[error("The dependency {0:?} was not found on the DHT")]
     DepMissingFromDht(AnyDhtHash),
+    #[error("DPKI does not consider the agent key {0:?} valid at {1}")]
+    DpkiAgentInvalid(AgentPubKey, Timestamp),
     #[error("The app entry def {0:?} entry def id was out of range")]
     EntryDefId(AppEntryDef),
     #[error("The entry has a different hash to the action's entry hash")]
@@ -131,6 +136,14 @@ pub enum ValidationOutcome {
     PrevActionError(#[from] PrevActionError),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
+    #[error("The action's rate bytes {0} don't match the {1} rate bytes of its entry")]
+    RateBytes(RateBytes, RateBytes),
+    #[error("The action would fill rate limit bucket {0} to {1}, over its capacity of {2}")]
+    RateLimitExceeded(RateBucketId, u64, RateBucketCapacity),
+    #[error(
+        "The action's weight {0:?} doesn't match the weight {1:?} its integrity zome gives it"
+    )]
+    RateWeight(RateWeight, RateWeight),
     #[error(
         "The DNA does not belong in this space! Action DNA hash: {0:?}, expected DNA hash: {1:?}"
     )]
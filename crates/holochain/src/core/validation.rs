@@ -1,13 +1,32 @@
 //! Types needed for all validation
+use std::collections::HashSet;
 use std::convert::TryFrom;
+use std::sync::Arc;
 
 use holo_hash::DhtOpHash;
+use holochain_cascade::Cascade;
+use holochain_p2p::HolochainP2pDna;
+use holochain_p2p::HolochainP2pDnaT;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::HostFnWorkspaceRead;
 use holochain_types::dht_op::DhtOp;
+use holochain_types::prelude::*;
+use tracing::*;
 
+use super::workflow::app_validation_workflow;
+use super::workflow::error::WorkflowError;
 use super::workflow::error::WorkflowResult;
+use super::workflow::sys_validation_workflow;
 use super::SourceChainError;
 use super::SysValidationError;
 use super::ValidationOutcome;
+use crate::conductor::conductor::CellStatus;
+use crate::conductor::error::ConductorResult;
+use crate::conductor::Conductor;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsHostAccess;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::RibosomeT;
+use crate::core::sys_validate::check_rate_limit;
 
 /// Exit early with either an outcome or an error
 pub enum OutcomeOrError<T, E> {
@@ -99,3 +118,159 @@ impl OutcomeOrError<ValidationOutcome, SysValidationError> {
         Err(SourceChainError::InvalidCommit(ValidationOutcome::try_from(self)?.to_string()).into())
     }
 }
+
+/// Issue a warrant against the author of each of these invalid actions.
+///
+/// The warrant is signed by one of our agents in this DNA, held and acted on
+/// by this conductor, and published to the warrantee's agent activity
+/// authorities. This is best effort: any warrant that can't be signed or sent
+/// is logged and skipped.
+pub async fn issue_warrants(
+    conductor: &Conductor,
+    network: &HolochainP2pDna,
+    invalid_actions: Vec<SignedAction>,
+    validation_type: ValidationType,
+) {
+    if invalid_actions.is_empty() {
+        return;
+    }
+    let dna_hash = network.dna_hash();
+    let our_agents: Vec<AgentPubKey> = conductor
+        .running_cell_ids(Some(CellStatus::Joined))
+        .into_iter()
+        .filter(|cell_id| *cell_id.dna_hash() == dna_hash)
+        .map(|cell_id| cell_id.agent_pubkey().clone())
+        .collect();
+    let warrantor = match our_agents.first() {
+        Some(agent) => agent.clone(),
+        None => return,
+    };
+
+    // Several ops from the same action may be rejected but one warrant is enough.
+    let mut warranted = HashSet::new();
+    for action in invalid_actions {
+        if our_agents.contains(action.action().author())
+            || !warranted.insert(ActionHash::with_data_sync(&action))
+        {
+            continue;
+        }
+        let warrant = Warrant::new(
+            WarrantProof::InvalidAction {
+                action,
+                validation_type,
+            },
+            warrantor.clone(),
+            Timestamp::now(),
+        );
+        let warrant = match SignedWarrant::sign(conductor.keystore(), warrant).await {
+            Ok(warrant) => warrant,
+            Err(e) => {
+                warn!(failed_to_sign_warrant = ?e);
+                continue;
+            }
+        };
+        if let Err(e) = conductor.hold_warrant(&dna_hash, warrant.clone()).await {
+            warn!(failed_to_hold_warrant = ?e);
+        }
+        if let Err(e) = network.publish_warrant(warrant).await {
+            // The authorities will still learn of the invalid action when
+            // they validate it themselves.
+            info!(failed_to_publish_warrant = ?e);
+        }
+    }
+}
+
+/// What validating the action a warrant is about locally makes of the warrant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarrantCheck {
+    /// The action is invalid, so the warrant is justified.
+    Justified,
+    /// The action is valid, so the warrant is false.
+    Unjustified,
+    /// The action or its dependencies can't be found yet.
+    Undecided,
+}
+
+/// Run sys and app validation on the action a warrant is about, rather than
+/// taking the word of the warrant's author that it is invalid.
+pub async fn check_warrant(
+    conductor: &Conductor,
+    network: HolochainP2pDna,
+    warrant: &SignedWarrant,
+) -> ConductorResult<WarrantCheck> {
+    let dna_hash = network.dna_hash();
+    let ribosome = conductor.get_ribosome(&dna_hash)?;
+    let space = conductor.get_or_create_space(&dna_hash)?;
+    let workspace: HostFnWorkspaceRead = HostFnWorkspace::new(
+        space.authored_db.into(),
+        space.dht_db.into(),
+        space.dht_query_cache,
+        space.cache_db,
+        conductor.keystore().clone(),
+        None,
+        Arc::new(ribosome.dna_def().as_content().clone()),
+    )
+    .await
+    .map_err(WorkflowError::from)?;
+    let cascade = Cascade::from_workspace_and_network(&workspace, network.clone());
+
+    // The warrant only carries the action, so fetch the whole record.
+    let record = match cascade
+        .retrieve(warrant.action_hash().into(), Default::default())
+        .await
+        .map_err(WorkflowError::from)?
+    {
+        Some((record, _)) => record,
+        None => return Ok(WarrantCheck::Undecided),
+    };
+
+    // Validating a record directly leaves out the author's rate limits,
+    // which are otherwise only checked against their agent activity.
+    let rate_limits = ribosome
+        .run_rate_limits(RateLimitsHostAccess, RateLimitsInvocation)
+        .map_err(WorkflowError::from)?;
+    let sys_outcome =
+        match sys_validation_workflow::sys_validate_record(&record, &cascade, conductor).await {
+            Ok(()) => match check_rate_limit(record.action(), &rate_limits, &cascade).await {
+                Err(SysValidationError::ValidationOutcome(outcome)) => {
+                    Err(OutcomeOrError::Outcome(outcome))
+                }
+                r => r.map_err(OutcomeOrError::Err),
+            },
+            r => r,
+        };
+    match sys_outcome {
+        Ok(()) => {}
+        Err(OutcomeOrError::Outcome(outcome)) => {
+            return Ok(if sys_validation_workflow::is_rejected(outcome) {
+                WarrantCheck::Justified
+            } else {
+                WarrantCheck::Undecided
+            });
+        }
+        Err(OutcomeOrError::Err(e)) => return Err(WorkflowError::from(e).into()),
+    }
+
+    for op_type in action_to_op_types(record.action()) {
+        let outcome = match app_validation_workflow::record_to_op(record.clone(), op_type, &cascade)
+            .await
+        {
+            Ok((op, _)) => {
+                app_validation_workflow::validate_op(&op, workspace.clone(), &network, &ribosome)
+                    .await
+            }
+            Err(outcome_or_err) => Err(outcome_or_err),
+        };
+        match outcome
+            .or_else(app_validation_workflow::Outcome::try_from)
+            .map_err(WorkflowError::from)?
+        {
+            app_validation_workflow::Outcome::Accepted => {}
+            app_validation_workflow::Outcome::Rejected(_) => return Ok(WarrantCheck::Justified),
+            app_validation_workflow::Outcome::AwaitingDeps(_) => {
+                return Ok(WarrantCheck::Undecided)
+            }
+        }
+    }
+    Ok(WarrantCheck::Unjustified)
+}
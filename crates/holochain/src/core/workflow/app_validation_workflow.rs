@@ -11,8 +11,11 @@ use crate::core::queue_consumer::WorkComplete;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
+use crate::core::ribosome::weigh_action;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomesToInvoke;
+use crate::core::sys_validate::ValidationOutcome;
+use crate::core::validation::issue_warrants;
 use error::AppValidationResult;
 pub use error::*;
 use futures::stream::StreamExt;
@@ -96,6 +99,7 @@ async fn app_validation_workflow_inner(
     let iter = sorted_ops.into_iter().map({
         let network = network.clone();
         let workspace = workspace.clone();
+        let conductor = conductor.clone();
         move |so| {
             let network = network.clone();
             let conductor = conductor.clone();
@@ -107,6 +111,7 @@ async fn app_validation_workflow_inner(
                 let action = op.action();
                 let dependency = get_dependency(op_type, &action);
                 let op_light = op.to_light();
+                let signed_action = SignedAction(action.clone(), op.signature().clone());
 
                 // If this is agent activity, track it for the cache.
                 let activity = matches!(op_type, DhtOpType::RegisterAgentActivity).then(|| {
@@ -125,7 +130,7 @@ async fn app_validation_workflow_inner(
                     }
                     Err(e) => Err(e),
                 };
-                (op_hash, dependency, op_light, r, activity)
+                (op_hash, dependency, op_light, r, activity, signed_action)
             }
         }
     });
@@ -163,15 +168,16 @@ async fn app_validation_workflow_inner(
             "Committing {} ops",
             chunk.iter().map(|c| c.len()).sum::<usize>()
         );
-        let (t, a, r, activity) = workspace
+        let (t, a, r, activity, invalid) = workspace
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut rejected = 0;
                 let mut agent_activity = Vec::new();
+                let mut invalid = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, dependency, op_light, outcome, activity) = outcome;
+                    let (op_hash, dependency, op_light, outcome, activity, signed_action) = outcome;
                     // Get the outcome or return the error
                     let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
 
@@ -209,6 +215,7 @@ async fn app_validation_workflow_inner(
                                 "Received invalid op. The op author will be blocked.\nOp: {:?}",
                                 op_light
                             );
+                            invalid.push(signed_action);
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
@@ -217,10 +224,12 @@ async fn app_validation_workflow_inner(
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
+                WorkflowResult::Ok((total, awaiting, rejected, agent_activity, invalid))
             })
             .await?;
 
+        issue_warrants(&conductor, network, invalid, ValidationType::App).await;
+
         // Once the database transaction is committed, add agent activity to the cache
         // that is ready for integration.
         for (author, seq, has_no_dependency) in activity {
@@ -476,6 +485,8 @@ pub async fn validate_op<R>(
 where
     R: RibosomeT,
 {
+    check_weight(op, ribosome)?;
+
     let zomes_to_invoke = match op {
         Op::RegisterAgentActivity(RegisterAgentActivity { .. }) => ZomesToInvoke::AllIntegrity,
         Op::StoreRecord(StoreRecord { record }) => {
@@ -530,6 +541,94 @@ where
     Ok(outcome)
 }
 
+/// Run the `weigh` callback of the integrity zome which defines the type of
+/// the op's action again, and reject the op if its author stamped a different
+/// weight on the action. Rate limits are checked against the stamped weight,
+/// so without this an author could dodge them by stamping a weight of zero.
+///
+/// Ops which don't carry everything the callback needs aren't weighed here.
+/// A [`Op::StoreRecord`] for a delete lacks the type of the deleted entry,
+/// but the [`Op::RegisterDelete`] for the same action has it. Actions for
+/// private entries can only be weighed by their author.
+fn check_weight(op: &Op, ribosome: &impl RibosomeT) -> AppValidationOutcome<()> {
+    let weighed = match op {
+        Op::StoreRecord(StoreRecord { record }) => match record.action() {
+            Action::Create(create) => record
+                .entry()
+                .as_option()
+                .map(|entry| weigh_create(create, entry)),
+            Action::Update(update) => record
+                .entry()
+                .as_option()
+                .map(|entry| weigh_update(update, entry)),
+            Action::CreateLink(create_link) => Some(weigh_create_link(create_link)),
+            _ => None,
+        },
+        Op::StoreEntry(StoreEntry { action, entry }) => match &action.hashed.content {
+            EntryCreationAction::Create(create) => Some(weigh_create(create, entry)),
+            EntryCreationAction::Update(update) => Some(weigh_update(update, entry)),
+        },
+        Op::RegisterUpdate(RegisterUpdate {
+            update, new_entry, ..
+        }) => new_entry
+            .as_ref()
+            .map(|entry| weigh_update(&update.hashed.content, entry)),
+        Op::RegisterDelete(RegisterDelete {
+            delete,
+            original_action,
+            ..
+        }) => Some((
+            delete.hashed.content.weight.clone(),
+            app_entry_zome_index(original_action.entry_type()),
+            WeighInput::Delete(delete.hashed.content.clone().unweighed()),
+        )),
+        Op::RegisterCreateLink(RegisterCreateLink { create_link }) => {
+            Some(weigh_create_link(&create_link.hashed.content))
+        }
+        Op::RegisterAgentActivity(_) | Op::RegisterDeleteLink(_) => None,
+    };
+    if let Some((stamped, zome_index, input)) = weighed {
+        let weight = weigh_action(ribosome, zome_index, input)?;
+        if weight != stamped {
+            return Outcome::exit_with_rejected(
+                ValidationOutcome::RateWeight(stamped, weight).to_string(),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn weigh_create(create: &Create, entry: &Entry) -> (RateWeight, Option<ZomeIndex>, WeighInput) {
+    (
+        create.weight.clone().into(),
+        app_entry_zome_index(&create.entry_type),
+        WeighInput::Create(create.clone().unweighed(), entry.clone()),
+    )
+}
+
+fn weigh_update(update: &Update, entry: &Entry) -> (RateWeight, Option<ZomeIndex>, WeighInput) {
+    (
+        update.weight.clone().into(),
+        app_entry_zome_index(&update.entry_type),
+        WeighInput::Update(update.clone().unweighed(), entry.clone()),
+    )
+}
+
+fn weigh_create_link(create_link: &CreateLink) -> (RateWeight, Option<ZomeIndex>, WeighInput) {
+    (
+        create_link.weight.clone(),
+        Some(create_link.zome_index),
+        WeighInput::Link(create_link.clone().unweighed()),
+    )
+}
+
+fn app_entry_zome_index(entry_type: &EntryType) -> Option<ZomeIndex> {
+    match entry_type {
+        EntryType::App(app_entry_def) => Some(app_entry_def.zome_index()),
+        _ => None,
+    }
+}
+
 pub fn entry_creation_zomes_to_invoke(
     action: &EntryCreationAction,
     ribosome: &impl RibosomeT,
@@ -71,6 +71,7 @@ async fn get_agent_activity_test() {
             status,
             highest_observed,
             agent: alice_agent_id.clone(),
+            warrants: vec![],
         }
     };
 
@@ -113,6 +114,7 @@ async fn get_agent_activity_test() {
             rejected_activity,
             status: activity.status,
             highest_observed: activity.highest_observed,
+            warrants: activity.warrants,
         };
         activity
     };
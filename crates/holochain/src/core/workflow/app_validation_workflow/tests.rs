@@ -214,6 +214,99 @@ async fn test_private_entries_are_passed_to_validation_only_when_authored_with_f
     )
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn actions_stamped_with_the_wrong_weight_are_rejected() {
+    use crate::sweettest::SweetInlineZomes;
+    use crate::test_utils::inline_zomes::AppString;
+
+    holochain_trace::test_run().ok();
+
+    // The same zomes, except for the weight they give creates. The weigh
+    // callback isn't part of the DNA hash, so both make the same DNA.
+    let zomes = |units: RateUnits| {
+        SweetInlineZomes(
+            InlineZomeSet::new(
+                [(
+                    SweetInlineZomes::INTEGRITY,
+                    "weigh_integrity".to_string(),
+                    vec![EntryDef::from_id("string")],
+                    0,
+                )],
+                [(
+                    SweetInlineZomes::COORDINATOR,
+                    "weigh_coordinator".to_string(),
+                )],
+            )
+            .with_dependency(SweetInlineZomes::COORDINATOR, SweetInlineZomes::INTEGRITY),
+        )
+        .integrity_function("weigh", move |_, input: WeighInput| {
+            Ok(match input {
+                WeighInput::Create(_, _) => RateWeight {
+                    bucket_id: 1,
+                    units,
+                },
+                _ => RateWeight::default(),
+            })
+        })
+        .function("create_string", |api, s: AppString| {
+            let entry = Entry::app(s.try_into().unwrap()).unwrap();
+            let hash = api.create(CreateInput::new(
+                InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                EntryVisibility::Public,
+                entry,
+                ChainTopOrdering::default(),
+            ))?;
+            Ok(hash)
+        })
+    };
+    let network_seed = random_network_seed();
+    let (honest_dna, _, _) = SweetDnaFile::from_inline_zomes(network_seed.clone(), zomes(10)).await;
+    let (cheating_dna, _, _) = SweetDnaFile::from_inline_zomes(network_seed, zomes(0)).await;
+    assert_eq!(honest_dna.dna_hash(), cheating_dna.dna_hash());
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let (alice,) = conductors[0]
+        .setup_app("app", &[cheating_dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    let (bob,) = conductors[1]
+        .setup_app("app", &[honest_dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    conductors.exchange_peer_info().await;
+
+    // Alice stamps her create with a weight of zero, so it would never fill
+    // a rate limit bucket.
+    let hash: ActionHash = conductors[0]
+        .call(
+            &alice.zome(SweetInlineZomes::COORDINATOR),
+            "create_string",
+            AppString::new("weightless"),
+        )
+        .await;
+
+    // Bob weighs it again, and rejects it.
+    let db = conductors[1].get_dht_db(bob.cell_id().dna_hash()).unwrap();
+    let store_record_status = || {
+        fresh_reader_test(db.clone(), |txn| {
+            txn.query_row(
+                "SELECT validation_status FROM DhtOp
+                WHERE action_hash = :action_hash AND type = :type",
+                named_params! {
+                    ":action_hash": hash,
+                    ":type": DhtOpType::StoreRecord,
+                },
+                |row| row.get::<_, Option<ValidationStatus>>(0),
+            )
+            .ok()
+            .flatten()
+        })
+    };
+    crate::assert_eq_retry_1m!(store_record_status(), Some(ValidationStatus::Rejected));
+}
+
 const SELECT: &'static str = "SELECT count(hash) FROM DhtOp WHERE";
 
 // These are the expected invalid ops
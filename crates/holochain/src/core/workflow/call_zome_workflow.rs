@@ -205,7 +205,10 @@ where
 {
     match invocation.is_authorized(&host_access).await? {
         ZomeCallAuthorization::Authorized => {
-            tokio::task::spawn_blocking(|| {
+            // Keep the span so the trace continues into any host calls.
+            let span = tracing::Span::current();
+            tokio::task::spawn_blocking(move || {
+                let _g = span.enter();
                 let r = ribosome.call_zome_function(host_access, invocation);
                 Ok((ribosome, r))
             })
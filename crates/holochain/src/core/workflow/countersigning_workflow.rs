@@ -1,41 +1,55 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use holo_hash::{ActionHash, AgentPubKey, DhtOpHash, EntryHash, OpBasis};
 use holochain_keystore::AgentPubKeyExt;
+use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::{HolochainP2pDna, HolochainP2pDnaT};
+use holochain_state::countersigning::{countersigning_session, countersigning_sessions};
 use holochain_state::integrate::authored_ops_to_dht_db_without_check;
 use holochain_state::mutations;
 use holochain_state::prelude::{
-    current_countersigning_session, SourceChainResult, StateMutationResult, Store,
+    current_countersigning_session, SourceChainResult, StateMutationResult, Store, Txn,
 };
+use holochain_types::activity::ChainItems;
+use holochain_types::countersigning::CountersigningSessionResolution;
 use holochain_types::dht_op::DhtOp;
 use holochain_types::signal::{Signal, SystemSignal};
 use holochain_zome_types::Timestamp;
-use holochain_zome_types::{Entry, SignedAction, ZomeCallResponse};
+use holochain_zome_types::{
+    CellId, ChainQueryFilter, ChainQueryFilterRange, Entry, EntryRateWeight, SignedAction,
+    ZomeCallResponse,
+};
 use kitsune_p2p_types::tx2::tx2_utils::Share;
 use rusqlite::{named_params, Transaction};
 
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::space::Space;
+use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::{QueueTriggers, TriggerSender, WorkComplete};
-use crate::core::ribosome::weigh_placeholder;
 
 use holochain_p2p::event::CountersigningSessionNegotiationMessage;
 
-use super::{error::WorkflowResult, incoming_dht_ops_workflow::incoming_dht_ops_workflow};
+use super::error::{WorkflowError, WorkflowResult};
+use super::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
 
 #[derive(Clone)]
 /// A cheaply clonable, thread safe and in-memory store for
 /// active countersigning sessions.
 pub struct CountersigningWorkspace {
     inner: Share<CountersigningWorkspaceInner>,
+    /// When this workspace was created. Sessions of this conductor's own
+    /// agents which started before this were interrupted by a restart.
+    created_at: Timestamp,
 }
 
 #[derive(Default)]
 /// Pending countersigning sessions.
 pub struct CountersigningWorkspaceInner {
     pending: HashMap<EntryHash, Session>,
+    /// The countersigned actions of this conductor's agents which have been
+    /// published again since startup, so that their sessions can complete.
+    resumed: HashSet<ActionHash>,
 }
 
 #[derive(Default)]
@@ -69,7 +83,8 @@ pub(crate) fn incoming_countersigning(
             if let Entry::CounterSign(session_data, _) = entry.as_ref() {
                 let entry_hash = EntryHash::with_data_sync(&**entry);
                 // Get the required actions for this session.
-                let weight = weigh_placeholder();
+                // Countersigned actions always carry the default weight.
+                let weight = EntryRateWeight::default();
                 let action_set = session_data.build_action_set(entry_hash, weight)?;
 
                 // Get the expires time for this session.
@@ -105,10 +120,15 @@ pub(crate) fn incoming_countersigning(
 
 /// Countersigning workflow that checks for complete sessions and
 /// pushes the complete ops to validation then messages the signers.
+///
+/// It also resumes the sessions of this conductor's own agents which are
+/// still in progress after a restart, and tries to resolve those which have
+/// expired without a response from the authorities.
 pub(crate) async fn countersigning_workflow(
     space: Space,
     network: impl HolochainP2pDnaT + Send + Sync,
     sys_validation_trigger: TriggerSender,
+    conductor: ConductorHandle,
 ) -> WorkflowResult<WorkComplete> {
     // Get any complete sessions.
     let complete_sessions = space.countersigning_workspace.get_complete_sessions();
@@ -148,6 +168,9 @@ pub(crate) async fn countersigning_workflow(
             );
         }
     }
+
+    resume_or_resolve_sessions(&space, &conductor).await?;
+
     Ok(WorkComplete::Complete)
 }
 
@@ -252,7 +275,7 @@ pub(crate) async fn countersigning_success(
             if let Some((cs_entry_hash, cs)) = current_countersigning_session(txn, Arc::new(author.clone()))? {
                 // Check we have the right session.
                 if cs_entry_hash == entry_hash {
-                    let weight = weigh_placeholder();
+                    let weight = EntryRateWeight::default();
                     let stored_actions = cs.build_action_set(entry_hash, weight)?;
                     if stored_actions.len() == incoming_actions.len() {
                         // Check all stored action hashes match an incoming action hash.
@@ -315,6 +338,277 @@ pub(crate) async fn countersigning_success(
     Ok(())
 }
 
+/// Resume or resolve the unresolved sessions of every agent in this space
+/// which has a running cell on this conductor.
+async fn resume_or_resolve_sessions(
+    space: &Space,
+    conductor: &ConductorHandle,
+) -> WorkflowResult<()> {
+    let sessions = space
+        .authored_db
+        .async_reader(|txn| countersigning_sessions(&txn))
+        .await?;
+    let now = Timestamp::now();
+    for session in sessions {
+        let cell_id = CellId::new((*space.dna_hash).clone(), session.author.clone());
+        let cell = match conductor.cell_by_id(&cell_id).await {
+            Ok(cell) => cell,
+            // The session will be picked up when the cell is running again.
+            Err(_) => continue,
+        };
+        let result = match &session.action_hash {
+            _ if session.is_expired(now) => {
+                cell.resolve_countersigning_session(None).await.map(|_| ())
+            }
+            // Sessions started since this conductor started were published
+            // when they were committed.
+            Some(action_hash)
+                if *session.preflight_request.session_times.start()
+                    < space.countersigning_workspace.created_at
+                    && !space.countersigning_workspace.is_resumed(action_hash) =>
+            {
+                cell.resume_countersigning_session().await.map(|_| {
+                    space
+                        .countersigning_workspace
+                        .set_resumed(action_hash.clone())
+                })
+            }
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            tracing::warn!(
+                ?error,
+                ?cell_id,
+                "Failed to resume or resolve countersigning session"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Publish the author's committed action for their countersigning session
+/// to the entry authorities again, e.g. after a restart when the authorities
+/// may have lost track of the session.
+pub(crate) async fn resume_countersigning_session(
+    space: &Space,
+    network: &HolochainP2pDna,
+    author: AgentPubKey,
+) -> WorkflowResult<()> {
+    let record = space
+        .authored_db
+        .async_reader({
+            let author = author.clone();
+            move |txn| {
+                let action_hash =
+                    match countersigning_session(&txn, &author)?.and_then(|s| s.action_hash) {
+                        Some(action_hash) => action_hash,
+                        None => return Ok(None),
+                    };
+                Txn::from(&txn).get_record(&action_hash.into())
+            }
+        })
+        .await?;
+    let op = record.and_then(|record| {
+        let (signed_action, entry) = record.into_inner();
+        Some(DhtOp::StoreEntry(
+            signed_action.signature().clone(),
+            signed_action.action().clone().try_into().ok()?,
+            Box::new(entry.into_option()?),
+        ))
+    });
+    if let Some(op) = op {
+        countersigning_publish(network, op, author)
+            .await
+            .map_err(|response| WorkflowError::Other(format!("{:?}", response).into()))?;
+    }
+    Ok(())
+}
+
+/// Resolve the author's countersigning session, if they have one.
+///
+/// Unless a resolution is forced, the agent activity authorities of the other
+/// signers are asked whether those signers committed their actions for the
+/// session. If they all did the session is completed; if any of them has
+/// committed a different action in its place the session is abandoned;
+/// otherwise it can't be resolved yet and `None` is returned.
+///
+/// A session in which the author hasn't committed anything is always abandoned,
+/// once it has expired or if a resolution is forced.
+pub(crate) async fn resolve_countersigning_session(
+    space: &Space,
+    network: &HolochainP2pDna,
+    author: AgentPubKey,
+    force: Option<CountersigningSessionResolution>,
+    trigger: QueueTriggers,
+    mut signal: SignalBroadcaster,
+) -> WorkflowResult<Option<CountersigningSessionResolution>> {
+    let session = match space
+        .authored_db
+        .async_reader({
+            let author = author.clone();
+            move |txn| countersigning_session(&txn, &author)
+        })
+        .await?
+    {
+        Some(session) => session,
+        None => return Ok(None),
+    };
+
+    let (entry_hash, action_hash) = match (session.entry_hash.clone(), session.action_hash.clone())
+    {
+        (Some(entry_hash), Some(action_hash)) => (entry_hash, action_hash),
+        // Nothing was committed so there is nothing to publish or remove.
+        _ => {
+            if force.is_none() && !session.is_expired(Timestamp::now()) {
+                return Ok(None);
+            }
+            space
+                .authored_db
+                .async_commit(move |txn| mutations::unlock_chain(txn, &author))
+                .await?;
+            return Ok(Some(CountersigningSessionResolution::Abandoned));
+        }
+    };
+
+    let resolution = match force {
+        Some(resolution) => resolution,
+        None => match check_session_outcome(space, network, &author, &action_hash).await? {
+            Some(resolution) => resolution,
+            None => return Ok(None),
+        },
+    };
+
+    match resolution {
+        CountersigningSessionResolution::Completed => {
+            let op_hashes = space
+                .authored_db
+                .async_commit({
+                    let author = author.clone();
+                    move |txn| {
+                        let op_hashes = txn
+                            .prepare("SELECT hash FROM DhtOp WHERE action_hash = :action_hash")?
+                            .query_map(named_params! { ":action_hash": action_hash }, |row| {
+                                row.get::<_, DhtOpHash>("hash")
+                            })?
+                            .collect::<Result<Vec<_>, _>>()?;
+                        for op_hash in &op_hashes {
+                            mutations::unset_withhold_publish(txn, op_hash)?;
+                        }
+                        mutations::unlock_chain(txn, &author)?;
+                        WorkflowResult::Ok(op_hashes)
+                    }
+                })
+                .await?;
+            authored_ops_to_dht_db_without_check(
+                op_hashes,
+                &(space.authored_db.clone().into()),
+                &space.dht_db,
+                &space.dht_query_cache,
+            )
+            .await?;
+            trigger
+                .integrate_dht_ops
+                .trigger(&"resolve_countersigning_session");
+            trigger
+                .publish_dht_ops
+                .trigger(&"resolve_countersigning_session");
+            signal.send(Signal::System(SystemSignal::SuccessfulCountersigning(
+                entry_hash,
+            )))?;
+        }
+        CountersigningSessionResolution::Abandoned => {
+            let removed = space
+                .authored_db
+                .async_commit({
+                    let action_hash = action_hash.clone();
+                    move |txn| {
+                        let removed =
+                            mutations::remove_countersigned_action(txn, &author, &action_hash)?;
+                        mutations::unlock_chain(txn, &author)?;
+                        WorkflowResult::Ok(removed)
+                    }
+                })
+                .await?;
+            if !removed {
+                tracing::warn!(
+                    ?action_hash,
+                    "Abandoned countersigned action has been built upon and can't be removed"
+                );
+            }
+            signal.send(Signal::System(SystemSignal::AbandonedCountersigning(
+                entry_hash,
+            )))?;
+        }
+    }
+    Ok(Some(resolution))
+}
+
+/// Ask the agent activity authorities of the other signers in the session
+/// what they have at the sequence number of their action for the session.
+async fn check_session_outcome(
+    space: &Space,
+    network: &HolochainP2pDna,
+    author: &AgentPubKey,
+    action_hash: &ActionHash,
+) -> WorkflowResult<Option<CountersigningSessionResolution>> {
+    let record = space
+        .authored_db
+        .async_reader({
+            let action_hash = action_hash.clone();
+            move |txn| Txn::from(&txn).get_record(&action_hash.into())
+        })
+        .await?;
+    let (entry_hash, session_data) = match record.map(|r| r.into_inner()) {
+        Some((signed_action, entry)) => match (
+            signed_action.action().entry_hash().cloned(),
+            entry.into_option(),
+        ) {
+            (Some(entry_hash), Some(Entry::CounterSign(session_data, _))) => {
+                (entry_hash, session_data)
+            }
+            _ => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+
+    // Countersigned actions always carry the default weight.
+    let actions = session_data.build_action_set(entry_hash, EntryRateWeight::default())?;
+    let options = GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+    let mut all_committed = true;
+    for action in actions {
+        if action.author() == author {
+            continue;
+        }
+        let expected = ActionHash::with_data_sync(&action);
+        let seq = action.action_seq();
+        let query =
+            ChainQueryFilter::new().sequence_range(ChainQueryFilterRange::ActionSeqRange(seq, seq));
+        let found: Vec<ActionHash> = network
+            .get_agent_activity(action.author().clone(), query, options.clone())
+            .await?
+            .into_iter()
+            .flat_map(|response| match response.valid_activity {
+                ChainItems::Hashes(hashes) => hashes,
+                _ => Vec::with_capacity(0),
+            })
+            .filter(|(s, _)| *s == seq)
+            .map(|(_, hash)| hash)
+            .collect();
+        if found.iter().any(|hash| *hash != expected) {
+            return Ok(Some(CountersigningSessionResolution::Abandoned));
+        }
+        if !found.contains(&expected) {
+            all_committed = false;
+        }
+    }
+    Ok(all_committed.then_some(CountersigningSessionResolution::Completed))
+}
+
 /// Publish to entry authorities so they can gather all the signed
 /// actions for this session and respond with a session complete.
 pub async fn countersigning_publish(
@@ -358,6 +652,7 @@ impl CountersigningWorkspace {
     pub fn new() -> CountersigningWorkspace {
         Self {
             inner: Share::new(Default::default()),
+            created_at: Timestamp::now(),
         }
     }
 
@@ -390,6 +685,23 @@ impl CountersigningWorkspace {
             .ok();
     }
 
+    /// Has this countersigned action been published again since startup?
+    fn is_resumed(&self, action_hash: &ActionHash) -> bool {
+        self.inner
+            .share_ref(|i| Ok(i.resumed.contains(action_hash)))
+            .unwrap_or(false)
+    }
+
+    fn set_resumed(&self, action_hash: ActionHash) {
+        self.inner
+            .share_mut(|i, _| {
+                i.resumed.insert(action_hash);
+                Ok(())
+            })
+            // We don't close this share so we can ignore this error.
+            .ok();
+    }
+
     fn get_complete_sessions(&self) -> Vec<(AgentsToNotify, Ops, SignedActions)> {
         let now = holochain_zome_types::Timestamp::now();
         self.inner
@@ -544,4 +856,96 @@ mod tests {
             })
             .unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sessions_are_resumed_after_a_restart() {
+        use crate::conductor::api::error::ConductorApiError;
+        use crate::conductor::CellError;
+        use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+        use crate::core::workflow::error::WorkflowError;
+        use holochain_state::source_chain::SourceChainError;
+        use holochain_types::prelude::*;
+        use holochain_wasm_test_utils::TestWasm;
+
+        holochain_trace::test_run().ok();
+        let RibosomeTestFixture {
+            mut conductor,
+            alice,
+            alice_pubkey,
+            bob,
+            bob_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::CounterSigning).await;
+
+        let preflight_request: PreflightRequest = conductor
+            .call(
+                &alice,
+                "generate_countersigning_preflight_request",
+                vec![
+                    (alice_pubkey.clone(), vec![Role(0)]),
+                    (bob_pubkey.clone(), vec![]),
+                ],
+            )
+            .await;
+        let mut responses = vec![];
+        for zome in [&alice, &bob] {
+            let acceptance: PreflightRequestAcceptance = conductor
+                .call(
+                    zome,
+                    "accept_countersigning_preflight_request",
+                    preflight_request.clone(),
+                )
+                .await;
+            match acceptance {
+                PreflightRequestAcceptance::Accepted(response) => responses.push(response),
+                _ => unreachable!(),
+            }
+        }
+        let countersigned_action_hash_alice: ActionHash = conductor
+            .call(&alice, "create_a_countersigned_thing", responses.clone())
+            .await;
+
+        // The conductor restarts before Bob commits.
+        conductor.shutdown().await;
+        conductor.startup().await;
+
+        // Alice's chain is still locked.
+        let result: Result<ActionHash, _> =
+            conductor.call_fallible(&alice, "create_a_thing", ()).await;
+        match result {
+            Err(ConductorApiError::CellError(CellError::WorkflowError(workflow_error))) => {
+                assert!(matches!(
+                    *workflow_error,
+                    WorkflowError::SourceChainError(SourceChainError::ChainLocked)
+                ))
+            }
+            something_else => panic!("{:?}", something_else),
+        }
+
+        // Once Bob commits too, Alice's session is picked up again and completes.
+        let _: ActionHash = conductor
+            .call(&bob, "create_a_countersigned_thing", responses)
+            .await;
+        let handle = conductor.raw_handle();
+        crate::assert_eq_retry_1m!(
+            handle.list_countersigning_sessions().await.unwrap().len(),
+            0
+        );
+        let alice_activity: AgentActivity = conductor
+            .call(
+                &alice,
+                "get_agent_activity",
+                GetAgentActivityInput {
+                    agent_pubkey: alice_pubkey.clone(),
+                    chain_query_filter: ChainQueryFilter::new(),
+                    activity_request: ActivityRequest::Full,
+                },
+            )
+            .await;
+        assert!(alice_activity
+            .valid_activity
+            .iter()
+            .any(|(_, hash)| *hash == countersigned_action_hash_alice));
+        let _: ActionHash = conductor.call(&alice, "create_a_thing", ()).await;
+    }
 }
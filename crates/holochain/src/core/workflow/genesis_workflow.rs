@@ -97,14 +97,14 @@ where
         return Err(WorkflowError::GenesisFailure(reason));
     }
 
-    // NB: this is just a placeholder for a real DPKI request to show intent
-    if !api
-        .conductor_services()
-        .dpki
-        .is_key_valid(agent_pubkey.clone(), Timestamp::now())
-        .await?
-    {
-        return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
+    // If the conductor is running DPKI, the agent key must be registered and valid
+    if let Some(dpki) = api.conductor_services().dpki {
+        if !dpki
+            .is_key_valid(agent_pubkey.clone(), Timestamp::now())
+            .await?
+        {
+            return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
+        }
     }
 
     source_chain::genesis(
@@ -192,8 +192,8 @@ pub mod tests {
             let mut api = MockCellConductorApiT::new();
             api.expect_conductor_services()
                 .return_const(ConductorServices {
-                    dpki: Arc::new(mock_dpki()),
-                    app_store: Arc::new(mock_app_store()),
+                    dpki: Some(Arc::new(mock_dpki())),
+                    app_store: Some(Arc::new(mock_app_store())),
                 });
             api.expect_keystore().return_const(keystore.clone());
             let mut ribosome = MockRibosomeT::new();
@@ -1,6 +1,7 @@
 //! The workflow and queue consumer for DhtOp integration
 
 use super::*;
+use crate::conductor::kitsune_host_impl::query_region_set::DhtRegionTree;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
 use error::WorkflowResult;
@@ -17,10 +18,11 @@ mod query_tests;
 #[cfg(feature = "test_utils")]
 mod tests;
 
-#[instrument(skip(vault, trigger_receipt, network, dht_query_cache))]
+#[instrument(skip(vault, trigger_receipt, network, dht_query_cache, dht_region_tree))]
 pub async fn integrate_dht_ops_workflow(
     vault: DbWrite<DbKindDht>,
     dht_query_cache: DhtDbQueryCache,
+    dht_region_tree: DhtRegionTree,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
 ) -> WorkflowResult<WorkComplete> {
@@ -96,6 +98,8 @@ pub async fn integrate_dht_ops_workflow(
     let ops_ps = changed as f64 / start.elapsed().as_micros() as f64 * 1_000_000.0;
     tracing::debug!(?changed, %ops_ps);
     if changed > 0 {
+        // Bring the region tree up to date before gossip is told about the new data.
+        dht_region_tree.sync(&vault).await?;
         trigger_receipt.trigger(&"integrate_dht_ops_workflow");
         network.new_integrated_data().await?;
         Ok(WorkComplete::Incomplete)
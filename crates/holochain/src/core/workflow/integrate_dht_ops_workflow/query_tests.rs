@@ -102,9 +102,15 @@ async fn integrate_query() {
     // dump_tmp(&db.db());
     let test_network = test_network(None, None).await;
     let holochain_p2p_cell = test_network.dna_network();
-    integrate_dht_ops_workflow(db.to_db().into(), db.to_db().into(), qt, holochain_p2p_cell)
-        .await
-        .unwrap();
+    integrate_dht_ops_workflow(
+        db.to_db().into(),
+        db.to_db().into(),
+        Default::default(),
+        qt,
+        holochain_p2p_cell,
+    )
+    .await
+    .unwrap();
     let hashes = db
         .conn()
         .unwrap()
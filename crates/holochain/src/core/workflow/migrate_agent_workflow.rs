@@ -0,0 +1,104 @@
+//! Migrating an agent to a new version of a DNA, by closing the agent's chain
+//! on the old DNA and opening a chain on the new DNA which refers back to it.
+
+use super::error::WorkflowResult;
+use crate::conductor::ConductorHandle;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use derive_more::Constructor;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_types::prelude::*;
+use holochain_zome_types::action::builder;
+
+#[derive(Constructor)]
+pub struct MigrateAgentWorkflowArgs<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    pub ribosome: Ribosome,
+    pub conductor_handle: ConductorHandle,
+    /// Whether this chain is being closed or opened
+    pub migrate_agent: MigrateAgent,
+    /// The DNA on the other side of the migration
+    pub other_dna_hash: DnaHash,
+}
+
+/// Run the `migrate_agent` callback of every zome for one side of a migration.
+/// Nothing is written to the chain, so the callbacks of both sides can be run
+/// before either chain is changed.
+pub fn check_migrate_agent<Ribosome>(
+    workspace: SourceChainWorkspace,
+    ribosome: &Ribosome,
+    migrate_agent: MigrateAgent,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT,
+{
+    let dna_def = ribosome.dna_def().as_content().clone();
+    Ok(ribosome.run_migrate_agent(
+        MigrateAgentHostAccess::new(workspace.into()),
+        MigrateAgentInvocation::new(dna_def, migrate_agent),
+    )?)
+}
+
+/// Commit the [`CloseChain`](Action::CloseChain) or [`OpenChain`](Action::OpenChain)
+/// action for one side of a migration.
+///
+/// The `migrate_agent` callbacks must already have passed,
+/// see [`check_migrate_agent`].
+pub async fn migrate_agent_workflow<Ribosome>(
+    workspace: SourceChainWorkspace,
+    network: HolochainP2pDna,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<ActionHash>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    let MigrateAgentWorkflowArgs {
+        ribosome,
+        conductor_handle,
+        migrate_agent,
+        other_dna_hash,
+    } = args;
+
+    let source_chain = workspace.source_chain();
+    let action_hash = match migrate_agent {
+        MigrateAgent::Close => {
+            source_chain
+                .put(
+                    builder::CloseChain {
+                        new_dna_hash: other_dna_hash,
+                    },
+                    None,
+                    ChainTopOrdering::Strict,
+                )
+                .await?
+        }
+        MigrateAgent::Open => {
+            source_chain
+                .put(
+                    builder::OpenChain {
+                        prev_dna_hash: other_dna_hash,
+                    },
+                    None,
+                    ChainTopOrdering::Strict,
+                )
+                .await?
+        }
+    };
+
+    super::inline_validation(
+        workspace.clone(),
+        network.clone(),
+        conductor_handle,
+        ribosome,
+    )
+    .await?;
+
+    HostFnWorkspace::from(workspace).flush(&network).await?;
+    Ok(action_hash)
+}
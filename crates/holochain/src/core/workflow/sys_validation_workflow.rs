@@ -6,6 +6,9 @@ use crate::conductor::Conductor;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsHostAccess;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::RibosomeT;
 use crate::core::sys_validate::check_and_hold_store_record;
 use crate::core::sys_validate::*;
 use crate::core::validation::*;
@@ -88,11 +91,19 @@ async fn sys_validation_workflow_inner(
     tracing::debug!("Validating {} ops", start_len);
     let start = (start_len >= NUM_CONCURRENT_OPS).then(std::time::Instant::now);
     let saturated = start.is_some();
-    let cascade = workspace.full_cascade(network);
+    let cascade = workspace.full_cascade(network.clone());
+
+    // The rate limits are the same for every op in this DNA.
+    let rate_limits: Arc<[RateLimit]> = conductor_handle
+        .get_ribosome(workspace.dna_hash())
+        .map_err(|_| SysValidationError::DnaMissing(workspace.dna_hash().clone()))?
+        .run_rate_limits(RateLimitsHostAccess, RateLimitsInvocation)?
+        .into();
 
     // Process each op
     let iter = sorted_ops.into_iter().map({
         let space = space.clone();
+        let conductor_handle = conductor_handle.clone();
         move |so| {
             // Create an incoming ops sender for any dependencies we find
             // that we are meant to be holding but aren't.
@@ -102,6 +113,7 @@ async fn sys_validation_workflow_inner(
             let conductor_handle = conductor_handle.clone();
             let workspace = workspace.clone();
             let cascade = cascade.clone();
+            let rate_limits = rate_limits.clone();
             async move {
                 let (op, op_hash) = so.into_inner();
                 let op_type = op.get_type();
@@ -114,10 +126,16 @@ async fn sys_validation_workflow_inner(
                     &workspace,
                     cascade,
                     conductor_handle.as_ref(),
+                    &rate_limits,
                     Some(incoming_dht_ops_sender),
                 )
                 .await;
-                r.map(|o| (op_hash, o, dependency))
+                r.map(|o| {
+                    // Keep hold of rejected actions so they can be warranted.
+                    let invalid = matches!(o, Outcome::Rejected)
+                        .then(|| SignedAction(action, op.signature().clone()));
+                    (op_hash, o, dependency, invalid)
+                })
             }
         }
     });
@@ -154,15 +172,17 @@ async fn sys_validation_workflow_inner(
     while let Some(chunk) = iter.next().await {
         let num_ops: usize = chunk.iter().map(|c| c.len()).sum();
         tracing::debug!("Committing {} ops", num_ops);
-        let (t, a, m, r) = space
+        let (t, a, m, r, invalid) = space
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut missing = 0;
                 let mut rejected = 0;
+                let mut invalid = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, outcome, dependency) = outcome?;
+                    let (op_hash, outcome, dependency, invalid_action) = outcome?;
+                    invalid.extend(invalid_action);
                     match outcome {
                         Outcome::Accepted => {
                             total += 1;
@@ -201,10 +221,12 @@ async fn sys_validation_workflow_inner(
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, missing, rejected))
+                WorkflowResult::Ok((total, awaiting, missing, rejected, invalid))
             })
             .await?;
 
+        issue_warrants(&conductor_handle, &network, invalid, ValidationType::Sys).await;
+
         total += t;
         if let (Some(start), Some(round_time)) = (start, &mut round_time) {
             let round_el = round_time.elapsed();
@@ -239,6 +261,7 @@ async fn validate_op(
     workspace: &SysValidationWorkspace,
     cascade: Cascade,
     conductor_handle: &Conductor,
+    rate_limits: &[RateLimit],
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> WorkflowResult<Outcome> {
     let dna_def = DnaDefHashed::from_content_sync((*workspace.dna_def()).clone());
@@ -247,6 +270,7 @@ async fn validate_op(
         &cascade,
         dna_def,
         conductor_handle,
+        rate_limits,
         incoming_dht_ops_sender,
     )
     .await
@@ -266,10 +290,26 @@ async fn validate_op(
             }
             Ok(outcome)
         }
+        // DPKI may just not be reachable yet, so try again later
+        Err(SysValidationError::DpkiServiceError(e)) => {
+            warn!(
+                msg = "Could not check the author key of a DhtOp with DPKI.",
+                ?op,
+                error = ?e,
+            );
+            Ok(Outcome::MissingDhtDep)
+        }
         Err(e) => Err(e.into()),
     }
 }
 
+/// Whether a failed direct validation, such as [`sys_validate_record`],
+/// shows the data to be invalid rather than just missing its dependencies.
+pub(crate) fn is_rejected(outcome: ValidationOutcome) -> bool {
+    !matches!(outcome, ValidationOutcome::Counterfeit(_, _))
+        && matches!(handle_failed(outcome), Outcome::Rejected)
+}
+
 /// For now errors result in an outcome but in the future
 /// we might find it useful to include the reason something
 /// was rejected etc.
@@ -282,6 +322,7 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         }
         ValidationOutcome::ActionNotInCounterSigningSession(_, _) => Rejected,
         ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
+        ValidationOutcome::DpkiAgentInvalid(_, _) => Rejected,
         ValidationOutcome::EntryDefId(_) => Rejected,
         ValidationOutcome::EntryHash => Rejected,
         ValidationOutcome::EntryTooLarge(_, _) => Rejected,
@@ -297,6 +338,9 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         ValidationOutcome::PrevActionError(_) => Rejected,
         ValidationOutcome::PrivateEntry => Rejected,
         ValidationOutcome::PreflightResponseSignature(_) => Rejected,
+        ValidationOutcome::RateBytes(_, _) => Rejected,
+        ValidationOutcome::RateLimitExceeded(_, _, _) => Rejected,
+        ValidationOutcome::RateWeight(_, _) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
         ValidationOutcome::VerifySignature(_, _) => Rejected,
         ValidationOutcome::WrongDna(_, _) => Rejected,
@@ -310,8 +354,11 @@ async fn validate_op_inner(
     cascade: &Cascade,
     dna_def: DnaDefHashed,
     conductor_handle: &Conductor,
+    rate_limits: &[RateLimit],
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
+    author_key_is_valid(&op.action(), dna_def.as_hash(), conductor_handle).await?;
+
     match op {
         DhtOp::StoreRecord(_, action, entry) => {
             store_record(action, cascade).await?;
@@ -374,7 +421,14 @@ async fn validate_op_inner(
             Ok(())
         }
         DhtOp::RegisterAgentActivity(_, action) => {
-            register_agent_activity(action, cascade, &dna_def, incoming_dht_ops_sender).await?;
+            register_agent_activity(
+                action,
+                cascade,
+                &dna_def,
+                rate_limits,
+                incoming_dht_ops_sender,
+            )
+            .await?;
             store_record(action, cascade).await?;
             Ok(())
         }
@@ -519,11 +573,10 @@ async fn sys_validate_record_inner(
     }
 }
 
-/// Check if the op has valid signature and author.
+/// Check if the op has a valid signature.
 /// Ops that fail this check should be dropped.
 pub async fn counterfeit_check(signature: &Signature, action: &Action) -> SysValidationResult<()> {
     verify_action_signature(signature, action).await?;
-    author_key_is_valid(action.author()).await?;
     Ok(())
 }
 
@@ -531,6 +584,7 @@ async fn register_agent_activity(
     action: &Action,
     cascade: &Cascade,
     dna_def: &DnaDefHashed,
+    rate_limits: &[RateLimit],
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
@@ -548,6 +602,7 @@ async fn register_agent_activity(
         )
         .await?;
     }
+    check_rate_limit(action, rate_limits, cascade).await?;
     // not appropriate for sys validation
     // check_chain_rollback(action, workspace).await?;
     Ok(())
@@ -598,6 +653,7 @@ async fn store_entry(
 
     check_entry_hash(entry_hash, entry).await?;
     check_entry_size(entry)?;
+    check_entry_rate_bytes(&action, entry)?;
 
     // Additional checks if this is an Update
     if let NewEntryActionRef::Update(entry_update) = action {
@@ -1,4 +1,6 @@
 use crate::holochain_wasmer_host::prelude::*;
+use crate::sweettest::SweetAgents;
+use crate::sweettest::SweetConductor;
 use crate::sweettest::SweetConductorBatch;
 use crate::sweettest::SweetDnaFile;
 use crate::test_utils::host_fn_caller::*;
@@ -29,24 +31,440 @@ async fn sys_validation_workflow_test() {
 
     let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
 
-    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
-    let apps = conductors
-        .setup_app(&"test_app", &[dna_file.clone()])
+    // Alice and bob share a conductor, which never warrants or blocks its own
+    // agents, so bob's ops keep reaching alice after she rejects some of them.
+    // See `authors_of_large_links_are_warranted` for what happens otherwise.
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let agents = SweetAgents::get(conductor.keystore(), 2).await;
+    let apps = conductor
+        .setup_app_for_agents("test_app", &agents, &[dna_file.clone()])
         .await
         .unwrap();
     let ((alice,), (bob,)) = apps.into_tuples();
     let alice_cell_id = alice.cell_id().clone();
     let bob_cell_id = bob.cell_id().clone();
 
+    run_test(alice_cell_id, bob_cell_id, conductor, dna_file).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn authors_of_large_links_are_warranted() {
+    use holochain_state::prelude::list_warrants;
+
+    holochain_trace::test_run().ok();
+
+    let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("test_app", &[dna_file.clone()])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let (_, _, link_add_hash) =
+        bob_makes_a_large_link(bob.cell_id(), &conductors[1].raw_handle(), &dna_file).await;
+
+    // Alice warrants bob for his large link and blocks him,
+    // so nothing more he publishes reaches her.
+    let alice_db = conductors[0]
+        .get_dht_db(alice.cell_id().dna_hash())
+        .unwrap();
+    let warranted_actions = || {
+        fresh_reader_test(alice_db.clone(), |txn| {
+            list_warrants(&txn, bob.agent_pubkey())
+                .unwrap()
+                .into_iter()
+                .map(|warrant| warrant.action_hash())
+                .collect::<Vec<_>>()
+        })
+    };
+    crate::assert_eq_retry_1m!(warranted_actions().contains(&link_add_hash), true);
+    assert!(conductors[0]
+        .is_blocked(BlockTargetId::Cell(bob.cell_id().clone()), Timestamp::now())
+        .await
+        .unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn actions_by_revoked_keys_are_rejected() {
+    use crate::conductor::conductor::{DpkiService, DpkiServiceExt};
+    use crate::conductor::Conductor;
+    use crate::sweettest::*;
+    use crate::test_utils::inline_zomes::{dpki_zome, simple_crud_zome, AppString};
+
+    holochain_trace::test_run().ok();
+
+    let (dpki_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(dpki_zome()).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+
+    let rendezvous = SweetLocalRendezvous::new().await;
+    let mut conductors = vec![];
+    for _ in 0..2 {
+        let config = SweetConductorConfig::standard()
+            .into_conductor_config(&*rendezvous)
+            .await;
+        let builder = Conductor::builder()
+            .config(config)
+            .with_dpki_dna(dpki_dna.clone());
+        conductors.push(SweetConductor::from_builder(builder).await);
+    }
+
+    let (alice,) = conductors[0]
+        .setup_app("app", [&dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    let (bob,) = conductors[1]
+        .setup_app("app", [&dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    SweetConductor::exchange_peer_info(&conductors).await;
+
+    let valid: ActionHash = conductors[0]
+        .call(
+            &alice.zome(SweetInlineZomes::COORDINATOR),
+            "create_string",
+            AppString::new("valid"),
+        )
+        .await;
+
+    conductors[0]
+        .dpki_service()
+        .unwrap()
+        .remove_key(alice.agent_pubkey().clone())
+        .await
+        .unwrap();
+
+    // Each conductor runs its own DPKI cell, so bob only learns of the
+    // revocation once it has reached him over the DPKI DNA's DHT.
+    let bob_key_state = || async {
+        conductors[1]
+            .dpki_service()
+            .unwrap()
+            .key_state(alice.agent_pubkey().clone())
+            .await
+            .unwrap()
+    };
+    crate::assert_eq_retry_1m!(
+        matches!(bob_key_state().await, KeyState::Invalidated { .. }),
+        true
+    );
+
+    let revoked: ActionHash = conductors[0]
+        .call(
+            &alice.zome(SweetInlineZomes::COORDINATOR),
+            "create_string",
+            AppString::new("revoked"),
+        )
+        .await;
+
+    // Bob accepts the ops of alice's action from before the revocation,
+    // and rejects the ops of the action from after it
+    let bob_db = conductors[1].get_dht_db(bob.cell_id().dna_hash()).unwrap();
+    let count_ops = |action_hash: &ActionHash, status: ValidationStatus| {
+        fresh_reader_test(bob_db.clone(), |txn| {
+            txn.query_row(
+                "SELECT COUNT(hash) FROM DhtOp
+                WHERE action_hash = :action_hash AND validation_status = :status",
+                named_params! {
+                    ":action_hash": action_hash,
+                    ":status": status,
+                },
+                |row| row.get::<_, usize>(0),
+            )
+            .unwrap()
+        })
+    };
+    crate::assert_eq_retry_1m!(count_ops(&valid, ValidationStatus::Valid), 3);
+    crate::assert_eq_retry_1m!(count_ops(&revoked, ValidationStatus::Rejected), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn actions_over_the_rate_limit_are_rejected() {
+    use crate::sweettest::*;
+    use crate::test_utils::inline_zomes::AppString;
+    use holochain_types::inline_zome::InlineZomeSet;
+    use rusqlite::OptionalExtension;
+
+    holochain_trace::test_run().ok();
+
+    let zomes = SweetInlineZomes::new(vec![EntryDef::from_id("string")], 0)
+        .integrity_function("weigh", |_, input: WeighInput| {
+            Ok(match input {
+                WeighInput::Create(_, _) => RateWeight {
+                    bucket_id: 1,
+                    units: 10,
+                },
+                _ => RateWeight::default(),
+            })
+        })
+        .integrity_function("rate_limits", |_, ()| {
+            Ok(vec![RateLimit {
+                bucket_id: 1,
+                capacity: 25,
+                drain_per_second: 1,
+            }])
+        })
+        .function("create_string", |api, s: AppString| {
+            let entry = Entry::app(s.try_into().unwrap()).unwrap();
+            let hash = api.create(CreateInput::new(
+                InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                EntryVisibility::Public,
+                entry,
+                ChainTopOrdering::default(),
+            ))?;
+            Ok(hash)
+        });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes).await;
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuples();
     conductors.exchange_peer_info().await;
 
-    run_test(alice_cell_id, bob_cell_id, conductors, dna_file).await;
+    // Each create costs 10 units plus 1 rate byte, so only two fit in the bucket
+    // unless several seconds pass between the creates.
+    let mut hashes: Vec<ActionHash> = vec![];
+    for s in ["one", "two", "three"] {
+        hashes.push(
+            conductors[0]
+                .call(
+                    &alice.zome(SweetInlineZomes::COORDINATOR),
+                    "create_string",
+                    AppString::new(s),
+                )
+                .await,
+        );
+    }
+
+    // Bob rejects the activity of alice's third action.
+    let db = conductors[1].get_dht_db(bob.cell_id().dna_hash()).unwrap();
+    let activity_status = |action_hash: &ActionHash| {
+        fresh_reader_test(db.clone(), |txn| {
+            txn.query_row(
+                "SELECT validation_status FROM DhtOp
+                WHERE action_hash = :action_hash AND type = :type",
+                named_params! {
+                    ":action_hash": action_hash,
+                    ":type": DhtOpType::RegisterAgentActivity,
+                },
+                |row| row.get::<_, Option<ValidationStatus>>(0),
+            )
+            .optional()
+            .unwrap()
+            .flatten()
+        })
+    };
+    crate::assert_eq_retry_1m!(activity_status(&hashes[0]), Some(ValidationStatus::Valid));
+    crate::assert_eq_retry_1m!(activity_status(&hashes[1]), Some(ValidationStatus::Valid));
+    crate::assert_eq_retry_1m!(
+        activity_status(&hashes[2]),
+        Some(ValidationStatus::Rejected)
+    );
+
+    // The weight from the callback was stamped on the action.
+    let action: SignedAction = fresh_reader_test(db.clone(), |txn| {
+        txn.query_row(
+            "SELECT blob FROM Action WHERE hash = :hash",
+            named_params! { ":hash": hashes[0] },
+            |row| Ok(from_blob::<SignedAction>(row.get("blob")?).unwrap()),
+        )
+        .unwrap()
+    });
+    assert_eq!(
+        action.action().entry_rate_data(),
+        Some(EntryRateWeight {
+            bucket_id: 1,
+            units: 10,
+            rate_bytes: 1,
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dnas_with_rate_limits_that_never_drain_are_refused() {
+    use crate::conductor::error::ConductorError;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::sweettest::*;
+    use matches::assert_matches;
+
+    let zomes = SweetInlineZomes::new(vec![], 0).integrity_function("rate_limits", |_, ()| {
+        Ok(vec![RateLimit {
+            bucket_id: 1,
+            capacity: 5,
+            drain_per_second: 0,
+        }])
+    });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes).await;
+
+    let conductor = SweetConductor::from_standard_config().await;
+    assert_matches!(
+        conductor.register_dna(dna_file).await,
+        Err(ConductorError::RibosomeError(RibosomeError::RateLimits(..)))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn authors_of_rejected_actions_are_warranted() {
+    use crate::sweettest::*;
+    use crate::test_utils::inline_zomes::AppString;
+    use holochain_state::prelude::list_warrants;
+    use holochain_types::inline_zome::InlineZomeSet;
+
+    holochain_trace::test_run().ok();
+
+    // Every create overflows the bucket, so sys validation rejects it.
+    let zomes = SweetInlineZomes::new(vec![EntryDef::from_id("string")], 0)
+        .integrity_function("weigh", |_, _: WeighInput| {
+            Ok(RateWeight {
+                bucket_id: 1,
+                units: 10,
+            })
+        })
+        .integrity_function("rate_limits", |_, ()| {
+            Ok(vec![RateLimit {
+                bucket_id: 1,
+                capacity: 5,
+                drain_per_second: 1,
+            }])
+        })
+        .function("create_string", |api, s: AppString| {
+            let entry = Entry::app(s.try_into().unwrap()).unwrap();
+            let hash = api.create(CreateInput::new(
+                InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                EntryVisibility::Public,
+                entry,
+                ChainTopOrdering::default(),
+            ))?;
+            Ok(hash)
+        });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes).await;
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let invalid: ActionHash = conductors[0]
+        .call(
+            &alice.zome(SweetInlineZomes::COORDINATOR),
+            "create_string",
+            AppString::new("too heavy"),
+        )
+        .await;
+
+    // Bob warrants alice for the invalid action and blocks her.
+    let bob_db = conductors[1].get_dht_db(bob.cell_id().dna_hash()).unwrap();
+    let warrants = |db: DbWrite<DbKindDht>| {
+        fresh_reader_test(db, |txn| list_warrants(&txn, alice.agent_pubkey()).unwrap())
+    };
+    crate::assert_eq_retry_1m!(warrants(bob_db.clone()).len(), 1);
+    let warrant = warrants(bob_db).pop().unwrap();
+    assert_eq!(warrant.warrant().author, *bob.agent_pubkey());
+    assert_eq!(warrant.warrant().warrantee(), alice.agent_pubkey());
+    assert_eq!(warrant.action_hash(), invalid);
+    assert!(warrant.is_valid().await);
+    assert!(conductors[1]
+        .is_blocked(
+            BlockTargetId::Cell(alice.cell_id().clone()),
+            Timestamp::now()
+        )
+        .await
+        .unwrap());
+
+    // Alice, as one of her own agent activity authorities, receives the
+    // warrant too, but doesn't block herself.
+    let alice_db = conductors[0]
+        .get_dht_db(alice.cell_id().dna_hash())
+        .unwrap();
+    crate::assert_eq_retry_1m!(warrants(alice_db.clone()), vec![warrant.clone()]);
+    assert!(!conductors[0]
+        .is_blocked(
+            BlockTargetId::Cell(alice.cell_id().clone()),
+            Timestamp::now()
+        )
+        .await
+        .unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn authors_of_false_warrants_are_blocked() {
+    use crate::sweettest::*;
+    use holochain_state::prelude::list_warrants;
+
+    holochain_trace::test_run().ok();
+
+    let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let ((alice,), (bob,)) = conductors
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuples();
+    let dna_hash = alice.cell_id().dna_hash().clone();
+
+    let _: ActionHash = conductors[0]
+        .call(
+            &alice.zome(TestWasm::Create.coordinator_zome_name()),
+            "create_entry",
+            (),
+        )
+        .await;
+    let record = conductors[0]
+        .get_or_create_space(&dna_hash)
+        .unwrap()
+        .source_chain(conductors[0].keystore(), alice.agent_pubkey().clone())
+        .await
+        .unwrap()
+        .query(ChainQueryFilter::new().descending())
+        .await
+        .unwrap()
+        .remove(0);
+
+    // Bob warrants alice for an action which is perfectly valid.
+    let warrant = Warrant::new(
+        WarrantProof::InvalidAction {
+            action: SignedAction(record.action().clone(), record.signature().clone()),
+            validation_type: ValidationType::App,
+        },
+        bob.agent_pubkey().clone(),
+        Timestamp::now(),
+    );
+    let warrant = SignedWarrant::sign(&conductors[1].keystore(), warrant)
+        .await
+        .unwrap();
+    assert!(warrant.is_valid().await);
+    conductors[0]
+        .handle_publish_warrant(&dna_hash, warrant)
+        .await
+        .unwrap();
+
+    // Validation shows the warrant to be false, so it isn't held and
+    // bob is blocked instead of alice.
+    let alice_db = conductors[0].get_dht_db(&dna_hash).unwrap();
+    let held = fresh_reader_test(alice_db, |txn| {
+        list_warrants(&txn, alice.agent_pubkey()).unwrap()
+    });
+    assert!(held.is_empty());
+    assert!(conductors[0]
+        .is_blocked(BlockTargetId::Cell(bob.cell_id().clone()), Timestamp::now())
+        .await
+        .unwrap());
 }
 
 async fn run_test(
     alice_cell_id: CellId,
     bob_cell_id: CellId,
-    conductors: SweetConductorBatch,
+    conductor: SweetConductor,
     dna_file: DnaFile,
 ) {
     // Check if the correct number of ops are integrated
@@ -55,14 +473,14 @@ async fn run_test(
     let num_attempts = 100;
     let delay_per_attempt = Duration::from_millis(100);
 
-    bob_links_in_a_legit_way(&bob_cell_id, &conductors[1].raw_handle(), &dna_file).await;
+    bob_links_in_a_legit_way(&bob_cell_id, &conductor.raw_handle(), &dna_file).await;
 
     // Integration should have 9 ops in it.
     // Plus another 14 for genesis.
     // Init is not run because we aren't calling the zome.
     let expected_count = 9 + 14;
 
-    let alice_dht_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    let alice_dht_db = conductor.get_dht_db(alice_cell_id.dna_hash()).unwrap();
     wait_for_integration(
         &alice_dht_db,
         expected_count,
@@ -99,12 +517,12 @@ async fn run_test(
     });
 
     let (bad_update_action, bad_update_entry_hash, link_add_hash) =
-        bob_makes_a_large_link(&bob_cell_id, &conductors[1].raw_handle(), &dna_file).await;
+        bob_makes_a_large_link(&bob_cell_id, &conductor.raw_handle(), &dna_file).await;
 
     // Integration should have 14 ops in it + the running tally
     let expected_count = 14 + expected_count;
 
-    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    let alice_db = conductor.get_dht_db(alice_cell_id.dna_hash()).unwrap();
     wait_for_integration(
         &alice_db,
         expected_count,
@@ -164,12 +582,12 @@ async fn run_test(
         assert_eq!(valid_ops, expected_count);
     });
 
-    dodgy_bob(&bob_cell_id, &conductors[1].raw_handle(), &dna_file).await;
+    dodgy_bob(&bob_cell_id, &conductor.raw_handle(), &dna_file).await;
 
     // Integration should have new 5 ops in it
     let expected_count = 5 + expected_count;
 
-    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    let alice_db = conductor.get_dht_db(alice_cell_id.dna_hash()).unwrap();
     wait_for_integration(
         &alice_db,
         expected_count,
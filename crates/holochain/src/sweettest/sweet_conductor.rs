@@ -100,10 +100,13 @@ impl SweetConductor {
         // to actually access those databases.
         // As a TODO, we can remove the need for TestEnvs in sweettest or have
         // some other better integration between the two.
-        let spaces = Spaces::new(&ConductorConfig {
-            environment_path: env_dir.to_path_buf().into(),
-            ..Default::default()
-        })
+        let spaces = Spaces::new(
+            &ConductorConfig {
+                environment_path: env_dir.to_path_buf().into(),
+                ..Default::default()
+            },
+            None,
+        )
         .unwrap();
 
         let keystore = handle.keystore().clone();
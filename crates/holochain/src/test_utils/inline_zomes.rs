@@ -1,5 +1,7 @@
 //! Collection of commonly used InlineZomes
 
+use std::collections::HashMap;
+
 use holo_hash::*;
 use holochain_types::inline_zome::InlineEntryTypes;
 use holochain_types::inline_zome::InlineZomeSet;
@@ -150,3 +152,119 @@ pub fn simple_crud_zome() -> InlineZomeSet {
         })
         .0
 }
+
+/// An InlineZome implementing the DPKI contract, for use as a conductor's
+/// DPKI service in tests.
+///
+/// Each change to a key's state is a link from the key to itself, tagged with
+/// what happened to it, so key states reach other conductors over the DHT of
+/// the DPKI DNA, like they would with a real DPKI implementation.
+pub fn dpki_zome() -> InlineZomeSet {
+    const REGISTERED: &[u8] = b"registered";
+    const INVALIDATED: &[u8] = b"invalidated";
+
+    let key_state = |api: &BoxApi, key: AgentPubKey| -> InlineZomeResult<KeyState> {
+        let links = api
+            .get_links(vec![GetLinksInput::new(
+                key.into(),
+                LinkTypeFilter::single_dep(0.into()),
+                None,
+            )])?
+            .pop()
+            .unwrap_or_default();
+        let happened_at = |tag: &[u8]| {
+            links
+                .iter()
+                .filter(|link| link.tag.0 == tag)
+                .map(|link| link.timestamp)
+                .min()
+        };
+        Ok(match (happened_at(REGISTERED), happened_at(INVALIDATED)) {
+            (None, _) => KeyState::NotFound,
+            (Some(registered_at), None) => KeyState::Valid { registered_at },
+            (Some(registered_at), Some(invalidated_at)) => KeyState::Invalidated {
+                registered_at,
+                invalidated_at,
+            },
+        })
+    };
+    let mark = |api: &BoxApi, key: AgentPubKey, tag: &[u8]| -> InlineZomeResult<()> {
+        api.create_link(CreateLinkInput::new(
+            key.clone().into(),
+            key.into(),
+            ZomeIndex(0),
+            LinkType::new(0),
+            tag.to_vec().into(),
+            ChainTopOrdering::default(),
+        ))?;
+        Ok(())
+    };
+    let invalidate = move |api: &BoxApi, key: AgentPubKey| -> InlineZomeResult<()> {
+        match key_state(api, key.clone())? {
+            KeyState::Valid { .. } => mark(api, key, INVALIDATED),
+            _ => Err(InlineZomeError::TestError(format!(
+                "Key {} is not valid, so cannot be invalidated",
+                key
+            ))),
+        }
+    };
+
+    InlineZomeSet::new_unique([("dpki_integrity", vec![], 1)], [DPKI_ZOME_NAME])
+        .with_dependency(DPKI_ZOME_NAME, "dpki_integrity")
+        .function(
+            DPKI_ZOME_NAME,
+            DPKI_KEY_STATE_FN,
+            move |api, key: AgentPubKey| key_state(&api, key),
+        )
+        .function(
+            DPKI_ZOME_NAME,
+            DPKI_REGISTER_KEY_FN,
+            move |api, key: AgentPubKey| mark(&api, key, REGISTERED),
+        )
+        .function(
+            DPKI_ZOME_NAME,
+            DPKI_UPDATE_KEY_FN,
+            move |api, input: UpdateKeyInput| {
+                invalidate(&api, input.old_key)?;
+                mark(&api, input.new_key, REGISTERED)
+            },
+        )
+        .function(
+            DPKI_ZOME_NAME,
+            DPKI_REVOKE_KEY_FN,
+            move |api, key: AgentPubKey| invalidate(&api, key),
+        )
+}
+
+/// The published bundles of an [`app_store_zome`], as encoded bytes keyed by
+/// the AppHash or DnaHash they were published under
+#[derive(Default)]
+pub struct AppStoreBundles {
+    /// Encoded app bundles
+    pub apps: HashMap<AppHash, Bytes>,
+    /// Encoded DNA bundles
+    pub dnas: HashMap<DnaHash, Bytes>,
+}
+
+/// Shared handle to the contents of an [`app_store_zome`]
+pub type AppStoreRegistry = std::sync::Arc<parking_lot::Mutex<AppStoreBundles>>;
+
+/// An InlineZome implementing the app store contract, for use as a conductor's
+/// app store in tests.
+///
+/// Bundles are "published" by inserting them into the returned
+/// [`AppStoreRegistry`], which serves them to every conductor running the DNA.
+pub fn app_store_zome() -> (InlineZomeSet, AppStoreRegistry) {
+    let registry = AppStoreRegistry::default();
+    let zomes =
+        InlineZomeSet::new_unique([("app_store_integrity", vec![], 0)], [APP_STORE_ZOME_NAME])
+            .function(APP_STORE_ZOME_NAME, APP_STORE_GET_APP_BUNDLE_FN, {
+                let registry = registry.clone();
+                move |_, hash: AppHash| Ok(registry.lock().apps.get(&hash).cloned())
+            })
+            .function(APP_STORE_ZOME_NAME, APP_STORE_GET_DNA_BUNDLE_FN, {
+                let registry = registry.clone();
+                move |_, hash: DnaHash| Ok(registry.lock().dnas.get(&hash).cloned())
+            });
+    (zomes, registry)
+}
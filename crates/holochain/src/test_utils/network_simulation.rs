@@ -236,10 +236,10 @@ fn cache_data(in_memory: bool, data: &MockNetworkData, is_cached: bool) -> Conne
         return conn;
     }
     holochain_sqlite::schema::SCHEMA_CELL
-        .initialize(&mut conn, None)
+        .initialize(&mut conn, None, None)
         .unwrap();
     holochain_sqlite::schema::SCHEMA_P2P_STATE
-        .initialize(&mut conn, None)
+        .initialize(&mut conn, None, None)
         .unwrap();
     let mut txn = conn
         .transaction_with_behavior(rusqlite::TransactionBehavior::Exclusive)
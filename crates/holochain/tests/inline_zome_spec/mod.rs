@@ -291,6 +291,180 @@ async fn signal_subscription() {
     assert_eq!(signals.len(), N);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "test_utils")]
+async fn signal_subscription_filters() {
+    use holochain::conductor::api::{AppRequest, AppResponse, InterfaceApi, RealAppInterfaceApi};
+    use holochain_conductor_api::signal_subscription::{
+        SignalFilter, SignalFilterSet, SignalSubscription,
+    };
+    use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
+
+    holochain_trace::test_run().ok();
+
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id().clone();
+    let zome = &app.cells()[0].zome(SweetInlineZomes::COORDINATOR);
+
+    let mut signals = conductor.signals();
+    let _: () = conductor.call(zome, "emit_signal", ()).await;
+    let signal = signals.next().await.unwrap();
+
+    let connect = || async {
+        let api = RealAppInterfaceApi::new(conductor.raw_handle()).for_connection();
+        let token = conductor
+            .raw_handle()
+            .issue_app_authentication_token(
+                IssueAppAuthenticationTokenPayload::for_installed_app_id("app".to_string()),
+            )
+            .await
+            .unwrap()
+            .token;
+        let response = api
+            .handle_request(Ok(AppRequest::Authenticate { token }))
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::Authenticated(_));
+        api
+    };
+    let api = connect().await;
+    let subscribe = |filter: SignalFilter| {
+        let filters = SignalFilterSet::Include([(cell_id.clone(), filter)].into());
+        AppRequest::SetSignalSubscription(Box::new(SignalSubscription::new(
+            "app".to_string(),
+            filters,
+        )))
+    };
+
+    // With no subscription every signal is allowed
+    assert!(api.allows_signal(&signal));
+
+    let response = api
+        .handle_request(Ok(subscribe(SignalFilter::empty().with_zome_name("other"))))
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::SignalSubscriptionSet);
+    assert!(!api.allows_signal(&signal));
+
+    // Subscriptions are not shared between connections
+    assert!(connect().await.allows_signal(&signal));
+
+    // Setting a subscription replaces the previous one
+    api.handle_request(Ok(subscribe(
+        SignalFilter::empty().with_zome_name(SweetInlineZomes::COORDINATOR),
+    )))
+    .await
+    .unwrap();
+    assert!(api.allows_signal(&signal));
+
+    // Subscribing to an app which isn't installed is an error
+    let response = api
+        .handle_request(Ok(AppRequest::SetSignalSubscription(Box::new(
+            SignalSubscription::new("nope".to_string(), SignalFilterSet::default()),
+        ))))
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::Error(_));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "test_utils")]
+async fn app_interface_connections_are_restricted_to_authenticated_app() {
+    use holochain::conductor::api::{AppRequest, AppResponse, InterfaceApi, RealAppInterfaceApi};
+    use holochain_conductor_api::{ExternalApiWireError, IssueAppAuthenticationTokenPayload};
+
+    holochain_trace::test_run().ok();
+
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice, bobbo) = SweetAgents::two(conductor.keystore()).await;
+    conductor
+        .setup_app_for_agent("alice_app", alice, &[dna_file.clone()])
+        .await
+        .unwrap();
+    let bobbo_app = conductor
+        .setup_app_for_agent("bobbo_app", bobbo, &[dna_file])
+        .await
+        .unwrap();
+    let bobbo_cell_id = bobbo_app.cells()[0].cell_id().clone();
+
+    let app_info = |installed_app_id: &str| {
+        Ok(AppRequest::AppInfo {
+            installed_app_id: installed_app_id.to_string(),
+        })
+    };
+
+    let api = RealAppInterfaceApi::new(conductor.raw_handle()).for_connection();
+
+    // Requests fail until the connection is authenticated
+    let response = api.handle_request(app_info("alice_app")).await.unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
+    let response = api
+        .handle_request(Ok(AppRequest::Authenticate { token: vec![0; 32] }))
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
+
+    let token = conductor
+        .raw_handle()
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_installed_app_id(
+            "alice_app".to_string(),
+        ))
+        .await
+        .unwrap()
+        .token;
+    let response = api
+        .handle_request(Ok(AppRequest::Authenticate {
+            token: token.clone(),
+        }))
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::Authenticated(id) if id == "alice_app");
+
+    let response = api.handle_request(app_info("alice_app")).await.unwrap();
+    assert_matches!(response, AppResponse::AppInfo(Some(_)));
+
+    // Requests for other apps are rejected
+    let response = api.handle_request(app_info("bobbo_app")).await.unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
+    let response = api
+        .handle_request(Ok(AppRequest::NetworkInfo(Box::new(
+            NetworkInfoRequestPayload {
+                agent_pub_key: bobbo_cell_id.agent_pubkey().clone(),
+                dnas: vec![bobbo_cell_id.dna_hash().clone()],
+                last_time_queried: None,
+            },
+        ))))
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
+
+    // Tokens are single use by default
+    let other_api = RealAppInterfaceApi::new(conductor.raw_handle()).for_connection();
+    let response = other_api
+        .handle_request(Ok(AppRequest::Authenticate { token }))
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
+}
+
 /// Simple zome which contains a validation rule which can fail
 fn simple_validation_zome() -> InlineZomeSet {
     let entry_def = EntryDef::from_id("string");
@@ -0,0 +1,123 @@
+#![cfg(all(feature = "test_utils", feature = "opentelemetry-on"))]
+//! These tests have their own binary because they need the tracing
+//! subscriber which exports spans to a local collector.
+
+use hdk::prelude::*;
+use holochain::sweettest::{SweetAgents, SweetConductorBatch, SweetConductorConfig, SweetDnaFile};
+use holochain_types::app::DisabledAppReason;
+use holochain_wasm_test_utils::TestWasm;
+use tracing::Instrument;
+
+#[tokio::test(flavor = "multi_thread")]
+/// A remote call is handled by the remote conductor as part of the caller's trace.
+async fn call_remote_continues_the_trace() {
+    let collector = holochain_trace::test_run_open().unwrap();
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::WhoAmI]).await;
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    let ((alice,), (bob,)) = apps.into_tuples();
+    conductors.exchange_peer_info().await;
+
+    let _: () = conductors[1]
+        .call(&bob.zome(TestWasm::WhoAmI), "set_access", ())
+        .await;
+    let agent_info: AgentInfo = conductors[0]
+        .call(
+            &alice.zome(TestWasm::WhoAmI),
+            "whoarethey",
+            bob.agent_pubkey().clone(),
+        )
+        .instrument(tracing::info_span!("ui_call"))
+        .await;
+    assert_eq!(&agent_info.agent_initial_pubkey, bob.agent_pubkey());
+
+    let ui_call = collector.spans_named("ui_call");
+    assert_eq!(ui_call.len(), 1);
+    let trace_id = ui_call[0].span_context.trace_id();
+
+    let in_trace = |name: &str| {
+        collector
+            .spans_named(name)
+            .into_iter()
+            .filter(|span| span.span_context.trace_id() == trace_id)
+            .count()
+    };
+    // The zome call on alice's conductor.
+    assert_eq!(in_trace("call_zome"), 1);
+    // Bob's cell handling the remote call from alice.
+    assert_eq!(in_trace("call_remote"), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// A get is answered by an authority as part of the caller's trace,
+/// down to the authority's lookup in its DHT database.
+async fn get_continues_the_trace() {
+    let collector = holochain_trace::test_run_open().unwrap();
+
+    // The first agent in a space on alice's conductor gets a full arc and
+    // any later agent an empty one. Once the first agent leaves, alice is
+    // not an authority for anything and has to get from bob.
+    let mut alice_config = SweetConductorConfig::standard();
+    alice_config.network = alice_config.network.take().map(|network| {
+        network.tune(|mut tp| {
+            tp.gossip_single_storage_arc_per_space = true;
+            tp
+        })
+    });
+    let mut conductors =
+        SweetConductorBatch::from_configs([alice_config, SweetConductorConfig::standard()]).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let first_agent = SweetAgents::one(conductors[0].keystore()).await;
+    conductors[0]
+        .setup_app_for_agent("first", first_agent, &[dna_file.clone()])
+        .await
+        .unwrap();
+    let alice_agent = SweetAgents::one(conductors[0].keystore()).await;
+    let (alice,) = conductors[0]
+        .setup_app_for_agent("app", alice_agent, &[dna_file.clone()])
+        .await
+        .unwrap()
+        .into_tuple();
+    conductors[0]
+        .disable_app("first".to_string(), DisabledAppReason::User)
+        .await
+        .unwrap();
+    let (bob,) = conductors[1]
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    conductors.exchange_peer_info().await;
+
+    let hash: ActionHash = conductors[1]
+        .call(&bob.zome(TestWasm::Create), "create_entry", ())
+        .await;
+
+    // Bob may not have integrated his own entry yet.
+    let mut record = None;
+    for _ in 0..100 {
+        record = conductors[0]
+            .call::<_, Option<Record>, _>(&alice.zome(TestWasm::Create), "get_post", hash.clone())
+            .instrument(tracing::info_span!("ui_call"))
+            .await;
+        if record.is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(record.unwrap().action_address(), &hash);
+
+    let ui_call = collector.spans_named("ui_call");
+    let trace_id = ui_call.last().unwrap().span_context.trace_id();
+    let in_trace = |name: &str| {
+        collector
+            .spans_named(name)
+            .into_iter()
+            .filter(|span| span.span_context.trace_id() == trace_id)
+            .count()
+    };
+    // Bob's cell handling the get from alice.
+    assert_eq!(in_trace("cell_handle_get"), 1);
+    // Bob's cell and then the authority reading its DHT database.
+    assert_eq!(in_trace("handle_get_record"), 2);
+}
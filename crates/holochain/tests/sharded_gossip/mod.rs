@@ -502,7 +502,9 @@ async fn mock_network_sharded_gossip() {
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
-                        holochain_p2p::WireMessage::Get { dht_hash, options } => {
+                        holochain_p2p::WireMessage::Get {
+                            dht_hash, options, ..
+                        } => {
                             let txn = conn
                                 .transaction_with_behavior(rusqlite::TransactionBehavior::Exclusive)
                                 .unwrap();
@@ -525,6 +527,7 @@ async fn mock_network_sharded_gossip() {
                         }
                         holochain_p2p::WireMessage::GetMeta { .. } => debug!("get_meta"),
                         holochain_p2p::WireMessage::GetLinks { .. } => debug!("get_links"),
+                        holochain_p2p::WireMessage::CountLinks { .. } => debug!("count_links"),
                         holochain_p2p::WireMessage::GetAgentActivity { .. } => {
                             debug!("get_agent_activity")
                         }
@@ -534,6 +537,9 @@ async fn mock_network_sharded_gossip() {
                         holochain_p2p::WireMessage::CountersigningSessionNegotiation { .. } => {
                             debug!("countersigning_session_negotiation")
                         }
+                        holochain_p2p::WireMessage::PublishWarrant { .. } => {
+                            debug!("PublishWarrant")
+                        }
                     },
                     HolochainP2pMockMsg::CallResp(_) => debug!("CallResp"),
                     HolochainP2pMockMsg::PeerGet(_) => debug!("PeerGet"),
@@ -1006,7 +1012,9 @@ async fn mock_network_sharding() {
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
-                        holochain_p2p::WireMessage::Get { dht_hash, options } => {
+                        holochain_p2p::WireMessage::Get {
+                            dht_hash, options, ..
+                        } => {
                             num_gets.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                             let ops = if data.agent_to_arc[&agent].contains(dht_hash.get_loc()) {
                                 let txn = conn
@@ -1042,6 +1050,7 @@ async fn mock_network_sharding() {
                         }
                         holochain_p2p::WireMessage::GetMeta { .. } => debug!("get_meta"),
                         holochain_p2p::WireMessage::GetLinks { .. } => debug!("get_links"),
+                        holochain_p2p::WireMessage::CountLinks { .. } => debug!("count_links"),
                         holochain_p2p::WireMessage::GetAgentActivity { .. } => {
                             debug!("get_agent_activity")
                         }
@@ -1054,6 +1063,9 @@ async fn mock_network_sharding() {
                         holochain_p2p::WireMessage::PublishCountersign { .. } => {
                             debug!("publish_countersign")
                         }
+                        holochain_p2p::WireMessage::PublishWarrant { .. } => {
+                            debug!("publish_warrant")
+                        }
                     },
                     HolochainP2pMockMsg::CallResp(_) => debug!("CallResp"),
                     HolochainP2pMockMsg::MetricExchange(_) => debug!("MetricExchange"),
@@ -21,6 +21,7 @@ use ::fixt::prelude::*;
 use hdk::prelude::*;
 use holochain::conductor::api::AdminRequest;
 use holochain::conductor::api::AdminResponse;
+use holochain::conductor::api::AllowedOrigins;
 use holochain::conductor::api::AppRequest;
 use holochain::conductor::api::AppResponse;
 use holochain::conductor::api::ZomeCall;
@@ -178,7 +179,12 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        driver: None,
+        allowed_origins: AllowedOrigins::Any,
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
@@ -186,6 +192,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
         _ => panic!("Attach app interface failed: {:?}", response),
     };
     let (mut app_interface, _) = websocket_client_by_port(app_port).await.unwrap();
+    authenticate_app_ws_client(&mut app_interface, &mut client, "test app".to_string()).await;
 
     // /////////////
     // END CONDUCTOR
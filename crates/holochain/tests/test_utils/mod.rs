@@ -28,7 +28,9 @@ use futures::Future;
 use holochain_conductor_api::conductor::ConductorConfig;
 use holochain_conductor_api::conductor::KeystoreConfig;
 use holochain_conductor_api::AdminInterfaceConfig;
+use holochain_conductor_api::AllowedOrigins;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
 use matches::assert_matches;
 use serde::Serialize;
 use std::time::Duration;
@@ -159,7 +161,12 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        driver: None,
+        allowed_origins: AllowedOrigins::Any,
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
@@ -168,6 +175,28 @@ pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16
     }
 }
 
+/// Issue a token for the app over the admin interface and use it to
+/// authenticate the app interface connection.
+pub async fn authenticate_app_ws_client(
+    app_tx: &mut WebsocketSender,
+    admin_tx: &mut WebsocketSender,
+    installed_app_id: InstalledAppId,
+) {
+    let request = AdminRequest::IssueAppAuthenticationToken(
+        IssueAppAuthenticationTokenPayload::for_installed_app_id(installed_app_id),
+    );
+    let response = admin_tx.request(request);
+    let response = check_timeout(response, 3000).await;
+    let token = match response {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => issued.token,
+        _ => panic!("Issue app authentication token failed: {:?}", response),
+    };
+    let request = AppRequest::Authenticate { token };
+    let response = app_tx.request(request);
+    let response = check_timeout(response, 3000).await;
+    assert_matches!(response, AppResponse::Authenticated(_));
+}
+
 pub async fn retry_admin_interface(
     port: u16,
     mut attempts: usize,
@@ -336,7 +365,7 @@ pub async fn check_started(holochain: &mut Child) {
 pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::websocket(port),
         }]),
         environment_path: environment_path.into(),
         keystore: KeystoreConfig::DangerTestKeystore,
@@ -8,7 +8,7 @@ use holochain::sweettest::SweetConductorBatch;
 use holochain::sweettest::SweetDnaFile;
 use holochain::{
     conductor::{
-        api::{AdminRequest, AdminResponse},
+        api::{AdminRequest, AdminResponse, InterfaceDriver},
         error::ConductorError,
         Conductor,
     },
@@ -182,6 +182,7 @@ async fn call_zome() {
     let app_port = attach_app_interface(&mut admin_tx, None).await;
 
     let (mut app_tx, _) = websocket_client_by_port(app_port).await.unwrap();
+    authenticate_app_ws_client(&mut app_tx, &mut admin_tx, "test".to_string()).await;
 
     // Call Zome
     tracing::info!("Calling zome");
@@ -228,6 +229,7 @@ async fn call_zome() {
     };
 
     let (mut app_tx, _) = websocket_client_by_port(app_port).await.unwrap();
+    authenticate_app_ws_client(&mut app_tx, &mut admin_tx, "test".to_string()).await;
 
     // Call Zome again on the existing app interface port
     tracing::info!("Calling zome again");
@@ -384,7 +386,9 @@ async fn emit_signals() {
     // Emit signals (the real test!)
 
     let (mut app_tx_1, app_rx_1) = websocket_client_by_port(app_port).await.unwrap();
-    let (_, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx_2, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    authenticate_app_ws_client(&mut app_tx_1, &mut admin_tx, "test".to_string()).await;
+    authenticate_app_ws_client(&mut app_tx_2, &mut admin_tx, "test".to_string()).await;
 
     call_zome_fn(
         &mut app_tx_1,
@@ -734,3 +738,175 @@ async fn full_state_dump_cursor_works() {
 
     assert_eq!(1, new_all_dht_ops_count);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interface_restricted_to_app_and_origins() {
+    use holochain::conductor::api::{AppRequest, AppResponse};
+    use holochain_conductor_api::{ExternalApiWireError, IssueAppAuthenticationTokenPayload};
+
+    holochain_trace::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice, bobbo) = SweetAgents::two(conductor.keystore()).await;
+    let dna_file = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .0;
+    conductor
+        .setup_app_for_agent("alice_app", alice, &[dna_file.clone()])
+        .await
+        .unwrap();
+    conductor
+        .setup_app_for_agent("bobbo_app", bobbo, &[dna_file])
+        .await
+        .unwrap();
+
+    let (mut admin_tx, _) = conductor.admin_ws_client().await;
+    let origin = "http://localhost:8888";
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        driver: None,
+        allowed_origins: [origin].into_iter().collect(),
+        installed_app_id: Some("alice_app".to_string()),
+    };
+    let response = check_timeout(admin_tx.request(request), 3000).await;
+    let app_port = match response {
+        AdminResponse::AppInterfaceAttached { port } => port,
+        _ => panic!("Attach app interface failed: {:?}", response),
+    };
+    let connect_from = |origin: &str| {
+        holochain_websocket::connect(
+            url2!("ws://127.0.0.1:{}", app_port),
+            Arc::new(WebsocketConfig::default().origin(origin)),
+        )
+    };
+
+    // Other origins can't connect
+    assert!(connect_from("http://localhost:9999").await.is_err());
+    assert!(websocket_client_by_port(app_port).await.is_err());
+
+    // Tokens for other apps are refused
+    let (mut app_tx, _) = connect_from(origin).await.unwrap();
+    let request = AdminRequest::IssueAppAuthenticationToken(
+        IssueAppAuthenticationTokenPayload::for_installed_app_id("bobbo_app".to_string()),
+    );
+    let response = check_timeout(admin_tx.request(request), 3000).await;
+    let token = match response {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => issued.token,
+        _ => panic!("Issue app authentication token failed: {:?}", response),
+    };
+    let response: AppResponse =
+        check_timeout(app_tx.request(AppRequest::Authenticate { token }), 3000).await;
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::AppAuthenticationFailed(_))
+    );
+
+    authenticate_app_ws_client(&mut app_tx, &mut admin_tx, "alice_app".to_string()).await;
+}
+
+/// Attach an app interface with the given driver and return the port it listens on,
+/// or 0 for unix domain sockets.
+async fn attach_app_interface_with_driver(
+    admin_tx: &mut WebsocketSender,
+    driver: InterfaceDriver,
+) -> u16 {
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        driver: Some(driver),
+        allowed_origins: AllowedOrigins::Any,
+        installed_app_id: None,
+    };
+    let response = check_timeout(admin_tx.request(request), 3000).await;
+    match response {
+        AdminResponse::AppInterfaceAttached { port } => port,
+        _ => panic!("Attach app interface failed: {:?}", response),
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interface_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+
+    holochain_trace::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let dna_file = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .0;
+    conductor.setup_app("app", &[dna_file]).await.unwrap();
+
+    let tmp_dir = TempDir::new().unwrap();
+    let path = tmp_dir.path().join("app.sock");
+    let (mut admin_tx, _) = conductor.admin_ws_client().await;
+    let port = attach_app_interface_with_driver(
+        &mut admin_tx,
+        InterfaceDriver::UnixSocket {
+            path: path.clone(),
+            mode: Some(0o600),
+        },
+    )
+    .await;
+    assert_eq!(port, 0);
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+
+    let (mut app_tx, _) = connect_unix(&path, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    authenticate_app_ws_client(&mut app_tx, &mut admin_tx, "app".to_string()).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interface_over_tls() {
+    use holochain::conductor::api::InterfaceTlsConfig;
+
+    holochain_trace::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let dna_file = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .0;
+    conductor.setup_app("app", &[dna_file]).await.unwrap();
+
+    // A certificate authority and a server certificate it signed
+    let tmp_dir = TempDir::new().unwrap();
+    let mut ca_params = rcgen::CertificateParams::new(vec![]);
+    ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    let ca = rcgen::Certificate::from_params(ca_params).unwrap();
+    let server = rcgen::Certificate::from_params(rcgen::CertificateParams::new(vec![
+        "localhost".to_string()
+    ]))
+    .unwrap();
+    let ca_path = tmp_dir.path().join("ca.pem");
+    let cert_path = tmp_dir.path().join("server.pem");
+    let key_path = tmp_dir.path().join("server.key");
+    std::fs::write(&ca_path, ca.serialize_pem().unwrap()).unwrap();
+    std::fs::write(&cert_path, server.serialize_pem_with_signer(&ca).unwrap()).unwrap();
+    std::fs::write(&key_path, server.serialize_private_key_pem()).unwrap();
+
+    let (mut admin_tx, _) = conductor.admin_ws_client().await;
+    let port = attach_app_interface_with_driver(
+        &mut admin_tx,
+        InterfaceDriver::Websocket {
+            port: 0,
+            tls: Some(InterfaceTlsConfig {
+                cert_path,
+                key_path,
+                client_ca_path: None,
+            }),
+        },
+    )
+    .await;
+
+    // Plain websockets can't connect
+    assert!(websocket_client_by_port(port).await.is_err());
+
+    let tls = tls::client_config(&ca_path, None).unwrap();
+    let (mut app_tx, _) = holochain_websocket::connect(
+        url2!("wss://localhost:{}", port),
+        Arc::new(WebsocketConfig::default().tls_client(tls)),
+    )
+    .await
+    .unwrap();
+    authenticate_app_ws_client(&mut app_tx, &mut admin_tx, "app".to_string()).await;
+}
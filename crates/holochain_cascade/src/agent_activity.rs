@@ -22,17 +22,20 @@ fn merge_hashes(
     let mut valid = HashSet::new();
     let mut rejected = HashSet::new();
     let mut merged_highest_observed = None;
+    let mut merged_warrants = Vec::new();
     for result in results {
         let AgentActivityResponse {
             agent: the_agent,
             highest_observed,
             valid_activity,
             rejected_activity,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merge_warrants(&mut merged_warrants, warrants);
 
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
@@ -84,6 +87,7 @@ fn merge_hashes(
         valid_activity,
         rejected_activity,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants,
     }
 }
 
@@ -147,16 +151,19 @@ fn merge_status_only(
 ) -> AgentActivityResponse<ActionHash> {
     let mut merged_status = None;
     let mut merged_highest_observed = None;
+    let mut merged_warrants = Vec::new();
     for result in results {
         let AgentActivityResponse {
             status,
             agent: the_agent,
             highest_observed,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merge_warrants(&mut merged_warrants, warrants);
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -234,5 +241,15 @@ fn merge_status_only(
         valid_activity: ChainItems::NotRequested,
         rejected_activity: ChainItems::NotRequested,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants,
+    }
+}
+
+/// Different authorities may hold the same warrant so only keep one copy.
+fn merge_warrants(merged: &mut Vec<SignedWarrant>, warrants: Vec<SignedWarrant>) {
+    for warrant in warrants {
+        if !merged.contains(&warrant) {
+            merged.push(warrant);
+        }
     }
 }
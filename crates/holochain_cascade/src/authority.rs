@@ -4,6 +4,7 @@ use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_agent_activity_query::must_get_agent_activity::must_get_agent_activity;
 use self::get_entry_ops_query::GetEntryOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
+use self::get_meta_query::GetMetaQuery;
 use self::{
     get_agent_activity_query::deterministic::DeterministicGetAgentActivityQuery,
     get_record_query::GetRecordOpsQuery,
@@ -12,8 +13,13 @@ use self::{
 use super::error::CascadeResult;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_state::query::link::LinksQuery;
 use holochain_state::query::Query;
+use holochain_state::query::StateQueryResult;
 use holochain_state::query::Txn;
+use holochain_state::warrants::list_warrants;
 use holochain_types::prelude::*;
 use holochain_zome_types::agent_activity::DeterministicGetAgentActivityFilter;
 use tracing::*;
@@ -24,6 +30,7 @@ mod test;
 pub(crate) mod get_agent_activity_query;
 pub(crate) mod get_entry_ops_query;
 pub(crate) mod get_links_ops_query;
+pub(crate) mod get_meta_query;
 pub(crate) mod get_record_query;
 
 /// Handler for get_entry query to an Entry authority
@@ -54,6 +61,20 @@ pub async fn handle_get_record(
     Ok(results)
 }
 
+/// Handler for get_meta query to a Record/Entry authority
+#[instrument(skip(env, _options))]
+pub async fn handle_get_meta(
+    env: DbRead<DbKindDht>,
+    hash: AnyDhtHash,
+    _options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let query = GetMetaQuery::new(hash);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
+
 /// Handler for get_agent_activity query to an Activity authority
 #[instrument(skip(env))]
 pub async fn handle_get_agent_activity(
@@ -62,9 +83,13 @@ pub async fn handle_get_agent_activity(
     query: ChainQueryFilter,
     options: holochain_p2p::event::GetActivityOptions,
 ) -> CascadeResult<AgentActivityResponse<ActionHash>> {
-    let query = GetAgentActivityQuery::new(agent, query, options);
+    let query = GetAgentActivityQuery::new(agent.clone(), query, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut results = query.run(Txn::from(&txn))?;
+            results.warrants = list_warrants(&txn, &agent)?;
+            StateQueryResult::Ok(results)
+        })
         .await?;
     Ok(results)
 }
@@ -107,3 +132,73 @@ pub async fn handle_get_links(
         .await?;
     Ok(results)
 }
+
+/// Handler for count_links query to a Record/Entry authority.
+///
+/// The links are counted in the database. Only the given create link
+/// actions, which the requester may not have published yet, are looked up
+/// one by one.
+#[instrument(skip(env, create_link_hashes))]
+pub async fn handle_count_links(
+    env: DbRead<DbKindDht>,
+    link_key: WireLinkKey,
+    create_link_hashes: Vec<ActionHash>,
+) -> CascadeResult<CountLinksResponse> {
+    let query = LinksQuery::from_key(WireLinkKey {
+        limit: None,
+        cursor: None,
+        ..link_key
+    });
+    let response = env
+        .async_reader(move |txn| {
+            let count: i64 =
+                txn.query_row(&query.count_query(), &query.params()[..], |row| row.get(0))?;
+            let mut response = CountLinksResponse {
+                count: count as usize,
+                ..Default::default()
+            };
+            for hash in create_link_hashes {
+                let held = txn
+                    .prepare_cached(
+                        "
+                        SELECT 1 FROM DhtOp
+                        WHERE DhtOp.action_hash = :hash
+                        AND DhtOp.type = :create
+                        AND DhtOp.validation_status = :status
+                        AND DhtOp.when_integrated IS NOT NULL
+                        ",
+                    )?
+                    .exists(named_params! {
+                        ":hash": hash,
+                        ":create": DhtOpType::RegisterAddLink,
+                        ":status": ValidationStatus::Valid,
+                    })?;
+                if !held {
+                    continue;
+                }
+                let deleted = txn
+                    .prepare_cached(
+                        "
+                        SELECT 1 FROM DhtOp
+                        JOIN Action On DhtOp.action_hash = Action.hash
+                        WHERE Action.create_link_hash = :hash
+                        AND DhtOp.type = :delete
+                        AND DhtOp.validation_status = :status
+                        AND DhtOp.when_integrated IS NOT NULL
+                        ",
+                    )?
+                    .exists(named_params! {
+                        ":hash": hash,
+                        ":delete": DhtOpType::RegisterRemoveLink,
+                        ":status": ValidationStatus::Valid,
+                    })?;
+                if deleted {
+                    response.deleted.push(hash.clone());
+                }
+                response.held.push(hash);
+            }
+            StateQueryResult::Ok(response)
+        })
+        .await?;
+    Ok(response)
+}
@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holo_hash::AnyLinkableHash;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::Row;
@@ -16,6 +18,8 @@ use holochain_zome_types::Judged;
 use holochain_zome_types::LinkTag;
 use holochain_zome_types::LinkTypeFilter;
 use holochain_zome_types::SignedAction;
+use holochain_zome_types::Timestamp;
+use holochain_zome_types::ValidationStatus;
 
 use super::WireLinkKey;
 
@@ -24,14 +28,30 @@ pub struct GetLinksOpsQuery {
     base: Arc<AnyLinkableHash>,
     type_query: LinkTypeFilter,
     tag: Option<Arc<LinkTag>>,
+    after: Option<Timestamp>,
+    before: Option<Timestamp>,
+    author: Option<AgentPubKey>,
+    limit: Option<u32>,
+    cursor_timestamp: Option<Timestamp>,
+    cursor_hash: Option<ActionHash>,
 }
 
 impl GetLinksOpsQuery {
     pub fn new(key: WireLinkKey) -> Self {
+        let (cursor_timestamp, cursor_hash) = key
+            .cursor
+            .map(|c| (c.timestamp, c.create_link_hash))
+            .unzip();
         Self {
             base: Arc::new(key.base),
             type_query: key.type_query,
             tag: key.tag.map(Arc::new),
+            after: key.after,
+            before: key.before,
+            author: key.author,
+            limit: key.limit,
+            cursor_timestamp,
+            cursor_hash,
         }
     }
     pub fn tag_to_hex(tag: &LinkTag) -> String {
@@ -70,6 +90,13 @@ impl Query for GetLinksOpsQuery {
             Action.base_hash = :base_hash
             AND
             DhtOp.when_integrated IS NOT NULL
+            AND (:after IS NULL OR DhtOp.authored_timestamp > :after)
+            AND (:before IS NULL OR DhtOp.authored_timestamp < :before)
+            AND (:author IS NULL OR Action.author = :author)
+            AND (
+                :cursor_timestamp IS NULL
+                OR (DhtOp.authored_timestamp, Action.hash) > (:cursor_timestamp, :cursor_hash)
+            )
         "
         .to_string();
 
@@ -92,7 +119,35 @@ impl Query for GetLinksOpsQuery {
             common_query,
             self.type_query.to_sql_statement(),
         );
-        let create_query = format!("{}{}", create, common_query);
+        let mut create_query = format!("{}{}", create, common_query);
+        if self.limit.is_some() {
+            // Only send the first page of live links, but still send the
+            // deletes for all the matching links so that any stale links
+            // the requester already has are removed.
+            create_query = format!(
+                "
+                SELECT * FROM (
+                    {}
+                    AND
+                    DhtOp.validation_status = :valid
+                    AND NOT EXISTS (
+                        SELECT 1 FROM DhtOp AS DeleteOp
+                        JOIN Action AS DeleteAction On DeleteOp.action_hash = DeleteAction.hash
+                        WHERE DeleteOp.type = :delete
+                        AND
+                        DeleteOp.validation_status = :valid
+                        AND
+                        DeleteOp.when_integrated IS NOT NULL
+                        AND
+                        DeleteAction.create_link_hash = Action.hash
+                    )
+                    ORDER BY DhtOp.authored_timestamp, Action.hash
+                    LIMIT :limit
+                )
+                ",
+                create_query
+            );
+        }
         let sub_create_query = format!("{}{}", sub_create, common_query);
         let delete_query = format!(
             "
@@ -112,12 +167,24 @@ impl Query for GetLinksOpsQuery {
     }
 
     fn params(&self) -> Vec<Params> {
-        named_params! {
+        let mut params = named_params! {
             ":create": DhtOpType::RegisterAddLink,
             ":delete": DhtOpType::RegisterRemoveLink,
             ":base_hash": self.base,
+            ":after": self.after,
+            ":before": self.before,
+            ":author": self.author,
+            ":cursor_timestamp": self.cursor_timestamp,
+            ":cursor_hash": self.cursor_hash,
+        }
+        .to_vec();
+        if self.limit.is_some() {
+            params.extend_from_slice(named_params! {
+                ":valid": ValidationStatus::Valid,
+                ":limit": self.limit,
+            });
         }
-        .to_vec()
+        params
     }
 
     fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
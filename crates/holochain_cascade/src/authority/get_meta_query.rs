@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use holo_hash::hash_type::AnyDht;
+use holo_hash::ActionHash;
+use holo_hash::AnyDhtHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::prelude::*;
+use holochain_state::query::StateQueryError;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::metadata::EntryDhtStatus;
+use holochain_types::metadata::MetadataSet;
+use holochain_types::metadata::TimedActionHash;
+use holochain_zome_types::Action;
+use holochain_zome_types::HasValidationStatus;
+use holochain_zome_types::Judged;
+use holochain_zome_types::SignedAction;
+use holochain_zome_types::ValidationStatus;
+
+#[derive(Debug, Clone)]
+pub struct GetMetaQuery(Arc<AnyDhtHash>);
+
+impl GetMetaQuery {
+    pub fn new(hash: AnyDhtHash) -> Self {
+        Self(Arc::new(hash))
+    }
+}
+
+pub struct Item {
+    op_type: DhtOpType,
+    action: SignedAction,
+    action_hash: ActionHash,
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    meta: MetadataSet,
+    /// Actions deleted by a valid delete.
+    deleted: HashSet<ActionHash>,
+    /// Links removed by a valid delete link.
+    removed_links: HashSet<ActionHash>,
+}
+
+impl Query for GetMetaQuery {
+    type Item = Judged<Item>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        "
+        SELECT Action.blob AS action_blob, Action.hash AS action_hash,
+        DhtOp.type AS dht_type, DhtOp.validation_status AS status
+        FROM DhtOp
+        JOIN Action On DhtOp.action_hash = Action.hash
+        WHERE DhtOp.type != :activity
+        AND
+        DhtOp.basis_hash = :hash
+        AND
+        DhtOp.when_integrated IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":activity": DhtOpType::RegisterAgentActivity,
+            ":hash": self.0,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let action =
+                from_blob::<SignedAction>(row.get(row.as_ref().column_index("action_blob")?)?)?;
+            let action_hash = row.get(row.as_ref().column_index("action_hash")?)?;
+            let op_type = row.get(row.as_ref().column_index("dht_type")?)?;
+            let validation_status = row.get(row.as_ref().column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    op_type,
+                    action,
+                    action_hash,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(Default::default())
+    }
+
+    fn fold(&self, mut state: Self::State, dht_op: Self::Item) -> StateQueryResult<Self::State> {
+        let status = dht_op.validation_status();
+        let Item {
+            op_type,
+            action: SignedAction(action, _),
+            action_hash,
+        } = dht_op.data;
+        let timed = TimedActionHash {
+            timestamp: action.timestamp(),
+            action_hash,
+        };
+        let valid = status == Some(ValidationStatus::Valid);
+        match op_type {
+            DhtOpType::StoreRecord | DhtOpType::StoreEntry => {
+                if op_type == DhtOpType::StoreRecord {
+                    state.meta.validation_status = status;
+                }
+                match status {
+                    Some(ValidationStatus::Valid) => {
+                        state.meta.actions.insert(timed);
+                    }
+                    Some(ValidationStatus::Rejected) => {
+                        state.meta.invalid_actions.insert(timed);
+                    }
+                    _ => (),
+                }
+            }
+            DhtOpType::RegisterUpdatedContent | DhtOpType::RegisterUpdatedRecord => {
+                if valid {
+                    state.meta.updates.insert(timed);
+                }
+            }
+            DhtOpType::RegisterDeletedBy | DhtOpType::RegisterDeletedEntryAction => {
+                if let (true, Action::Delete(delete)) = (valid, &action) {
+                    state.deleted.insert(delete.deletes_address.clone());
+                    state.meta.deletes.insert(timed);
+                }
+            }
+            DhtOpType::RegisterAddLink => {
+                if valid {
+                    state.meta.links.insert(timed);
+                }
+            }
+            DhtOpType::RegisterRemoveLink => {
+                if let (true, Action::DeleteLink(delete_link)) = (valid, &action) {
+                    state
+                        .removed_links
+                        .insert(delete_link.link_add_address.clone());
+                }
+            }
+            DhtOpType::RegisterAgentActivity => return Err(StateQueryError::UnexpectedOp(op_type)),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let State {
+            mut meta,
+            deleted,
+            removed_links,
+        } = state;
+        meta.links
+            .retain(|link| !removed_links.contains(&link.action_hash));
+        if *self.0.hash_type() == AnyDht::Entry {
+            meta.entry_dht_status = if meta
+                .actions
+                .iter()
+                .any(|a| !deleted.contains(&a.action_hash))
+            {
+                Some(EntryDhtStatus::Live)
+            } else if !meta.actions.is_empty() {
+                Some(EntryDhtStatus::Dead)
+            } else if !meta.invalid_actions.is_empty() {
+                Some(EntryDhtStatus::Rejected)
+            } else {
+                None
+            };
+        }
+        Ok(meta)
+    }
+}
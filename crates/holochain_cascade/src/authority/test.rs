@@ -1,6 +1,7 @@
 use super::*;
 use crate::authority::handle_get_agent_activity;
 use crate::test_utils::*;
+use ::fixt::prelude::*;
 use holochain_p2p::actor;
 use holochain_p2p::event::GetRequest;
 use holochain_state::prelude::test_dht_db;
@@ -188,6 +189,106 @@ async fn get_links() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_links_filtered_and_paged() {
+    holochain_trace::test_run().ok();
+    let db = test_dht_db();
+    let base: AnyLinkableHash = fixt!(EntryHash).into();
+    let alice = fixt!(AgentPubKey);
+    let bob = fixt!(AgentPubKey);
+    let at = |seconds: i64| Timestamp::from_micros(seconds * 1_000_000);
+
+    // A link a second, alternating between alice and bob.
+    let creates: Vec<CreateLink> = (0..4)
+        .map(|i| {
+            let mut create_link = fixt!(CreateLink);
+            create_link.base_address = base.clone();
+            create_link.zome_index = 0.into();
+            create_link.author = if i % 2 == 0 { &alice } else { &bob }.clone();
+            create_link.timestamp = at(i);
+            create_link
+        })
+        .collect();
+    for create_link in &creates {
+        fill_db(
+            &db.to_db(),
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAddLink(
+                fixt!(Signature),
+                create_link.clone(),
+            )),
+        );
+    }
+    let create_hash =
+        |i: usize| ActionHash::with_data_sync(&Action::CreateLink(creates[i].clone()));
+
+    // The first link is deleted.
+    let mut delete_link = fixt!(DeleteLink);
+    delete_link.base_address = base.clone();
+    delete_link.link_add_address = create_hash(0);
+    fill_db(
+        &db.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::RegisterRemoveLink(fixt!(Signature), delete_link)),
+    );
+
+    let get = |key: WireLinkKey| {
+        let db = db.to_db();
+        async move {
+            let options = actor::GetLinksOptions::default();
+            let mut result = handle_get_links(db.into(), key, (&options).into())
+                .await
+                .unwrap();
+            result.creates.sort_by_key(|c| c.timestamp);
+            result
+        }
+    };
+    let timestamps = |result: &WireLinkOps| {
+        result
+            .creates
+            .iter()
+            .map(|c| c.timestamp)
+            .collect::<Vec<_>>()
+    };
+    let key = WireLinkKey::new(base.clone(), LinkTypeFilter::single_dep(0.into()), None);
+
+    let result = get(WireLinkKey {
+        author: Some(bob.clone()),
+        ..key.clone()
+    })
+    .await;
+    assert_eq!(timestamps(&result), vec![at(1), at(3)]);
+    assert!(result.deletes.is_empty());
+
+    let result = get(WireLinkKey {
+        after: Some(at(0)),
+        before: Some(at(3)),
+        ..key.clone()
+    })
+    .await;
+    assert_eq!(timestamps(&result), vec![at(1), at(2)]);
+    assert!(result.deletes.is_empty());
+
+    // A page skips the deleted link but still includes the delete.
+    let result = get(WireLinkKey {
+        limit: Some(2),
+        ..key.clone()
+    })
+    .await;
+    assert_eq!(timestamps(&result), vec![at(1), at(2)]);
+    assert_eq!(result.deletes.len(), 1);
+
+    let result = get(WireLinkKey {
+        limit: Some(2),
+        cursor: Some(LinkCursor {
+            timestamp: at(2),
+            create_link_hash: create_hash(2),
+        }),
+        ..key
+    })
+    .await;
+    assert_eq!(timestamps(&result), vec![at(3)]);
+    assert!(result.deletes.is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_agent_activity() {
     holochain_trace::test_run().ok();
@@ -223,6 +324,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: vec![],
     };
     assert_eq!(result, expected);
 
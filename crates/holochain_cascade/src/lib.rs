@@ -23,6 +23,7 @@
 //!
 #![warn(missing_docs)]
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use error::CascadeResult;
@@ -32,6 +33,7 @@ use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
 use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::actor::GetLinksOptions;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
@@ -394,6 +396,19 @@ where
         Ok(())
     }
 
+    #[instrument(skip(self, options))]
+    async fn fetch_link_count(
+        &self,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        options: GetLinksOptions,
+    ) -> CascadeResult<Vec<CountLinksResponse>> {
+        let network = some_or_return!(self.network.as_ref(), Vec::with_capacity(0));
+        Ok(network
+            .count_links(link_key, create_link_hashes, options)
+            .await?)
+    }
+
     #[instrument(skip(self, options))]
     async fn fetch_agent_activity(
         &self,
@@ -405,6 +420,16 @@ where
         Ok(network.get_agent_activity(agent, query, options).await?)
     }
 
+    #[instrument(skip(self, options))]
+    async fn fetch_meta(
+        &self,
+        hash: AnyDhtHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<Vec<MetadataSet>> {
+        let network = some_or_return!(self.network.as_ref(), Vec::with_capacity(0));
+        Ok(network.get_meta(hash, options).await?)
+    }
+
     #[instrument(skip(self))]
     /// Fetch hash bounded agent activity from the network.
     async fn fetch_must_get_agent_activity(
@@ -845,6 +870,39 @@ where
         }
     }
 
+    #[instrument(skip(self, options))]
+    /// Get the [`MetadataSet`] for an entry or action without fetching
+    /// the records themselves.
+    /// Authorities answer from their own DHT database. Anyone else merges
+    /// what they hold with the responses of the authorities, unless
+    /// [`GetStrategy::Content`] is requested and something is held locally.
+    /// For [`GetStrategy::Latest`] every authority asked is waited on,
+    /// otherwise the first to answer is enough.
+    pub async fn get_meta(
+        &self,
+        hash: AnyDhtHash,
+        options: GetOptions,
+    ) -> CascadeResult<MetadataSet> {
+        let meta_options = GetMetaOptions::from(options.clone());
+        let mut meta = match self.dht.clone() {
+            Some(dht) => {
+                authority::handle_get_meta(dht, hash.clone(), (&meta_options).into()).await?
+            }
+            None => MetadataSet::default(),
+        };
+        let found_locally = !meta.actions.is_empty() || !meta.invalid_actions.is_empty();
+        if matches!(options.strategy, GetStrategy::Content) && found_locally {
+            return Ok(meta);
+        }
+        let authority = self.am_i_an_authority(hash.clone().into()).await?;
+        if !authority {
+            for other in self.fetch_meta(hash, meta_options).await? {
+                merge_meta(&mut meta, other);
+            }
+        }
+        Ok(meta)
+    }
+
     #[instrument(skip(self, options))]
     /// Gets an links from the cas or cache depending on it's metadata
     // The default behavior is to skip deleted or replaced entries.
@@ -857,11 +915,135 @@ where
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinksQuery::new(key.base, key.type_query, key.tag);
+        let query = GetLinksQuery::from_key(key);
         let results = self.cascading(query).await?;
         Ok(results)
     }
 
+    #[instrument(skip(self, options))]
+    /// Count the live links matching a key without fetching them.
+    /// The limit and cursor of the key are ignored.
+    ///
+    /// Authorities count from their own databases. Anyone else asks an
+    /// authority for its count, and corrects it for the links this agent
+    /// has created or deleted which may not have reached the authority yet.
+    /// Nothing is written to the cache.
+    pub async fn dht_count_links(
+        &self,
+        key: WireLinkKey,
+        options: GetLinksOptions,
+    ) -> CascadeResult<usize> {
+        let key = WireLinkKey {
+            limit: None,
+            cursor: None,
+            ..key
+        };
+        let authority = self.am_i_an_authority(key.base.clone()).await?;
+        if authority || self.network.is_none() {
+            return self.count_links_locally(key).await;
+        }
+
+        // Only look at our own links, a stale cache could count links
+        // which the authorities know to be deleted.
+        let own = Self {
+            dht: None,
+            cache: None,
+            ..self.clone()
+        };
+        let own_links = own
+            .cascading(GetLinkDetailsQuery::from_key(key.clone()))
+            .await?;
+        let published = self
+            .published_actions(
+                own_links
+                    .iter()
+                    .flat_map(|(create, deletes)| std::iter::once(create).chain(deletes))
+                    .map(|action| action.as_hash().clone())
+                    .collect(),
+            )
+            .await?;
+        // Links we created which the authority may not hold yet, and links
+        // which the authority may still count because it hasn't heard of our delete.
+        let mut unpublished_creates = HashSet::new();
+        let mut unpublished_deletes = HashSet::new();
+        for (create, deletes) in own_links {
+            let hash = create.as_hash();
+            if deletes.iter().any(|d| !published.contains(d.as_hash())) {
+                unpublished_deletes.insert(hash.clone());
+            } else if deletes.is_empty() && !published.contains(hash) {
+                unpublished_creates.insert(hash.clone());
+            }
+        }
+
+        let create_link_hashes = unpublished_creates
+            .iter()
+            .chain(unpublished_deletes.iter())
+            .cloned()
+            .collect();
+        // Like get_links only one authority is asked, but if several answer
+        // trust the one which knows of the most links.
+        let response = match self
+            .fetch_link_count(key.clone(), create_link_hashes, options)
+            .await?
+            .into_iter()
+            .max_by_key(|response| response.count)
+        {
+            Some(response) => response,
+            None => return self.count_links_locally(key).await,
+        };
+        let held: HashSet<_> = response.held.into_iter().collect();
+        let deleted: HashSet<_> = response.deleted.into_iter().collect();
+        // A link the authority holds is never added again, even if it left
+        // it out of the count because it knows the link to be deleted.
+        let added = unpublished_creates
+            .iter()
+            .filter(|hash| !held.contains(*hash))
+            .count();
+        let removed = unpublished_deletes
+            .iter()
+            .filter(|hash| held.contains(*hash) && !deleted.contains(*hash))
+            .count();
+        Ok((response.count + added).saturating_sub(removed))
+    }
+
+    async fn count_links_locally(&self, key: WireLinkKey) -> CascadeResult<usize> {
+        let query = GetLinksQuery::from_key(key);
+        Ok(self.cascading(query).await?.len())
+    }
+
+    /// Which of these actions authored by this agent have been published
+    /// to enough authorities.
+    async fn published_actions(
+        &self,
+        hashes: Vec<ActionHash>,
+    ) -> CascadeResult<HashSet<ActionHash>> {
+        let authored = some_or_return!(self.authored.clone(), HashSet::new());
+        let published = authored
+            .async_reader(move |txn| {
+                let mut stmt = txn.prepare_cached(
+                    "
+                    SELECT 1 FROM DhtOp
+                    WHERE action_hash = :hash
+                    AND type IN (:create, :delete)
+                    AND receipts_complete IS NOT NULL
+                    ",
+                )?;
+                let mut published = HashSet::new();
+                for hash in hashes {
+                    if stmt.exists(named_params! {
+                        ":hash": hash,
+                        ":create": DhtOpType::RegisterAddLink,
+                        ":delete": DhtOpType::RegisterRemoveLink,
+                    })? {
+                        published.insert(hash);
+                    }
+                }
+                StateQueryResult::Ok(published)
+            })
+            .await?;
+        Ok(published)
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink actions
     /// and DeleteLink actions ordered by time.
@@ -874,7 +1056,7 @@ where
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinkDetailsQuery::new(key.base, key.type_query, key.tag);
+        let query = GetLinkDetailsQuery::from_key(key);
         let results = self.cascading(query).await?;
         Ok(results)
     }
@@ -1005,6 +1187,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -1059,6 +1242,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
@@ -1083,3 +1267,24 @@ where
         }
     }
 }
+
+/// Combine the metadata from another authority into ours.
+/// Where the authorities disagree on a status, the first one seen is kept.
+fn merge_meta(meta: &mut MetadataSet, other: MetadataSet) {
+    let MetadataSet {
+        actions,
+        invalid_actions,
+        deletes,
+        updates,
+        links,
+        entry_dht_status,
+        validation_status,
+    } = other;
+    meta.actions.extend(actions);
+    meta.invalid_actions.extend(invalid_actions);
+    meta.deletes.extend(deletes);
+    meta.updates.extend(updates);
+    meta.links.extend(links);
+    meta.entry_dht_status = meta.entry_dht_status.or(entry_dht_status);
+    meta.validation_status = meta.validation_status.or(validation_status);
+}
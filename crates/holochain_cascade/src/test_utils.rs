@@ -47,6 +47,7 @@ use holochain_types::metadata::MetadataSet;
 use holochain_types::prelude::WireEntryOps;
 use holochain_types::record::WireRecordOps;
 use holochain_types::test_utils::chain::*;
+use holochain_types::warrant::SignedWarrant;
 use holochain_zome_types::zome_io::Nonce256Bits;
 use holochain_zome_types::ActionRefMut;
 use holochain_zome_types::QueryFilter;
@@ -134,10 +135,23 @@ impl HolochainP2pDnaT for PassThroughNetwork {
 
     async fn get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: actor::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
     ) -> actor::HolochainP2pResult<Vec<MetadataSet>> {
-        todo!()
+        // A race is won by the first authority to answer.
+        let count = match options.remote_agent_count {
+            Some(count) => count as usize,
+            None if options.as_race => 1,
+            None => self.envs.len(),
+        };
+        let mut out = Vec::new();
+        for env in self.envs.iter().take(count) {
+            let r = authority::handle_get_meta(env.clone(), dht_hash.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
     }
 
     async fn get_links(
@@ -155,6 +169,26 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         Ok(out)
     }
 
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        _options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>> {
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_count_links(
+                env.clone(),
+                link_key.clone(),
+                create_link_hashes.clone(),
+            )
+            .await
+            .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
+    }
+
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -242,6 +276,10 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         todo!()
     }
 
+    async fn publish_warrant(&self, _warrant: SignedWarrant) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn send_validation_receipt(
         &self,
         _to_agent: AgentPubKey,
@@ -373,6 +411,19 @@ impl HolochainP2pDnaT for MockNetwork {
         self.0.lock().await.get_links(link_key, options).await
     }
 
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>> {
+        self.0
+            .lock()
+            .await
+            .count_links(link_key, create_link_hashes, options)
+            .await
+    }
+
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -445,6 +496,10 @@ impl HolochainP2pDnaT for MockNetwork {
         todo!()
     }
 
+    async fn publish_warrant(&self, _warrant: SignedWarrant) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn send_validation_receipt(
         &self,
         _to_agent: AgentPubKey,
@@ -168,16 +168,16 @@ impl EntryTestData {
             signature,
         );
 
-        let link_key = WireLinkKey {
-            base: create_link.base_address.clone(),
-            type_query: LinkTypeFilter::single_dep(0.into()),
-            tag: None,
-        };
-        let link_key_tag = WireLinkKey {
-            base: create_link.base_address.clone(),
-            type_query: LinkTypeFilter::single_dep(0.into()),
-            tag: Some(create_link.tag.clone()),
-        };
+        let link_key = WireLinkKey::new(
+            create_link.base_address.clone(),
+            LinkTypeFilter::single_dep(0.into()),
+            None,
+        );
+        let link_key_tag = WireLinkKey::new(
+            create_link.base_address.clone(),
+            LinkTypeFilter::single_dep(0.into()),
+            Some(create_link.tag.clone()),
+        );
 
         let link = Link {
             author: create_link.author,
@@ -172,6 +172,101 @@ async fn links_authoring() {
     assert!(r.is_empty());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn count_links_not_authority() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let vault = test_authored_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td = EntryTestData::create();
+    fill_db(&vault.to_db(), td.store_entry_op.clone());
+    fill_db(&vault.to_db(), td.create_link_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let cascade = Cascade::empty()
+        .with_network(network, cache.to_db())
+        .with_authored(vault.to_db().into());
+
+    // The authority hasn't received our link yet.
+    let r = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(r, 1);
+
+    // Our link is only counted once when the authority has it too.
+    fill_db(&authority.to_db(), td.store_entry_op.clone());
+    fill_db(&authority.to_db(), td.create_link_op.clone());
+
+    let r = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(r, 1);
+
+    // A link the authority knows to be deleted is not added back.
+    fill_db(&authority.to_db(), td.delete_link_op.clone());
+
+    let r = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(r, 0);
+
+    // Our delete is not subtracted twice.
+    fill_db(&vault.to_db(), td.delete_link_op.clone());
+
+    let r = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(r, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_links_not_authority_unpublished_delete() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let vault = test_authored_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td = EntryTestData::create();
+    fill_db(&authority.to_db(), td.store_entry_op.clone());
+    fill_db(&authority.to_db(), td.create_link_op.clone());
+    fill_db(&vault.to_db(), td.create_link_op.clone());
+    fill_db(&vault.to_db(), td.delete_link_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let cascade = Cascade::empty()
+        .with_network(network, cache.to_db())
+        .with_authored(vault.to_db().into());
+
+    // The authority still counts the link we deleted.
+    let r = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(r, 0);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "todo"]
 async fn test_links_can_match_a_partial_tag() {
@@ -0,0 +1,164 @@
+use holo_hash::ActionHash;
+use holochain_cascade::test_utils::*;
+use holochain_cascade::Cascade;
+use holochain_state::prelude::test_cache_db;
+use holochain_state::prelude::test_dht_db;
+use holochain_types::metadata::TimedActionHash;
+use holochain_zome_types::EntryDhtStatus;
+use holochain_zome_types::GetOptions;
+use holochain_zome_types::ValidationStatus;
+use std::collections::BTreeSet;
+
+fn hashes(set: &BTreeSet<TimedActionHash>) -> Vec<ActionHash> {
+    set.iter().map(|t| t.action_hash.clone()).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta_for_entry_and_action() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td_entry = EntryTestData::create();
+    let td_record = RecordTestData::create();
+    fill_db(&authority.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&authority.to_db(), td_entry.update_content_op.clone());
+    fill_db(&authority.to_db(), td_entry.create_link_op.clone());
+    fill_db(&authority.to_db(), td_record.any_store_record_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let meta = cascade
+        .get_meta(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&meta.actions), vec![td_entry.create_hash.clone()]);
+    assert_eq!(hashes(&meta.updates), vec![td_entry.update_hash.clone()]);
+    assert!(meta.deletes.is_empty());
+    assert_eq!(
+        hashes(&meta.links),
+        vec![td_entry.create_link_action.as_hash().clone()]
+    );
+    assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Live));
+    assert_eq!(meta.validation_status, None);
+
+    let meta = cascade
+        .get_meta(
+            td_record.any_action_hash.clone().into(),
+            GetOptions::latest(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        hashes(&meta.actions),
+        vec![td_record.any_action_hash.clone()]
+    );
+    assert_eq!(meta.validation_status, Some(ValidationStatus::Valid));
+    assert_eq!(meta.entry_dht_status, None);
+
+    // Once the entry and link are deleted they no longer count.
+    fill_db(&authority.to_db(), td_entry.delete_entry_action_op.clone());
+    fill_db(&authority.to_db(), td_entry.delete_link_op.clone());
+    let meta = cascade
+        .get_meta(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&meta.deletes), vec![td_entry.delete_hash.clone()]);
+    assert!(meta.links.is_empty());
+    assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Dead));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta_merges_authorities() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority_1 = test_dht_db();
+    let authority_2 = test_dht_db();
+
+    // Data
+    let td_entry = EntryTestData::create();
+    fill_db(&authority_1.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&authority_1.to_db(), td_entry.update_content_op.clone());
+    fill_db_rejected(&authority_2.to_db(), td_entry.store_entry_op.clone());
+    fill_db(
+        &authority_2.to_db(),
+        td_entry.delete_entry_action_op.clone(),
+    );
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        authority_1.to_db().clone().into(),
+        authority_2.to_db().clone().into(),
+    ]);
+
+    // Cascade
+    let cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let meta = cascade
+        .get_meta(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&meta.actions), vec![td_entry.create_hash.clone()]);
+    assert_eq!(
+        hashes(&meta.invalid_actions),
+        vec![td_entry.create_hash.clone()]
+    );
+    assert_eq!(hashes(&meta.updates), vec![td_entry.update_hash.clone()]);
+    assert_eq!(hashes(&meta.deletes), vec![td_entry.delete_hash.clone()]);
+    // The first authority to answer decides the status.
+    assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Live));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta_latest_waits_for_every_authority() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority_1 = test_dht_db();
+    let authority_2 = test_dht_db();
+
+    // Data
+    // The first authority hasn't heard of the delete yet.
+    let td_entry = EntryTestData::create();
+    fill_db(&authority_1.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&authority_2.to_db(), td_entry.store_entry_op.clone());
+    fill_db(
+        &authority_2.to_db(),
+        td_entry.delete_entry_action_op.clone(),
+    );
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        authority_1.to_db().clone().into(),
+        authority_2.to_db().clone().into(),
+    ]);
+
+    // Cascade
+    let cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    // Racing for the content settles for the first answer.
+    let meta = cascade
+        .get_meta(td_entry.hash.clone().into(), GetOptions::content())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&meta.actions), vec![td_entry.create_hash.clone()]);
+    assert!(meta.deletes.is_empty());
+    assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Live));
+
+    let meta = cascade
+        .get_meta(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&meta.actions), vec![td_entry.create_hash.clone()]);
+    assert_eq!(hashes(&meta.deletes), vec![td_entry.delete_hash.clone()]);
+}
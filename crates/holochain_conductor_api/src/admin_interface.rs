@@ -1,9 +1,13 @@
 use holo_hash::*;
 use holochain_types::prelude::*;
+use holochain_websocket::AllowedOrigins;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
-use crate::{AppInfo, FullStateDump, StorageInfo};
+use crate::{
+    AppAuthenticationToken, AppAuthenticationTokenIssued, AppInfo, FullStateDump, InterfaceDriver,
+    IssueAppAuthenticationTokenPayload, StorageInfo,
+};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -161,10 +165,29 @@ pub enum AdminRequest {
     /// a free port is chosen by the conductor.
     /// The response will contain the port chosen by the conductor if `None` was passed.
     ///
+    /// Instead of a port, a `driver` may be given to serve the interface over TLS
+    /// or a unix domain socket. A websocket driver with port 0 also lets the
+    /// conductor choose a free port. Giving both a port and a driver is an error.
+    ///
+    /// Browsers can only connect to the interface from the `allowed_origins`,
+    /// and if an `installed_app_id` is given, connections can only
+    /// authenticate for that app.
+    ///
     /// [`AppRequest`]: super::AppRequest
     AttachAppInterface {
         /// Optional port number
         port: Option<u16>,
+        /// By what means the interface will be exposed,
+        /// if not a plain websocket on `port`
+        #[serde(default)]
+        driver: Option<InterfaceDriver>,
+        /// The web origins which may connect to the interface.
+        /// Defaults to allowing any origin.
+        #[serde(default)]
+        allowed_origins: AllowedOrigins,
+        /// The only app which connections to the interface may access.
+        #[serde(default)]
+        installed_app_id: Option<InstalledAppId>,
     },
 
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
@@ -324,8 +347,80 @@ pub enum AdminRequest {
     /// [`AdminResponse::CloneCellDeleted`]
     DeleteCloneCell(Box<DeleteCloneCellPayload>),
 
+    /// Migrate the agent of an app role's cell to a new version of its DNA,
+    /// which must already be registered.
+    ///
+    /// The `migrate_agent` callbacks of both DNAs must pass. The agent's chain
+    /// on the old DNA is then closed, and a chain which refers back to it is
+    /// opened on the new DNA. The old cell keeps serving its data, but nothing
+    /// more can be written to it.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellMigrated`]
+    MigrateCell(Box<MigrateCellPayload>),
+
     /// Info about storage used by apps
     StorageInfo,
+
+    /// List the countersigning sessions of this conductor's agents which
+    /// have not yet been resolved, e.g. because the conductor was stopped
+    /// during a session.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CountersigningSessionsListed`]
+    ListCountersigningSessions,
+
+    /// Resolve a cell's countersigning session.
+    ///
+    /// Without a forced resolution, the session is only resolved if the
+    /// other signers' agent activity shows whether or not it completed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CountersigningSessionResolved`]
+    ResolveCountersigningSession {
+        /// The cell whose session should be resolved.
+        cell_id: CellId,
+        /// Complete or abandon the session regardless of what the other
+        /// signers did. Forcing a session to complete when the other signers
+        /// have abandoned it will leave this agent's chain invalid.
+        force: Option<CountersigningSessionResolution>,
+    },
+
+    /// Issue a token which a client must present in the first message on
+    /// a connection to an app interface. The connection may then only make
+    /// requests on behalf of the app the token was issued for.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppAuthenticationTokenIssued`]
+    IssueAppAuthenticationToken(IssueAppAuthenticationTokenPayload),
+
+    /// Revoke a token so that it can't be used to authenticate any more
+    /// connections. Connections which were already authenticated with it
+    /// are not affected.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppAuthenticationTokenRevoked`]
+    RevokeAppAuthenticationToken(AppAuthenticationToken),
+
+    /// Change which logs the conductor records, without restarting it.
+    ///
+    /// The filter uses the same syntax as `RUST_LOG`,
+    /// e.g. `info,holochain_p2p=debug`. It replaces the filter from the
+    /// conductor's [`LoggerConfig`](crate::config::conductor::LoggerConfig)
+    /// until the conductor restarts, but the config itself is not changed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::LogFilterSet`]
+    SetLogFilter {
+        /// The new filter.
+        filter: String,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -396,7 +491,8 @@ pub enum AdminResponse {
     /// If no port was specified in the request, contains the port number that was
     /// selected by the conductor for running this app interface.
     AppInterfaceAttached {
-        /// Networking port of the new `AppInterfaceApi`
+        /// Networking port of the new `AppInterfaceApi`,
+        /// or 0 if it is served over a unix domain socket
         port: u16,
     },
 
@@ -462,8 +558,33 @@ pub enum AdminResponse {
     /// The successful response to an [`AdminRequest::DeleteCloneCell`].
     CloneCellDeleted,
 
+    /// The successful response to an [`AdminRequest::MigrateCell`].
+    ///
+    /// The id of the role's new cell.
+    CellMigrated(CellId),
+
     /// The successful response to an [`AdminRequest::StorageInfo`].
     StorageInfo(StorageInfo),
+
+    /// The successful response to an [`AdminRequest::ListCountersigningSessions`].
+    ///
+    /// The unresolved session of each cell which has one.
+    CountersigningSessionsListed(Vec<(CellId, CountersigningSessionState)>),
+
+    /// The successful response to an [`AdminRequest::ResolveCountersigningSession`].
+    ///
+    /// How the session was resolved, or `None` if it couldn't be resolved yet
+    /// or the cell had no session.
+    CountersigningSessionResolved(Option<CountersigningSessionResolution>),
+
+    /// The successful response to an [`AdminRequest::IssueAppAuthenticationToken`].
+    AppAuthenticationTokenIssued(AppAuthenticationTokenIssued),
+
+    /// The successful response to an [`AdminRequest::RevokeAppAuthenticationToken`].
+    AppAuthenticationTokenRevoked,
+
+    /// The successful response to an [`AdminRequest::SetLogFilter`].
+    LogFilterSet,
 }
 
 /// Error type that goes over the websocket wire.
@@ -489,6 +610,9 @@ pub enum ExternalApiWireError {
     ZomeCallUnauthorized(String),
     /// A countersigning session has failed.
     CountersigningSessionError(String),
+    /// The connection to an app interface isn't authenticated, or is not
+    /// authenticated for the app the request is for.
+    AppAuthenticationFailed(String),
 }
 
 impl ExternalApiWireError {
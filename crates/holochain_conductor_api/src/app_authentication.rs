@@ -0,0 +1,75 @@
+//! Types for authenticating client connections to app interfaces.
+//!
+//! A client gets a token from an admin interface with
+//! [`AdminRequest::IssueAppAuthenticationToken`](crate::AdminRequest::IssueAppAuthenticationToken)
+//! and sends it in the first message on its app interface connection,
+//! [`AppRequest::Authenticate`](crate::AppRequest::Authenticate).
+//! The connection can then only make requests on behalf of the app the token
+//! was issued for.
+
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::app::InstalledAppId;
+use holochain_zome_types::Timestamp;
+
+/// An opaque token which authenticates a connection to an app interface
+/// for a single app.
+pub type AppAuthenticationToken = Vec<u8>;
+
+/// The number of seconds an issued token is valid for, if not specified.
+pub const DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS: u64 = 30;
+
+/// Arguments for issuing an [`AppAuthenticationToken`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct IssueAppAuthenticationTokenPayload {
+    /// The app which connections authenticated with the token may access.
+    pub installed_app_id: InstalledAppId,
+    /// How long the token can be used for, in seconds.
+    /// A value of 0 means the token never expires.
+    #[serde(default = "default_expiry_seconds")]
+    pub expiry_seconds: u64,
+    /// Whether the token becomes invalid once it has been used to
+    /// authenticate a connection.
+    #[serde(default = "default_single_use")]
+    pub single_use: bool,
+}
+
+impl IssueAppAuthenticationTokenPayload {
+    /// A single use token for the given app which expires after
+    /// [`DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS`].
+    pub fn for_installed_app_id(installed_app_id: InstalledAppId) -> Self {
+        Self {
+            installed_app_id,
+            expiry_seconds: default_expiry_seconds(),
+            single_use: default_single_use(),
+        }
+    }
+
+    /// Set how long the token can be used for, in seconds.
+    pub fn expiry_seconds(mut self, expiry_seconds: u64) -> Self {
+        self.expiry_seconds = expiry_seconds;
+        self
+    }
+
+    /// Set whether the token can only be used once.
+    pub fn single_use(mut self, single_use: bool) -> Self {
+        self.single_use = single_use;
+        self
+    }
+}
+
+fn default_expiry_seconds() -> u64 {
+    DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS
+}
+
+fn default_single_use() -> bool {
+    true
+}
+
+/// A newly issued [`AppAuthenticationToken`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct AppAuthenticationTokenIssued {
+    /// The token to send in an [`AppRequest::Authenticate`](crate::AppRequest::Authenticate).
+    pub token: AppAuthenticationToken,
+    /// When the token expires, if ever.
+    pub expires_at: Option<Timestamp>,
+}
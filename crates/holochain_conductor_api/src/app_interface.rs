@@ -1,3 +1,5 @@
+use crate::signal_subscription::SignalSubscription;
+use crate::AppAuthenticationToken;
 use crate::ExternalApiWireError;
 use holo_hash::AgentPubKey;
 use holochain_keystore::LairResult;
@@ -16,6 +18,20 @@ use std::collections::HashMap;
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum AppRequest {
+    /// Authenticate this connection with a token issued by an admin interface.
+    ///
+    /// This must be the first request on a connection to an app interface.
+    /// All other requests fail until the connection is authenticated, and
+    /// afterwards may only be made on behalf of the app the token was issued for.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::Authenticated`]
+    Authenticate {
+        /// A token from [`AdminRequest::IssueAppAuthenticationToken`](crate::AdminRequest::IssueAppAuthenticationToken)
+        token: AppAuthenticationToken,
+    },
+
     /// Get info about the app identified by the given `installed_app_id` argument,
     /// including info about each cell installed by this app.
     ///
@@ -68,6 +84,16 @@ pub enum AppRequest {
     /// [`AppResponse::CloneCellEnabled`]
     EnableCloneCell(Box<EnableCloneCellPayload>),
 
+    /// Provision the cell of a role whose provisioning was deferred when the
+    /// app was installed, running genesis with the given membrane proof.
+    ///
+    /// Until then the role is listed in [`AppInfo`] as a [`CellInfo::Stem`].
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::RoleProvisioned`]
+    ProvisionRole(Box<ProvisionRolePayload>),
+
     /// Info about networking processes
     NetworkInfo(Box<NetworkInfoRequestPayload>),
 
@@ -77,6 +103,18 @@ pub enum AppRequest {
     ///
     /// [`AppResponse::ListWasmHostFunctions`]
     ListWasmHostFunctions,
+
+    /// Choose which signals from the cells of an app are sent over this
+    /// connection, replacing any subscription previously set for that app
+    /// on this connection. Other connections are not affected.
+    ///
+    /// Until a subscription is set for an app, all of its signals are sent.
+    /// The subscription applies to the cells which are in the app when it is set.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalSubscriptionSet`]
+    SetSignalSubscription(Box<SignalSubscription>),
 }
 
 /// Represents the possible responses to an [`AppRequest`].
@@ -117,11 +155,25 @@ pub enum AppResponse {
     /// is returned.
     CloneCellEnabled(ClonedCell),
 
+    /// The successful response to an [`AppRequest::ProvisionRole`].
+    ///
+    /// The cell of the deferred role has been created and is running if the
+    /// app is running.
+    RoleProvisioned(ProvisionedCell),
+
     /// NetworkInfo is returned
     NetworkInfo(Vec<NetworkInfo>),
 
     /// All the wasm host functions supported by this conductor.
     ListWasmHostFunctions(Vec<String>),
+
+    /// The successful response to an [`AppRequest::SetSignalSubscription`].
+    SignalSubscriptionSet,
+
+    /// The successful response to an [`AppRequest::Authenticate`].
+    ///
+    /// The app which this connection is now authenticated for.
+    Authenticated(InstalledAppId),
 }
 
 /// The data provided over an app interface in order to make a zome call
@@ -221,7 +273,7 @@ pub enum CellInfo {
     Cloned(ClonedCell),
 
     /// Potential cells with deferred installation as defined in the bundle.
-    /// Not yet implemented.
+    /// They can be provisioned with [`AppRequest::ProvisionRole`].
     Stem(StemCell),
 }
 
@@ -254,7 +306,6 @@ impl CellInfo {
 }
 
 /// Cell whose instantiation has been deferred.
-/// Not yet implemented.
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct StemCell {
     /// The hash of the DNA that this cell would be instantiated from
@@ -350,10 +401,16 @@ impl AppInfo {
                 } else {
                     tracing::error!("no DNA definition found for cell id {}", provisioned_cell);
                 }
-            } else {
-                // no provisioned cell, thus there must be a deferred cell
-                // this is not implemented as of now
-                unimplemented!()
+            } else if let Some(deferred_cell) = role_assignment.deferred_cell() {
+                if let Some(dna_def) = dna_definitions.get(deferred_cell) {
+                    cell_info_for_role.push(CellInfo::Stem(StemCell {
+                        original_dna_hash: dna_def.hash.clone(),
+                        dna_modifiers: dna_def.modifiers.to_owned(),
+                        name: None,
+                    }));
+                } else {
+                    tracing::error!("no DNA definition found for cell id {}", deferred_cell);
+                }
             };
 
             // push enabled clone cells to the vector of cell infos
@@ -7,20 +7,24 @@ use serde::Deserialize;
 use serde::Serialize;
 
 mod admin_interface_config;
+mod app_store_config;
 mod dpki_config;
 #[allow(missing_docs)]
 mod error;
 mod keystore_config;
+mod logger_config;
+mod metrics_config;
 pub mod paths;
-//mod logger_config;
 //mod signal_config;
 pub use paths::DatabaseRootPath;
 
 pub use super::*;
+pub use app_store_config::AppStoreConfig;
 pub use dpki_config::DpkiConfig;
-//pub use logger_config::LoggerConfig;
 pub use error::*;
 pub use keystore_config::KeystoreConfig;
+pub use logger_config::{LogFileConfig, LogFormat, LogLevel, LoggerConfig};
+pub use metrics_config::MetricsConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 
@@ -44,6 +48,11 @@ pub struct ConductorConfig {
     /// keys for new instances.
     pub dpki: Option<DpkiConfig>,
 
+    /// Optional app store configuration, if the conductor should be able to
+    /// install apps and DNAs which have been published to an app store DNA.
+    #[serde(default)]
+    pub app_store: Option<AppStoreConfig>,
+
     /// Setup admin interfaces to control this conductor through a websocket connection.
     pub admin_interfaces: Option<Vec<AdminInterfaceConfig>>,
 
@@ -66,6 +75,23 @@ pub struct ConductorConfig {
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     #[serde(default)]
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// The longest time, in milliseconds, that a zome call may suspend itself
+    /// for with a single call to the `sleep` host function. Longer sleeps are
+    /// cut short to this duration.
+    /// If omitted, defaults to [`DEFAULT_MAX_ZOME_SLEEP_MS`].
+    #[serde(default)]
+    pub max_zome_sleep_ms: Option<u64>,
+
+    /// Optional HTTP endpoint to serve metrics on, for Prometheus or other
+    /// OpenMetrics compatible scrapers.
+    #[serde(default)]
+    pub metrics: Option<MetricsConfig>,
+
+    /// Optional config for the conductor's logs. If omitted, logs are
+    /// filtered with `RUST_LOG` and written to stderr.
+    #[serde(default)]
+    pub logger: Option<LoggerConfig>,
     //
     //
     // Which signals to emit
@@ -73,6 +99,9 @@ pub struct ConductorConfig {
     // pub signals: SignalConfig,
 }
 
+/// The default for [`ConductorConfig::max_zome_sleep_ms`].
+pub const DEFAULT_MAX_ZOME_SLEEP_MS: u64 = 10_000;
+
 /// Helper function to load a config from a YAML string.
 fn config_from_yaml<T>(yaml: &str) -> ConductorConfigResult<T>
 where
@@ -92,6 +121,13 @@ impl ConductorConfig {
         })?;
         config_from_yaml(&config_yaml)
     }
+
+    /// The longest time a zome call may sleep for at once.
+    pub fn max_zome_sleep(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.max_zome_sleep_ms.unwrap_or(DEFAULT_MAX_ZOME_SLEEP_MS),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -135,10 +171,14 @@ pub mod tests {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 network: None,
                 dpki: None,
+                app_store: None,
                 keystore: KeystoreConfig::DangerTestKeystore,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
                 chc_namespace: None,
+                max_zome_sleep_ms: None,
+                metrics: None,
+                logger: None,
             }
         );
     }
@@ -157,8 +197,11 @@ pub mod tests {
       type: lair_server_in_proc
 
     dpki:
-      instance_id: some_id
-      init_params: some_params
+      dna_path: /path/to/dpki.dna
+      network_seed: some_seed
+
+    app_store:
+      dna_path: /path/to/app_store.dna
 
     admin_interfaces:
       - driver:
@@ -182,6 +225,21 @@ pub mod tests {
       network_type: quic_bootstrap
 
     db_sync_strategy: Fast
+
+    max_zome_sleep_ms: 5000
+
+    metrics:
+      bind_address: 127.0.0.1:9100
+
+    logger:
+      level: warn
+      targets:
+        holochain_p2p: debug
+        kitsune_p2p: error
+      format: json
+      file:
+        path: /path/to/conductor.log
+        max_files: 3
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -207,16 +265,39 @@ pub mod tests {
                 tracing_override: None,
                 environment_path: PathBuf::from("/path/to/env").into(),
                 dpki: Some(DpkiConfig {
-                    instance_id: "some_id".into(),
-                    init_params: "some_params".into()
+                    dna_path: PathBuf::from("/path/to/dpki.dna"),
+                    network_seed: Some("some_seed".into()),
+                }),
+                app_store: Some(AppStoreConfig {
+                    dna_path: PathBuf::from("/path/to/app_store.dna"),
+                    network_seed: None,
                 }),
                 keystore: KeystoreConfig::LairServerInProc { lair_root: None },
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::websocket(1234)
                 }]),
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
                 chc_namespace: None,
+                max_zome_sleep_ms: Some(5000),
+                metrics: Some(MetricsConfig {
+                    bind_address: ([127, 0, 0, 1], 9100).into(),
+                }),
+                logger: Some(LoggerConfig {
+                    level: LogLevel::Warn,
+                    targets: [
+                        ("holochain_p2p".to_string(), LogLevel::Debug),
+                        ("kitsune_p2p".to_string(), LogLevel::Error),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    format: Some(LogFormat::Json),
+                    file: Some(LogFileConfig {
+                        path: PathBuf::from("/path/to/conductor.log"),
+                        max_file_size_bytes: 10 * 1024 * 1024,
+                        max_files: 3,
+                    }),
+                }),
             }
         );
     }
@@ -239,13 +320,78 @@ pub mod tests {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 network: None,
                 dpki: None,
+                app_store: None,
                 keystore: KeystoreConfig::LairServer {
                     connection_url: url2::url2!("unix:///var/run/lair-keystore/socket?k=EcRDnP3xDIZ9Rk_1E-egPE0mGZi5CcszeRxVkb2QXXQ").into(),
                 },
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::Fast,
                 chc_namespace: None,
+                max_zome_sleep_ms: None,
+                metrics: None,
+                logger: None,
             }
         );
     }
+
+    #[test]
+    fn test_config_secure_admin_interfaces() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+    keystore:
+      type: danger_test_keystore
+    admin_interfaces:
+      - driver:
+          type: unix_socket
+          path: /run/holochain/admin.sock
+          mode: 0o600
+      - driver:
+          type: websocket
+          port: 1234
+          tls:
+            cert_path: /path/to/cert.pem
+            key_path: /path/to/key.pem
+            client_ca_path: /path/to/ca.pem
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.admin_interfaces,
+            Some(vec![
+                AdminInterfaceConfig {
+                    driver: InterfaceDriver::UnixSocket {
+                        path: PathBuf::from("/run/holochain/admin.sock"),
+                        mode: Some(0o600),
+                    },
+                },
+                AdminInterfaceConfig {
+                    driver: InterfaceDriver::Websocket {
+                        port: 1234,
+                        tls: Some(InterfaceTlsConfig {
+                            cert_path: PathBuf::from("/path/to/cert.pem"),
+                            key_path: PathBuf::from("/path/to/key.pem"),
+                            client_ca_path: Some(PathBuf::from("/path/to/ca.pem")),
+                        }),
+                    },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_logger_filter_directives() {
+        let yaml = r#"---
+    level: error
+    targets:
+      holochain: debug
+      holochain_p2p::spawn: trace
+    "#;
+        let logger: LoggerConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(logger.format, None);
+        assert_eq!(logger.file, None);
+        assert_eq!(
+            logger.filter_directives(),
+            "error,holochain=debug,holochain_p2p::spawn=trace"
+        );
+        assert_eq!(LoggerConfig::default().filter_directives(), "info");
+    }
 }
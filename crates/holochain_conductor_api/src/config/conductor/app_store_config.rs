@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Configure the DNA which the conductor will install and run as its app store.
+///
+/// The DNA must implement the app store contract defined in
+/// `holochain_zome_types::app_store`. Like DPKI, it is installed under a
+/// reserved app id when the conductor first starts up, and apps and DNAs
+/// published to it can then be installed by hash.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct AppStoreConfig {
+    /// Path to the DNA bundle of the app store
+    pub dna_path: PathBuf,
+
+    /// Optional network seed to use for the app store DNA, to join a
+    /// particular app store network
+    #[serde(default)]
+    pub network_seed: Option<String>,
+}
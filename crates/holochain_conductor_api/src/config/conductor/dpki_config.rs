@@ -1,13 +1,19 @@
-// Legacy config that will probably change
-#![allow(missing_docs)]
-
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 
-/// Configure which app instance ID to treat as the DPKI application handler
-/// as well as what parameters to pass it on its initialization.
+/// Configure the DNA which the conductor will install and run as its DPKI service.
+///
+/// The DNA must implement the DPKI contract defined in `holochain_zome_types::dpki`.
+/// It is installed under a reserved app id when the conductor first starts up,
+/// and cannot be disabled or uninstalled through the admin interface.
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct DpkiConfig {
-    pub instance_id: String,
-    pub init_params: String,
+    /// Path to the DNA bundle of the DPKI service
+    pub dna_path: PathBuf,
+
+    /// Optional network seed to use for the DPKI DNA, to create a
+    /// separate DPKI network
+    #[serde(default)]
+    pub network_seed: Option<String>,
 }
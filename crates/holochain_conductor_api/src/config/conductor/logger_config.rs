@@ -1,5 +1,118 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
-/// FIXME: implement
-#[derive(Deserialize, Serialize, Default, Debug, PartialEq)]
-pub struct LoggerConfig {}
+/// Configure which logs the conductor records, how they are formatted
+/// and where they are written.
+///
+/// The filter can be changed while the conductor is running with
+/// [`AdminRequest::SetLogFilter`](crate::AdminRequest::SetLogFilter).
+/// If `tracing_override` is also set in the conductor config it takes
+/// precedence over `level` and `targets`.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq, Eq)]
+pub struct LoggerConfig {
+    /// The level for all targets which aren't listed in `targets`.
+    #[serde(default)]
+    pub level: LogLevel,
+
+    /// Levels for particular targets, e.g. `holochain_p2p: debug`.
+    /// A target also sets the level of all of its submodules.
+    #[serde(default)]
+    pub targets: BTreeMap<String, LogLevel>,
+
+    /// How each log line is formatted.
+    /// If omitted, the format given on the command line is used.
+    #[serde(default)]
+    pub format: Option<LogFormat>,
+
+    /// Write logs to a file which is rotated by size, instead of to stderr.
+    #[serde(default)]
+    pub file: Option<LogFileConfig>,
+}
+
+impl LoggerConfig {
+    /// The filter for this config, in the same syntax as `RUST_LOG`.
+    pub fn filter_directives(&self) -> String {
+        std::iter::once(self.level.as_str().to_string())
+            .chain(
+                self.targets
+                    .iter()
+                    .map(|(target, level)| format!("{}={}", target, level.as_str())),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// The most verbose level of logs which are recorded.
+#[derive(Clone, Copy, Deserialize, Serialize, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    /// Don't record any logs.
+    Off,
+    /// Only errors.
+    Error,
+    /// Errors and warnings.
+    Warn,
+    /// Errors, warnings and info.
+    #[default]
+    Info,
+    /// Everything except trace.
+    Debug,
+    /// Everything.
+    Trace,
+}
+
+impl LogLevel {
+    /// The name of this level in a filter directive.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+/// How each log line is formatted.
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human readable, with the spans each event happened in.
+    Log,
+    /// Human readable, with less information on each line.
+    Compact,
+    /// One json object per line, for querying with other tools.
+    Json,
+}
+
+/// A log file which is rotated once it reaches a maximum size.
+///
+/// When `path` is full it is renamed to `path.1`, older files move along
+/// to `path.2` and so on, and the oldest file beyond `max_files` is deleted.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct LogFileConfig {
+    /// The file which logs are currently written to.
+    pub path: PathBuf,
+
+    /// The size in bytes a file may grow to before it is rotated.
+    /// Defaults to 10 MiB.
+    #[serde(default = "default_max_file_size_bytes")]
+    pub max_file_size_bytes: u64,
+
+    /// The number of rotated files to keep besides the current one.
+    /// Defaults to 5.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_file_size_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_max_files() -> usize {
+    5
+}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::SocketAddr;
+
+/// Configure the HTTP endpoint which exports the conductor's metrics.
+///
+/// When set, `GET /metrics` on this address serves the conductor, database
+/// and network metrics in the Prometheus text exposition format, so they
+/// can be scraped by Prometheus or any OpenMetrics compatible collector.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct MetricsConfig {
+    /// The address to serve metrics on, e.g. `127.0.0.1:9100`.
+    /// Use port 0 to have one chosen automatically.
+    pub bind_address: SocketAddr,
+}
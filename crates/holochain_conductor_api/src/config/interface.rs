@@ -1,11 +1,13 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 
 /// Information neeeded to spawn an admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
     /// By what means the interface will be exposed.
-    /// Currently the only option is a local websocket running on a configurable port.
+    /// Either a local websocket running on a configurable port,
+    /// or a websocket served over a unix domain socket.
     pub driver: InterfaceDriver,
     // How long will this interface be accessible between authentications?
     // TODO: implement once we have authentication
@@ -28,14 +30,51 @@ pub enum InterfaceDriver {
     Websocket {
         /// The port on which to establish the WebsocketListener
         port: u16,
+        /// Serve the websocket over TLS, optionally requiring clients
+        /// to present a certificate.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tls: Option<InterfaceTlsConfig>,
     },
+    /// An interface implemented via websockets over a unix domain socket.
+    /// Access is controlled by the permissions of the socket file.
+    /// Only available on unix platforms.
+    UnixSocket {
+        /// The path of the socket file to create.
+        /// A socket file already at this path is replaced.
+        path: PathBuf,
+        /// The permissions of the socket file, e.g. `0o600` to only allow
+        /// the user running the conductor to connect.
+        /// If not set, the permissions come from the umask of the conductor process.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
+}
+
+/// TLS settings for a websocket interface.
+/// All files are PEM encoded.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct InterfaceTlsConfig {
+    /// The certificate chain the interface presents to clients.
+    pub cert_path: PathBuf,
+    /// The private key of the interface's certificate.
+    pub key_path: PathBuf,
+    /// If set, clients must authenticate with a certificate signed by
+    /// one of the certificate authorities in this file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_path: Option<PathBuf>,
 }
 
 impl InterfaceDriver {
-    /// Get the port for this driver.
-    pub fn port(&self) -> u16 {
+    /// Create a plain websocket driver for this port.
+    pub fn websocket(port: u16) -> Self {
+        InterfaceDriver::Websocket { port, tls: None }
+    }
+
+    /// Get the port for this driver, if it listens on one.
+    pub fn port(&self) -> Option<u16> {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port, .. } => Some(*port),
+            InterfaceDriver::UnixSocket { .. } => None,
         }
     }
 }
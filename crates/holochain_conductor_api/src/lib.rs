@@ -13,6 +13,7 @@
 //! signals from the app.
 
 mod admin_interface;
+pub mod app_authentication;
 mod app_interface;
 pub mod config;
 pub mod signal_subscription;
@@ -20,7 +21,9 @@ pub mod state_dump;
 pub mod storage_info;
 
 pub use admin_interface::*;
+pub use app_authentication::*;
 pub use app_interface::*;
 pub use config::*;
+pub use holochain_websocket::AllowedOrigins;
 pub use state_dump::*;
 pub use storage_info::*;
@@ -1,16 +1,29 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
 use holochain_zome_types::cell::CellId;
+use holochain_zome_types::signal::AppSignal;
+use holochain_zome_types::zome::ZomeName;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct SignalSubscription {
     /// The app for which to manage subscription
-    installed_app_id: InstalledAppId,
+    pub installed_app_id: InstalledAppId,
     /// Fine-grained per-cell filters
-    filters: SignalFilterSet,
+    pub filters: SignalFilterSet,
+}
+
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
 }
 
 /// Associate a SignalFilter with each Cell in an App.
@@ -46,22 +59,300 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
-}
-
-/// Specifies fine-grained filter controls for the signals
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
 
-impl Default for SignalFilter {
-    fn default() -> Self {
-        // The default is no filter
-        Self::empty()
+    /// Should this app signal be let through?
+    pub fn allows(&self, cell_id: &CellId, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        match self {
+            Self::Include(filters) => filters
+                .get(cell_id)
+                .map_or(false, |f| f.matches(zome_name, signal)),
+            Self::Exclude(filters) => filters
+                .get(cell_id)
+                .map_or(true, |f| !f.matches(zome_name, signal)),
+        }
     }
 }
 
+/// Specifies fine-grained filter controls for the signals.
+///
+/// A signal matches the filter if it matches every criterion which is set.
+/// The empty filter, with no criteria set, matches every signal.
+#[derive(
+    Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct SignalFilter {
+    /// Only match signals emitted by one of these zomes.
+    #[serde(default)]
+    pub zome_names: Option<HashSet<ZomeName>>,
+    /// Only match signals with one of these types.
+    ///
+    /// The type of a signal is read from its payload, which is the case for
+    /// most signals defined as a Rust enum:
+    /// - the value of the `type` field, if the payload is a map with a string
+    ///   `type` field, as produced by `#[serde(tag = "type")]`
+    /// - otherwise the key of a map with a single string key, as produced
+    ///   by serde's default enum representation
+    /// - or the string itself, if the payload is a string
+    ///
+    /// Signals without a type never match.
+    #[serde(default)]
+    pub signal_types: Option<HashSet<String>>,
+    /// Only match signals whose serialized payload starts with these bytes.
+    #[serde(default, with = "serde_bytes")]
+    pub payload_prefix: Option<Vec<u8>>,
+}
+
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        Self::default()
+    }
+
+    /// Also match signals emitted by this zome
+    pub fn with_zome_name(mut self, zome_name: impl Into<ZomeName>) -> Self {
+        self.zome_names
+            .get_or_insert_with(HashSet::new)
+            .insert(zome_name.into());
+        self
+    }
+
+    /// Also match signals of this type
+    pub fn with_signal_type(mut self, signal_type: impl Into<String>) -> Self {
+        self.signal_types
+            .get_or_insert_with(HashSet::new)
+            .insert(signal_type.into());
+        self
+    }
+
+    /// Only match signals whose payload starts with these bytes
+    pub fn with_payload_prefix(mut self, prefix: impl Into<Vec<u8>>) -> Self {
+        self.payload_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Does the signal match this filter?
+    pub fn matches(&self, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        let bytes = signal.as_inner().0.as_slice();
+        self.zome_names
+            .as_ref()
+            .map_or(true, |names| names.contains(zome_name))
+            && self
+                .payload_prefix
+                .as_ref()
+                .map_or(true, |prefix| bytes.starts_with(prefix))
+            && self.signal_types.as_ref().map_or(true, |types| {
+                signal_type(bytes).map_or(false, |t| types.contains(&t))
+            })
+    }
+}
+
+/// Read the type of a signal from its payload, as described on
+/// [`SignalFilter::signal_types`].
+pub fn signal_type(bytes: &[u8]) -> Option<String> {
+    holochain_serialized_bytes::decode::<_, SignalType>(bytes)
+        .ok()
+        .and_then(|t| t.0)
+}
+
+/// Deserializes just enough of a signal payload to find its type.
+#[derive(Debug)]
+struct SignalType(Option<String>);
+
+impl<'de> serde::Deserialize<'de> for SignalType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SignalTypeVisitor { from_map: true })
+    }
+}
+
+/// Deserializes any value, keeping it only if it's a string.
+struct MaybeString(Option<String>);
+
+impl<'de> serde::Deserialize<'de> for MaybeString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(SignalTypeVisitor { from_map: false })
+            .map(|t| MaybeString(t.0))
+    }
+}
+
+struct SignalTypeVisitor {
+    /// Whether to look for the type inside a map
+    from_map: bool,
+}
+
+impl<'de> serde::de::Visitor<'de> for SignalTypeVisitor {
+    type Value = SignalType;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(SignalType(Some(v.to_string())))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use serde::de::IgnoredAny;
+        let mut tag = None;
+        let mut first_key = None;
+        let mut len = 0;
+        while let Some(MaybeString(key)) = map.next_key()? {
+            len += 1;
+            if self.from_map && tag.is_none() && key.as_deref() == Some("type") {
+                tag = map.next_value::<MaybeString>()?.0;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+            if len == 1 {
+                first_key = key;
+            }
+        }
+        if !self.from_map {
+            return Ok(SignalType(None));
+        }
+        Ok(SignalType(tag.or(if len == 1 { first_key } else { None })))
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+        Ok(SignalType(None))
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, _: &[u8]) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(SignalType(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_zome_types::ExternIO;
+
+    #[derive(Debug, serde::Serialize)]
+    enum Externally {
+        Created { id: u32 },
+        Deleted,
+    }
+
+    #[derive(Debug, serde::Serialize)]
+    #[serde(tag = "type")]
+    enum Internally {
+        Created { id: u32 },
+    }
+
+    #[derive(Debug, serde::Serialize)]
+    struct Untyped {
+        id: u32,
+        name: String,
+    }
+
+    fn signal<T: serde::Serialize + std::fmt::Debug>(t: T) -> AppSignal {
+        AppSignal::new(ExternIO::encode(t).unwrap())
+    }
+
+    #[test]
+    fn reads_signal_types() {
+        let ty = |s: AppSignal| signal_type(&s.as_inner().0);
+        assert_eq!(
+            ty(signal(Externally::Created { id: 1 })),
+            Some("Created".into())
+        );
+        assert_eq!(ty(signal(Externally::Deleted)), Some("Deleted".into()));
+        assert_eq!(
+            ty(signal(Internally::Created { id: 1 })),
+            Some("Created".into())
+        );
+        assert_eq!(
+            ty(signal(Untyped {
+                id: 1,
+                name: "x".into()
+            })),
+            None
+        );
+        assert_eq!(ty(signal(42)), None);
+        assert_eq!(ty(signal(vec![1, 2, 3])), None);
+    }
+
+    #[test]
+    fn filters_signals() {
+        let zome_a = ZomeName::from("a");
+        let zome_b = ZomeName::from("b");
+        let created = signal(Externally::Created { id: 1 });
+        let deleted = signal(Externally::Deleted);
+
+        assert!(SignalFilter::empty().matches(&zome_a, &created));
+
+        let filter = SignalFilter::empty()
+            .with_zome_name("a")
+            .with_signal_type("Created");
+        assert!(filter.matches(&zome_a, &created));
+        assert!(!filter.matches(&zome_b, &created));
+        assert!(!filter.matches(&zome_a, &deleted));
+
+        let prefix = created.as_inner().0[..2].to_vec();
+        let filter = SignalFilter::empty().with_payload_prefix(prefix);
+        assert!(filter.matches(&zome_b, &created));
+        assert!(!filter.matches(&zome_b, &signal(42)));
+    }
+
+    #[test]
+    fn filter_sets() {
+        let cell_1 = CellId::new(
+            holo_hash::DnaHash::from_raw_36(vec![1; 36]),
+            holo_hash::AgentPubKey::from_raw_36(vec![1; 36]),
+        );
+        let cell_2 = CellId::new(
+            holo_hash::DnaHash::from_raw_36(vec![2; 36]),
+            holo_hash::AgentPubKey::from_raw_36(vec![2; 36]),
+        );
+        let zome = ZomeName::from("a");
+        let s = signal(Externally::Deleted);
+        let only_a = SignalFilter::empty().with_zome_name("a");
+
+        assert!(SignalFilterSet::allow_all().allows(&cell_1, &zome, &s));
+        assert!(!SignalFilterSet::block_all().allows(&cell_1, &zome, &s));
+
+        let include = SignalFilterSet::Include([(cell_1.clone(), only_a.clone())].into());
+        assert!(include.allows(&cell_1, &zome, &s));
+        assert!(!include.allows(&cell_1, &"b".into(), &s));
+        assert!(!include.allows(&cell_2, &zome, &s));
+
+        let exclude = SignalFilterSet::Exclude([(cell_1.clone(), only_a)].into());
+        assert!(!exclude.allows(&cell_1, &zome, &s));
+        assert!(exclude.allows(&cell_1, &"b".into(), &s));
+        assert!(exclude.allows(&cell_2, &zome, &s));
+    }
+
+    #[test]
+    fn filters_roundtrip() {
+        let filter = SignalFilter::empty()
+            .with_zome_name("a")
+            .with_payload_prefix(vec![1, 2]);
+        let bytes = SerializedBytes::try_from(filter.clone()).unwrap();
+        assert_eq!(SignalFilter::try_from(bytes).unwrap(), filter);
     }
 }
@@ -2,7 +2,7 @@
 
 use holochain_serialized_bytes::prelude::*;
 
-use crate::{Create, CreateLink, Delete, Entry, Update};
+use crate::{Create, CreateLink, Delete, Entry, Update, ENTRY_SIZE_LIMIT};
 
 /// Input to the `weigh` callback. Includes an "unweighed" action, and Entry
 /// if applicable.
@@ -31,6 +31,53 @@ pub type RateBytes = u8;
 /// The amount that a bucket is "filled"
 pub type RateBucketCapacity = u32;
 
+/// The number of entry bytes which make up a single unit of [`RateBytes`].
+/// Chosen so that an entry at the [`ENTRY_SIZE_LIMIT`] just fits in a [`RateBytes`].
+pub const RATE_BYTES_UNIT: usize = ENTRY_SIZE_LIMIT / RateBytes::MAX as usize + 1;
+
+/// The [`RateBytes`] of an entry, i.e. its size in units of [`RATE_BYTES_UNIT`],
+/// rounded up. Only app entries have a size worth counting.
+pub fn rate_bytes(entry: &Entry) -> RateBytes {
+    match entry {
+        Entry::App(bytes) => {
+            let units = (bytes.bytes().len() + RATE_BYTES_UNIT - 1) / RATE_BYTES_UNIT;
+            units.min(RateBytes::MAX as usize) as RateBytes
+        }
+        _ => 0,
+    }
+}
+
+/// A limit on how quickly a single author may fill a bucket.
+///
+/// Every weighed action adds its units (and rate bytes, if it has an entry)
+/// to the bucket named by its weight, and the bucket drains at a constant rate.
+/// Actions which would fill a bucket beyond its capacity are rejected.
+/// Limits are declared by integrity zomes via the `rate_limits` callback.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    SerializedBytes,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RateLimit {
+    /// The bucket this limit applies to.
+    pub bucket_id: RateBucketId,
+    /// The most the bucket can hold before actions are rejected.
+    pub capacity: RateBucketCapacity,
+    /// How much drains from the bucket every second.
+    /// Must not be zero, and a full bucket must drain within a week,
+    /// otherwise the DNA is refused.
+    pub drain_per_second: RateBucketCapacity,
+}
+
 /// Combination of two rate limiting data types, for convenience
 #[derive(
     Debug,
@@ -51,6 +98,22 @@ pub struct RateWeight {
     pub units: RateUnits,
 }
 
+impl RateWeight {
+    /// Add the size of an entry to this weight.
+    pub fn with_entry(self, entry: &Entry) -> EntryRateWeight {
+        EntryRateWeight {
+            bucket_id: self.bucket_id,
+            units: self.units,
+            rate_bytes: rate_bytes(entry),
+        }
+    }
+
+    /// How much this weight fills its bucket.
+    pub fn cost(&self) -> RateBucketCapacity {
+        self.units as RateBucketCapacity
+    }
+}
+
 impl Default for RateWeight {
     fn default() -> Self {
         Self {
@@ -81,6 +144,13 @@ pub struct EntryRateWeight {
     pub rate_bytes: RateBytes,
 }
 
+impl EntryRateWeight {
+    /// How much this weight fills its bucket.
+    pub fn cost(&self) -> RateBucketCapacity {
+        self.units as RateBucketCapacity + self.rate_bytes as RateBucketCapacity
+    }
+}
+
 impl Default for EntryRateWeight {
     fn default() -> Self {
         Self {
@@ -10,6 +10,7 @@ pub use kitsune_p2p_types::dependencies::lair_keystore_api::LairResult;
 
 const TIME_CHECK_FREQ: std::time::Duration = std::time::Duration::from_secs(5);
 const CON_CHECK_STUB_TAG: &str = "HC_CON_CHK_STUB";
+const DATABASE_KEY_CONTEXT: &[u8] = b"holochain database encryption key";
 const RECON_INIT_MS: u64 = 100;
 const RECON_MAX_MS: u64 = 5000;
 
@@ -396,4 +397,74 @@ impl MetaLairClient {
             Ok((info.digest, info.cert.to_vec().into(), pk))
         }
     }
+
+    /// Get the key which databases are encrypted with, creating the seed it
+    /// is derived from in lair if needed. The key is the same every time it
+    /// is fetched for the same tag, but can't be derived without lair.
+    pub fn get_or_create_database_key(
+        &self,
+        tag: Arc<str>,
+    ) -> impl Future<Output = LairResult<[u8; 32]>> + 'static + Send {
+        let (client, esnd) = self.cli();
+        async move {
+            // don't echk! this top one, the seed won't exist the first time
+            let pub_key = match client.get_entry(tag.clone()).await {
+                Ok(LairEntryInfo::Seed { seed_info, .. }) => seed_info.ed25519_pub_key,
+                Ok(oth) => {
+                    return Err(format!("invalid entry type, expecting seed: {:?}", oth).into())
+                }
+                Err(_) => {
+                    let esnd = esnd.clone();
+                    echk!(esnd, client.new_seed(tag, None, false).await).ed25519_pub_key
+                }
+            };
+            // ed25519 signatures are deterministic, so the key is too
+            let sig = echk!(
+                esnd,
+                client
+                    .sign_by_pub_key(pub_key, None, DATABASE_KEY_CONTEXT.into())
+                    .await
+            );
+            let mut key = [0; 32];
+            key.copy_from_slice(&holo_hash::blake2b_256(&sig.0[..]));
+            Ok(key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn database_key_is_stable_and_private_to_keystore() {
+        let keystore = crate::spawn_mem_keystore().await.unwrap();
+        let key = keystore
+            .get_or_create_database_key("db".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            key,
+            keystore
+                .get_or_create_database_key("db".into())
+                .await
+                .unwrap()
+        );
+        assert_ne!(
+            key,
+            keystore
+                .get_or_create_database_key("other".into())
+                .await
+                .unwrap()
+        );
+
+        let other_keystore = crate::spawn_mem_keystore().await.unwrap();
+        assert_ne!(
+            key,
+            other_keystore
+                .get_or_create_database_key("db".into())
+                .await
+                .unwrap()
+        );
+    }
 }
@@ -96,6 +96,9 @@ pub trait HolochainP2pDnaT: Send + Sync {
         op: DhtOp,
     ) -> actor::HolochainP2pResult<()>;
 
+    /// Publish a warrant to the agent activity authorities of the warranted agent.
+    async fn publish_warrant(&self, warrant: SignedWarrant) -> actor::HolochainP2pResult<()>;
+
     /// Get an entry from the DHT.
     async fn get(
         &self,
@@ -117,6 +120,15 @@ pub trait HolochainP2pDnaT: Send + Sync {
         options: actor::GetLinksOptions,
     ) -> actor::HolochainP2pResult<Vec<WireLinkOps>>;
 
+    /// Count the live links on a base, from the DHT, asking also
+    /// whether the authority holds the given create link actions.
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>>;
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
@@ -296,6 +308,13 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Publish a warrant to the agent activity authorities of the warranted agent.
+    async fn publish_warrant(&self, warrant: SignedWarrant) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .publish_warrant((*self.dna_hash).clone(), warrant)
+            .await
+    }
+
     /// Get [`DhtOp::StoreRecord`] or [`DhtOp::StoreEntry`] from the DHT.
     async fn get(
         &self,
@@ -330,6 +349,24 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Count the live links on a base, from the DHT, asking also
+    /// whether the authority holds the given create link actions.
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>> {
+        self.sender
+            .count_links(
+                (*self.dna_hash).clone(),
+                link_key,
+                create_link_hashes,
+                options,
+            )
+            .await
+    }
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
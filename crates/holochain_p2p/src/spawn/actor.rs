@@ -20,8 +20,13 @@ use ghost_actor::dependencies::tracing_futures::Instrument;
 use holochain_zome_types::zome::FunctionName;
 use kitsune_p2p::actor::KitsuneP2pSender;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
 
 macro_rules! timing_trace {
     ($code:block $($rest:tt)*) => {{
@@ -135,12 +140,22 @@ impl WrapEvtSender {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<SerializedBytes>> + 'static + Send {
         timing_trace!(
             {
                 self.0.call_remote(
-                    dna_hash, from, signature, to_agent, zome_name, fn_name, cap_secret, payload,
-                    nonce, expires_at,
+                    dna_hash,
+                    from,
+                    signature,
+                    to_agent,
+                    zome_name,
+                    fn_name,
+                    cap_secret,
+                    payload,
+                    nonce,
+                    expires_at,
+                    trace_context,
                 )
             },
             "(hp2p:handle) call_remote",
@@ -153,22 +168,38 @@ impl WrapEvtSender {
         request_validation_receipt: bool,
         countersigning_session: bool,
         ops: Vec<holochain_types::dht_op::DhtOp>,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<()>> + 'static + Send {
         let op_count = ops.len();
         timing_trace!({
-            self.0.publish(dna_hash, request_validation_receipt, countersigning_session, ops)
+            self.0.publish(dna_hash, request_validation_receipt, countersigning_session, ops, trace_context)
         }, %op_count, "(hp2p:handle) publish")
     }
 
+    fn publish_warrant(
+        &self,
+        dna_hash: DnaHash,
+        warrant: SignedWarrant,
+    ) -> impl Future<Output = HolochainP2pResult<()>> + 'static + Send {
+        timing_trace!(
+            { self.0.publish_warrant(dna_hash, warrant) },
+            "(hp2p:handle) publish_warrant",
+        )
+    }
+
     fn get(
         &self,
         dna_hash: DnaHash,
         to_agent: AgentPubKey,
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetOptions,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<WireOps>> + 'static + Send {
         timing_trace!(
-            { self.0.get(dna_hash, to_agent, dht_hash, options) },
+            {
+                self.0
+                    .get(dna_hash, to_agent, dht_hash, options, trace_context)
+            },
             "(hp2p:handle) get",
         )
     }
@@ -179,9 +210,13 @@ impl WrapEvtSender {
         to_agent: AgentPubKey,
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetMetaOptions,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<MetadataSet>> + 'static + Send {
         timing_trace!(
-            { self.0.get_meta(dna_hash, to_agent, dht_hash, options) },
+            {
+                self.0
+                    .get_meta(dna_hash, to_agent, dht_hash, options, trace_context)
+            },
             "(hp2p:handle) get_meta",
         )
     }
@@ -192,13 +227,39 @@ impl WrapEvtSender {
         to_agent: AgentPubKey,
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<WireLinkOps>> + 'static + Send {
         timing_trace!(
-            { self.0.get_links(dna_hash, to_agent, link_key, options) },
+            {
+                self.0
+                    .get_links(dna_hash, to_agent, link_key, options, trace_context)
+            },
             "(hp2p:handle) get_links",
         )
     }
 
+    fn count_links(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        trace_context: Vec<u8>,
+    ) -> impl Future<Output = HolochainP2pResult<CountLinksResponse>> + 'static + Send {
+        timing_trace!(
+            {
+                self.0.count_links(
+                    dna_hash,
+                    to_agent,
+                    link_key,
+                    create_link_hashes,
+                    trace_context,
+                )
+            },
+            "(hp2p:handle) count_links",
+        )
+    }
+
     fn get_agent_activity(
         &self,
         dna_hash: DnaHash,
@@ -206,12 +267,13 @@ impl WrapEvtSender {
         agent: AgentPubKey,
         query: ChainQueryFilter,
         options: event::GetActivityOptions,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<AgentActivityResponse<ActionHash>>> + 'static + Send
     {
         timing_trace!(
             {
                 self.0
-                    .get_agent_activity(dna_hash, to_agent, agent, query, options)
+                    .get_agent_activity(dna_hash, to_agent, agent, query, options, trace_context)
             },
             "(hp2p:handle) get_agent_activity",
         )
@@ -223,12 +285,13 @@ impl WrapEvtSender {
         to_agent: AgentPubKey,
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
+        trace_context: Vec<u8>,
     ) -> impl Future<Output = HolochainP2pResult<MustGetAgentActivityResponse>> + 'static + Send
     {
         timing_trace!(
             {
                 self.0
-                    .must_get_agent_activity(dna_hash, to_agent, agent, filter)
+                    .must_get_agent_activity(dna_hash, to_agent, agent, filter, trace_context)
             },
             "(hp2p:handle) must_get_agent_activity",
         )
@@ -315,11 +378,52 @@ impl WrapEvtSender {
     }
 }
 
+/// How long the span context of a publish is kept for the
+/// authorities fetching its ops.
+const PUBLISH_TRACE_CONTEXT_TTL: Duration = Duration::from_secs(60);
+
+/// The span contexts of recent publishes by op hash. Published ops are
+/// fetched by the authorities after the publish, so this is how the
+/// trace of a publish reaches them.
+#[derive(Clone, Default)]
+struct PublishTraceContexts(
+    Arc<Mutex<HashMap<Arc<kitsune_p2p::KitsuneOpHash>, (Vec<u8>, Instant)>>>,
+);
+
+impl PublishTraceContexts {
+    fn insert(
+        &self,
+        op_hashes: impl Iterator<Item = Arc<kitsune_p2p::KitsuneOpHash>>,
+        trace_context: Vec<u8>,
+    ) {
+        if trace_context.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let mut contexts = self.0.lock().expect("publish trace contexts poisoned");
+        contexts.retain(|_, (_, at)| now.duration_since(*at) < PUBLISH_TRACE_CONTEXT_TTL);
+        for op_hash in op_hashes {
+            contexts.insert(op_hash, (trace_context.clone(), now));
+        }
+    }
+
+    fn get(&self, op_hash: &Arc<kitsune_p2p::KitsuneOpHash>) -> Vec<u8> {
+        self.0
+            .lock()
+            .expect("publish trace contexts poisoned")
+            .get(op_hash)
+            .filter(|(_, at)| at.elapsed() < PUBLISH_TRACE_CONTEXT_TTL)
+            .map(|(trace_context, _)| trace_context.clone())
+            .unwrap_or_default()
+    }
+}
+
 pub(crate) struct HolochainP2pActor {
     tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
     evt_sender: WrapEvtSender,
     kitsune_p2p: ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
     host: kitsune_p2p::HostApi,
+    publish_trace_contexts: PublishTraceContexts,
 }
 
 impl ghost_actor::GhostControlHandler for HolochainP2pActor {
@@ -355,6 +459,7 @@ impl HolochainP2pActor {
             evt_sender: WrapEvtSender(evt_sender),
             kitsune_p2p,
             host,
+            publish_trace_contexts: PublishTraceContexts::default(),
         })
     }
 
@@ -372,6 +477,7 @@ impl HolochainP2pActor {
         data: Vec<u8>,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
@@ -387,6 +493,7 @@ impl HolochainP2pActor {
                     ExternIO::from(data),
                     nonce,
                     expires_at,
+                    trace_context,
                 )
                 .await;
             res.map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -397,17 +504,23 @@ impl HolochainP2pActor {
     }
 
     /// receiving an incoming get request from a remote node
-    #[tracing::instrument(skip(self, dna_hash, to_agent, dht_hash, options), level = "trace")]
+    #[tracing::instrument(
+        skip(self, dna_hash, to_agent, dht_hash, options, trace_context),
+        level = "trace"
+    )]
     fn handle_incoming_get(
         &mut self,
         dna_hash: DnaHash,
         to_agent: AgentPubKey,
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetOptions,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
-            let res = evt_sender.get(dna_hash, to_agent, dht_hash, options).await;
+            let res = evt_sender
+                .get(dna_hash, to_agent, dht_hash, options, trace_context)
+                .await;
             res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
                 .map_err(kitsune_p2p::KitsuneP2pError::from)
                 .map(|res| UnsafeBytes::from(res).into())
@@ -424,11 +537,12 @@ impl HolochainP2pActor {
         to_agent: AgentPubKey,
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetMetaOptions,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             let res = evt_sender
-                .get_meta(dna_hash, to_agent, dht_hash, options)
+                .get_meta(dna_hash, to_agent, dht_hash, options, trace_context)
                 .await;
             res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
                 .map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -445,11 +559,40 @@ impl HolochainP2pActor {
         to_agent: AgentPubKey,
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
+        trace_context: Vec<u8>,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let res = evt_sender
+                .get_links(dna_hash, to_agent, link_key, options, trace_context)
+                .await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
+    /// receiving an incoming count_links request from a remote node
+    fn handle_incoming_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             let res = evt_sender
-                .get_links(dna_hash, to_agent, link_key, options)
+                .count_links(
+                    dna_hash,
+                    to_agent,
+                    link_key,
+                    create_link_hashes,
+                    trace_context,
+                )
                 .await;
             res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
                 .map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -467,11 +610,12 @@ impl HolochainP2pActor {
         agent: AgentPubKey,
         query: ChainQueryFilter,
         options: event::GetActivityOptions,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             let res = evt_sender
-                .get_agent_activity(dna_hash, to_agent, agent, query, options)
+                .get_agent_activity(dna_hash, to_agent, agent, query, options, trace_context)
                 .await;
             res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
                 .map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -488,11 +632,12 @@ impl HolochainP2pActor {
         to_agent: AgentPubKey,
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             let res = evt_sender
-                .must_get_agent_activity(dna_hash, to_agent, agent, filter)
+                .must_get_agent_activity(dna_hash, to_agent, agent, filter, trace_context)
                 .await;
             res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
                 .map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -509,6 +654,7 @@ impl HolochainP2pActor {
         request_validation_receipt: bool,
         countersigning_session: bool,
         ops: Vec<holochain_types::dht_op::DhtOp>,
+        trace_context: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
@@ -518,6 +664,7 @@ impl HolochainP2pActor {
                     request_validation_receipt,
                     countersigning_session,
                     ops,
+                    trace_context,
                 )
                 .await?;
             Ok(())
@@ -548,6 +695,21 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming warrant from a remote node
+    fn handle_incoming_publish_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        warrant: SignedWarrant,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            evt_sender.publish_warrant(dna_hash, warrant).await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_incoming_countersigning_session_negotiation(
         &mut self,
         dna_hash: DnaHash,
@@ -690,9 +852,19 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 to_agent,
                 nonce,
                 expires_at,
+                trace_context,
             } => self.handle_incoming_call_remote(
-                space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret, data,
-                *nonce, expires_at,
+                space,
+                from_agent,
+                signature,
+                to_agent,
+                zome_name,
+                fn_name,
+                cap_secret,
+                data,
+                *nonce,
+                expires_at,
+                trace_context,
             ),
             crate::wire::WireMessage::CallRemoteMulti {
                 zome_name,
@@ -703,35 +875,78 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 to_agents,
                 nonce,
                 expires_at,
+                trace_context,
             } => {
                 match to_agents
                     .into_iter()
                     .find(|(_signature, agent)| agent == &to_agent)
                 {
                     Some((signature, to_agent)) => self.handle_incoming_call_remote(
-                        space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret,
-                        data, *nonce, expires_at,
+                        space,
+                        from_agent,
+                        signature,
+                        to_agent,
+                        zome_name,
+                        fn_name,
+                        cap_secret,
+                        data,
+                        *nonce,
+                        expires_at,
+                        trace_context,
                     ),
                     None => Err(HolochainP2pError::RoutingAgentError(to_agent).into()),
                 }
             }
-            crate::wire::WireMessage::Get { dht_hash, options } => {
-                self.handle_incoming_get(space, to_agent, dht_hash, options)
-            }
-            crate::wire::WireMessage::GetMeta { dht_hash, options } => {
-                self.handle_incoming_get_meta(space, to_agent, dht_hash, options)
-            }
-            crate::wire::WireMessage::GetLinks { link_key, options } => {
-                self.handle_incoming_get_links(space, to_agent, link_key, options)
-            }
+            crate::wire::WireMessage::Get {
+                dht_hash,
+                options,
+                trace_context,
+            } => self.handle_incoming_get(space, to_agent, dht_hash, options, trace_context),
+            crate::wire::WireMessage::GetMeta {
+                dht_hash,
+                options,
+                trace_context,
+            } => self.handle_incoming_get_meta(space, to_agent, dht_hash, options, trace_context),
+            crate::wire::WireMessage::GetLinks {
+                link_key,
+                options,
+                trace_context,
+            } => self.handle_incoming_get_links(space, to_agent, link_key, options, trace_context),
+            crate::wire::WireMessage::CountLinks {
+                link_key,
+                create_link_hashes,
+                trace_context,
+            } => self.handle_incoming_count_links(
+                space,
+                to_agent,
+                link_key,
+                create_link_hashes,
+                trace_context,
+            ),
             crate::wire::WireMessage::GetAgentActivity {
                 agent,
                 query,
                 options,
-            } => self.handle_incoming_get_agent_activity(space, to_agent, agent, query, options),
-            crate::wire::WireMessage::MustGetAgentActivity { agent, filter } => {
-                self.handle_incoming_must_get_agent_activity(space, to_agent, agent, filter)
-            }
+                trace_context,
+            } => self.handle_incoming_get_agent_activity(
+                space,
+                to_agent,
+                agent,
+                query,
+                options,
+                trace_context,
+            ),
+            crate::wire::WireMessage::MustGetAgentActivity {
+                agent,
+                filter,
+                trace_context,
+            } => self.handle_incoming_must_get_agent_activity(
+                space,
+                to_agent,
+                agent,
+                filter,
+                trace_context,
+            ),
             crate::wire::WireMessage::ValidationReceipt { receipt } => {
                 self.handle_incoming_validation_receipt(space, to_agent, receipt)
             }
@@ -743,6 +958,12 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 )
                 .into())
             }
+            crate::wire::WireMessage::PublishWarrant { .. } => {
+                Err(HolochainP2pError::invalid_p2p_message(
+                    "invalid: warrants are broadcast, not requests".to_string(),
+                )
+                .into())
+            }
         }
     }
 
@@ -765,6 +986,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::Get { .. }
             | crate::wire::WireMessage::GetMeta { .. }
             | crate::wire::WireMessage::GetLinks { .. }
+            | crate::wire::WireMessage::CountLinks { .. }
             | crate::wire::WireMessage::GetAgentActivity { .. }
             | crate::wire::WireMessage::MustGetAgentActivity { .. }
             | crate::wire::WireMessage::ValidationReceipt { .. } => {
@@ -783,10 +1005,20 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 data,
                 nonce,
                 expires_at,
+                trace_context,
             } => {
                 let fut = self.handle_incoming_call_remote(
-                    space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret, data,
-                    *nonce, expires_at,
+                    space,
+                    from_agent,
+                    signature,
+                    to_agent,
+                    zome_name,
+                    fn_name,
+                    cap_secret,
+                    data,
+                    *nonce,
+                    expires_at,
+                    trace_context,
                 );
                 Ok(async move {
                     let _ = fut?.await?;
@@ -804,6 +1036,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 data,
                 nonce,
                 expires_at,
+                trace_context,
             } => {
                 match to_agents
                     .into_iter()
@@ -811,8 +1044,17 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 {
                     Some((signature, to_agent)) => {
                         let fut = self.handle_incoming_call_remote(
-                            space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret,
-                            data, *nonce, expires_at,
+                            space,
+                            from_agent,
+                            signature,
+                            to_agent,
+                            zome_name,
+                            fn_name,
+                            cap_secret,
+                            data,
+                            *nonce,
+                            expires_at,
+                            trace_context,
                         );
                         Ok(async move {
                             let _ = fut?.await?;
@@ -827,8 +1069,13 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::CountersigningSessionNegotiation { message } => {
                 self.handle_incoming_countersigning_session_negotiation(space, to_agent, message)
             }
-            crate::wire::WireMessage::PublishCountersign { flag, op } => {
-                self.handle_incoming_publish(space, false, flag, vec![op])
+            crate::wire::WireMessage::PublishCountersign {
+                flag,
+                op,
+                trace_context,
+            } => self.handle_incoming_publish(space, false, flag, vec![op], trace_context),
+            crate::wire::WireMessage::PublishWarrant { warrant } => {
+                self.handle_incoming_publish_warrant(space, warrant)
             }
         }
     }
@@ -841,25 +1088,45 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         context: Option<FetchContext>,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
         let space = DnaHash::from_kitsune(&space);
-        let ops = ops
-            .into_iter()
-            .map(|op_data| {
-                let op = crate::wire::WireDhtOpData::decode(op_data.0.clone())
-                    .map_err(HolochainP2pError::from)?
-                    .op_data;
-                Ok(op)
-            })
-            .collect::<Result<_, HolochainP2pError>>()?;
-        if let Some(context) = context {
-            self.handle_incoming_publish(
-                space,
+        // Ops fetched together may have been sent by different publishes,
+        // so each publish is handled with its own span context.
+        let mut publishes: Vec<(Vec<u8>, Vec<DhtOp>)> = Vec::new();
+        for op_data in ops {
+            let crate::wire::WireDhtOpData {
+                op_data,
+                trace_context,
+            } = crate::wire::WireDhtOpData::decode(op_data.0.clone())
+                .map_err(HolochainP2pError::from)?;
+            match publishes.iter_mut().find(|(t, _)| *t == trace_context) {
+                Some((_, ops)) => ops.push(op_data),
+                None => publishes.push((trace_context, vec![op_data])),
+            }
+        }
+        let (request_validation_receipt, countersigning_session) = match context {
+            Some(context) => (
                 context.has_request_validation_receipt(),
                 context.has_countersigning_session(),
-                ops,
-            )
-        } else {
-            self.handle_incoming_publish(space, false, false, ops)
+            ),
+            None => (false, false),
+        };
+        let publishes = publishes
+            .into_iter()
+            .map(|(trace_context, ops)| {
+                self.handle_incoming_publish(
+                    space.clone(),
+                    request_validation_receipt,
+                    countersigning_session,
+                    ops,
+                    trace_context,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(async move {
+            futures::future::try_join_all(publishes).await?;
+            Ok(())
         }
+        .boxed()
+        .into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
@@ -901,15 +1168,21 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         let query = FetchOpDataQuery::from_kitsune(query);
 
         let evt_sender = self.evt_sender.clone();
+        let publish_trace_contexts = self.publish_trace_contexts.clone();
         Ok(async move {
             let mut out = vec![];
             for (op_hash, dht_op) in evt_sender.fetch_op_data(space.clone(), query).await? {
+                let op_hash = op_hash.into_kitsune();
+                let trace_context = publish_trace_contexts.get(&op_hash);
                 out.push((
-                    op_hash.into_kitsune(),
+                    op_hash,
                     KitsuneOpData::new(
-                        crate::wire::WireDhtOpData { op_data: dht_op }
-                            .encode()
-                            .map_err(kitsune_p2p::KitsuneP2pError::other)?,
+                        crate::wire::WireDhtOpData {
+                            op_data: dht_op,
+                            trace_context,
+                        }
+                        .encode()
+                        .map_err(kitsune_p2p::KitsuneP2pError::other)?,
                     ),
                 ));
             }
@@ -995,14 +1268,15 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let space = dna_hash.into_kitsune();
         let to_agent_kitsune = to_agent.clone().into_kitsune();
 
-        let req = crate::wire::WireMessage::call_remote(
-            zome_name, fn_name, from_agent, signature, to_agent, cap_secret, payload, nonce,
-            expires_at,
-        )
-        .encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
+        // The request is built in the returned future, which runs in the
+        // caller's span, so that the trace context of the caller is sent.
         Ok(async move {
+            let req = crate::wire::WireMessage::call_remote(
+                zome_name, fn_name, from_agent, signature, to_agent, cap_secret, payload, nonce,
+                expires_at,
+            )
+            .encode()?;
             let result: Vec<u8> = kitsune_p2p
                 .rpc_single(space, to_agent_kitsune, req, None)
                 .await?;
@@ -1032,22 +1306,21 @@ impl HolochainP2pHandler for HolochainP2pActor {
             .map(|(_signature, agent)| agent.clone().into_kitsune())
             .collect();
 
-        let req = crate::wire::WireMessage::call_remote_multi(
-            zome_name,
-            fn_name,
-            from_agent,
-            to_agent_list,
-            cap,
-            payload,
-            nonce,
-            expires_at,
-        )
-        .encode()?;
-
         let timeout = self.tuning_params.implicit_timeout();
 
         let kitsune_p2p = self.kitsune_p2p.clone();
         Ok(async move {
+            let req = crate::wire::WireMessage::call_remote_multi(
+                zome_name,
+                fn_name,
+                from_agent,
+                to_agent_list,
+                cap,
+                payload,
+                nonce,
+                expires_at,
+            )
+            .encode()?;
             kitsune_p2p
                 .targeted_broadcast(space, to_agents, timeout, req, true)
                 .await?;
@@ -1083,6 +1356,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
             .with_request_validation_receipt(request_validation_receipt)
             .with_countersigning_session(countersigning_session);
 
+        let trace_context = crate::wire::current_trace_context();
+        self.publish_trace_contexts
+            .insert(op_hash_list.iter().map(|x| x.data()), trace_context.clone());
+
         let kitsune_p2p = self.kitsune_p2p.clone();
         let host = self.host.clone();
         let evt_sender = self.evt_sender.clone();
@@ -1094,6 +1371,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
                         request_validation_receipt,
                         countersigning_session,
                         reflect_ops,
+                        trace_context,
                     )
                     .await;
             }
@@ -1154,6 +1432,30 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_publish_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        warrant: SignedWarrant,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        // Warrants are held by the agent activity authorities of the warrantee.
+        let basis = holo_hash::OpBasis::from(warrant.warrant().warrantee().clone()).to_kitsune();
+        let timeout = self.tuning_params.implicit_timeout();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            let payload = crate::wire::WireMessage::publish_warrant(warrant).encode()?;
+
+            kitsune_p2p
+                .broadcast(space, basis, timeout, BroadcastData::User(payload))
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self, dna_hash, dht_hash, options), level = "trace")]
     fn handle_get(
         &mut self,
@@ -1165,11 +1467,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let basis = dht_hash.to_kitsune();
         let r_options: event::GetOptions = (&options).into();
 
-        let payload = crate::wire::WireMessage::get(dht_hash, r_options).encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
+            let payload = crate::wire::WireMessage::get(dht_hash, r_options).encode()?;
             let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
             let result = kitsune_p2p
                 .rpc_multi(input)
@@ -1199,12 +1500,18 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let basis = dht_hash.to_kitsune();
         let r_options: event::GetMetaOptions = (&options).into();
 
-        let payload = crate::wire::WireMessage::get_meta(dht_hash, r_options).encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
-            let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            let payload = crate::wire::WireMessage::get_meta(dht_hash, r_options).encode()?;
+            let mut input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            if let Some(remote_agent_count) = options.remote_agent_count {
+                input.max_remote_agent_count = remote_agent_count;
+            }
+            if let Some(timeout_ms) = options.timeout_ms {
+                input.max_timeout = kitsune_p2p_types::KitsuneTimeout::from_millis(timeout_ms);
+            }
             let result = kitsune_p2p.rpc_multi(input).await?;
 
             let mut out = Vec::new();
@@ -1230,11 +1537,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let basis = link_key.base.to_kitsune();
         let r_options: event::GetLinksOptions = (&options).into();
 
-        let payload = crate::wire::WireMessage::get_links(link_key, r_options).encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
+            let payload = crate::wire::WireMessage::get_links(link_key, r_options).encode()?;
             let mut input =
                 kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
             // NOTE - We're just targeting a single remote node for now
@@ -1255,6 +1561,40 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        _options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<CountLinksResponse>> {
+        let space = dna_hash.into_kitsune();
+        let basis = link_key.base.to_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.clone();
+        Ok(async move {
+            let payload =
+                crate::wire::WireMessage::count_links(link_key, create_link_hashes).encode()?;
+            let mut input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            // NOTE - Like get_links, just ask a single authority for now.
+            input.max_remote_agent_count = 1;
+            let result = kitsune_p2p.rpc_multi(input).await?;
+
+            let mut out = Vec::new();
+            for item in result {
+                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
+                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
+            }
+
+            Ok(out)
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_get_agent_activity(
         &mut self,
@@ -1270,12 +1610,11 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let basis = agent_hash.to_kitsune();
         let r_options: event::GetActivityOptions = (&options).into();
 
-        let payload =
-            crate::wire::WireMessage::get_agent_activity(agent, query, r_options).encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
+            let payload =
+                crate::wire::WireMessage::get_agent_activity(agent, query, r_options).encode()?;
             let mut input =
                 kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
             // TODO - We're just targeting a single remote node for now
@@ -1309,11 +1648,11 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let agent_hash: AnyDhtHash = agent.clone().into();
         let basis = agent_hash.to_kitsune();
 
-        let payload = crate::wire::WireMessage::must_get_agent_activity(agent, filter).encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
+            let payload =
+                crate::wire::WireMessage::must_get_agent_activity(agent, filter).encode()?;
             let mut input =
                 kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
             // TODO - We're just targeting a single remote node for now
@@ -86,6 +86,14 @@ impl HolochainP2pHandler for StubNetwork {
         Err("stub".into())
     }
 
+    fn handle_publish_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        warrant: SignedWarrant,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+
     fn handle_get(
         &mut self,
         dna_hash: DnaHash,
@@ -113,6 +121,16 @@ impl HolochainP2pHandler for StubNetwork {
         Err("stub".into())
     }
 
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        create_link_hashes: Vec<ActionHash>,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<CountLinksResponse>> {
+        Err("stub".into())
+    }
+
     fn handle_get_agent_activity(
         &mut self,
         dna_hash: DnaHash,
@@ -644,11 +662,7 @@ mod tests {
             b"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_vec(),
             holo_hash::hash_type::Entry,
         );
-        let link_key = WireLinkKey {
-            base: hash.into(),
-            type_query: LinkTypeFilter::single_dep(0.into()),
-            tag: None,
-        };
+        let link_key = WireLinkKey::new(hash.into(), LinkTypeFilter::single_dep(0.into()), None);
 
         let res = p2p
             .get_links(dna, link_key, crate::actor::GetLinksOptions::default())
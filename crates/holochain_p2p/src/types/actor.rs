@@ -185,6 +185,19 @@ impl Default for GetMetaOptions {
     }
 }
 
+impl From<holochain_zome_types::entry::GetOptions> for GetMetaOptions {
+    fn from(options: holochain_zome_types::entry::GetOptions) -> Self {
+        Self {
+            // Only the latest metadata is worth waiting for every authority.
+            as_race: !matches!(
+                options.strategy,
+                holochain_zome_types::entry::GetStrategy::Latest
+            ),
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 /// Get links from the DHT.
 /// Fields tagged with ```[Network]``` are network-level controls.
@@ -302,6 +315,12 @@ ghost_actor::ghost_chan! {
             op: DhtOp,
         ) -> ();
 
+        /// Publish a warrant to the agent activity authorities of the warranted agent.
+        fn publish_warrant(
+            dna_hash: DnaHash,
+            warrant: SignedWarrant,
+        ) -> ();
+
         /// Get an entry from the DHT.
         fn get(
             dna_hash: DnaHash,
@@ -323,6 +342,15 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> Vec<WireLinkOps>;
 
+        /// Count the live links on a base, from the DHT, asking also
+        /// whether the authority holds the given create link actions.
+        fn count_links(
+            dna_hash: DnaHash,
+            link_key: WireLinkKey,
+            create_link_hashes: Vec<ActionHash>,
+            options: GetLinksOptions,
+        ) -> Vec<CountLinksResponse>;
+
         /// Get agent activity from the DHT.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -185,6 +185,8 @@ ghost_actor::ghost_chan! {
             payload: ExternIO,
             nonce: Nonce256Bits,
             expires_at: Timestamp,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
         ) -> SerializedBytes;
 
         /// A remote node is publishing data in a range we claim to be holding.
@@ -193,6 +195,15 @@ ghost_actor::ghost_chan! {
             request_validation_receipt: bool,
             countersigning_session: bool,
             ops: Vec<holochain_types::dht_op::DhtOp>,
+            // The span context of the remote node, if it was sent.
+            trace_context: Vec<u8>,
+        ) -> ();
+
+        /// A remote node is publishing a warrant against an agent whose
+        /// activity we may be an authority for.
+        fn publish_warrant(
+            dna_hash: DnaHash,
+            warrant: SignedWarrant,
         ) -> ();
 
         /// A remote node is requesting entry data from us.
@@ -201,6 +212,8 @@ ghost_actor::ghost_chan! {
             to_agent: AgentPubKey,
             dht_hash: holo_hash::AnyDhtHash,
             options: GetOptions,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
         ) -> WireOps;
 
         /// A remote node is requesting metadata from us.
@@ -209,6 +222,8 @@ ghost_actor::ghost_chan! {
             to_agent: AgentPubKey,
             dht_hash: holo_hash::AnyDhtHash,
             options: GetMetaOptions,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
         ) -> MetadataSet;
 
         /// A remote node is requesting link data from us.
@@ -217,8 +232,20 @@ ghost_actor::ghost_chan! {
             to_agent: AgentPubKey,
             link_key: WireLinkKey,
             options: GetLinksOptions,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
         ) -> WireLinkOps;
 
+        /// A remote node is asking us how many live links we hold on a base.
+        fn count_links(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            link_key: WireLinkKey,
+            create_link_hashes: Vec<ActionHash>,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
+        ) -> CountLinksResponse;
+
         /// A remote node is requesting agent activity from us.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -226,6 +253,8 @@ ghost_actor::ghost_chan! {
             agent: AgentPubKey,
             query: ChainQueryFilter,
             options: GetActivityOptions,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
         ) -> AgentActivityResponse<ActionHash>;
 
         /// A remote node is requesting agent activity from us.
@@ -234,6 +263,8 @@ ghost_actor::ghost_chan! {
             to_agent: AgentPubKey,
             author: AgentPubKey,
             filter: holochain_zome_types::chain::ChainFilter,
+            // The span context of the remote node.
+            trace_context: Vec<u8>,
         ) -> MustGetAgentActivityResponse;
 
         /// A remote node has sent us a validation receipt.
@@ -288,6 +319,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::Get { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetMeta { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
+            HolochainP2pEvent::CountLinks { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::MustGetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
@@ -303,6 +335,7 @@ impl HolochainP2pEvent {
     pub fn dna_hash(&self) -> &DnaHash {
         match_p2p_evt!(self => |dna_hash| { dna_hash }, {
             HolochainP2pEvent::Publish { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::PublishWarrant { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::FetchOpData { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryOpHashes { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryAgentInfoSigned { dna_hash, .. } => { dna_hash }
@@ -317,6 +350,7 @@ impl HolochainP2pEvent {
     pub fn target_agents(&self) -> &AgentPubKey {
         match_p2p_evt!(self => |to_agent| { to_agent }, {
             HolochainP2pEvent::Publish { .. } => { unimplemented!("There is no single agent target for Publish") }
+            HolochainP2pEvent::PublishWarrant { .. } => { unimplemented!("There is no single agent target for PublishWarrant") }
             HolochainP2pEvent::FetchOpData { .. } => { unimplemented!("There is no single agent target for FetchOpData") }
             HolochainP2pEvent::QueryOpHashes { .. } => { unimplemented!("There is no single agent target for QueryOpHashes") }
             HolochainP2pEvent::QueryAgentInfoSigned { .. } => { unimplemented!("There is no single agent target for QueryAgentInfoSigned") },
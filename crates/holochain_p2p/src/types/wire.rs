@@ -6,6 +6,10 @@ use holochain_zome_types::zome::FunctionName;
 pub struct WireDhtOpData {
     /// The dht op.
     pub op_data: holochain_types::dht_op::DhtOp,
+    /// The span context of the publish which sent this op,
+    /// if it is being fetched because of a recent publish.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace_context: Vec<u8>,
 }
 
 impl WireDhtOpData {
@@ -36,6 +40,9 @@ pub enum WireMessage {
         data: Vec<u8>,
         nonce: Box<Nonce256Bits>,
         expires_at: Timestamp,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     CallRemoteMulti {
         zome_name: ZomeName,
@@ -47,6 +54,9 @@ pub enum WireMessage {
         data: Vec<u8>,
         nonce: Box<Nonce256Bits>,
         expires_at: Timestamp,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     ValidationReceipt {
         #[serde(with = "serde_bytes")]
@@ -55,23 +65,48 @@ pub enum WireMessage {
     Get {
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetOptions,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     GetMeta {
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetMetaOptions,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     GetLinks {
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
+    },
+    CountLinks {
+        link_key: WireLinkKey,
+        /// The sender's own links which it hasn't published yet,
+        /// to ask whether the authority already holds them.
+        #[serde(default)]
+        create_link_hashes: Vec<ActionHash>,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     GetAgentActivity {
         agent: AgentPubKey,
         query: ChainQueryFilter,
         options: event::GetActivityOptions,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     MustGetAgentActivity {
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
     },
     CountersigningSessionNegotiation {
         message: event::CountersigningSessionNegotiationMessage,
@@ -79,6 +114,12 @@ pub enum WireMessage {
     PublishCountersign {
         flag: bool,
         op: DhtOp,
+        /// The span context of the sender, so the receiver can continue its trace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_bytes")]
+        trace_context: Vec<u8>,
+    },
+    PublishWarrant {
+        warrant: SignedWarrant,
     },
 }
 
@@ -93,7 +134,15 @@ impl WireMessage {
     }
 
     pub fn publish_countersign(flag: bool, op: DhtOp) -> WireMessage {
-        Self::PublishCountersign { flag, op }
+        Self::PublishCountersign {
+            flag,
+            op,
+            trace_context: current_trace_context(),
+        }
+    }
+
+    pub fn publish_warrant(warrant: SignedWarrant) -> WireMessage {
+        Self::PublishWarrant { warrant }
     }
 
     /// For an outgoing remote call.
@@ -119,6 +168,7 @@ impl WireMessage {
             data: payload.into_vec(),
             nonce: Box::new(nonce),
             expires_at,
+            trace_context: current_trace_context(),
         }
     }
 
@@ -142,6 +192,7 @@ impl WireMessage {
             data: payload.into_vec(),
             nonce: Box::new(nonce),
             expires_at,
+            trace_context: current_trace_context(),
         }
     }
 
@@ -152,18 +203,38 @@ impl WireMessage {
     }
 
     pub fn get(dht_hash: holo_hash::AnyDhtHash, options: event::GetOptions) -> WireMessage {
-        Self::Get { dht_hash, options }
+        Self::Get {
+            dht_hash,
+            options,
+            trace_context: current_trace_context(),
+        }
     }
 
     pub fn get_meta(
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetMetaOptions,
     ) -> WireMessage {
-        Self::GetMeta { dht_hash, options }
+        Self::GetMeta {
+            dht_hash,
+            options,
+            trace_context: current_trace_context(),
+        }
     }
 
     pub fn get_links(link_key: WireLinkKey, options: event::GetLinksOptions) -> WireMessage {
-        Self::GetLinks { link_key, options }
+        Self::GetLinks {
+            link_key,
+            options,
+            trace_context: current_trace_context(),
+        }
+    }
+
+    pub fn count_links(link_key: WireLinkKey, create_link_hashes: Vec<ActionHash>) -> WireMessage {
+        Self::CountLinks {
+            link_key,
+            create_link_hashes,
+            trace_context: current_trace_context(),
+        }
     }
 
     pub fn get_agent_activity(
@@ -175,6 +246,7 @@ impl WireMessage {
             agent,
             query,
             options,
+            trace_context: current_trace_context(),
         }
     }
 
@@ -182,7 +254,11 @@ impl WireMessage {
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
     ) -> WireMessage {
-        Self::MustGetAgentActivity { agent, filter }
+        Self::MustGetAgentActivity {
+            agent,
+            filter,
+            trace_context: current_trace_context(),
+        }
     }
 
     pub fn countersigning_session_negotiation(
@@ -191,3 +267,10 @@ impl WireMessage {
         Self::CountersigningSessionNegotiation { message }
     }
 }
+
+/// The context of the current span as bytes, so the remote node
+/// can continue the trace when handling a request.
+pub(crate) fn current_trace_context() -> Vec<u8> {
+    use holochain_trace::OpenSpanExt;
+    holochain_trace::tracing::Span::get_current_bytes()
+}
@@ -106,13 +106,17 @@ impl Databases {
 pub(crate) fn new_connection_pool(
     path: Option<&Path>,
     synchronous_level: DbSyncLevel,
+    key: Option<DbKey>,
 ) -> ConnectionPool {
     use r2d2_sqlite::SqliteConnectionManager;
     let manager = match path {
         Some(path) => SqliteConnectionManager::file(path),
         None => SqliteConnectionManager::memory(),
     };
-    let customizer = Box::new(ConnCustomizer { synchronous_level });
+    let customizer = Box::new(ConnCustomizer {
+        synchronous_level,
+        key,
+    });
     // We need the same amount of connections as reader threads plus one for the writer thread.
     let max_cons = num_read_threads() + 1;
     r2d2::Pool::builder()
@@ -131,6 +135,7 @@ pub(crate) fn new_connection_pool(
 #[derive(Debug)]
 struct ConnCustomizer {
     synchronous_level: DbSyncLevel,
+    key: Option<DbKey>,
 }
 
 /// The sqlite synchronous level.
@@ -176,7 +181,7 @@ impl Default for DbSyncStrategy {
 
 impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnCustomizer {
     fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
-        initialize_connection(conn, self.synchronous_level)?;
+        initialize_connection(conn, self.synchronous_level, self.key.as_ref())?;
         Ok(())
     }
 }
@@ -184,25 +189,19 @@ impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnCustomizer {
 pub(crate) fn initialize_connection(
     conn: &mut Connection,
     synchronous_level: DbSyncLevel,
+    key: Option<&DbKey>,
 ) -> rusqlite::Result<()> {
     // Tell SQLite to wait this long during write contention.
     conn.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
 
+    // The key must be set before anything else reads the database.
+    // Without the `sqlite-encrypted` feature databases are never encrypted.
     #[cfg(feature = "sqlite-encrypted")]
-    {
-        use std::io::Write;
-        let key = get_encryption_key_shim();
-        let mut hex = *br#"0000000000000000000000000000000000000000000000000000000000000000"#;
-        let mut c = std::io::Cursor::new(&mut hex[..]);
-        for b in &key {
-            write!(c, "{:02X}", b)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-        }
-        let _keyval = std::str::from_utf8(&hex).unwrap();
-        const FAKE_KEY: &str = "x'98483C6EB40B6C31A448C22A66DED3B5E5E8D5119CAC8327B655C8B5C483648101010101010101010101010101010101'";
-        // conn.pragma_update(None, "key", &keyval)?;
-        conn.pragma_update(None, "key", &FAKE_KEY)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key.pragma_value())?;
     }
+    #[cfg(not(feature = "sqlite-encrypted"))]
+    let _ = key;
 
     // this is recommended to always be off:
     // https://sqlite.org/pragma.html#pragma_trusted_schema
@@ -222,15 +221,6 @@ pub(crate) fn initialize_connection(
     Ok(())
 }
 
-#[cfg(feature = "sqlite-encrypted")]
-/// Simulate getting an encryption key from Lair.
-fn get_encryption_key_shim() -> [u8; 32] {
-    [
-        26, 111, 7, 31, 52, 204, 156, 103, 203, 171, 156, 89, 98, 51, 158, 143, 57, 134, 93, 56,
-        199, 225, 53, 141, 39, 77, 145, 130, 136, 108, 96, 201,
-    ]
-}
-
 /// Singleton Connection
 #[derive(shrinkwraprs::Shrinkwrap)]
 #[shrinkwrap(mutable, unsafe_ignore_visibility)]
@@ -2,6 +2,7 @@
 
 use crate::{
     conn::{new_connection_pool, ConnectionPool, DbSyncLevel, PConn, DATABASE_HANDLES},
+    metrics::{observe_permit_wait, DbAccess},
     prelude::*,
 };
 use derive_more::Into;
@@ -191,11 +192,15 @@ impl<Kind: DbKindT> DbRead<Kind> {
     }
 
     async fn acquire_reader_permit(&self) -> OwnedSemaphorePermit {
-        self.read_semaphore
+        let start = std::time::Instant::now();
+        let permit = self
+            .read_semaphore
             .clone()
             .acquire_owned()
             .await
-            .expect("We don't ever close these semaphores")
+            .expect("We don't ever close these semaphores");
+        observe_permit_wait(&self.kind.kind(), DbAccess::Read, start.elapsed());
+        permit
     }
 }
 
@@ -208,7 +213,7 @@ pub struct DbWrite<Kind: DbKindT>(DbRead<Kind>);
 impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     /// Create or open an existing database reference,
     pub fn open(path_prefix: &Path, kind: Kind) -> DatabaseResult<Self> {
-        Self::open_with_sync_level(path_prefix, kind, DbSyncLevel::default())
+        Self::open_with_sync_level(path_prefix, kind, DbSyncLevel::default(), None)
     }
 
     pub async fn conn_write_permit(&self) -> PConnPermit {
@@ -216,13 +221,16 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         PConnPermit(g)
     }
 
+    /// Create or open an existing database reference.
+    /// If a key is given the database is encrypted with a key derived from it.
     pub fn open_with_sync_level(
         path_prefix: &Path,
         kind: Kind,
         sync_level: DbSyncLevel,
+        key: Option<&DbKey>,
     ) -> DatabaseResult<Self> {
         DATABASE_HANDLES.get_or_insert(&kind, path_prefix, |kind| {
-            Self::new(Some(path_prefix), kind, sync_level)
+            Self::new(Some(path_prefix), kind, sync_level, key.cloned())
         })
     }
 
@@ -230,7 +238,12 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         path_prefix: Option<&Path>,
         kind: Kind,
         sync_level: DbSyncLevel,
+        key: Option<DbKey>,
     ) -> DatabaseResult<Self> {
+        // Each database has its own key. In-memory databases are never encrypted.
+        let key = key
+            .filter(|_| path_prefix.is_some())
+            .map(|key| key.derive(&kind.filename()));
         let path = match path_prefix {
             Some(path_prefix) => {
                 let path = path_prefix.join(kind.filename());
@@ -241,12 +254,18 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                     std::fs::create_dir_all(parent)
                         .map_err(|_e| DatabaseError::DatabaseMissing(parent.to_owned()))?;
                 }
+                // A database which the key doesn't open isn't corrupt,
+                // so it must not be wiped.
+                #[cfg(feature = "sqlite-encrypted")]
+                if let Some(key) = &key {
+                    crate::encryption::check_database_key(&path, key)?;
+                }
                 // Check if the database is valid and take the appropriate
                 // action if it isn't.
                 match Connection::open(&path)
                     // For some reason calling pragma_update is necessary to prove the database file is valid.
                     .and_then(|mut c| {
-                        crate::conn::initialize_connection(&mut c, sync_level)?;
+                        crate::conn::initialize_connection(&mut c, sync_level, key.as_ref())?;
                         c.pragma_update(None, "synchronous", "0".to_string())
                     }) {
                     Ok(_) => (),
@@ -283,11 +302,11 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         };
 
         // Now we know the database file is valid we can open a connection pool.
-        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level);
+        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level, key);
         let mut conn = pool.get()?;
         // set to faster write-ahead-log mode
         conn.pragma_update(None, "journal_mode", "WAL".to_string())?;
-        crate::table::initialize_database(&mut conn, kind.kind())?;
+        crate::table::initialize_database(&mut conn, kind.kind(), path.as_deref())?;
 
         Ok(DbWrite(DbRead {
             write_semaphore: Self::get_write_semaphore(kind.kind()),
@@ -322,12 +341,12 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     /// connection pool, useful for testing.
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test(path: &Path, kind: Kind) -> DatabaseResult<Self> {
-        Self::new(Some(path), kind, DbSyncLevel::default())
+        Self::new(Some(path), kind, DbSyncLevel::default(), None)
     }
 
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test_in_mem(kind: Kind) -> DatabaseResult<Self> {
-        Self::new(None, kind, DbSyncLevel::default())
+        Self::new(None, kind, DbSyncLevel::default(), None)
     }
 
     pub async fn async_commit<E, R, F>(&self, f: F) -> Result<R, E>
@@ -367,12 +386,16 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     }
 
     async fn acquire_writer_permit(&self) -> OwnedSemaphorePermit {
-        self.0
+        let start = std::time::Instant::now();
+        let permit = self
+            .0
             .write_semaphore
             .clone()
             .acquire_owned()
             .await
-            .expect("We don't ever close these semaphores")
+            .expect("We don't ever close these semaphores");
+        observe_permit_wait(&self.kind.kind(), DbAccess::Write, start.elapsed());
+        permit
     }
 }
 
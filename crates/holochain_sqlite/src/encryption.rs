@@ -0,0 +1,430 @@
+//! At-rest encryption of databases.
+//!
+//! With the `sqlite-encrypted` feature, SQLCipher encrypts each database with
+//! its own key. Every key is derived from a single [`DbKey`] which the
+//! conductor gets from its keystore, so no key is ever written to disk.
+//!
+//! Databases which were created before encryption was enabled, or which were
+//! encrypted with the fixed key of older conductors, can be encrypted in place
+//! with [`encrypt_databases_in_dir`], while no conductor is using them.
+
+use crate::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// The length in bytes of a database key.
+pub const DB_KEY_LEN: usize = 32;
+
+/// The first bytes of every plaintext SQLite database file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// The fixed key which older conductors encrypted every database with,
+/// in SQLCipher's raw key syntax.
+#[cfg(feature = "sqlite-encrypted")]
+const LEGACY_KEY: &str = "x'98483C6EB40B6C31A448C22A66DED3B5E5E8D5119CAC8327B655C8B5C483648101010101010101010101010101010101'";
+
+/// A key which databases are encrypted with.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DbKey([u8; DB_KEY_LEN]);
+
+impl DbKey {
+    /// Use these bytes as a key.
+    pub fn from_bytes(bytes: [u8; DB_KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Derive the key of the database at `db_file`, which is relative to the
+    /// database root directory, as given by [`DbKindT::filename`].
+    pub fn derive(&self, db_file: &Path) -> Self {
+        let mut data = self.0.to_vec();
+        for component in db_file.components() {
+            data.push(b'/');
+            data.extend_from_slice(component.as_os_str().to_string_lossy().as_bytes());
+        }
+        let mut key = [0; DB_KEY_LEN];
+        key.copy_from_slice(&holo_hash::encode::blake2b_256(&data));
+        Self(key)
+    }
+
+    /// The value of `PRAGMA key` for this key, in SQLCipher's raw key syntax,
+    /// which skips SQLCipher's own key derivation.
+    pub(crate) fn pragma_value(&self) -> String {
+        let hex: String = self.0.iter().map(|b| format!("{:02X}", b)).collect();
+        format!("x'{}'", hex)
+    }
+}
+
+impl std::fmt::Debug for DbKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DbKey(<redacted>)")
+    }
+}
+
+/// Whether the file at `path` is a plaintext SQLite database.
+/// Encrypted databases are indistinguishable from random data.
+pub fn is_plaintext_database(path: &Path) -> DatabaseResult<bool> {
+    use std::io::Read;
+    let mut header = [0; SQLITE_HEADER.len()];
+    let mut file = std::fs::File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == SQLITE_HEADER),
+        // An empty file is a new database which SQLite hasn't written to yet
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether the database at `path` can be read after setting `PRAGMA key`
+/// to `key_pragma`.
+#[cfg(feature = "sqlite-encrypted")]
+fn key_opens_database(path: &Path, key_pragma: &str) -> bool {
+    rusqlite::Connection::open(path)
+        .and_then(|conn| {
+            conn.pragma_update(None, "key", key_pragma)?;
+            conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        })
+        .is_ok()
+}
+
+/// Check that the existing database at `path`, if there is one, can be opened
+/// with `key`. A plaintext database or one encrypted with another key would
+/// otherwise look corrupt, and might be wiped.
+#[cfg(feature = "sqlite-encrypted")]
+pub(crate) fn check_database_key(path: &Path, key: &DbKey) -> DatabaseResult<()> {
+    if !path.is_file() || std::fs::metadata(path)?.len() == 0 {
+        return Ok(());
+    }
+    if is_plaintext_database(path)? {
+        return Err(DatabaseError::DatabaseNotEncrypted(path.to_owned()));
+    }
+    if !key_opens_database(path, &key.pragma_value()) {
+        return Err(DatabaseError::DatabaseKeyMismatch(path.to_owned()));
+    }
+    Ok(())
+}
+
+/// Whether the database at `path` is encrypted with the fixed key of older
+/// conductors.
+#[cfg(feature = "sqlite-encrypted")]
+pub fn is_legacy_encrypted_database(path: &Path) -> bool {
+    key_opens_database(path, LEGACY_KEY)
+}
+
+/// Re-encrypt the database at `path`, which is encrypted with the fixed key
+/// of older conductors, with `key`.
+///
+/// No connections to the database may be open while it is re-encrypted.
+#[cfg(feature = "sqlite-encrypted")]
+pub fn rekey_legacy_database(path: &Path, key: &DbKey) -> DatabaseResult<()> {
+    {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.pragma_update(None, "key", LEGACY_KEY)?;
+        // Move everything out of the write-ahead log, since SQLCipher
+        // can only rekey a database which isn't in WAL mode.
+        conn.pragma_update(None, "wal_checkpoint", "TRUNCATE")?;
+        conn.pragma_update(None, "journal_mode", "DELETE")?;
+        conn.pragma_update(None, "rekey", key.pragma_value())?;
+    }
+    remove_if_exists(&with_suffix(path, "-wal"))?;
+    remove_if_exists(&with_suffix(path, "-shm"))?;
+    Ok(())
+}
+
+/// Encrypt the plaintext database at `path` with `key`, replacing the file.
+///
+/// No connections to the database may be open while it is encrypted.
+#[cfg(feature = "sqlite-encrypted")]
+pub fn encrypt_database(path: &Path, key: &DbKey) -> DatabaseResult<()> {
+    use rusqlite::Connection;
+
+    let encrypted_path = with_suffix(path, ".encrypting");
+    remove_if_exists(&encrypted_path)?;
+    {
+        let conn = Connection::open(path)?;
+        // Move everything out of the write-ahead log so it is exported too
+        conn.pragma_update(None, "wal_checkpoint", "TRUNCATE")?;
+        let user_version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            rusqlite::params![encrypted_path.to_string_lossy(), key.pragma_value()],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        // The export doesn't include the schema version used by migrations
+        conn.pragma_update(
+            Some(rusqlite::DatabaseName::Attached("encrypted")),
+            "user_version",
+            user_version,
+        )?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+    }
+    std::fs::rename(&encrypted_path, path)?;
+    // The log and shared memory files of the plaintext database would
+    // otherwise be applied to the encrypted one.
+    remove_if_exists(&with_suffix(path, "-wal"))?;
+    remove_if_exists(&with_suffix(path, "-shm"))?;
+    Ok(())
+}
+
+/// Encrypt every plaintext database under the database root directory `root`,
+/// each with its key derived from `key`, and re-encrypt every database which
+/// is encrypted with the fixed key of older conductors in the same way.
+/// Backups made before migrating a database are encrypted with the key of
+/// that database, so they can still be restored.
+/// Returns the paths of the databases and backups which were encrypted.
+///
+/// No conductor may be using the databases while they are encrypted.
+#[cfg(feature = "sqlite-encrypted")]
+pub fn encrypt_databases_in_dir(root: &Path, key: &DbKey) -> DatabaseResult<Vec<PathBuf>> {
+    let mut encrypted = Vec::new();
+    for path in database_files(root)? {
+        let db_file = database_of(&path);
+        let db_file = db_file
+            .strip_prefix(root)
+            .expect("database files are found under the root directory");
+        if is_plaintext_database(&path)? {
+            encrypt_database(&path, &key.derive(db_file))?;
+            tracing::info!(?path, "Encrypted database");
+            encrypted.push(path);
+        } else if is_legacy_encrypted_database(&path) {
+            rekey_legacy_database(&path, &key.derive(db_file))?;
+            tracing::info!(?path, "Re-encrypted database with its own key");
+            encrypted.push(path);
+        }
+    }
+    Ok(encrypted)
+}
+
+/// All the database files and their backups under `dir` and its subdirectories.
+fn database_files(dir: &Path) -> DatabaseResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(database_files(&path)?);
+        } else if database_of(&path)
+            .extension()
+            .map_or(false, |ext| ext == "sqlite3")
+        {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// The database which the file at `path` is a backup of, as named by
+/// [`Schema::initialize`](crate::schema::Schema::initialize), or `path`
+/// itself if it isn't a backup.
+fn database_of(path: &Path) -> PathBuf {
+    let database = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".backup"))
+        .and_then(|name| name.rsplit_once(".v"))
+        .filter(|(_, version)| version.parse::<usize>().is_ok())
+        .map(|(database, _)| database.to_owned());
+    match database {
+        Some(database) => path.with_file_name(database),
+        None => path.to_owned(),
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_keys_differ_per_database() {
+        let key = DbKey::from_bytes([1; DB_KEY_LEN]);
+        let conductor = key.derive(&DbKindConductor.filename());
+        let wasm = key.derive(&DbKindWasm.filename());
+        assert_ne!(conductor, wasm);
+        assert_ne!(conductor, key);
+        assert_eq!(conductor, key.derive(&DbKindConductor.filename()));
+        assert_ne!(
+            conductor,
+            DbKey::from_bytes([2; DB_KEY_LEN]).derive(&DbKindConductor.filename())
+        );
+    }
+
+    #[test]
+    fn key_is_not_printed() {
+        let key = DbKey::from_bytes([0xAB; DB_KEY_LEN]);
+        assert!(!format!("{:?}", key).contains("AB"));
+        assert!(!format!("{:?}", key).contains("171"));
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    fn write_secret(db: &DbWrite<DbKindWasm>) {
+        db.test_commit(|txn| {
+            txn.execute("CREATE TABLE Secret (data TEXT)", []).unwrap();
+            txn.execute("INSERT INTO Secret VALUES ('my source chain')", [])
+                .unwrap();
+        });
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    fn read_secret(path: &Path, key: Option<&DbKey>) -> rusqlite::Result<String> {
+        let conn = rusqlite::Connection::open(path)?;
+        if let Some(key) = key {
+            conn.pragma_update(None, "key", key.pragma_value())?;
+        }
+        conn.query_row("SELECT data FROM Secret", [], |row| row.get(0))
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    #[test]
+    fn database_is_unreadable_without_its_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = DbKey::from_bytes([3; DB_KEY_LEN]);
+        let db = DbWrite::new(
+            Some(dir.path()),
+            DbKindWasm,
+            Default::default(),
+            Some(key.clone()),
+        )
+        .unwrap();
+        write_secret(&db);
+        let path = db.path().clone();
+        drop(db);
+
+        assert!(!is_plaintext_database(&path).unwrap());
+        let contents = std::fs::read(&path).unwrap();
+        assert!(!contents
+            .windows(b"my source chain".len())
+            .any(|w| w == b"my source chain"));
+
+        // The database is encrypted with its derived key, not the key it was
+        // derived from
+        assert!(read_secret(&path, None).is_err());
+        assert!(read_secret(&path, Some(&key)).is_err());
+        assert_eq!(
+            read_secret(&path, Some(&key.derive(&DbKindWasm.filename()))).unwrap(),
+            "my source chain"
+        );
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    #[test]
+    fn plaintext_databases_are_encrypted_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DbWrite::new(Some(dir.path()), DbKindWasm, Default::default(), None).unwrap();
+        write_secret(&db);
+        let path = db.path().clone();
+        drop(db);
+        assert!(is_plaintext_database(&path).unwrap());
+
+        let key = DbKey::from_bytes([4; DB_KEY_LEN]);
+        let encrypted = encrypt_databases_in_dir(dir.path(), &key).unwrap();
+        assert_eq!(encrypted, vec![path.clone()]);
+        assert!(!is_plaintext_database(&path).unwrap());
+        assert!(read_secret(&path, None).is_err());
+
+        // The conductor can open it with its key, and nothing is left to encrypt
+        let db = DbWrite::new(
+            Some(dir.path()),
+            DbKindWasm,
+            Default::default(),
+            Some(key.clone()),
+        )
+        .unwrap();
+        let secret: String = db.test_commit(|txn| {
+            txn.query_row("SELECT data FROM Secret", [], |row| row.get(0))
+                .unwrap()
+        });
+        assert_eq!(secret, "my source chain");
+        drop(db);
+        assert!(encrypt_databases_in_dir(dir.path(), &key)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    #[test]
+    fn backups_are_encrypted_with_the_key_of_their_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DbWrite::new(Some(dir.path()), DbKindWasm, Default::default(), None).unwrap();
+        write_secret(&db);
+        let path = db.path().clone();
+        drop(db);
+        let backup = with_suffix(&path, ".v1.backup");
+        std::fs::copy(&path, &backup).unwrap();
+
+        let key = DbKey::from_bytes([7; DB_KEY_LEN]);
+        let mut encrypted = encrypt_databases_in_dir(dir.path(), &key).unwrap();
+        encrypted.sort();
+        assert_eq!(encrypted, vec![path.clone(), backup.clone()]);
+        assert!(!is_plaintext_database(&backup).unwrap());
+        assert_eq!(
+            read_secret(&backup, Some(&key.derive(&DbKindWasm.filename()))).unwrap(),
+            "my source chain"
+        );
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    #[test]
+    fn plaintext_database_is_refused_rather_than_wiped() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DbWrite::new(Some(dir.path()), DbKindWasm, Default::default(), None).unwrap();
+        write_secret(&db);
+        let path = db.path().clone();
+        drop(db);
+
+        let key = DbKey::from_bytes([5; DB_KEY_LEN]);
+        let err = DbWrite::new(Some(dir.path()), DbKindWasm, Default::default(), Some(key))
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(err, DatabaseError::DatabaseNotEncrypted(path.clone()));
+        assert_eq!(read_secret(&path, None).unwrap(), "my source chain");
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    #[test]
+    fn legacy_encrypted_databases_are_rekeyed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DbKindWasm.filename());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        {
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            conn.pragma_update(None, "key", LEGACY_KEY).unwrap();
+            conn.execute("CREATE TABLE Secret (data TEXT)", []).unwrap();
+            conn.execute("INSERT INTO Secret VALUES ('my source chain')", [])
+                .unwrap();
+        }
+        assert!(is_legacy_encrypted_database(&path));
+
+        // A conductor with a key refuses the database instead of wiping it
+        let key = DbKey::from_bytes([6; DB_KEY_LEN]);
+        let err = DbWrite::new(
+            Some(dir.path()),
+            DbKindWasm,
+            Default::default(),
+            Some(key.clone()),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(err, DatabaseError::DatabaseKeyMismatch(path.clone()));
+
+        let encrypted = encrypt_databases_in_dir(dir.path(), &key).unwrap();
+        assert_eq!(encrypted, vec![path.clone()]);
+        assert!(!is_legacy_encrypted_database(&path));
+        assert_eq!(
+            read_secret(&path, Some(&key.derive(&DbKindWasm.filename()))).unwrap(),
+            "my source chain"
+        );
+        assert!(encrypt_databases_in_dir(dir.path(), &key)
+            .unwrap()
+            .is_empty());
+    }
+}
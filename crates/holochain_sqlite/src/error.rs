@@ -59,6 +59,28 @@ pub enum DatabaseError {
 
     #[error(transparent)]
     GetRandom(getrandom::Error),
+
+    #[error(
+        "The {db_kind} database has schema version {version}, but this version of Holochain only supports up to version {supported}. Restore the backup of the database at version {supported} to use it with this version."
+    )]
+    SchemaTooNew {
+        db_kind: String,
+        version: usize,
+        supported: usize,
+    },
+
+    #[error("The database doesn't have the declared schema (- declared, + actual):\n{0}")]
+    SchemaMismatch(String),
+
+    #[error(
+        "The database at {0} is not encrypted. Encrypt it by running `holochain --encrypt-databases` while no conductor is using it."
+    )]
+    DatabaseNotEncrypted(PathBuf),
+
+    #[error(
+        "The database at {0} can't be opened with its key. If it was encrypted by an older version of Holochain, re-encrypt it by running `holochain --encrypt-databases` while no conductor is using it."
+    )]
+    DatabaseKeyMismatch(PathBuf),
 }
 
 impl From<TimestampError> for DatabaseError {
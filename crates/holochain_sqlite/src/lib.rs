@@ -6,10 +6,12 @@
 
 pub mod conn;
 pub mod db;
+pub mod encryption;
 pub mod error;
 pub mod exports;
 pub mod fatal;
 pub mod functions;
+pub mod metrics;
 pub mod nonce;
 pub mod prelude;
 pub mod schema;
@@ -0,0 +1,49 @@
+//! Prometheus metrics for database access, registered with the default
+//! [`prometheus`] registry.
+
+use crate::db::DbKind;
+use once_cell::sync::Lazy;
+use prometheus::HistogramVec;
+use std::time::Duration;
+
+static DB_PERMIT_WAIT: Lazy<HistogramVec> = Lazy::new(|| {
+    prometheus::register_histogram_vec!(
+        "holochain_db_permit_wait_seconds",
+        "Time spent waiting for a database connection permit",
+        &["kind", "access"],
+        vec![0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]
+    )
+    .expect("Metric names are unique")
+});
+
+/// Whether a connection permit was acquired for reading or writing.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DbAccess {
+    Read,
+    Write,
+}
+
+/// Record how long it took to acquire a connection permit for a database.
+pub(crate) fn observe_permit_wait(kind: &DbKind, access: DbAccess, wait: Duration) {
+    let access = match access {
+        DbAccess::Read => "read",
+        DbAccess::Write => "write",
+    };
+    DB_PERMIT_WAIT
+        .with_label_values(&[kind_label(kind), access])
+        .observe(wait.as_secs_f64());
+}
+
+/// The databases of each kind are labelled together, regardless of which
+/// DNA or space they belong to, to keep the number of series bounded.
+fn kind_label(kind: &DbKind) -> &'static str {
+    match kind {
+        DbKind::Authored(_) => "authored",
+        DbKind::Dht(_) => "dht",
+        DbKind::Cache(_) => "cache",
+        DbKind::Conductor => "conductor",
+        DbKind::Wasm => "wasm",
+        DbKind::P2pAgentStore(_) => "p2p_agent_store",
+        DbKind::P2pMetrics(_) => "p2p_metrics",
+    }
+}
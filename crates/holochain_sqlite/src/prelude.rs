@@ -1,6 +1,7 @@
 //! Common types, especially traits, which we'd like to import en masse
 
 pub use crate::db::*;
+pub use crate::encryption::DbKey;
 pub use crate::error::*;
 pub use crate::exports::*;
 pub use crate::fresh_reader_test;
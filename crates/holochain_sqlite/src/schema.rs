@@ -5,9 +5,16 @@
 //! The `Migration` must specify the actual forward migration script, as well as
 //! an updated schema defining the result of running the migration.
 //!
-//! Currently, the updated schema only serves as a point of reference for examining
-//! the current schema. In the future, we should find a way to compare the actual
-//! schema resulting from migrations with the schema provided, to make sure they match.
+//! [`Schema::verify`] checks that running all the migrations on an empty database
+//! results in the schema of the last migration, which is tested for every schema.
+//! Tables, columns, foreign keys and indexes are compared, not the SQL text.
+//! Set the [`VERIFY_SCHEMA_ENV`] environment variable to also check each database
+//! as it is opened, in debug builds.
+//!
+//! Before an existing database is migrated, its file is copied alongside it, to
+//! `<database file>.v<version>.backup`. To go back to an older version of Holochain,
+//! restore the backup for that version's schema. Only the [`MAX_BACKUPS`] most
+//! recent backups of each database are kept.
 //!
 //! Note that there is code in `build.rs` which fails the build if any schema or migration
 //! file has a change according to `git diff`. This will hopefully help prevent accidental
@@ -15,15 +22,29 @@
 
 use once_cell::sync::Lazy;
 use rusqlite::{Connection, Transaction};
+use std::path::{Path, PathBuf};
 
 use crate::db::DbKind;
+use crate::error::{DatabaseError, DatabaseResult};
+
+/// Set this environment variable to check, in debug builds, that each database
+/// has the declared schema once it has been migrated.
+pub const VERIFY_SCHEMA_ENV: &str = "HC_VERIFY_DB_SCHEMA";
 
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| Schema {
     migrations: vec![
         M::initial(include_str!("sql/cell/schema/0.sql")),
         M {
             forward: include_str!("sql/cell/schema/1-up.sql").into(),
-            _schema: include_str!("sql/cell/schema/1.sql").into(),
+            schema: include_str!("sql/cell/schema/1.sql").into(),
+        },
+        M {
+            forward: include_str!("sql/cell/schema/2-up.sql").into(),
+            schema: include_str!("sql/cell/schema/2.sql").into(),
+        },
+        M {
+            forward: include_str!("sql/cell/schema/3-up.sql").into(),
+            schema: include_str!("sql/cell/schema/3.sql").into(),
         },
     ],
 });
@@ -32,8 +53,8 @@ pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| Schema {
     migrations: vec![
         M::initial(include_str!("sql/conductor/schema/0.sql")),
         M {
-            forward: include_str!("sql/conductor/schema/1.sql").into(),
-            _schema: "".into(),
+            forward: include_str!("sql/conductor/schema/1-up.sql").into(),
+            schema: include_str!("sql/conductor/schema/1.sql").into(),
         },
     ],
 });
@@ -55,15 +76,30 @@ pub struct Schema {
 }
 
 impl Schema {
+    /// The schema used by this kind of database.
+    pub fn for_kind(db_kind: &DbKind) -> &'static Schema {
+        match db_kind {
+            DbKind::Authored(_) | DbKind::Dht(_) | DbKind::Cache(_) => Lazy::force(&SCHEMA_CELL),
+            DbKind::Conductor => Lazy::force(&SCHEMA_CONDUCTOR),
+            DbKind::Wasm => Lazy::force(&SCHEMA_WASM),
+            DbKind::P2pAgentStore(_) => Lazy::force(&SCHEMA_P2P_STATE),
+            DbKind::P2pMetrics(_) => Lazy::force(&SCHEMA_P2P_METRICS),
+        }
+    }
+
     /// Determine if any database migrations need to run, and run them if so.
     /// The decision is based on the difference between this Schema's
     /// current_index and the user_version pragma value in the database itself.
     /// NB: The current_index is 0-based, and the user_version is 1-based.
+    ///
+    /// If `path` is the file of an existing database, it is backed up before
+    /// it is migrated.
     pub fn initialize(
         &self,
         conn: &mut Connection,
         db_kind: Option<DbKind>,
-    ) -> rusqlite::Result<()> {
+        path: Option<&Path>,
+    ) -> DatabaseResult<()> {
         let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
         let db_kind = db_kind
             .as_ref()
@@ -74,6 +110,15 @@ impl Schema {
         let num_migrations = self.migrations.len();
         match migrations_applied.cmp(&(num_migrations)) {
             std::cmp::Ordering::Less => {
+                // A new database has nothing to back up
+                if let Some(path) = path.filter(|_| migrations_applied > 0) {
+                    let backup_path = backup(conn, path, migrations_applied)?;
+                    tracing::info!(
+                        ?backup_path,
+                        "backed up database {} before migrating it",
+                        db_kind
+                    );
+                }
                 let mut txn = conn.transaction()?;
                 // run forward migrations
                 for v in migrations_applied..num_migrations {
@@ -97,17 +142,184 @@ impl Schema {
                 );
             }
             std::cmp::Ordering::Greater => {
-                unimplemented!("backward migrations unimplemented");
+                return Err(DatabaseError::SchemaTooNew {
+                    db_kind,
+                    version: migrations_applied,
+                    supported: num_migrations,
+                });
             }
         }
 
+        #[cfg(debug_assertions)]
+        if std::env::var_os(VERIFY_SCHEMA_ENV).is_some() {
+            self.verify_connection(conn).map_err(|e| {
+                tracing::error!("database {} doesn't have the declared schema", db_kind);
+                e
+            })?;
+        }
+
         Ok(())
     }
+
+    /// Check that running every migration on an empty database results in
+    /// the schema declared by the last migration.
+    pub fn verify(&self) -> DatabaseResult<()> {
+        let mut conn = Connection::open_in_memory()?;
+        let mut txn = conn.transaction()?;
+        for migration in &self.migrations {
+            migration.run_forward(&mut txn)?;
+        }
+        txn.commit()?;
+        self.verify_connection(&conn)
+    }
+
+    /// Check that the database on this connection has the schema declared by
+    /// the last migration.
+    pub fn verify_connection(&self, conn: &Connection) -> DatabaseResult<()> {
+        let declared = Connection::open_in_memory()?;
+        if let Some(migration) = self.migrations.last() {
+            declared.execute_batch(&migration.schema)?;
+        }
+        let declared = normalized_schema(&declared)?;
+        let actual = normalized_schema(conn)?;
+        if declared == actual {
+            return Ok(());
+        }
+        let mut diff = String::new();
+        for line in declared.iter().filter(|line| !actual.contains(line)) {
+            diff.push_str(&format!("- {}\n", line));
+        }
+        for line in actual.iter().filter(|line| !declared.contains(line)) {
+            diff.push_str(&format!("+ {}\n", line));
+        }
+        Err(DatabaseError::SchemaMismatch(diff))
+    }
+}
+
+/// A description of the tables, columns, foreign keys and indexes in a database,
+/// one per line, which doesn't depend on how the SQL which created them was written.
+fn normalized_schema(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    let tables = conn
+        .prepare(
+            "SELECT name FROM sqlite_master
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for table in tables {
+        let mut stmt = conn.prepare(
+            "SELECT name, upper(type), \"notnull\", dflt_value, pk FROM pragma_table_xinfo(?1)",
+        )?;
+        let columns = stmt.query_map([&table], |row| {
+            Ok(format!(
+                "{}: column {} {} notnull={} default={:?} pk={}",
+                table,
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+        for column in columns {
+            lines.push(column?);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT \"table\", \"from\", \"to\", on_update, on_delete
+            FROM pragma_foreign_key_list(?1)",
+        )?;
+        let foreign_keys = stmt.query_map([&table], |row| {
+            Ok(format!(
+                "{}: foreign key {} references {}({:?}) on update {} on delete {}",
+                table,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for foreign_key in foreign_keys {
+            lines.push(foreign_key?);
+        }
+
+        let indexes = conn
+            .prepare("SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1)")?
+            .query_map([&table], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, bool>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for (index, unique, origin, partial) in indexes {
+            let columns = conn
+                .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?
+                .query_map([&index], |row| row.get::<_, Option<String>>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            lines.push(format!(
+                "{}: index {} on {:?} unique={} origin={} partial={}",
+                table, index, columns, unique, origin, partial
+            ));
+        }
+    }
+    lines.sort();
+    Ok(lines)
+}
+
+/// How many backups of each database are kept. Older ones are removed
+/// when a new backup is made.
+pub const MAX_BACKUPS: usize = 3;
+
+/// Copy the file of a database at schema `version` to
+/// `<path>.v<version>.backup`, replacing any earlier backup at that version,
+/// and remove all but the [`MAX_BACKUPS`] newest backups.
+fn backup(conn: &Connection, path: &Path, version: usize) -> DatabaseResult<PathBuf> {
+    // Move everything out of the write-ahead log so that the copy is complete
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    let mut backup_path = path.as_os_str().to_owned();
+    backup_path.push(format!(".v{}.backup", version));
+    let backup_path = PathBuf::from(backup_path);
+    std::fs::copy(path, &backup_path)?;
+
+    let mut backups = backups_of(path)?;
+    backups.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    for (_, old) in backups.into_iter().skip(MAX_BACKUPS) {
+        std::fs::remove_file(&old)?;
+    }
+    Ok(backup_path)
+}
+
+/// The backups of the database at `path`, with the schema version of each.
+fn backups_of(path: &Path) -> DatabaseResult<Vec<(usize, PathBuf)>> {
+    let (dir, file_name) = match (path.parent(), path.file_name()) {
+        (Some(dir), Some(file_name)) => (dir, file_name.to_string_lossy()),
+        _ => return Ok(Vec::new()),
+    };
+    let prefix = format!("{}.v", file_name);
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let backup = entry?.path();
+        let version = backup
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|rest| rest.strip_suffix(".backup"))
+            .and_then(|version| version.parse().ok());
+        if let Some(version) = version {
+            backups.push((version, backup));
+        }
+    }
+    Ok(backups)
 }
 
 #[derive(Clone, Debug)]
 pub struct Migration {
-    _schema: Sql,
+    schema: Sql,
     forward: Sql,
 }
 
@@ -115,7 +327,7 @@ impl Migration {
     /// The initial migration's forward migration is the entire schema
     pub fn initial(schema: &str) -> Self {
         Self {
-            _schema: schema.into(),
+            schema: schema.into(),
             forward: schema.into(),
         }
     }
@@ -132,6 +344,145 @@ type Sql = String;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use holo_hash::DnaHash;
+    use kitsune_p2p::KitsuneSpace;
+    use std::sync::Arc;
+
+    #[test]
+    fn declared_schemas_match_migrations() {
+        let dna_hash = Arc::new(DnaHash::from_raw_36(vec![0; 36]));
+        let space = Arc::new(KitsuneSpace(vec![0; 36]));
+        for db_kind in [
+            DbKind::Authored(dna_hash.clone()),
+            DbKind::Dht(dna_hash.clone()),
+            DbKind::Cache(dna_hash),
+            DbKind::Conductor,
+            DbKind::Wasm,
+            DbKind::P2pAgentStore(space.clone()),
+            DbKind::P2pMetrics(space),
+        ] {
+            if let Err(e) = Schema::for_kind(&db_kind).verify() {
+                panic!("{}: {}", db_kind, e);
+            }
+        }
+    }
+
+    #[test]
+    fn schema_mismatch_is_reported() {
+        let schema = Schema {
+            migrations: vec![
+                M::initial("CREATE TABLE Numbers (num INTEGER);"),
+                M {
+                    forward: "ALTER TABLE Numbers ADD COLUMN name TEXT;".into(),
+                    schema: "CREATE TABLE Numbers (num INTEGER, name TEXT NOT NULL);".into(),
+                },
+            ],
+        };
+        match schema.verify() {
+            Err(DatabaseError::SchemaMismatch(diff)) => assert_eq!(
+                diff,
+                "- Numbers: column name TEXT notnull=true default=None pk=0\n\
+                + Numbers: column name TEXT notnull=false default=None pk=0\n"
+            ),
+            r => panic!("expected a schema mismatch, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn existing_database_is_backed_up_before_migrating() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("numbers.sqlite3");
+        let mut schema = Schema {
+            migrations: vec![M::initial("CREATE TABLE Numbers (num INTEGER);")],
+        };
+        let mut conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "journal_mode", "WAL").unwrap();
+        schema.initialize(&mut conn, None, Some(&path)).unwrap();
+        let backups = || {
+            std::fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.extension().map_or(false, |ext| ext == "backup"))
+                .collect::<Vec<_>>()
+        };
+        // Nothing to back up when the database is created
+        assert!(backups().is_empty());
+        conn.execute("INSERT INTO Numbers (num) VALUES (1)", ())
+            .unwrap();
+
+        schema.migrations.push(M {
+            forward: "CREATE TABLE Names (name TEXT);".into(),
+            schema: "n/a".into(),
+        });
+        schema.initialize(&mut conn, None, Some(&path)).unwrap();
+        drop(conn);
+
+        assert_eq!(
+            backups(),
+            vec![dir.path().join("numbers.sqlite3.v1.backup")]
+        );
+        let backup = Connection::open(&backups()[0]).unwrap();
+        let version: u16 = backup
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 1);
+        let num: i64 = backup
+            .query_row("SELECT num FROM Numbers", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(num, 1);
+        assert!(backup
+            .execute("INSERT INTO Names (name) VALUES ('Mike')", ())
+            .is_err());
+    }
+
+    #[test]
+    fn only_the_newest_backups_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("numbers.sqlite3");
+        let mut schema = Schema {
+            migrations: vec![M::initial("CREATE TABLE Numbers (num INTEGER);")],
+        };
+        let mut conn = Connection::open(&path).unwrap();
+        schema.initialize(&mut conn, None, Some(&path)).unwrap();
+        for i in 0..MAX_BACKUPS + 2 {
+            schema.migrations.push(M {
+                forward: format!("CREATE TABLE Names{} (name TEXT);", i),
+                schema: "n/a".into(),
+            });
+            schema.initialize(&mut conn, None, Some(&path)).unwrap();
+        }
+        drop(conn);
+
+        // Another database's backups are left alone
+        let other = dir.path().join("numbers.sqlite3.v1.backup.txt");
+        std::fs::write(&other, "").unwrap();
+
+        let mut versions: Vec<_> = backups_of(&path)
+            .unwrap()
+            .into_iter()
+            .map(|(version, _)| version)
+            .collect();
+        versions.sort();
+        assert_eq!(versions, vec![3, 4, 5]);
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+        let schema = Schema {
+            migrations: vec![M::initial("CREATE TABLE Numbers (num INTEGER);")],
+        };
+        assert!(matches!(
+            schema.initialize(&mut conn, None, None),
+            Err(DatabaseError::SchemaTooNew {
+                version: 2,
+                supported: 1,
+                ..
+            })
+        ));
+    }
 
     #[test]
     fn test_migrations_initial() {
@@ -140,7 +491,7 @@ mod tests {
                 M::initial("CREATE TABLE Numbers (num INTEGER);"),
                 M {
                     forward: "CREATE TABLE Names (name TEXT);".into(),
-                    _schema: "n/a".into(),
+                    schema: "n/a".into(),
                 },
             ],
         };
@@ -148,7 +499,7 @@ mod tests {
         let mut conn = Connection::open_in_memory().unwrap();
 
         // The Names table doesn't exist yet, since the current_index is set to 0.
-        schema.initialize(&mut conn, None).unwrap();
+        schema.initialize(&mut conn, None, None).unwrap();
         assert_eq!(
             conn.execute("INSERT INTO Numbers (num) VALUES (1)", ())
                 .unwrap(),
@@ -170,7 +521,7 @@ mod tests {
         let mut conn = Connection::open_in_memory().unwrap();
 
         // The Names table doesn't exist yet, since the current_index is set to 0.
-        schema.initialize(&mut conn, None).unwrap();
+        schema.initialize(&mut conn, None, None).unwrap();
         assert_eq!(
             conn.execute("INSERT INTO Numbers (num) VALUES (1)", ())
                 .unwrap(),
@@ -185,10 +536,10 @@ mod tests {
             M::initial("This bad SQL won't run, phew!"),
             M {
                 forward: "CREATE TABLE Names (name TEXT);".into(),
-                _schema: "n/a".into(),
+                schema: "n/a".into(),
             },
         ];
-        schema.initialize(&mut conn, None).unwrap();
+        schema.initialize(&mut conn, None, None).unwrap();
         assert_eq!(
             conn.execute("INSERT INTO Numbers (num) VALUES (1)", ())
                 .unwrap(),
@@ -22,6 +22,8 @@ pub mod sql_cell {
         include_str!("sql/cell/fetch_hashes/fetch_op_hashes_p2.sql");
 
     pub const FETCH_OP_REGION: &str = include_str!("sql/cell/fetch_op_region.sql");
+    pub const FETCH_OP_REGION_DATA_SINCE: &str =
+        include_str!("sql/cell/fetch_op_region_data_since.sql");
     pub const FETCH_OPS_BY_REGION: &str = include_str!("sql/cell/fetch_ops_by_region.sql");
     pub const FETCH_REGION_OP_HASHES: &str = include_str!("sql/cell/fetch_region_op_hashes.sql");
 
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS Warrant (
+  author BLOB NOT NULL,
+  warrantee BLOB NOT NULL,
+  action_hash BLOB NOT NULL,
+  blob BLOB NOT NULL,
+  PRIMARY KEY (author, action_hash) ON CONFLICT IGNORE
+);
+CREATE INDEX IF NOT EXISTS Warrant_warrantee_idx ON Warrant (warrantee);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS CountersigningSession (
+  author BLOB PRIMARY KEY ON CONFLICT REPLACE,
+  preflight_request BLOB NOT NULL,
+  entry_hash BLOB NULL,
+  action_hash BLOB NULL,
+  expires_at_timestamp INTEGER NOT NULL
+);
@@ -1,33 +1,18 @@
 //! Functionality for safely accessing databases.
 
 use rusqlite::Connection;
+use std::path::Path;
 
 use crate::db::DbKind;
+use crate::error::DatabaseResult;
+use crate::schema::Schema;
 
-/// Enumeration of all databases needed by Holochain
-pub(crate) fn initialize_database(conn: &mut Connection, db_kind: DbKind) -> rusqlite::Result<()> {
-    match db_kind {
-        DbKind::Dht(_) => {
-            crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Authored(_) => {
-            crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Conductor => {
-            crate::schema::SCHEMA_CONDUCTOR.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Wasm => {
-            crate::schema::SCHEMA_WASM.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::P2pAgentStore(_) => {
-            crate::schema::SCHEMA_P2P_STATE.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::P2pMetrics(_) => {
-            crate::schema::SCHEMA_P2P_METRICS.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Cache(_) => {
-            crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
-        }
-    }
-    Ok(())
+/// Create or migrate the tables of a database of any kind.
+/// `path` is the database file, if it isn't in memory.
+pub(crate) fn initialize_database(
+    conn: &mut Connection,
+    db_kind: DbKind,
+    path: Option<&Path>,
+) -> DatabaseResult<()> {
+    Schema::for_kind(&db_kind).initialize(conn, Some(db_kind), path)
 }
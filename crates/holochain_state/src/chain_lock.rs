@@ -6,7 +6,9 @@ use holochain_zome_types::Timestamp;
 
 /// True if the chain is currently locked for the given lock id.
 /// The chain is never locked for the id that created it.
-/// The chain is always locked for all other ids until the lock end time is in the past.
+/// The chain is always locked for all other ids until the lock end time is in the past,
+/// and for as long after that as the author's countersigning session is unresolved,
+/// so nothing can be built on top of a countersigned action which may yet be abandoned.
 pub fn is_chain_locked(
     txn: &Transaction,
     lock: &[u8],
@@ -19,9 +21,14 @@ pub fn is_chain_locked(
             "
             SELECT 1
             FROM ChainLock
-            WHERE expires_at_timestamp >= :now
-            AND lock != :lock
+            WHERE lock != :lock
             AND author = :author
+            AND (
+                expires_at_timestamp >= :now
+                OR EXISTS (
+                    SELECT 1 FROM CountersigningSession WHERE author = :author
+                )
+            )
             LIMIT 1
             ",
             named_params! {
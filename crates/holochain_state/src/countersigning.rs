@@ -0,0 +1,158 @@
+//! Queries for the countersigning sessions which agents have joined.
+
+use holo_hash::AgentPubKey;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Row;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::prelude::CountersigningSessionState;
+
+use crate::prelude::from_blob;
+use crate::prelude::StateQueryResult;
+
+/// The unresolved countersigning session of an agent, if any.
+pub fn countersigning_session(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Option<CountersigningSessionState>> {
+    let r = txn
+        .query_row(
+            "
+            SELECT author, preflight_request, entry_hash, action_hash
+            FROM CountersigningSession
+            WHERE author = :author
+            ",
+            named_params! {
+                ":author": author
+            },
+            |row| Ok(session_from_row(row)),
+        )
+        .optional()?;
+    r.transpose()
+}
+
+/// All unresolved countersigning sessions in this database.
+pub fn countersigning_sessions(
+    txn: &Transaction,
+) -> StateQueryResult<Vec<CountersigningSessionState>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT author, preflight_request, entry_hash, action_hash
+        FROM CountersigningSession
+        ",
+    )?;
+    let iter = stmt.query_and_then([], session_from_row)?;
+    iter.collect()
+}
+
+fn session_from_row(row: &Row) -> StateQueryResult<CountersigningSessionState> {
+    Ok(CountersigningSessionState {
+        author: row.get("author")?,
+        preflight_request: from_blob(row.get("preflight_request")?)?,
+        entry_hash: row.get("entry_hash")?,
+        action_hash: row.get("action_hash")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_lock::is_chain_locked;
+    use crate::mutations::*;
+    use crate::test_utils::fresh_reader_test;
+    use crate::test_utils::test_authored_db;
+    use arbitrary::Arbitrary;
+    use fixt::prelude::*;
+    use holochain_types::prelude::*;
+
+    #[test]
+    fn sessions_are_kept_until_the_chain_is_unlocked() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let db = test_authored_db().to_db();
+        let author = fake_agent_pubkey_1();
+        let preflight_request = PreflightRequest::arbitrary(&mut u).unwrap();
+        let entry_hash = EntryHash::arbitrary(&mut u).unwrap();
+        let action_hash = ActionHash::arbitrary(&mut u).unwrap();
+
+        db.test_commit(|txn| {
+            insert_countersigning_session(txn, &author, &preflight_request).unwrap();
+        });
+        fresh_reader_test(db.clone(), |txn| {
+            let session = countersigning_session(&txn, &author).unwrap().unwrap();
+            assert_eq!(session.preflight_request, preflight_request);
+            assert_eq!(session.action_hash, None);
+        });
+
+        db.test_commit(|txn| {
+            set_countersigning_session_action(txn, &author, &entry_hash, &action_hash).unwrap();
+        });
+        fresh_reader_test(db.clone(), |txn| {
+            let sessions = countersigning_sessions(&txn).unwrap();
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].entry_hash, Some(entry_hash.clone()));
+            assert_eq!(sessions[0].action_hash, Some(action_hash.clone()));
+        });
+
+        db.test_commit(|txn| unlock_chain(txn, &author).unwrap());
+        fresh_reader_test(db, |txn| {
+            assert!(countersigning_sessions(&txn).unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn chain_stays_locked_past_expiry_until_the_session_is_resolved() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let db = test_authored_db().to_db();
+        let author = fake_agent_pubkey_1();
+        let preflight_request = PreflightRequest::arbitrary(&mut u).unwrap();
+        let expired = (Timestamp::now() - std::time::Duration::from_secs(60)).unwrap();
+
+        db.test_commit(|txn| {
+            lock_chain(txn, b"session", &author, &expired).unwrap();
+        });
+        fresh_reader_test(db.clone(), |txn| {
+            assert!(!is_chain_locked(&txn, &[], &author).unwrap());
+        });
+
+        db.test_commit(|txn| {
+            insert_countersigning_session(txn, &author, &preflight_request).unwrap();
+        });
+        fresh_reader_test(db.clone(), |txn| {
+            assert!(is_chain_locked(&txn, &[], &author).unwrap());
+            assert!(!is_chain_locked(&txn, b"session", &author).unwrap());
+        });
+
+        db.test_commit(|txn| unlock_chain(txn, &author).unwrap());
+        fresh_reader_test(db, |txn| {
+            assert!(!is_chain_locked(&txn, &[], &author).unwrap());
+        });
+    }
+
+    #[test]
+    fn only_the_chain_head_can_be_removed() {
+        let db = test_authored_db().to_db();
+        let author = fake_agent_pubkey_1();
+        let actions: Vec<_> = (0..2)
+            .map(|seq| {
+                let mut builder = fixt!(ActionBuilderCommon);
+                builder.action_seq = seq;
+                builder.author = author.clone();
+                SignedActionHashed::with_presigned(
+                    ActionHashed::from_content_sync(
+                        InitZomesComplete::from_builder(builder).into(),
+                    ),
+                    fixt!(Signature),
+                )
+            })
+            .collect();
+
+        db.test_commit(|txn| {
+            for action in &actions {
+                insert_action(txn, action).unwrap();
+            }
+            assert!(!remove_countersigned_action(txn, &author, actions[0].as_hash()).unwrap());
+            assert!(remove_countersigned_action(txn, &author, actions[1].as_hash()).unwrap());
+            assert!(remove_countersigned_action(txn, &author, actions[0].as_hash()).unwrap());
+        });
+    }
+}
@@ -18,6 +18,7 @@ use holochain_types::dht_op::{DhtOpHashed, DhtOpType};
 use holochain_types::prelude::DhtOpError;
 use holochain_types::prelude::DnaDefHashed;
 use holochain_types::prelude::DnaWasmHashed;
+use holochain_types::prelude::SignedWarrantExt;
 use holochain_types::sql::AsSql;
 use holochain_zome_types::block::Block;
 use holochain_zome_types::block::BlockTargetId;
@@ -216,6 +217,21 @@ pub fn insert_validation_receipt(
     Ok(())
 }
 
+/// Insert a [`SignedWarrant`] into the database.
+/// Only the first warrant from any author about any one action is kept.
+pub fn insert_warrant(txn: &mut Transaction, warrant: SignedWarrant) -> StateMutationResult<()> {
+    let author = warrant.warrant().author.clone();
+    let warrantee = warrant.warrant().warrantee().clone();
+    let action_hash = warrant.action_hash();
+    sql_insert!(txn, Warrant, {
+        "author": author,
+        "warrantee": warrantee,
+        "action_hash": action_hash,
+        "blob": to_blob(&warrant)?,
+    })?;
+    Ok(())
+}
+
 /// Insert a [`DnaWasm`](holochain_types::prelude::DnaWasm) into the database.
 pub fn insert_wasm(txn: &mut Transaction, wasm: DnaWasmHashed) -> StateMutationResult<()> {
     let (wasm, hash) = wasm.into_inner();
@@ -719,14 +735,85 @@ pub fn lock_chain(
     Ok(())
 }
 
-/// Unlock the chain by dropping all records in the lock table.
+/// Unlock the chain by dropping all records in the lock table,
+/// along with the agent's countersigning session.
 /// This should be done very carefully as it can e.g. invalidate a shared
 /// countersigning session that is inflight.
 pub fn unlock_chain(txn: &mut Transaction, author: &AgentPubKey) -> StateMutationResult<()> {
     txn.execute("DELETE FROM ChainLock WHERE author = ?", [author])?;
+    txn.execute(
+        "DELETE FROM CountersigningSession WHERE author = ?",
+        [author],
+    )?;
     Ok(())
 }
 
+/// Record that the author has joined the countersigning session for this
+/// preflight request, replacing any previous session.
+pub fn insert_countersigning_session(
+    txn: &mut Transaction,
+    author: &AgentPubKey,
+    preflight_request: &PreflightRequest,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, CountersigningSession, {
+        "author": author,
+        "preflight_request": to_blob(preflight_request)?,
+        "expires_at_timestamp": preflight_request.session_times.end(),
+    })?;
+    Ok(())
+}
+
+/// Record the author's committed action for their current countersigning session.
+pub fn set_countersigning_session_action(
+    txn: &mut Transaction,
+    author: &AgentPubKey,
+    entry_hash: &EntryHash,
+    action_hash: &ActionHash,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "
+        UPDATE CountersigningSession
+        SET entry_hash = :entry_hash, action_hash = :action_hash
+        WHERE author = :author
+        ",
+        named_params! {
+            ":author": author,
+            ":entry_hash": entry_hash,
+            ":action_hash": action_hash,
+        },
+    )?;
+    Ok(())
+}
+
+/// Remove an abandoned countersigned action, and its ops, from the author's chain.
+/// The action is only removed if it is still the head of the chain, as it may
+/// have been built upon once the session's chain lock expired.
+/// Returns true if the action was removed.
+pub fn remove_countersigned_action(
+    txn: &mut Transaction,
+    author: &AgentPubKey,
+    action_hash: &ActionHash,
+) -> StateMutationResult<bool> {
+    // The ops are removed by the foreign key cascade.
+    let removed = txn.execute(
+        "
+        DELETE FROM Action
+        WHERE hash = :hash
+        AND author = :author
+        AND NOT EXISTS (
+            SELECT 1 FROM Action AS Later
+            WHERE Later.author = :author
+            AND Later.seq > Action.seq
+        )
+        ",
+        named_params! {
+            ":hash": action_hash,
+            ":author": author,
+        },
+    )?;
+    Ok(removed > 0)
+}
+
 pub fn delete_all_ephemeral_scheduled_fns(txn: &mut Transaction) -> StateMutationResult<()> {
     txn.execute(
         holochain_sqlite::sql::sql_cell::schedule::DELETE_ALL_EPHEMERAL,
@@ -1,6 +1,7 @@
 use holo_hash::*;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_types::dht_op::DhtOpType;
+use holochain_types::link::WireLinkKey;
 use holochain_types::sql::ToSqlStatement;
 use holochain_zome_types::*;
 use std::fmt::Debug;
@@ -17,22 +18,75 @@ pub struct LinksQuery {
     pub base: Arc<AnyLinkableHash>,
     pub type_query: LinkTypeFilter,
     pub tag: Option<String>,
+    pub after: Option<Timestamp>,
+    pub before: Option<Timestamp>,
+    pub author: Option<AgentPubKey>,
+    pub limit: Option<u32>,
+    pub cursor: Option<LinkCursor>,
+    // The cursor's fields, to bind to the query.
+    cursor_timestamp: Option<Timestamp>,
+    cursor_hash: Option<ActionHash>,
     query: String,
 }
 
 impl LinksQuery {
     pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
-        let tag = tag.map(|tag| Self::tag_to_hex(&tag));
-        let create_string = Self::create_query_string(&type_query, tag.clone());
-        let delete_string = Self::delete_query_string(&type_query, tag.clone());
+        Self::from_key(WireLinkKey::new(base, type_query, tag))
+    }
+
+    /// A query for the links matching all the filters of a [`WireLinkKey`].
+    pub fn from_key(key: WireLinkKey) -> Self {
+        let tag = key.tag.map(|tag| Self::tag_to_hex(&tag));
+        let create_string = Self::create_query_string(&key.type_query, tag.clone());
+        let delete_string = Self::delete_query_string(&key.type_query, tag.clone());
+        let (cursor_timestamp, cursor_hash) = key
+            .cursor
+            .clone()
+            .map(|c| (c.timestamp, c.create_link_hash))
+            .unzip();
         Self {
-            base: Arc::new(base),
-            type_query,
+            base: Arc::new(key.base),
+            type_query: key.type_query,
             tag,
+            after: key.after,
+            before: key.before,
+            author: key.author,
+            limit: key.limit,
+            cursor: key.cursor,
+            cursor_timestamp,
+            cursor_hash,
             query: Self::create_query(create_string, delete_string),
         }
     }
 
+    /// Does a create link match the time, author and cursor filters of this query.
+    pub fn matches_filters(&self, create_link: &CreateLink, create_link_hash: &ActionHash) -> bool {
+        let position = LinkCursor {
+            timestamp: create_link.timestamp,
+            create_link_hash: create_link_hash.clone(),
+        };
+        self.after.map_or(true, |after| position.timestamp > after)
+            && self
+                .before
+                .map_or(true, |before| position.timestamp < before)
+            && self
+                .author
+                .as_ref()
+                .map_or(true, |author| create_link.author == *author)
+            && self
+                .cursor
+                .as_ref()
+                .map_or(true, |cursor| position > *cursor)
+    }
+
+    /// Order links by their [`LinkCursor`] and keep at most the limit.
+    pub fn paginate<T>(&self, links: &mut Vec<T>, cursor: impl Fn(&T) -> LinkCursor) {
+        links.sort_by_cached_key(cursor);
+        if let Some(limit) = self.limit {
+            links.truncate(limit as usize);
+        }
+    }
+
     pub fn tag_to_hex(tag: &LinkTag) -> String {
         use std::fmt::Write;
         let mut s = String::with_capacity(tag.0.len());
@@ -54,6 +108,37 @@ impl LinksQuery {
         self.query.clone()
     }
 
+    /// A query for the number of create links matching this query which
+    /// haven't been deleted. The limit doesn't apply.
+    pub fn count_query(&self) -> String {
+        let mut creates = format!(
+            "
+            SELECT Action.hash FROM DhtOp
+            {}
+            ",
+            Self::common_query_string()
+        );
+        creates = Self::add_type_query(creates, &self.type_query);
+        creates = Self::add_tag(creates, self.tag.clone());
+        format!(
+            "
+            SELECT COUNT(*) FROM ({}) AS Creates
+            WHERE NOT EXISTS (
+                SELECT 1 FROM DhtOp
+                JOIN Action On DhtOp.action_hash = Action.hash
+                WHERE DhtOp.type = :delete
+                AND
+                Action.create_link_hash = Creates.hash
+                AND
+                DhtOp.validation_status = :status
+                AND
+                DhtOp.when_integrated IS NOT NULL
+            )
+            ",
+            creates
+        )
+    }
+
     fn common_query_string() -> &'static str {
         "
             JOIN Action On DhtOp.action_hash = Action.hash
@@ -63,6 +148,13 @@ impl LinksQuery {
             AND
             DhtOp.validation_status = :status
             AND DhtOp.when_integrated IS NOT NULL
+            AND (:after IS NULL OR DhtOp.authored_timestamp > :after)
+            AND (:before IS NULL OR DhtOp.authored_timestamp < :before)
+            AND (:author IS NULL OR Action.author = :author)
+            AND (
+                :cursor_timestamp IS NULL
+                OR (DhtOp.authored_timestamp, Action.hash) > (:cursor_timestamp, :cursor_hash)
+            )
         "
     }
     fn create_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
@@ -126,6 +218,11 @@ impl LinksQuery {
                 ":delete": DhtOpType::RegisterRemoveLink,
                 ":status": ValidationStatus::Valid,
                 ":base_hash": self.base,
+                ":after": self.after,
+                ":before": self.before,
+                ":author": self.author,
+                ":cursor_timestamp": self.cursor_timestamp,
+                ":cursor_hash": self.cursor_hash,
             }
         }
         .to_vec()
@@ -144,6 +241,14 @@ impl GetLinksQuery {
             query: LinksQuery::base(base, dependencies),
         }
     }
+
+    /// Get the links matching all the filters of a [`WireLinkKey`],
+    /// including its limit and cursor.
+    pub fn from_key(key: WireLinkKey) -> Self {
+        Self {
+            query: LinksQuery::from_key(key),
+        }
+    }
 }
 
 impl Query for GetLinksQuery {
@@ -169,25 +274,19 @@ impl Query for GetLinksQuery {
     }
 
     fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
-        let query = &self.query;
-        let base_filter = query.base.clone();
-        let type_query_filter = query.type_query.clone();
-        let tag_filter = query.tag.clone();
+        let query = self.query.clone();
         let f = move |action: &QueryData<Self>| match action.action() {
-            Action::CreateLink(CreateLink {
-                base_address,
-                tag,
-                zome_index,
-                link_type,
-                ..
-            }) => {
-                *base_address == *base_filter
-                    && type_query_filter.contains(zome_index, link_type)
-                    && tag_filter
-                        .as_ref()
-                        .map_or(true, |t| LinksQuery::tag_to_hex(tag).starts_with(&(**t)))
+            Action::CreateLink(create_link) => {
+                create_link.base_address == *query.base
+                    && query
+                        .type_query
+                        .contains(&create_link.zome_index, &create_link.link_type)
+                    && query.tag.as_ref().map_or(true, |t| {
+                        LinksQuery::tag_to_hex(&create_link.tag).starts_with(&(**t))
+                    })
+                    && query.matches_filters(create_link, action.as_hash())
             }
-            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *base_filter,
+            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *query.base,
             _ => false,
         };
         Box::new(f)
@@ -219,7 +318,8 @@ impl Query for GetLinksQuery {
         S: Store,
     {
         let mut links: Self::Output = state.creates.into_values().collect();
-        links.sort_by_key(|l| l.timestamp);
+        self.query
+            .paginate(&mut links, |link: &Link| LinkCursor::from(link));
         Ok(links)
     }
 }
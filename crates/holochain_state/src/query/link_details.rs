@@ -1,4 +1,5 @@
 use holo_hash::*;
+use holochain_types::link::WireLinkKey;
 use holochain_zome_types::*;
 use std::fmt::Debug;
 
@@ -16,6 +17,14 @@ impl GetLinkDetailsQuery {
             query: LinksQuery::new(base, type_query, tag),
         }
     }
+
+    /// Get the details of the links matching all the filters of a [`WireLinkKey`],
+    /// including its limit and cursor.
+    pub fn from_key(key: WireLinkKey) -> Self {
+        Self {
+            query: LinksQuery::from_key(key),
+        }
+    }
 }
 
 impl Query for GetLinkDetailsQuery {
@@ -41,25 +50,19 @@ impl Query for GetLinkDetailsQuery {
     }
 
     fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
-        let query = &self.query;
-        let base_filter = query.base.clone();
-        let type_query_filter = query.type_query.clone();
-        let tag_filter = query.tag.clone();
+        let query = self.query.clone();
         let f = move |action: &QueryData<Self>| match action.action() {
-            Action::CreateLink(CreateLink {
-                base_address,
-                tag,
-                zome_index,
-                link_type,
-                ..
-            }) => {
-                *base_address == *base_filter
-                    && type_query_filter.contains(zome_index, link_type)
-                    && tag_filter
-                        .as_ref()
-                        .map_or(true, |t| LinksQuery::tag_to_hex(tag).starts_with(&(**t)))
+            Action::CreateLink(create_link) => {
+                create_link.base_address == *query.base
+                    && query
+                        .type_query
+                        .contains(&create_link.zome_index, &create_link.link_type)
+                    && query.tag.as_ref().map_or(true, |t| {
+                        LinksQuery::tag_to_hex(&create_link.tag).starts_with(&(**t))
+                    })
+                    && query.matches_filters(create_link, action.as_hash())
             }
-            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *base_filter,
+            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *query.base,
             _ => false,
         };
         Box::new(f)
@@ -106,7 +109,10 @@ impl Query for GetLinkDetailsQuery {
                 })
             })
             .collect::<Vec<_>>();
-        r.sort_by_key(|l| l.0.action().timestamp());
+        self.query.paginate(&mut r, |(create, _)| LinkCursor {
+            timestamp: create.action().timestamp(),
+            create_link_hash: create.as_hash().clone(),
+        });
         Ok(r)
     }
 }
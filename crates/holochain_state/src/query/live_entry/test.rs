@@ -13,7 +13,7 @@ async fn can_handle_update_in_scratch() {
     holochain_trace::test_run().ok();
     let mut scratch = Scratch::new();
     let mut conn = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
     let mut txn = conn
         .transaction_with_behavior(TransactionBehavior::Exclusive)
@@ -33,10 +33,10 @@ async fn get_links() {
     holochain_trace::test_run().ok();
     let mut scratch = Scratch::new();
     let mut conn = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
     let mut cache = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut cache, None).unwrap();
+    SCHEMA_CELL.initialize(&mut cache, None, None).unwrap();
 
     let mut txn = conn
         .transaction_with_behavior(TransactionBehavior::Exclusive)
@@ -114,10 +114,10 @@ async fn get_entry() {
     holochain_trace::test_run().ok();
     let mut scratch = Scratch::new();
     let mut conn = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
     let mut cache = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut cache, None).unwrap();
+    SCHEMA_CELL.initialize(&mut cache, None, None).unwrap();
 
     let mut txn = conn
         .transaction_with_behavior(TransactionBehavior::Exclusive)
@@ -180,8 +180,8 @@ async fn insert_op_equivalence() {
     holochain_trace::test_run().ok();
     let mut conn1 = Connection::open_in_memory().unwrap();
     let mut conn2 = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn1, None).unwrap();
-    SCHEMA_CELL.initialize(&mut conn2, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn1, None, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn2, None, None).unwrap();
 
     let mut create_action = fixt!(Create);
     let create_entry = fixt!(Entry);
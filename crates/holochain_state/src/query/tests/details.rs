@@ -12,7 +12,7 @@ async fn entry_scratch_same_as_sql() {
     holochain_trace::test_run().ok();
     let mut scratch = Scratch::new();
     let mut conn = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
     let mut txn = conn
         .transaction_with_behavior(TransactionBehavior::Exclusive)
@@ -46,7 +46,7 @@ async fn record_scratch_same_as_sql() {
     holochain_trace::test_run().ok();
     let mut scratch = Scratch::new();
     let mut conn = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
     let mut txn = conn
         .transaction_with_behavior(TransactionBehavior::Exclusive)
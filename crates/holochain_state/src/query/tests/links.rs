@@ -5,7 +5,7 @@ use super::*;
 #[tokio::test(flavor = "multi_thread")]
 async fn link_queries_are_ordered_by_timestamp() {
     let mut conn = Connection::open_in_memory().unwrap();
-    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
     let mut txn = conn
         .transaction_with_behavior(TransactionBehavior::Exclusive)
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -43,7 +44,11 @@ use holochain_zome_types::ActionHashed;
 use holochain_zome_types::ActionType;
 use holochain_zome_types::ActionUnweighed;
 use holochain_zome_types::CapAccess;
+use holochain_zome_types::CapClaim;
+use holochain_zome_types::CapClaimQuery;
 use holochain_zome_types::CapGrant;
+use holochain_zome_types::CapGrantInfo;
+use holochain_zome_types::CapGrantQuery;
 use holochain_zome_types::CapSecret;
 use holochain_zome_types::CellId;
 use holochain_zome_types::ChainQueryFilter;
@@ -52,6 +57,7 @@ use holochain_zome_types::CounterSigningAgentState;
 use holochain_zome_types::CounterSigningSessionData;
 use holochain_zome_types::Entry;
 use holochain_zome_types::EntryRateWeight;
+use holochain_zome_types::EntryType;
 use holochain_zome_types::EntryVisibility;
 use holochain_zome_types::GrantedFunction;
 use holochain_zome_types::MembraneProof;
@@ -168,6 +174,7 @@ impl SourceChain {
                     author.as_ref(),
                     preflight_request.session_times.end(),
                 )?;
+                insert_countersigning_session(txn, author.as_ref(), &preflight_request)?;
                 SourceChainResult::Ok(countersigning_agent_state)
             })
             .await?;
@@ -237,6 +244,21 @@ impl SourceChain {
         chain_top_ordering: ChainTopOrdering,
         weight: W,
     ) -> SourceChainResult<ActionHash> {
+        let unweighed = self.next_action(action_builder)?;
+        self.put_with_action(
+            unweighed.weighed(weight).into(),
+            maybe_entry,
+            chain_top_ordering,
+        )
+        .await
+    }
+
+    /// Build the unweighed action which would come next on this chain, so that
+    /// it can be weighed before being put with [`SourceChain::put_with_action`].
+    pub fn next_action<U: ActionUnweighed, B: ActionBuilder<U>>(
+        &self,
+        action_builder: B,
+    ) -> SourceChainResult<U> {
         let HeadInfo {
             action: prev_action,
             seq: chain_head_seq,
@@ -261,12 +283,7 @@ impl SourceChain {
             action_seq,
             prev_action,
         };
-        self.put_with_action(
-            action_builder.build(common).weighed(weight).into(),
-            maybe_entry,
-            chain_top_ordering,
-        )
-        .await
+        Ok(action_builder.build(common))
     }
 
     #[cfg(feature = "test_utils")]
@@ -311,12 +328,14 @@ impl SourceChain {
             chc.add_entries(entries.clone())
                 .await
                 .map_err(SourceChainError::other)?;
-            if let Err(err @ ChcError::InvalidChain(_, _)) = chc.add_actions(actions.clone()).await
-            {
-                return Err(SourceChainError::ChcHeadMoved(
-                    "SourceChain::flush".into(),
-                    err,
-                ));
+            match chc.add_actions(actions.clone()).await {
+                Err(err @ ChcError::InvalidChain(_, _)) => {
+                    return Err(SourceChainError::ChcHeadMoved(
+                        "SourceChain::flush".into(),
+                        err,
+                    ));
+                }
+                e => e.map_err(SourceChainError::other)?,
             }
         }
 
@@ -368,6 +387,10 @@ impl SourceChain {
                     ));
                 }
 
+                if let Some(new_dna_hash) = chain_closed_db(txn, author.as_ref())? {
+                    return Err(SourceChainError::ChainClosed(new_dna_hash));
+                }
+
                 if is_chain_locked(txn, &lock, author.as_ref())? {
                     return Err(SourceChainError::ChainLocked);
                 }
@@ -395,6 +418,17 @@ impl SourceChain {
                         set_withhold_publish(txn, op_hash)?;
                     }
                 }
+                if is_countersigning_session {
+                    // There is exactly one action in a countersigning commit.
+                    if let Some(entry_hash) = actions[0].action().entry_hash() {
+                        set_countersigning_session_action(
+                            txn,
+                            author.as_ref(),
+                            entry_hash,
+                            actions[0].as_hash(),
+                        )?;
+                    }
+                }
                 SourceChainResult::Ok(actions)
             })
             .await
@@ -587,6 +621,17 @@ where
         self.zomes_initialized.store(value, Ordering::Relaxed);
     }
 
+    /// If this chain has been closed by migrating its agent to another DNA,
+    /// get the hash of the DNA it was migrated to.
+    /// Nothing more can be written to a closed chain.
+    pub async fn closed_for_dna(&self) -> SourceChainResult<Option<DnaHash>> {
+        let author = self.author.clone();
+        Ok(self
+            .vault
+            .async_reader(move |txn| chain_closed_db(&txn, &author))
+            .await?)
+    }
+
     pub fn is_empty(&self) -> SourceChainResult<bool> {
         Ok(self.len()? == 0)
     }
@@ -714,6 +759,81 @@ where
         Ok(valid_cap_grant)
     }
 
+    /// List the capability grants committed to this chain which match the query.
+    /// A grant is revoked by the first update or delete on this chain which
+    /// references it.
+    pub async fn cap_grants(&self, query: CapGrantQuery) -> SourceChainResult<Vec<CapGrantInfo>> {
+        let revoked = self.revoked_actions().await?;
+        let grants = self
+            .query(
+                QueryFilter::new()
+                    .entry_type(EntryType::CapGrant)
+                    .include_entries(true),
+            )
+            .await?;
+        Ok(grants
+            .into_iter()
+            .filter_map(|record| {
+                let cap_grant = match record.entry().as_option()? {
+                    Entry::CapGrant(cap_grant) => cap_grant.clone(),
+                    _ => return None,
+                };
+                let action_hash = record.action_address().clone();
+                let revoked_at = revoked.get(&action_hash).cloned();
+                (query.check(&cap_grant) && (query.include_revoked || revoked_at.is_none())).then(
+                    || CapGrantInfo {
+                        cap_grant,
+                        action_hash,
+                        created_at: record.action().timestamp(),
+                        revoked_at,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// List the capability claims committed to this chain which match the
+    /// query and have not been deleted.
+    pub async fn cap_claims(&self, query: CapClaimQuery) -> SourceChainResult<Vec<CapClaim>> {
+        let revoked = self.revoked_actions().await?;
+        let claims = self
+            .query(
+                QueryFilter::new()
+                    .entry_type(EntryType::CapClaim)
+                    .include_entries(true),
+            )
+            .await?;
+        Ok(claims
+            .into_iter()
+            .filter(|record| !revoked.contains_key(record.action_address()))
+            .filter_map(|record| record.entry().as_option()?.as_cap_claim().cloned())
+            .filter(|claim| query.check(claim))
+            .collect())
+    }
+
+    /// The earliest time each action on this chain was updated or deleted.
+    async fn revoked_actions(&self) -> SourceChainResult<HashMap<ActionHash, Timestamp>> {
+        let mut revoked: HashMap<ActionHash, Timestamp> = HashMap::new();
+        for action_type in [ActionType::Update, ActionType::Delete] {
+            for record in self
+                .query(QueryFilter::new().action_type(action_type))
+                .await?
+            {
+                let timestamp = record.action().timestamp();
+                let original = match record.action() {
+                    Action::Update(update) => update.original_action_address.clone(),
+                    Action::Delete(delete) => delete.deletes_address.clone(),
+                    _ => continue,
+                };
+                revoked
+                    .entry(original)
+                    .and_modify(|t| *t = (*t).min(timestamp))
+                    .or_insert(timestamp);
+            }
+        }
+        Ok(revoked)
+    }
+
     /// Query Actions in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
@@ -1142,6 +1262,32 @@ pub fn chain_head_db_nonempty(
     chain_head_db(txn, author)?.ok_or(SourceChainError::ChainEmpty)
 }
 
+/// If the author's chain has been closed by migrating to another DNA,
+/// get the hash of the DNA it was migrated to.
+pub fn chain_closed_db(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Option<DnaHash>> {
+    let blob = txn
+        .query_row(
+            "
+            SELECT blob FROM Action
+            WHERE author = :author AND type = :close_chain
+            LIMIT 1
+            ",
+            named_params! {
+                ":author": author,
+                ":close_chain": ActionType::CloseChain.to_string(),
+            },
+            |row| row.get::<_, Vec<u8>>(0),
+        )
+        .optional()?;
+    Ok(match blob.map(from_blob::<SignedAction>).transpose()? {
+        Some(SignedAction(Action::CloseChain(close_chain), _)) => Some(close_chain.new_dna_hash),
+        _ => None,
+    })
+}
+
 /// Check if there is a current countersigning session and if so, return the
 /// session data and the entry hash.
 pub fn current_countersigning_session(
@@ -1372,9 +1518,7 @@ pub mod tests {
         )
         .await?;
 
-        let action_builder = builder::CloseChain {
-            new_dna_hash: fixt!(DnaHash),
-        };
+        let action_builder = builder::InitZomesComplete {};
         chain_1
             .put(action_builder.clone(), None, ChainTopOrdering::Strict)
             .await?;
@@ -1416,6 +1560,72 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn nothing_is_written_after_chain_is_closed() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let new_chain = || {
+            SourceChain::new(
+                db.clone().into(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+        };
+
+        let chain: SourceChain = new_chain().await?;
+        assert_eq!(chain.closed_for_dna().await?, None);
+        let new_dna_hash = fake_dna_hash(2);
+        chain
+            .put(
+                builder::CloseChain {
+                    new_dna_hash: new_dna_hash.clone(),
+                },
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        chain.flush(&mock).await?;
+        assert_eq!(chain.closed_for_dna().await?, Some(new_dna_hash.clone()));
+
+        let chain: SourceChain = new_chain().await?;
+        chain
+            .put(
+                builder::InitZomesComplete {},
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        assert_matches!(
+            chain.flush(&mock).await,
+            Err(SourceChainError::ChainClosed(dna_hash)) if dna_hash == new_dna_hash
+        );
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_relaxed_ordering_with_entry() -> SourceChainResult<()> {
         let test_db = test_authored_db();
@@ -1867,6 +2077,179 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_cap_grants_and_claims() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChain::new(
+            db.clone(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+
+        let foo: GrantedFunction = ("zome".into(), "foo".into());
+        let secret = || CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let foo_grant = ZomeCallCapGrant::new(
+            "foo".into(),
+            CapAccess::from(secret()),
+            GrantedFunctions::Listed([foo.clone()].into_iter().collect()),
+        );
+        let all_grant = ZomeCallCapGrant::new(
+            "all".into(),
+            CapAccess::from(secret()),
+            GrantedFunctions::All,
+        );
+        let bobs_claim = CapClaim::new("bob".into(), bob.clone(), secret());
+        let alices_claim = CapClaim::new("alice".into(), alice.clone(), secret());
+
+        let create = |entry: Entry, entry_type: EntryType| {
+            let chain = &chain;
+            async move {
+                let (entry, entry_hash) = EntryHashed::from_content_sync(entry).into_inner();
+                let action = chain
+                    .put_weightless(
+                        builder::Create {
+                            entry_type,
+                            entry_hash: entry_hash.clone(),
+                        },
+                        Some(entry),
+                        ChainTopOrdering::default(),
+                    )
+                    .await
+                    .unwrap();
+                (action, entry_hash)
+            }
+        };
+        let (foo_action, foo_entry) =
+            create(Entry::CapGrant(foo_grant.clone()), EntryType::CapGrant).await;
+        let (all_action, _) = create(Entry::CapGrant(all_grant.clone()), EntryType::CapGrant).await;
+        create(Entry::CapClaim(bobs_claim.clone()), EntryType::CapClaim).await;
+        let (alices_claim_action, alices_claim_entry) =
+            create(Entry::CapClaim(alices_claim.clone()), EntryType::CapClaim).await;
+        chain.flush(&mock).await.unwrap();
+
+        let chain = SourceChain::new(
+            db.clone(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+        let hashes = |grants: Vec<CapGrantInfo>| {
+            grants
+                .into_iter()
+                .map(|g| g.action_hash)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            hashes(chain.cap_grants(CapGrantQuery::new()).await?),
+            vec![foo_action.clone(), all_action.clone()]
+        );
+        assert_eq!(
+            hashes(chain.cap_grants(CapGrantQuery::new().tag("all")).await?),
+            vec![all_action.clone()]
+        );
+        // Grants for all functions give access to any function.
+        assert_eq!(
+            hashes(
+                chain
+                    .cap_grants(CapGrantQuery::new().function(foo.clone()))
+                    .await?
+            ),
+            vec![foo_action.clone(), all_action.clone()]
+        );
+        assert_eq!(
+            hashes(
+                chain
+                    .cap_grants(CapGrantQuery::new().function(("zome".into(), "bar".into())))
+                    .await?
+            ),
+            vec![all_action.clone()]
+        );
+
+        // Revoke the foo grant with an update and delete alice's claim,
+        // without flushing so that the scratch is checked too.
+        let updated_grant =
+            ZomeCallCapGrant::new("foo".into(), CapAccess::Unrestricted, GrantedFunctions::All);
+        let (entry, entry_hash) =
+            EntryHashed::from_content_sync(Entry::CapGrant(updated_grant.clone())).into_inner();
+        let update_action = chain
+            .put_weightless(
+                builder::Update {
+                    entry_type: EntryType::CapGrant,
+                    entry_hash,
+                    original_action_address: foo_action.clone(),
+                    original_entry_address: foo_entry,
+                },
+                Some(entry),
+                ChainTopOrdering::default(),
+            )
+            .await?;
+        chain
+            .put_weightless(
+                builder::Delete {
+                    deletes_address: alices_claim_action,
+                    deletes_entry_address: alices_claim_entry,
+                },
+                None,
+                ChainTopOrdering::default(),
+            )
+            .await?;
+
+        let live = chain.cap_grants(CapGrantQuery::new().tag("foo")).await?;
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].action_hash, update_action);
+        assert_eq!(live[0].cap_grant, updated_grant);
+        assert_eq!(live[0].revoked_at, None);
+
+        let all = chain
+            .cap_grants(CapGrantQuery::new().tag("foo").include_revoked(true))
+            .await?;
+        assert_eq!(hashes(all.clone()), vec![foo_action, update_action]);
+        assert_eq!(all[0].cap_grant, foo_grant);
+        assert!(all[0].revoked_at.is_some());
+
+        assert_eq!(
+            chain.cap_claims(CapClaimQuery::new()).await?,
+            vec![bobs_claim.clone()]
+        );
+        assert_eq!(
+            chain.cap_claims(CapClaimQuery::new().grantor(bob)).await?,
+            vec![bobs_claim]
+        );
+        assert!(chain
+            .cap_claims(CapClaimQuery::new().tag("alice"))
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
     // @todo bring all this back when we want to administer cap claims better
     // #[tokio::test(flavor = "multi_thread")]
     // async fn test_get_cap_claim() -> SourceChainResult<()> {
@@ -48,6 +48,9 @@ pub enum SourceChainError {
     #[error("Attempted to write a countersigning session that has already expired")]
     LockExpired,
 
+    #[error("Attempted to write to a source chain which was closed when its agent migrated to the DNA {0}")]
+    ChainClosed(DnaHash),
+
     #[error("Attempted to write anything other than the countersigning session entry at the same time as the session entry.")]
     DirtyCounterSigningWrite,
 
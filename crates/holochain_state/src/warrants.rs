@@ -0,0 +1,87 @@
+//! Queries for warrants held by agent activity authorities.
+
+use holo_hash::AgentPubKey;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::prelude::SignedWarrant;
+
+use crate::prelude::from_blob;
+use crate::prelude::StateQueryResult;
+
+/// List all warrants held against the given agent.
+pub fn list_warrants(
+    txn: &Transaction,
+    warrantee: &AgentPubKey,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT blob FROM Warrant WHERE warrantee = :warrantee
+        ",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":warrantee": warrantee
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::insert_warrant;
+    use crate::test_utils::fresh_reader_test;
+    use crate::test_utils::test_dht_db;
+    use fixt::prelude::*;
+    use holochain_types::prelude::*;
+    use holochain_zome_types::fixt::*;
+
+    #[test]
+    fn warrants_are_listed_by_warrantee_without_duplicates() {
+        let db = test_dht_db().to_db();
+        let warrant = |author: AgentPubKey, warrantee: AgentPubKey, seq: u32| {
+            let mut action = fixt!(Create);
+            action.author = warrantee;
+            action.action_seq = seq;
+            SignedWarrant::new(
+                Warrant::new(
+                    WarrantProof::InvalidAction {
+                        action: SignedAction(action.into(), fixt!(Signature)),
+                        validation_type: ValidationType::App,
+                    },
+                    author,
+                    Timestamp::now(),
+                ),
+                fixt!(Signature),
+            )
+        };
+        let alice = fake_agent_pubkey_1();
+        let bob = fake_agent_pubkey_2();
+        let carol = fixt!(AgentPubKey);
+
+        let w1 = warrant(alice.clone(), bob.clone(), 1);
+        let w2 = warrant(carol.clone(), bob.clone(), 1);
+        let w3 = warrant(alice.clone(), carol.clone(), 1);
+        // The same author warranting the same action again is ignored.
+        let mut w1_again = w1.clone();
+        w1_again.warrant.timestamp =
+            (w1.warrant.timestamp + std::time::Duration::from_secs(1)).unwrap();
+
+        db.test_commit(|txn| {
+            for w in [w1.clone(), w2.clone(), w3.clone(), w1_again] {
+                insert_warrant(txn, w).unwrap();
+            }
+        });
+
+        fresh_reader_test(db, |txn| {
+            let mut bobs = list_warrants(&txn, &bob).unwrap();
+            bobs.sort_by_key(|w| w.warrant.author.clone());
+            let mut expected = vec![w1, w2];
+            expected.sort_by_key(|w| w.warrant.author.clone());
+            assert_eq!(bobs, expected);
+            assert_eq!(list_warrants(&txn, &carol).unwrap(), vec![w3]);
+            assert!(list_warrants(&txn, &alice).unwrap().is_empty());
+        });
+    }
+}
@@ -75,7 +75,7 @@ fn create_corrupt_db<Kind: DbKindT>(kind: Kind, u: &mut arbitrary::Unstructured)
     std::fs::create_dir_all(path.parent().unwrap()).unwrap();
     let mut conn = Connection::open(&path).unwrap();
     holochain_sqlite::schema::SCHEMA_CELL
-        .initialize(&mut conn, Some(kind.kind()))
+        .initialize(&mut conn, Some(kind.kind()), None)
         .unwrap();
     let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
         Signature::arbitrary(u).unwrap(),
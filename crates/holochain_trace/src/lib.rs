@@ -73,15 +73,20 @@
 //! tad log.csv
 //! ```
 
+use once_cell::sync::OnceCell;
 use tracing::Subscriber;
 use tracing_subscriber::{
     filter::EnvFilter,
-    fmt::{format::FmtSpan, time::UtcTime},
+    fmt::{format::FmtSpan, time::UtcTime, writer::BoxMakeWriter},
+    prelude::*,
     registry::LookupSpan,
-    FmtSubscriber,
+    reload, Registry,
 };
 
-use std::{str::FromStr, sync::Once};
+use std::{
+    str::FromStr,
+    sync::{Arc, Once},
+};
 
 use flames::{toml_path, FlameTimed};
 use fmt::*;
@@ -89,13 +94,17 @@ use fmt::*;
 mod flames;
 mod fmt;
 pub mod metrics;
-// mod open;
+mod open;
+mod rotating_file;
 
-// #[cfg(all(feature = "opentelemetry-on", feature = "channels"))]
-// pub use open::channel;
-// #[cfg(feature = "opentelemetry-on")]
-// pub use open::should_run;
-// pub use open::{Config, Context, MsgWrap, OpenSpanExt};
+#[cfg(all(feature = "opentelemetry-on", feature = "channels"))]
+pub use open::channel;
+#[cfg(feature = "opentelemetry-on")]
+pub use open::should_run;
+#[cfg(feature = "opentelemetry-on")]
+pub use open::LocalCollector;
+pub use open::{Context, MsgWrap, OpenSpanExt};
+pub use rotating_file::{RotatingFile, RotatingFileWriter};
 
 pub use tracing;
 
@@ -116,8 +125,9 @@ pub enum Output {
     FlameTimed,
     /// Creates a flamegraph from timed spans using idle time
     IceTimed,
-    // /// Opentelemetry tracing
-    // OpenTel,
+    /// Regular logging plus exporting spans to an OpenTelemetry collector over OTLP.
+    /// The collector is set with `OTEL_EXPORTER_OTLP_ENDPOINT`.
+    OpenTel,
     /// No logging to console
     None,
 }
@@ -127,6 +137,11 @@ pub type ParseError = String;
 
 static INIT: Once = Once::new();
 
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Changes the filter of the global subscriber.
+static FILTER_HANDLE: OnceCell<FilterHandle> = OnceCell::new();
+
 impl FromStr for Output {
     type Err = ParseError;
     fn from_str(day: &str) -> Result<Self, Self::Err> {
@@ -138,7 +153,7 @@ impl FromStr for Output {
             "LogTimed" => Ok(Output::LogTimed),
             "FlameTimed" => Ok(Output::FlameTimed),
             "Compact" => Ok(Output::Compact),
-            // "OpenTel" => Ok(Output::OpenTel),
+            "OpenTel" => Ok(Output::OpenTel),
             "None" => Ok(Output::None),
             _ => Err("Could not parse log output type".into()),
         }
@@ -155,14 +170,52 @@ pub fn test_run() -> Result<(), errors::TracingError> {
     init_fmt(Output::Log)
 }
 
-// /// Run tracing in a test that uses open telemetry to
-// /// send span contexts across process and thread boundaries.
-// pub fn test_run_open() -> Result<(), errors::TracingError> {
-//     if std::env::var_os("RUST_LOG").is_none() {
-//         return Ok(());
-//     }
-//     init_fmt(Output::OpenTel)
-// }
+/// Run tracing in a test that uses open telemetry to
+/// send span contexts across process and thread boundaries.
+/// Spans are exported to the returned [`LocalCollector`] instead of a real collector,
+/// and logs are only output if RUST_LOG is set.
+/// Spans are recorded at the levels in OPEN_TEL_FILTER,
+/// or `info,holochain=debug,holochain_p2p=debug,holochain_cascade=debug` by default.
+///
+/// The collector is shared by every test in the process, so look for the
+/// spans of a particular test by their trace. This must be called before
+/// any other tracing is set up in the process, or no spans are collected.
+#[cfg(feature = "opentelemetry-on")]
+pub fn test_run_open() -> Result<LocalCollector, errors::TracingError> {
+    // The tracer only holds a weak reference to its provider,
+    // so the provider must be created once and kept alive.
+    static LOCAL: once_cell::sync::Lazy<(LocalCollector, opentelemetry_sdk::trace::Tracer)> =
+        once_cell::sync::Lazy::new(|| {
+            let collector = LocalCollector::default();
+            (collector.clone(), open::local_tracer(collector))
+        });
+
+    let filter = EnvFilter::try_from_env("OPEN_TEL_FILTER").unwrap_or_else(|_| {
+        EnvFilter::new("info,holochain=debug,holochain_p2p=debug,holochain_cascade=debug")
+    });
+    let logs = std::env::var_os("RUST_LOG").is_some().then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(EnvFilter::from_default_env())
+    });
+    let telemetry = tracing_opentelemetry::layer()
+        .with_tracer(LOCAL.1.clone())
+        .with_filter(filter);
+    open::OPEN_ON.store(true, std::sync::atomic::Ordering::SeqCst);
+    finish(
+        tracing_subscriber::registry().with(telemetry).with(logs),
+        None,
+    )?;
+    Ok(LOCAL.0.clone())
+}
+
+/// Export any spans which haven't been sent to the OpenTelemetry collector yet.
+/// Call this before the process exits when using [`Output::OpenTel`].
+/// This blocks, so call it outside of an async context.
+pub fn shutdown_open() {
+    #[cfg(feature = "opentelemetry-on")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
 
 /// Same as test_run but with timed spans
 pub fn test_run_timed() -> Result<(), errors::TracingError> {
@@ -223,9 +276,26 @@ pub fn test_run_timed_ice(path: Option<&str>) -> Result<Option<impl Drop>, error
 /// This checks RUST_LOG for a filter but doesn't complain if there is none or it doesn't parse.
 /// It then checks for CUSTOM_FILTER which if set will output an error if it doesn't parse.
 pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
-    let mut filter = match std::env::var("RUST_LOG") {
-        Ok(_) => EnvFilter::from_default_env(),
-        Err(_) => EnvFilter::from_default_env().add_directive("[wasm_debug]=debug".parse()?),
+    init_fmt_with(output, None, None)
+}
+
+/// Same as [init_fmt] but the filter and where logs are written can be set.
+///
+/// If `directives` is given it is used as the filter instead of RUST_LOG,
+/// although CUSTOM_FILTER still overrides it.
+/// If `file` is given logs are written to it instead of stderr.
+/// The filter can be changed later without restarting with [set_filter].
+pub fn init_fmt_with(
+    output: Output,
+    directives: Option<&str>,
+    file: Option<RotatingFile>,
+) -> Result<(), errors::TracingError> {
+    let mut filter = match directives {
+        Some(directives) => EnvFilter::try_new(directives)?,
+        None => match std::env::var("RUST_LOG") {
+            Ok(_) => EnvFilter::from_default_env(),
+            Err(_) => EnvFilter::from_default_env().add_directive("[wasm_debug]=debug".parse()?),
+        },
     };
     if std::env::var("CUSTOM_FILTER").is_ok() {
         EnvFilter::try_from_env("CUSTOM_FILTER")
@@ -236,89 +306,105 @@ pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
             .ok();
     }
 
-    let subscriber = FmtSubscriber::builder()
-        .with_writer(std::io::stderr)
+    // Colors are only useful when writing to a terminal.
+    let ansi = file.is_none();
+    let writer = match file {
+        Some(file) => BoxMakeWriter::new(Arc::new(file.open()?)),
+        None => BoxMakeWriter::new(std::io::stderr),
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+    let handle = Some(handle);
+    let registry = tracing_subscriber::registry().with(filter);
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi)
         .with_file(true)
         .with_line_number(true)
         .with_target(true);
 
     match output {
         Output::Json => {
-            let subscriber = subscriber
-                .with_env_filter(filter)
+            let layer = layer
                 .with_timer(UtcTime::rfc_3339())
                 .json()
                 .event_format(FormatEvent);
-            finish(subscriber.finish())
+            finish(registry.with(layer), handle)
         }
         Output::JsonTimed => {
-            let subscriber = subscriber
+            let layer = layer
                 .with_span_events(FmtSpan::CLOSE)
-                .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
                 .json()
                 .event_format(FormatEvent);
-            finish(subscriber.finish())
+            finish(registry.with(layer), handle)
         }
-        Output::Log => finish(subscriber.with_env_filter(filter).finish()),
+        Output::Log => finish(registry.with(layer), handle),
         Output::LogTimed => {
-            let subscriber = subscriber.with_span_events(FmtSpan::CLOSE);
-            finish(subscriber.with_env_filter(filter).finish())
+            let layer = layer.with_span_events(FmtSpan::CLOSE);
+            finish(registry.with(layer), handle)
         }
         Output::FlameTimed => {
-            let subscriber = subscriber
+            let layer = layer
                 .with_span_events(FmtSpan::CLOSE)
-                .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
                 .event_format(FormatEventFlame);
-            finish(subscriber.finish())
+            finish(registry.with(layer), handle)
         }
         Output::IceTimed => {
-            let subscriber = subscriber
+            let layer = layer
                 .with_span_events(FmtSpan::CLOSE)
-                .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
                 .event_format(FormatEventIce);
-            finish(subscriber.finish())
+            finish(registry.with(layer), handle)
         }
-        Output::Compact => {
-            let subscriber = subscriber.compact();
-            finish(subscriber.with_env_filter(filter).finish())
+        Output::Compact => finish(registry.with(layer.compact()), handle),
+        Output::OpenTel => {
+            #[cfg(feature = "opentelemetry-on")]
+            {
+                use open::OPEN_ON;
+                OPEN_ON.store(true, std::sync::atomic::Ordering::SeqCst);
+                let telemetry = tracing_opentelemetry::layer().with_tracer(open::otlp_tracer()?);
+                finish(registry.with(layer).with(telemetry), handle)
+            }
+            #[cfg(not(feature = "opentelemetry-on"))]
+            {
+                // There is nowhere to export spans to, so just log.
+                finish(registry.with(layer), handle)
+            }
         }
-        // Output::OpenTel => {
-        //     #[cfg(feature = "opentelemetry-on")]
-        //     {
-        //         use open::OPEN_ON;
-        //         use opentelemetry::api::Provider;
-        //         OPEN_ON.store(true, std::sync::atomic::Ordering::SeqCst);
-        //         use tracing_subscriber::prelude::*;
-        //         open::init();
-        //         let tracer = opentelemetry::sdk::Provider::default().get_tracer("component_name");
-        //         let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
-        //         finish(
-        //             subscriber
-        //                 .with_env_filter(filter)
-        //                 .finish()
-        //                 .with(telemetry)
-        //                 .with(open::OpenLayer),
-        //         )
-        //     }
-        //     #[cfg(not(feature = "opentelemetry-on"))]
-        //     {
-        //         Ok(())
-        //     }
-        // }
         Output::None => Ok(()),
     }
 }
 
-fn finish<S>(subscriber: S) -> Result<(), errors::TracingError>
+/// Change the filter of the subscriber set up by [init_fmt] or [init_fmt_with],
+/// without restarting. The directives use the same syntax as RUST_LOG.
+pub fn set_filter(directives: &str) -> Result<(), errors::TracingError> {
+    let filter = EnvFilter::try_new(directives)?;
+    FILTER_HANDLE
+        .get()
+        .ok_or(errors::TracingError::FilterNotReloadable)?
+        .reload(filter)?;
+    Ok(())
+}
+
+/// The filter currently in use, if it can be changed with [set_filter].
+pub fn current_filter() -> Option<String> {
+    FILTER_HANDLE
+        .get()?
+        .with_current(|filter| filter.to_string())
+        .ok()
+}
+
+fn finish<S>(subscriber: S, handle: Option<FilterHandle>) -> Result<(), errors::TracingError>
 where
     S: Subscriber + Send + Sync + for<'span> LookupSpan<'span>,
 {
     let mut result = Ok(());
     INIT.call_once(|| {
         result = tracing::subscriber::set_global_default(subscriber).map_err(Into::into);
+        if let (Ok(()), Some(handle)) = (&result, handle) {
+            let _ = FILTER_HANDLE.set(handle);
+        }
     });
     result
 }
@@ -338,5 +424,14 @@ pub mod errors {
         TracingFlame,
         #[error(transparent)]
         BadDirective(#[from] tracing_subscriber::filter::ParseError),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Reload(#[from] tracing_subscriber::reload::Error),
+        #[error("Tracing was not set up with a filter which can be changed")]
+        FilterNotReloadable,
+        #[cfg(feature = "opentelemetry-on")]
+        #[error(transparent)]
+        OpenTelemetry(#[from] opentelemetry::trace::TraceError),
     }
 }
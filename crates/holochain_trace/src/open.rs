@@ -29,9 +29,12 @@ pub trait OpenSpanExt {
         #[cfg(feature = "opentelemetry-on")]
         {
             use holochain_serialized_bytes::prelude::*;
-            let wc: WireContext = (&self.get_context().0).into();
-            // This shouldn't fail because there should always be a context
-            // to serialize even if it's empty.
+            let wc = WireContext::from(&self.get_context());
+            if wc.is_empty() {
+                return Vec::with_capacity(0);
+            }
+            // This shouldn't fail because a map of strings
+            // can always be serialized.
             let sb: SerializedBytes = wc.try_into().expect("Failed to serialize tracing wire");
             let ub: UnsafeBytes = sb.into();
             ub.into()
@@ -49,22 +52,24 @@ pub trait OpenSpanExt {
     fn set_current_context(context: Context);
     #[allow(unused_variables)]
     /// Set the context of this span from bytes over the network.
+    /// Empty bytes mean the sender had no context to share and are ignored.
     fn set_from_bytes(&self, bytes: Vec<u8>) {
         #[cfg(feature = "opentelemetry-on")]
         {
             use holochain_serialized_bytes::prelude::*;
-            use opentelemetry::api;
+            if bytes.is_empty() {
+                return;
+            }
             let sb: SerializedBytes = UnsafeBytes::from(bytes).into();
-            let context = match WireContext::try_from(sb) {
-                Ok(w) => api::Context::from(w).into(),
+            match WireContext::try_from(sb) {
+                Ok(w) => self.set_context(w.into()),
                 Err(e) => {
                     tracing::error!(
-                        msg = "Failed to deserialize tracing wire context into context"
+                        ?e,
+                        "Failed to deserialize tracing wire context into context"
                     );
-                    Context::new()
                 }
-            };
-            self.set_context(context);
+            }
         }
     }
     /// Set the current span context from message pack bytes.
@@ -76,92 +81,52 @@ pub trait OpenSpanExt {
 #[cfg(feature = "opentelemetry-on")]
 #[warn(missing_docs)]
 mod on {
-    use once_cell::sync::OnceCell;
-
     use super::*;
     use holochain_serialized_bytes::prelude::*;
-    use opentelemetry::api::{self, KeyValue, Link, SpanContext, TraceContextExt, Value};
+    use opentelemetry::propagation::TextMapPropagator;
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::trace::TraceResult;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::export::trace::SpanData;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::Span;
+    use opentelemetry_sdk::trace::SpanProcessor;
+    use opentelemetry_sdk::trace::Tracer;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicBool;
     use std::sync::atomic::Ordering;
-    use std::{collections::HashMap, ffi::OsString, sync::atomic::AtomicBool};
-    use tracing::{span::Attributes, Subscriber};
+    use std::sync::Arc;
+    use std::sync::Mutex;
     use tracing_opentelemetry::OpenTelemetrySpanExt;
-    use tracing_subscriber::{registry::LookupSpan, Layer};
 
     pub(crate) static OPEN_ON: AtomicBool = AtomicBool::new(false);
-    static CONFIG: OnceCell<Config> = OnceCell::new();
-    static PROCESS_NAME: OnceCell<String> = OnceCell::new();
 
     /// The context holds the current state of a span.
     /// This can be used to transfer contexts across boundaries.
-    /// A boundary crossing will show up as a follower for a span.
-    #[derive(Debug, Clone, derive_more::From, derive_more::Into)]
-    pub struct Context(pub(super) api::Context);
-
-    /// Configuration for open telemetry tracing.
-    /// These can all be configured by setting the
-    /// `OPEN_TEL='process:true,file:false'`.
-    /// They all have default settings.
-    #[derive(Debug, Clone)]
-    pub struct Config {
-        /// Propagate the name of the process running
-        /// on the sender side of the boundary crossing
-        /// in the context and output when calling `spawn_context!()`.
-        /// [Default: false]
-        pub process: bool,
-        /// Propagate the name of the file and line
-        /// number of the sender side of the boundary crossing
-        /// in the context and output when calling `spawn_context!()`.
-        /// [Default: false]
-        pub file: bool,
-        /// Propagate the name of the span name
-        /// of the sender side of the boundary crossing
-        /// in the context and output when calling `spawn_context!()`.
-        /// [Default: true]
-        pub span_name: bool,
-        /// Require there to be a span enabled for the sending side of
-        /// the boundary crossing. [Default: true]
-        pub require_span: bool,
-    }
-
-    #[derive(Debug, Clone, Serialize, Deserialize, SerializedBytes)]
-    pub struct WireContext {
-        span_context: WireSpanContext,
-        links: Option<WireLinks>,
-    }
+    /// A span given this context becomes a child of the span
+    /// on the other side of the boundary, in the same trace.
+    #[derive(Debug, Clone, Default, derive_more::From, derive_more::Into)]
+    pub struct Context(pub(super) opentelemetry::Context);
 
-    #[derive(
-        Debug, Clone, Serialize, Deserialize, SerializedBytes, derive_more::From, derive_more::Into,
-    )]
-    pub struct WireLinks(pub Vec<WireLink>);
-
-    /// Needed because SB doesn't do u128
-    #[derive(Debug, Clone, Serialize, Deserialize, SerializedBytes)]
-    pub struct WireLink {
-        span_context: WireSpanContext,
-        attributes: Vec<api::KeyValue>,
-    }
-
-    /// Needed because SB doesn't do u128
-    #[derive(Debug, Clone, Serialize, Deserialize, SerializedBytes)]
-    pub struct WireSpanContext {
-        trace_id: String,
-        span_id: api::SpanId,
-        trace_flags: u8,
-        is_remote: bool,
-    }
+    /// The span context as W3C trace context fields,
+    /// i.e. `traceparent` and `tracestate`.
+    #[derive(Debug, Clone, Default, Serialize, Deserialize, SerializedBytes)]
+    pub(super) struct WireContext(HashMap<String, String>);
 
     impl Context {
         /// Create a new blank context.
         /// You usually won't do this and
         /// instead get the context from a span.
         pub fn new() -> Self {
-            Context(api::Context::new())
+            Self::default()
         }
     }
 
-    impl Default for Context {
-        fn default() -> Self {
-            Self::new()
+    impl WireContext {
+        pub(super) fn is_empty(&self) -> bool {
+            self.0.is_empty()
         }
     }
 
@@ -175,10 +140,7 @@ mod on {
             if should_not_run(self) {
                 return Context::new();
             }
-            let context = self.context();
-            let span = context.span().span_context();
-            let context = context.with_remote_span_context(span);
-            get_followers(self, context).into()
+            Context(self.context())
         }
 
         fn get_current_bytes() -> Vec<u8> {
@@ -187,12 +149,10 @@ mod on {
         }
 
         fn set_context(&self, context: Context) {
-            if should_not_run(self) {
+            if should_not_run(self) || !context.0.span().span_context().is_valid() {
                 return;
             }
-
-            self.set_parent(&context.0);
-            set_followers(self, &context.0);
+            self.set_parent(context.0);
         }
 
         fn set_current_context(context: Context) {
@@ -258,297 +218,90 @@ mod on {
     }
 
     fn should_not_run(span: &tracing::Span) -> bool {
-        !OPEN_ON.load(Ordering::Relaxed) || (span.is_disabled() && Config::require_span())
+        !OPEN_ON.load(Ordering::Relaxed) || span.is_disabled()
     }
 
     impl std::fmt::Display for Context {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let context = &self.0;
+            let span = self.0.span();
+            let span_context = span.span_context();
             write!(
                 f,
-                "trace_id: {}",
-                context.span().span_context().trace_id().to_u128()
-            )?;
-            if let Some((_, links)) = context.get::<Vec<Link>>().and_then(|l| l.split_last()) {
-                for link in links {
-                    write!(f, " ->")?;
-                    for kv in link.attributes() {
-                        if let Value::String(v) = &kv.value {
-                            write!(f, " {}: {};", kv.key.as_str(), v)?;
-                        }
-                    }
-                }
-            }
-            Ok(())
-        }
-    }
-
-    pub(crate) fn init() {
-        CONFIG.get_or_init(|| Config::from(std::env::var_os("OPEN_TEL")));
-        PROCESS_NAME.get_or_init(|| {
-            std::env::current_exe()
-                .ok()
-                .and_then(|p| p.file_name().map(|s| s.to_string_lossy().into_owned()))
-                .unwrap_or_else(|| "not_found".to_string())
-        });
-    }
-
-    fn get_followers(span: &tracing::Span, context: api::Context) -> api::Context {
-        let mut links = None;
-        span.with_subscriber(|(id, dispatch)| {
-            if let Some(registry) = dispatch.downcast_ref::<tracing_subscriber::Registry>() {
-                if let Some(span_ref) = registry.span(id) {
-                    let extensions = span_ref.extensions();
-                    if let Some(sb) = extensions.get::<api::SpanBuilder>() {
-                        links = sb.links.clone();
-                    }
-                }
-            }
-        });
-
-        let links = links
-            .map(|mut l| {
-                if let Some(link) = create_link(span, &context) {
-                    l.push(link);
-                }
-                l
-            })
-            .or_else(|| create_link(span, &context).map(|l| vec![l]));
-
-        match links {
-            Some(links) => context.with_value(links),
-            None => context,
+                "trace_id: {}, span_id: {}",
+                span_context.trace_id(),
+                span_context.span_id()
+            )
         }
     }
 
-    fn set_followers(span: &tracing::Span, context: &api::Context) {
-        let new_links = context.get::<Vec<Link>>().cloned().unwrap_or_default();
-        if !new_links.is_empty() {
-            span.with_subscriber(|(id, dispatch)| {
-                if let Some(registry) = dispatch.downcast_ref::<tracing_subscriber::Registry>() {
-                    if let Some(span_ref) = registry.span(id) {
-                        let mut extensions = span_ref.extensions_mut();
-                        if let Some(sb) = extensions.get_mut::<api::SpanBuilder>() {
-                            let mut new_links = new_links
-                                .into_iter()
-                                .rev()
-                                .take_while(|link| {
-                                    Some(link.span_context().span_id()) != sb.span_id
-                                })
-                                .collect::<Vec<_>>();
-                            new_links.reverse();
-                            sb.links = Some(new_links);
-                        }
-                    }
-                }
-            });
+    impl From<&Context> for WireContext {
+        fn from(c: &Context) -> Self {
+            let mut fields = HashMap::new();
+            TraceContextPropagator::new().inject_context(&c.0, &mut fields);
+            WireContext(fields)
         }
     }
 
-    fn create_link(span: &tracing::Span, context: &api::Context) -> Option<Link> {
-        if let Some(meta) = span.metadata() {
-            let mut kvs = Vec::with_capacity(2);
-            if Config::span_name() {
-                kvs.push(KeyValue::new("span", meta.name()));
-            }
-            if Config::file() {
-                if let (Some(file), Some(line)) = (meta.file(), meta.line()) {
-                    kvs.push(KeyValue::new("file", format!("{}:{}", file, line)));
-                }
-            }
-            if Config::process() {
-                kvs.push(KeyValue::new(
-                    "process",
-                    PROCESS_NAME
-                        .get()
-                        .cloned()
-                        .unwrap_or_else(|| "not_found".to_string()),
-                ))
-            }
-            let span_context = context.span().span_context();
-            return Some(Link::new(span_context, kvs));
-        }
-        None
-    }
-
-    impl Config {
-        fn require_span() -> bool {
-            CONFIG
-                .get()
-                .map(|c| c.require_span)
-                .unwrap_or_else(|| Config::default().require_span)
-        }
-        fn span_name() -> bool {
-            CONFIG
-                .get()
-                .map(|c| c.span_name)
-                .unwrap_or_else(|| Config::default().span_name)
-        }
-        fn file() -> bool {
-            CONFIG
-                .get()
-                .map(|c| c.file)
-                .unwrap_or_else(|| Config::default().file)
-        }
-        fn process() -> bool {
-            CONFIG
-                .get()
-                .map(|c| c.process)
-                .unwrap_or_else(|| Config::default().process)
+    impl From<WireContext> for Context {
+        fn from(wc: WireContext) -> Self {
+            Context(TraceContextPropagator::new().extract(&wc.0))
         }
     }
 
-    pub struct OpenLayer;
-
-    impl<S> Layer<S> for OpenLayer
-    where
-        S: Subscriber + for<'span> LookupSpan<'span>,
-    {
-        fn new_span(
-            &self,
-            attrs: &Attributes<'_>,
-            id: &tracing::span::Id,
-            ctx: tracing_subscriber::layer::Context<'_, S>,
-        ) {
-            let span = ctx.span(id).expect("Span should not be missing");
-            let mut extensions = span.extensions_mut();
-            if let Some(parent) = attrs.parent() {
-                let parent = ctx.span(parent).expect("Span should not be missing");
-                let parent_extensions = parent.extensions();
-                if let Some((p, s)) = parent_extensions
-                    .get::<api::SpanBuilder>()
-                    .and_then(|p| extensions.get_mut::<api::SpanBuilder>().map(|s| (p, s)))
-                {
-                    s.links = p.links.clone()
-                }
-            } else if attrs.is_contextual() {
-                if let Some(parent) = ctx.lookup_current() {
-                    let parent_extensions = parent.extensions();
-                    if let Some((p, s)) = parent_extensions
-                        .get::<api::SpanBuilder>()
-                        .and_then(|p| extensions.get_mut::<api::SpanBuilder>().map(|s| (p, s)))
-                    {
-                        s.links = p.links.clone()
-                    }
-                }
-            }
-        }
+    /// Export spans over OTLP to the collector at `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// `http://localhost:4317` by default.
+    /// Must be called from within a tokio runtime.
+    pub(crate) fn otlp_tracer() -> TraceResult<Tracer> {
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_env())
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
     }
 
-    impl From<&api::Context> for WireContext {
-        fn from(c: &api::Context) -> Self {
-            let span_context = c.span().span_context().into();
-            let links = c
-                .get::<Vec<Link>>()
-                .cloned()
-                .map(|links| WireLinks(links.into_iter().map(WireLink::from).collect()));
-            WireContext {
-                span_context,
-                links,
-            }
-        }
+    /// Export spans to a [`LocalCollector`].
+    pub(crate) fn local_tracer(collector: LocalCollector) -> Tracer {
+        let provider = TracerProvider::builder()
+            .with_span_processor(collector)
+            .build();
+        let tracer = provider.tracer("holochain_trace");
+        opentelemetry::global::set_tracer_provider(provider);
+        tracer
     }
 
-    impl From<WireContext> for api::Context {
-        fn from(wc: WireContext) -> Self {
-            let mut c = api::Context::new().with_remote_span_context(wc.span_context.into());
-            if let Some(links) = wc.links {
-                let links: Vec<Link> = links.0.into_iter().map(Link::from).collect();
-                c = c.with_value(links);
-            }
-            c
-        }
-    }
+    /// Stands in for an OpenTelemetry collector by keeping every
+    /// finished span in memory, so tests can check how a trace
+    /// was propagated across threads and conductors.
+    #[derive(Debug, Clone, Default)]
+    pub struct LocalCollector(Arc<Mutex<Vec<SpanData>>>);
 
-    impl From<Link> for WireLink {
-        fn from(l: Link) -> Self {
-            WireLink {
-                span_context: l.span_context().clone().into(),
-                attributes: l.attributes().clone(),
-            }
+    impl LocalCollector {
+        /// All the spans which have finished so far.
+        pub fn spans(&self) -> Vec<SpanData> {
+            self.0.lock().expect("Collector lock poisoned").clone()
         }
-    }
 
-    impl From<WireLink> for Link {
-        fn from(wl: WireLink) -> Self {
-            Link::new(wl.span_context.into(), wl.attributes)
+        /// The finished spans with this name.
+        pub fn spans_named(&self, name: &str) -> Vec<SpanData> {
+            self.spans()
+                .into_iter()
+                .filter(|span| span.name == name)
+                .collect()
         }
     }
 
-    impl From<SpanContext> for WireSpanContext {
-        fn from(sc: SpanContext) -> Self {
-            WireSpanContext {
-                trace_id: sc.trace_id().to_u128().to_string(),
-                span_id: sc.span_id(),
-                trace_flags: sc.trace_flags(),
-                is_remote: sc.is_remote(),
-            }
-        }
-    }
+    impl SpanProcessor for LocalCollector {
+        fn on_start(&self, _span: &mut Span, _cx: &opentelemetry::Context) {}
 
-    impl From<WireSpanContext> for SpanContext {
-        fn from(wsc: WireSpanContext) -> Self {
-            SpanContext::new(
-                api::TraceId::from_u128(
-                    wsc.trace_id
-                        .parse::<u128>()
-                        .expect("Failed to parse trace id"),
-                ),
-                wsc.span_id,
-                wsc.trace_flags,
-                wsc.is_remote,
-            )
+        fn on_end(&self, span: SpanData) {
+            self.0.lock().expect("Collector lock poisoned").push(span);
         }
-    }
 
-    impl Default for Config {
-        fn default() -> Self {
-            Self {
-                process: false,
-                file: false,
-                span_name: true,
-                require_span: true,
-            }
+        fn force_flush(&self) -> TraceResult<()> {
+            Ok(())
         }
-    }
-
-    impl From<Option<OsString>> for Config {
-        fn from(var: Option<OsString>) -> Self {
-            let var = match var.and_then(|v| v.into_string().ok()) {
-                Some(var) => var,
-                None => return Self::default(),
-            };
-            let options = var.split(',').filter_map(|kv|{
-                let kv = kv.split(':').map(|i|i.trim()).collect::<Vec<_>>();
-                if kv.len() == 2 {
-                    Some((kv[0], kv[1]))
-                } else {
-                    eprintln!("Failed to parse config from OPEN_TEL.\nFormat is `OPEN_TEL='key: value, key: value'`");
-                    None
-                }
-            })
-            .collect::<HashMap<&str, &str>>();
-            let mut config = Config::default();
-            if let Some(file) = options.get("file").and_then(|v| v.parse::<bool>().ok()) {
-                config.file = file;
-            }
-            if let Some(process) = options.get("process").and_then(|v| v.parse::<bool>().ok()) {
-                config.process = process;
-            }
-            if let Some(span_name) = options
-                .get("span_name")
-                .and_then(|v| v.parse::<bool>().ok())
-            {
-                config.span_name = span_name;
-            }
-            if let Some(require_span) = options
-                .get("require_span")
-                .and_then(|v| v.parse::<bool>().ok())
-            {
-                config.require_span = require_span;
-            }
 
-            config
+        fn shutdown(&mut self) -> TraceResult<()> {
+            Ok(())
         }
     }
 }
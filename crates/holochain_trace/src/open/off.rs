@@ -2,21 +2,6 @@ use super::*;
 #[derive(Debug, Clone, Default)]
 pub struct Context;
 
-#[derive(Debug, Clone)]
-pub struct WireContext {
-    span_context: WireSpanContext,
-    links: Option<WireLinks>,
-}
-
-#[derive(Debug, Clone, derive_more::From, derive_more::Into)]
-pub struct WireLinks(pub Vec<WireLink>);
-
-#[derive(Debug, Clone)]
-pub struct WireLink;
-
-#[derive(Debug, Clone)]
-pub struct WireSpanContext;
-
 impl OpenSpanExt for tracing::Span {
     fn get_current_context() -> Context {
         Context
@@ -44,4 +29,3 @@ impl std::fmt::Display for Context {
         Ok(())
     }
 }
-pub struct Config;
@@ -0,0 +1,161 @@
+//! A log file which is rotated once it reaches a maximum size.
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Where to write logs to on disk and how much disk they may take up.
+///
+/// Logs are written to `path` until it would grow past `max_file_size` bytes.
+/// The file is then renamed to `path.1`, any older `path.N` files are moved
+/// along to `path.N+1`, and logging continues in a new empty file at `path`.
+/// At most `max_files` rotated files are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotatingFile {
+    /// The file currently being written to.
+    pub path: PathBuf,
+    /// The size in bytes a file may grow to before it is rotated.
+    pub max_file_size: u64,
+    /// The number of rotated files to keep alongside the current one.
+    pub max_files: usize,
+}
+
+impl RotatingFile {
+    /// Open the log file for writing, creating it and its directory if needed.
+    pub fn open(self) -> io::Result<RotatingFileWriter> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFileWriter {
+            config: self,
+            current: Mutex::new(Current { file, size }),
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = OsString::from(self.path.as_os_str());
+        path.push(format!(".{}", n));
+        path.into()
+    }
+
+    fn rotate(&self) -> io::Result<File> {
+        if self.max_files > 0 {
+            remove_if_exists(&self.rotated_path(self.max_files))?;
+            for n in (1..self.max_files).rev() {
+                rename_if_exists(&self.rotated_path(n), &self.rotated_path(n + 1))?;
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    }
+}
+
+/// Writes to a [`RotatingFile`].
+/// Share it behind an `Arc` to use it as the writer for a tracing subscriber.
+pub struct RotatingFileWriter {
+    config: RotatingFile,
+    current: Mutex<Current>,
+}
+
+struct Current {
+    file: File,
+    size: u64,
+}
+
+impl Write for &RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut current = self
+            .current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // A single write larger than the limit still goes into its own file.
+        if current.size > 0 && current.size + buf.len() as u64 > self.config.max_file_size {
+            current.file.flush()?;
+            current.file = self.config.rotate()?;
+            current.size = 0;
+        }
+        let written = current.file.write(buf)?;
+        current.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .file
+            .flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_when_full_and_keeps_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("conductor.log");
+        let writer = RotatingFile {
+            path: path.clone(),
+            max_file_size: 10,
+            max_files: 2,
+        }
+        .open()
+        .unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            (&writer).write_all(line.as_bytes()).unwrap();
+        }
+        (&writer).flush().unwrap();
+
+        let read = |p: &Path| std::fs::read_to_string(p).unwrap();
+        assert_eq!(read(&path), "fourth\n");
+        assert_eq!(read(&path.with_extension("log.1")), "third\n");
+        assert_eq!(read(&path.with_extension("log.2")), "second\n");
+        assert!(!path.with_extension("log.3").exists());
+    }
+
+    #[test]
+    fn appends_to_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conductor.log");
+        std::fs::write(&path, "before\n").unwrap();
+        let writer = RotatingFile {
+            path: path.clone(),
+            max_file_size: 1024,
+            max_files: 1,
+        }
+        .open()
+        .unwrap();
+
+        (&writer).write_all(b"after\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "before\nafter\n");
+    }
+}
@@ -0,0 +1,42 @@
+use holochain_trace::{Output, RotatingFile};
+use tracing::*;
+
+#[test]
+fn filter_can_be_changed_while_logging_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("conductor.log");
+    holochain_trace::init_fmt_with(
+        Output::Json,
+        Some("warn"),
+        Some(RotatingFile {
+            path: path.clone(),
+            max_file_size: 1024 * 1024,
+            max_files: 1,
+        }),
+    )
+    .unwrap();
+
+    info!("before the filter changed");
+    warn!("always recorded");
+    holochain_trace::set_filter("info").unwrap();
+    info!("after the filter changed");
+
+    assert_eq!(holochain_trace::current_filter().as_deref(), Some("info"));
+    assert!(holochain_trace::set_filter("info,holochain=loud").is_err());
+
+    let logs = std::fs::read_to_string(&path).unwrap();
+    let messages: Vec<String> = logs
+        .lines()
+        .map(|line| {
+            let line: serde_json::Value = serde_json::from_str(line).unwrap();
+            line["fields"]["message"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "always recorded".to_string(),
+            "after the filter changed".to_string()
+        ]
+    );
+}
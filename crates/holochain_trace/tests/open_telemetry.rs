@@ -1,83 +1,99 @@
-/*
-use holochain_trace::{span_context, Context, OpenSpanExt};
+#![cfg(feature = "opentelemetry-on")]
+
+use holochain_trace::{span_context, LocalCollector, OpenSpanExt};
 use tokio::sync::mpsc;
 use tracing::*;
 
-#[tokio::test(threaded_scheduler)]
+fn span_named(
+    collector: &LocalCollector,
+    name: &str,
+) -> opentelemetry_sdk::export::trace::SpanData {
+    let mut spans = collector.spans_named(name);
+    assert_eq!(spans.len(), 1, "Expected a single span named {}", name);
+    spans.remove(0)
+}
+
+#[tokio::test(flavor = "multi_thread")]
 async fn same_thread_test() {
-    holochain_trace::test_run_open().ok();
-    let span = debug_span!("span a");
-    let context = span.get_context();
-    let _g = span.enter();
+    let collector = holochain_trace::test_run_open().unwrap();
+    {
+        let span = info_span!("span a");
+        let context = span.get_context();
+        let _g = span.enter();
 
-    span_context!(span, Level::DEBUG);
-    debug!(msg = "in span a");
+        span_context!(span, Level::INFO);
+        info!(msg = "in span a");
 
-    let span = debug_span!("span b");
-    let _g = span.enter();
-    debug!("in span b");
-    span_context!(span, Level::DEBUG);
+        let span = info_span!("span c");
+        span.set_context(context);
+        span_context!(span, Level::INFO);
+        let _g = span.enter();
+        info!("in span c");
+    }
 
-    let span = debug_span!("span c");
-    span.set_context(context);
-    span_context!(span, Level::DEBUG);
-    let _g = span.enter();
-    debug!("in span c");
+    let a = span_named(&collector, "span a");
+    let c = span_named(&collector, "span c");
+    assert_eq!(a.span_context.trace_id(), c.span_context.trace_id());
+    assert_eq!(a.span_context.span_id(), c.parent_span_id);
 }
 
-#[tokio::test(threaded_scheduler)]
+#[tokio::test(flavor = "multi_thread")]
 async fn cross_thread_test() {
-    holochain_trace::test_run_open().ok();
-    let (mut tx1, rx1) = mpsc::channel(100);
+    let collector = holochain_trace::test_run_open().unwrap();
+    let (tx1, rx1) = mpsc::channel(100);
     let (tx2, mut rx2) = mpsc::channel(100);
-    tokio::task::spawn(across_thread(rx1, tx2));
+    let handle = tokio::task::spawn(across_thread(rx1, tx2));
     {
-        let span = debug_span!("from original thread");
-        let context = span.get_context();
-        let _g = span.enter();
-        span_context!(span, Level::DEBUG);
-        tx1.send(context).await.unwrap();
+        let span = info_span!("from original thread");
+        span_context!(span, Level::INFO);
+        tx1.send(span.get_context_bytes()).await.unwrap();
     }
     {
-        let context = rx2.recv().await.unwrap();
+        let bytes = rx2.recv().await.unwrap();
 
-        let span = debug_span!("original thread");
-        span.set_context(context);
-        span_context!(span, Level::DEBUG);
-        let _g = span.enter();
-        let span = debug_span!("inner");
+        let span = info_span!("back on original thread");
+        span.set_from_bytes(bytes);
         let _g = span.enter();
-        span_context!(span, Level::DEBUG);
     }
-    {
-        let context = rx2.recv().await.unwrap();
+    handle.await.unwrap();
 
-        let span = debug_span!("original thread");
-        span.set_context(context);
-        span_context!(span, Level::DEBUG);
-        let _g = span.enter();
-    }
+    let original = span_named(&collector, "from original thread");
+    let across = span_named(&collector, "across thread");
+    let inner = span_named(&collector, "inner across thread");
+    let back = span_named(&collector, "back on original thread");
+    let trace_id = original.span_context.trace_id();
+    assert_eq!(across.span_context.trace_id(), trace_id);
+    assert_eq!(inner.span_context.trace_id(), trace_id);
+    assert_eq!(back.span_context.trace_id(), trace_id);
+    assert_eq!(across.parent_span_id, original.span_context.span_id());
+    assert_eq!(inner.parent_span_id, across.span_context.span_id());
+    assert_eq!(back.parent_span_id, inner.span_context.span_id());
 }
 
-async fn across_thread(mut rx: mpsc::Receiver<Context>, mut tx: mpsc::Sender<Context>) {
+#[tokio::test(flavor = "multi_thread")]
+async fn empty_bytes_are_ignored() {
+    let collector = holochain_trace::test_run_open().unwrap();
     {
-        let context = rx.recv().await.unwrap();
-        let span = debug_span!("across thread");
-        span.set_context(context);
-        span_context!(span, Level::DEBUG);
-        let _g = span.enter();
-        let span = debug_span!("inner");
+        let span = info_span!("without remote context");
+        span.set_from_bytes(Vec::new());
         let _g = span.enter();
-        span_context!(span, Level::DEBUG);
-        tx.send(span.get_context()).await.unwrap();
     }
-    tokio::time::delay_for(std::time::Duration::from_millis(100)).await;
-    {
-        let span = debug_span!("from another thread");
-        let context = span.get_context();
+
+    let span = span_named(&collector, "without remote context");
+    assert_eq!(span.parent_span_id, opentelemetry::trace::SpanId::INVALID);
+}
+
+async fn across_thread(mut rx: mpsc::Receiver<Vec<u8>>, tx: mpsc::Sender<Vec<u8>>) {
+    let bytes = rx.recv().await.unwrap();
+    let bytes = {
+        let span = info_span!("across thread");
+        span.set_from_bytes(bytes);
+        span_context!(span, Level::INFO);
         let _g = span.enter();
-        span_context!(span, Level::DEBUG);
-        tx.send(context).await.unwrap();
-    }
+        let span = info_span!("inner across thread");
+        let _g = span.enter();
+        span_context!(span, Level::INFO);
+        span.get_context_bytes()
+    };
+    tx.send(bytes).await.unwrap();
 }
-*/
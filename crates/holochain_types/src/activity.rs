@@ -20,6 +20,8 @@ pub struct AgentActivityResponse<T = SignedActionHashed> {
     /// The highest chain action that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
+    /// Warrants issued against this agent.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 holochain_serial!(AgentActivityResponse<ActionHash>);
@@ -38,6 +40,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: convert_activity(&other.rejected_activity),
             status: ChainStatus::Empty,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 
@@ -49,6 +52,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: ChainItems::NotRequested,
             status: ChainStatus::Empty,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 
@@ -65,6 +69,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: convert_activity(other.rejected_activity),
             status: other.status,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 }
@@ -103,7 +108,7 @@ impl From<AgentActivityResponse<Record>> for holochain_zome_types::query::AgentA
             rejected_activity,
             status: a.status,
             highest_observed: a.highest_observed,
-            warrants: Vec::with_capacity(0),
+            warrants: a.warrants,
         }
     }
 }
@@ -16,7 +16,7 @@ pub use app_bundle::*;
 pub use app_manifest::app_manifest_validated::*;
 pub use app_manifest::*;
 use derive_more::{Display, Into};
-use holo_hash::{AgentPubKey, DnaHash};
+use holo_hash::{AgentPubKey, AppHash, DnaHash};
 use holochain_serialized_bytes::prelude::*;
 use holochain_util::ffs;
 use holochain_zome_types::cell::CloneId;
@@ -37,7 +37,8 @@ pub enum DnaSource {
     Path(PathBuf),
     /// register the dna as provided in the DnaBundle data structure
     Bundle(Box<DnaBundle>),
-    /// register the dna from an existing registered DNA (assumes properties will be set)
+    /// register the dna from an existing registered DNA (assumes properties will be set),
+    /// or, if no such DNA is registered, fetch it from the conductor's app store
     Hash(DnaHash),
 }
 
@@ -126,6 +127,32 @@ pub type EnableCloneCellPayload = DisableCloneCellPayload;
 /// Arguments to delete a disabled clone cell of an app.
 pub type DeleteCloneCellPayload = DisableCloneCellPayload;
 
+/// Arguments to provision the cell of a role whose provisioning was deferred
+/// when the app was installed.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProvisionRolePayload {
+    /// The app id that the role belongs to
+    pub app_id: InstalledAppId,
+    /// The name of the deferred role
+    pub role_name: RoleName,
+    /// Optionally set a proof of membership for the cell
+    pub membrane_proof: Option<MembraneProof>,
+}
+
+/// Arguments to migrate the agent of a role's cell to a new version of its DNA.
+/// The new DNA must already be registered.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MigrateCellPayload {
+    /// The app id that the role belongs to
+    pub app_id: InstalledAppId,
+    /// The name of the role whose cell is migrated
+    pub role_name: RoleName,
+    /// The DNA to migrate the cell to
+    pub new_dna_hash: DnaHash,
+    /// Optionally set a proof of membership for the new cell
+    pub membrane_proof: Option<MembraneProof>,
+}
+
 /// An [AppBundle] along with an [AgentPubKey] and optional [InstalledAppId]
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -158,16 +185,22 @@ pub enum AppBundleSource {
     Bundle(AppBundle),
     /// A local file path
     Path(PathBuf),
+    /// The hash of a bundle which has been published to the conductor's app store
+    Hash(AppHash),
     // /// A URL
     // Url(String),
 }
 
 impl AppBundleSource {
     /// Get the bundle from the source. Consumes the source.
+    ///
+    /// A [`AppBundleSource::Hash`] can only be resolved by a conductor with an
+    /// app store, so it produces an error here.
     pub async fn resolve(self) -> Result<AppBundle, AppBundleError> {
         Ok(match self {
             Self::Bundle(bundle) => bundle,
             Self::Path(path) => AppBundle::decode(&ffs::read(&path).await?)?,
+            Self::Hash(hash) => return Err(AppBundleError::UnresolvedAppHash(hash)),
             // Self::Url(url) => todo!("reqwest::get"),
         })
     }
@@ -501,21 +534,42 @@ impl InstalledAppCommon {
         self.disabled_clone_cells().map(|(_, cell_id)| cell_id)
     }
 
-    /// Iterator of all cells, both provisioned and cloned
+    /// Accessor
+    pub fn deferred_cells(&self) -> impl Iterator<Item = (&RoleName, &CellId)> {
+        self.role_assignments
+            .iter()
+            .filter_map(|(role_name, role)| role.deferred_cell().map(|c| (role_name, c)))
+    }
+
+    /// Accessor
+    pub fn closed_cells(&self) -> impl Iterator<Item = (&RoleName, &CellId)> {
+        self.role_assignments.iter().flat_map(|(role_name, role)| {
+            role.closed_cells
+                .iter()
+                .map(move |cell_id| (role_name, cell_id))
+        })
+    }
+
+    /// Iterator of all cells, both provisioned and cloned,
+    /// including the closed cells of migrated roles
     pub fn all_cells(&self) -> impl Iterator<Item = &CellId> {
         self.provisioned_cells()
             .map(|(_, c)| c)
             .chain(self.clone_cell_ids())
             .chain(self.disabled_clone_cell_ids())
+            .chain(self.closed_cells().map(|(_, c)| c))
     }
 
     /// Iterator of all running cells, both provisioned and cloned.
     /// Provisioned cells will always be running if the app is running,
     /// but some cloned cells may be disabled and will not be returned.
+    /// Closed cells keep running so that they still serve their data,
+    /// but nothing more can be written to their chains.
     pub fn all_enabled_cells(&self) -> impl Iterator<Item = &CellId> {
         self.provisioned_cells()
             .map(|(_, c)| c)
             .chain(self.clone_cell_ids())
+            .chain(self.closed_cells().map(|(_, c)| c))
     }
 
     /// Iterator of all "required" cells, meaning Cells which must be running
@@ -575,6 +629,53 @@ impl InstalledAppCommon {
         Ok(clone_id)
     }
 
+    /// Mark the cell of a deferred role as being provisioned, before genesis
+    /// is run for it. Fails if the role is already being provisioned.
+    pub fn start_provisioning_deferred_role(&mut self, role_name: &RoleName) -> AppResult<CellId> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        if !app_role_assignment.is_deferred {
+            return Err(AppError::RoleNotDeferred(role_name.clone()));
+        }
+        if app_role_assignment.is_provisioning {
+            return Err(AppError::RoleAlreadyProvisioning(role_name.clone()));
+        }
+        app_role_assignment.is_provisioning = true;
+        Ok(app_role_assignment.base_cell_id.clone())
+    }
+
+    /// Mark the cell of a deferred role as deferred again, if genesis failed for it.
+    pub fn abort_provisioning_deferred_role(&mut self, role_name: &RoleName) -> AppResult<()> {
+        self.role_mut(role_name)?.is_provisioning = false;
+        Ok(())
+    }
+
+    /// Mark the cell of a deferred role as provisioned, once genesis has been run for it.
+    pub fn provision_deferred_role(&mut self, role_name: &RoleName) -> AppResult<CellId> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        if !app_role_assignment.is_deferred {
+            return Err(AppError::RoleNotDeferred(role_name.clone()));
+        }
+        app_role_assignment.is_deferred = false;
+        app_role_assignment.is_provisioning = false;
+        app_role_assignment.is_provisioned = true;
+        Ok(app_role_assignment.base_cell_id.clone())
+    }
+
+    /// Provision a role with the cell its agent was migrated to, once the
+    /// chain of its current cell has been closed. The current cell is kept
+    /// as a closed cell of the role. Returns the closed cell.
+    pub fn migrate_role(&mut self, role_name: &RoleName, new_cell_id: CellId) -> AppResult<CellId> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        if !app_role_assignment.is_provisioned {
+            return Err(AppError::RoleNotProvisioned(role_name.clone()));
+        }
+        let closed_cell_id = std::mem::replace(&mut app_role_assignment.base_cell_id, new_cell_id);
+        app_role_assignment
+            .closed_cells
+            .push(closed_cell_id.clone());
+        Ok(closed_cell_id)
+    }
+
     /// Get a clone cell id from its clone id.
     pub fn get_clone_cell_id(&self, clone_cell_id: &CloneCellId) -> AppResult<CellId> {
         let cell_id = match clone_cell_id {
@@ -766,10 +867,13 @@ impl InstalledAppCommon {
                 let role = AppRoleAssignment {
                     base_cell_id: cell_id,
                     is_provisioned: true,
+                    is_deferred: false,
+                    is_provisioning: false,
                     clones: HashMap::new(),
                     clone_limit: 256,
                     next_clone_index: 0,
                     disabled_clones: HashMap::new(),
+                    closed_cells: Vec::new(),
                 };
                 (role_name, role)
             })
@@ -1006,6 +1110,15 @@ pub struct AppRoleAssignment {
     /// If false, then `base_cell_id` is just recording what that cell will be
     /// called in the future.
     is_provisioned: bool,
+    /// Records whether provisioning of the base cell was deferred when the app
+    /// was installed. A deferred cell is not provisioned until it is requested,
+    /// along with its membrane proof.
+    #[serde(default)]
+    is_deferred: bool,
+    /// Records whether the deferred base cell is being provisioned, so that
+    /// genesis is only run for it once.
+    #[serde(default)]
+    is_provisioning: bool,
     /// The number of allowed clone cells.
     clone_limit: u32,
     /// The index of the next clone cell to be created.
@@ -1017,6 +1130,11 @@ pub struct AppRoleAssignment {
     /// any longer and are not returned as part of the app info either.
     /// Disabled clone cells can be deleted through the Admin API.
     disabled_clones: HashMap<CloneId, CellId>,
+    /// Cells whose chains were closed when the agent was migrated to a new
+    /// version of the role's DNA, oldest first. Their data is kept, but
+    /// nothing more can be written to their chains.
+    #[serde(default)]
+    closed_cells: Vec<CellId>,
 }
 
 impl AppRoleAssignment {
@@ -1025,10 +1143,21 @@ impl AppRoleAssignment {
         Self {
             base_cell_id,
             is_provisioned,
+            is_deferred: false,
+            is_provisioning: false,
             clone_limit,
             clones: HashMap::new(),
             next_clone_index: 0,
             disabled_clones: HashMap::new(),
+            closed_cells: Vec::new(),
+        }
+    }
+
+    /// Constructor for a role whose cell will be provisioned later.
+    pub fn new_deferred(base_cell_id: CellId, clone_limit: u32) -> Self {
+        Self {
+            is_deferred: true,
+            ..Self::new(base_cell_id, false, clone_limit)
         }
     }
 
@@ -1056,6 +1185,25 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Accessor
+    pub fn deferred_cell(&self) -> Option<&CellId> {
+        if self.is_deferred {
+            Some(&self.base_cell_id)
+        } else {
+            None
+        }
+    }
+
+    /// Accessor
+    pub fn is_deferred(&self) -> bool {
+        self.is_deferred
+    }
+
+    /// Accessor
+    pub fn closed_cells(&self) -> &[CellId] {
+        &self.closed_cells
+    }
+
     /// Accessor
     pub fn clone_ids(&self) -> impl Iterator<Item = &CloneId> {
         self.clones.keys()
@@ -1191,4 +1339,88 @@ mod tests {
         // Assert the deleted cell cannot be enabled
         assert!(app.enable_clone_cell(&clone_id_0).is_err());
     }
+
+    #[test]
+    fn migrated_role_keeps_closed_cell() {
+        let old_cell_id = fixt!(CellId);
+        let agent = old_cell_id.agent_pubkey().clone();
+        let new_cell_id = CellId::new(fixt!(DnaHash), agent.clone());
+        let role_name: RoleName = "role_name".into();
+        let deferred_role_name: RoleName = "deferred".into();
+        let manifest = AppManifest::arbitrary(&mut unstructured_noise()).unwrap();
+        let mut app = InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            vec![
+                (
+                    role_name.clone(),
+                    AppRoleAssignment::new(old_cell_id.clone(), true, 0),
+                ),
+                (
+                    deferred_role_name.clone(),
+                    AppRoleAssignment::new_deferred(fixt!(CellId), 0),
+                ),
+            ],
+            manifest,
+        )
+        .unwrap();
+
+        assert_eq!(
+            app.migrate_role(&role_name, new_cell_id.clone()).unwrap(),
+            old_cell_id
+        );
+        assert_eq!(app.role(&role_name).unwrap().cell_id(), &new_cell_id);
+        assert_eq!(
+            app.closed_cells().collect::<Vec<_>>(),
+            vec![(&role_name, &old_cell_id)]
+        );
+        // The closed cell keeps running, but the role is provisioned with the new cell
+        assert!(app.all_enabled_cells().any(|c| *c == old_cell_id));
+        assert_eq!(
+            app.required_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &new_cell_id }
+        );
+
+        matches::assert_matches!(
+            app.migrate_role(&deferred_role_name, fixt!(CellId)),
+            Err(AppError::RoleNotProvisioned(_))
+        );
+    }
+
+    #[test]
+    fn deferred_role_is_provisioned_once() {
+        let cell_id = fixt!(CellId);
+        let agent = cell_id.agent_pubkey().clone();
+        let role_name: RoleName = "role_name".into();
+        let manifest = AppManifest::arbitrary(&mut unstructured_noise()).unwrap();
+        let mut app = InstalledAppCommon::new(
+            "app",
+            agent,
+            vec![(
+                role_name.clone(),
+                AppRoleAssignment::new_deferred(cell_id.clone(), 0),
+            )],
+            manifest,
+        )
+        .unwrap();
+
+        assert_eq!(
+            app.start_provisioning_deferred_role(&role_name).unwrap(),
+            cell_id
+        );
+        matches::assert_matches!(
+            app.start_provisioning_deferred_role(&role_name),
+            Err(AppError::RoleAlreadyProvisioning(_))
+        );
+
+        // A failed genesis lets the role be provisioned again
+        app.abort_provisioning_deferred_role(&role_name).unwrap();
+        app.start_provisioning_deferred_role(&role_name).unwrap();
+        assert_eq!(app.provision_deferred_role(&role_name).unwrap(), cell_id);
+        assert_eq!(app.deferred_cells().count(), 0);
+        matches::assert_matches!(
+            app.start_provisioning_deferred_role(&role_name),
+            Err(AppError::RoleNotDeferred(_))
+        );
+    }
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use self::error::AppBundleResult;
 
@@ -17,6 +21,25 @@ mod tests;
 #[derive(Debug, Serialize, Deserialize, derive_more::From, shrinkwraprs::Shrinkwrap)]
 pub struct AppBundle(mr_bundle::Bundle<AppManifest>);
 
+/// An AppHash is the hash of the manifest together with every bundled resource.
+/// The bytes are not compressed before hashing, so the hash does not depend on
+/// how the bundle happened to be encoded.
+impl HashableContent for AppBundle {
+    type HashType = hash_type::App;
+
+    fn hash_type(&self) -> Self::HashType {
+        hash_type::App
+    }
+
+    fn hashable_content(&self) -> HashableContentBytes {
+        HashableContentBytes::Content(
+            holochain_serialized_bytes::encode(&self.0)
+                .map(|bytes| SerializedBytes::from(UnsafeBytes::from(bytes)))
+                .expect("Could not serialize HashableContent"),
+        )
+    }
+}
+
 impl AppBundle {
     /// Create an AppBundle from a manifest and DNA files
     pub async fn new<R: IntoIterator<Item = (PathBuf, DnaBundle)>>(
@@ -40,6 +63,11 @@ impl AppBundle {
             .map_err(Into::into)
     }
 
+    /// Compute the content-addressed hash of this bundle, as used by the app store
+    pub async fn app_hash(&self) -> AppHash {
+        AppHash::with_data(self).await
+    }
+
     /// Convert to the inner Bundle
     pub fn into_inner(self) -> mr_bundle::Bundle<AppManifest> {
         self.0
@@ -57,12 +85,16 @@ impl AppBundle {
     }
 
     /// Given a partial list of already available DnaFiles, fetch the missing others via
-    /// mr_bundle::Location resolution
+    /// mr_bundle::Location resolution.
+    ///
+    /// `existing_cells` are the cells already provisioned by other apps, which
+    /// roles with `UseExisting` or `CreateIfNotExists` provisioning can bind to.
     pub async fn resolve_cells(
         self,
         dna_store: &impl DnaStore,
         agent: AgentPubKey,
         membrane_proofs: HashMap<RoleName, MembraneProof>,
+        existing_cells: &HashSet<CellId>,
     ) -> AppBundleResult<AppRoleResolution> {
         let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
@@ -70,7 +102,9 @@ impl AppBundle {
             let bundle = bundle.clone();
             Ok((
                 role_name.clone(),
-                bundle.resolve_cell(dna_store, role_name, role).await?,
+                bundle
+                    .resolve_cell(dna_store, existing_cells, &agent, role_name, role)
+                    .await?,
             ))
         });
         let resolution = futures::future::join_all(tasks)
@@ -111,6 +145,23 @@ impl AppBundle {
                                     AppRoleAssignment::new(cell_id, false, clone_limit),
                                 ));
                             }
+                            CellProvisioningOp::Deferred(dna, clone_limit) => {
+                                // The membrane proof is given when the role is provisioned
+                                if membrane_proofs.contains_key(&role_name) {
+                                    return Err(AppBundleError::MembraneProofForDeferredRole(
+                                        role_name,
+                                    ));
+                                }
+                                let agent = resolution.agent.clone();
+                                let dna_hash = dna.dna_hash().clone();
+                                let cell_id = CellId::new(dna_hash, agent);
+
+                                resolution.dnas_to_register.push((dna, None));
+                                resolution.role_assignments.push((
+                                    role_name,
+                                    AppRoleAssignment::new_deferred(cell_id, clone_limit),
+                                ));
+                            }
                             other @ (CellProvisioningOp::HashMismatch(_, _)
                             | CellProvisioningOp::Conflict(_)) => {
                                 tracing::error!(
@@ -134,6 +185,8 @@ impl AppBundle {
     async fn resolve_cell(
         &self,
         dna_store: &impl DnaStore,
+        existing_cells: &HashSet<CellId>,
+        agent: &AgentPubKey,
         role_name: RoleName,
         role: AppRoleManifestValidated,
     ) -> AppBundleResult<CellProvisioningOp> {
@@ -143,55 +196,81 @@ impl AppBundle {
                 installed_hash,
                 clone_limit,
                 modifiers,
-                deferred: _,
+                deferred,
             } => {
                 let dna = self
                     .resolve_dna(
                         role_name,
                         dna_store,
-                        &location,
+                        Some(&location),
                         installed_hash.as_ref(),
                         modifiers,
                     )
                     .await?;
-                CellProvisioningOp::CreateFromDnaFile(dna, clone_limit)
+                if deferred {
+                    CellProvisioningOp::Deferred(dna, clone_limit)
+                } else {
+                    CellProvisioningOp::CreateFromDnaFile(dna, clone_limit)
+                }
             }
 
+            // An existing cell has already been through genesis, so there
+            // is nothing to defer.
             AppRoleManifestValidated::UseExisting {
+                location,
                 installed_hash,
                 clone_limit,
+                modifiers,
                 deferred: _,
-            } => self.resolve_cell_existing(&installed_hash, clone_limit),
+            } => {
+                // Without modifiers the installed hash is the hash of the cell's DNA,
+                // so there is no need to get hold of the DNA itself
+                let dna_hash = if modifiers == DnaModifiersOpt::none() {
+                    installed_hash.into()
+                } else {
+                    self.resolve_dna(
+                        role_name.clone(),
+                        dna_store,
+                        location.as_ref(),
+                        Some(&installed_hash),
+                        modifiers,
+                    )
+                    .await?
+                    .dna_hash()
+                    .clone()
+                };
+                match Self::resolve_cell_existing(existing_cells, agent, &dna_hash) {
+                    Some(cell_id) => CellProvisioningOp::Existing(cell_id, clone_limit),
+                    None => {
+                        return Err(AppBundleError::CellResolutionFailure(
+                            role_name,
+                            format!("No existing cell with DNA hash {}", dna_hash),
+                        ))
+                    }
+                }
+            }
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 installed_hash,
                 clone_limit,
                 modifiers,
-                deferred: _,
-            } => match self.resolve_cell_existing(&installed_hash, clone_limit) {
-                op @ CellProvisioningOp::Existing(_, _) => op,
-                CellProvisioningOp::HashMismatch(_, _) => {
-                    let dna = self
-                        .resolve_dna(
-                            role_name,
-                            dna_store,
-                            &location,
-                            Some(&installed_hash),
-                            modifiers,
-                        )
-                        .await?;
-                    CellProvisioningOp::CreateFromDnaFile(dna, clone_limit)
-                }
-                CellProvisioningOp::Conflict(_) => {
-                    unimplemented!("conflicts are not handled, or even possible yet")
-                }
-                CellProvisioningOp::CreateFromDnaFile(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a Create op")
-                }
-                CellProvisioningOp::ProvisionOnly(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a ProvisionOnly")
+                deferred,
+            } => {
+                let dna = self
+                    .resolve_dna(
+                        role_name,
+                        dna_store,
+                        Some(&location),
+                        Some(&installed_hash),
+                        modifiers,
+                    )
+                    .await?;
+                match Self::resolve_cell_existing(existing_cells, agent, dna.dna_hash()) {
+                    Some(cell_id) => CellProvisioningOp::Existing(cell_id, clone_limit),
+                    None if deferred => CellProvisioningOp::Deferred(dna, clone_limit),
+                    None => CellProvisioningOp::CreateFromDnaFile(dna, clone_limit),
                 }
-            },
+            }
             AppRoleManifestValidated::CloneOnly {
                 clone_limit,
                 location,
@@ -202,7 +281,7 @@ impl AppBundle {
                     .resolve_dna(
                         role_name,
                         dna_store,
-                        &location,
+                        Some(&location),
                         Some(&installed_hash),
                         modifiers,
                     )
@@ -216,7 +295,7 @@ impl AppBundle {
         &self,
         role_name: RoleName,
         dna_store: &impl DnaStore,
-        location: &mr_bundle::Location,
+        location: Option<&mr_bundle::Location>,
         installed_hash: Option<&DnaHashB64>,
         modifiers: DnaModifiersOpt,
     ) -> AppBundleResult<DnaFile> {
@@ -226,8 +305,13 @@ impl AppBundle {
                     let original_hash = dna_file.dna_hash().clone();
                     dna_file = dna_file.update_modifiers(modifiers);
                     (dna_file, original_hash)
-                } else {
+                } else if let Some(location) = location {
                     self.resolve_location(location, modifiers).await?
+                } else {
+                    return Err(AppBundleError::CellResolutionFailure(
+                        role_name,
+                        format!("DNA {} is not installed and has no location", hash),
+                    ));
                 };
             let expected_hash: DnaHash = hash.clone().into();
             if expected_hash != original_hash {
@@ -237,18 +321,26 @@ impl AppBundle {
                 ));
             }
             dna_file
-        } else {
+        } else if let Some(location) = location {
             self.resolve_location(location, modifiers).await?.0
+        } else {
+            return Err(AppBundleError::CellResolutionFailure(
+                role_name,
+                "The DNA has neither an installed hash nor a location".to_string(),
+            ));
         };
         Ok(dna_file)
     }
 
+    /// Find the existing cell of this agent with the given DNA hash, which must
+    /// be the hash after the role's modifiers have been applied.
     fn resolve_cell_existing(
-        &self,
-        _version: &DnaHashB64,
-        _clone_limit: u32,
-    ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+        existing_cells: &HashSet<CellId>,
+        agent: &AgentPubKey,
+        dna_hash: &DnaHash,
+    ) -> Option<CellId> {
+        let cell_id = CellId::new(dna_hash.clone(), agent.clone());
+        existing_cells.contains(&cell_id).then_some(cell_id)
     }
 
     async fn resolve_location(
@@ -263,12 +355,6 @@ impl AppBundle {
     }
 }
 
-/// This function is called in places where it will be necessary to rework that
-/// area after use_existing has been implemented
-pub fn we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-) {
-}
-
 /// The answer to the question:
 /// "how do we concretely assign DNAs to the open roles of this App?"
 /// Includes the DNAs selected to fill the roles and the details of the role assignments.
@@ -292,8 +378,14 @@ impl AppRoleResolution {
     }
 
     /// Return the IDs of new cells to be created as part of the resolution.
-    /// Does not return existing cells to be reused.
+    /// Does not return existing cells to be reused, or cells whose
+    /// provisioning has been deferred.
     pub fn cells_to_create(&self) -> Vec<(CellId, Option<MembraneProof>)> {
+        let deferred: Vec<&CellId> = self
+            .role_assignments
+            .iter()
+            .filter_map(|(_, role)| role.deferred_cell())
+            .collect();
         self.dnas_to_register
             .iter()
             .map(|(dna, proof)| {
@@ -302,6 +394,7 @@ impl AppRoleResolution {
                     proof.clone(),
                 )
             })
+            .filter(|(cell_id, _)| !deferred.contains(&cell_id))
             .collect()
     }
 }
@@ -317,6 +410,8 @@ pub enum CellProvisioningOp {
     /// No creation needed, but there might be a clone_limit, and so we need
     /// to know which DNA to use for making clones
     ProvisionOnly(DnaFile, u32),
+    /// Register the DNA, but don't create the Cell until it is requested
+    Deferred(DnaFile, u32),
     /// The specified installed_hash does not match the actual hash of the DNA selected for provisioning. Expected: {0}, Actual: {1}
     HashMismatch(DnaHash, DnaHash),
     /// Ambiguous result, needs manual resolution; can't provision (should this be an Err?)
@@ -1,7 +1,7 @@
 use holochain_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, DnaError, RoleName};
+use crate::prelude::{AppHash, AppManifestError, DnaError, RoleName};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
@@ -9,6 +9,12 @@ pub enum AppBundleError {
     #[error("Could not resolve the app role '{0}'. Detail: {1}")]
     CellResolutionFailure(RoleName, String),
 
+    #[error("A membrane proof was given for the deferred role '{0}'. It must be given when the role is provisioned")]
+    MembraneProofForDeferredRole(RoleName),
+
+    #[error("The app bundle with hash {0} must be fetched from an app store")]
+    UnresolvedAppHash(AppHash),
+
     #[error(transparent)]
     AppManifestError(#[from] AppManifestError),
 
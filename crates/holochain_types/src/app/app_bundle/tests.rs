@@ -61,6 +61,7 @@ async fn provisioning_1_create() {
             &std::collections::HashMap::new(),
             agent.clone(),
             Default::default(),
+            &Default::default(),
         )
         .await
         .unwrap();
@@ -76,3 +77,129 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that a deferred role registers its DNA without creating the cell
+#[tokio::test]
+async fn provisioning_1_deferred() {
+    holochain_trace::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture(DnaModifiersOpt::none()).await;
+    let mut manifest = bundle.manifest().clone();
+    match &mut manifest {
+        AppManifest::V1(manifest) => {
+            manifest.roles[0].provisioning = Some(CellProvisioning::Create { deferred: true });
+        }
+    }
+    let bundle = AppBundle::from(bundle.into_inner().update_manifest(manifest).unwrap());
+
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    // The membrane proof of a deferred role is given when it is provisioned
+    let proof: MembraneProof = std::sync::Arc::new(().try_into().unwrap());
+    let membrane_proofs = [("role_name".into(), proof)].into_iter().collect();
+    let bytes = bundle.encode().unwrap();
+    let err = AppBundle::decode(&bytes)
+        .unwrap()
+        .resolve_cells(
+            &std::collections::HashMap::new(),
+            agent.clone(),
+            membrane_proofs,
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+    matches::assert_matches!(
+        err,
+        AppBundleError::MembraneProofForDeferredRole(role_name) if role_name == "role_name"
+    );
+
+    let resolution = AppBundle::decode(&bytes)
+        .unwrap()
+        .resolve_cells(
+            &std::collections::HashMap::new(),
+            agent.clone(),
+            Default::default(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let role = AppRoleAssignment::new_deferred(cell_id.clone(), 50);
+    assert_eq!(role.deferred_cell(), Some(&cell_id));
+    assert_eq!(role.provisioned_cell(), None);
+
+    let expected = AppRoleResolution {
+        agent,
+        dnas_to_register: vec![(dna, None)],
+        role_assignments: vec![("role_name".into(), role)],
+    };
+    assert_eq!(resolution, expected);
+    assert!(resolution.cells_to_create().is_empty());
+}
+
+/// Test that a role which uses an existing cell binds to that cell,
+/// and that installation fails if there is none
+#[tokio::test]
+async fn provisioning_1_use_existing() {
+    holochain_trace::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture(DnaModifiersOpt::none()).await;
+    let mut manifest = bundle.manifest().clone();
+    match &mut manifest {
+        AppManifest::V1(manifest) => {
+            manifest.roles[0].provisioning =
+                Some(CellProvisioning::UseExisting { deferred: false });
+        }
+    }
+    let bundle = AppBundle::from(bundle.into_inner().update_manifest(manifest).unwrap());
+    let bytes = bundle.encode().unwrap();
+
+    let cell_id = CellId::new(dna.dna_hash().clone(), agent.clone());
+    let other_agents_cell_id = CellId::new(dna.dna_hash().clone(), fixt!(AgentPubKey));
+
+    let err = AppBundle::decode(&bytes)
+        .unwrap()
+        .resolve_cells(
+            &std::collections::HashMap::new(),
+            agent.clone(),
+            Default::default(),
+            &[other_agents_cell_id].into_iter().collect(),
+        )
+        .await
+        .unwrap_err();
+    matches::assert_matches!(err, AppBundleError::CellResolutionFailure(_, _));
+
+    let resolution = bundle
+        .resolve_cells(
+            &std::collections::HashMap::new(),
+            agent.clone(),
+            Default::default(),
+            &[cell_id.clone()].into_iter().collect(),
+        )
+        .await
+        .unwrap();
+
+    let expected = AppRoleResolution {
+        agent,
+        dnas_to_register: vec![],
+        role_assignments: vec![(
+            "role_name".into(),
+            AppRoleAssignment::new(cell_id, true, 50),
+        )],
+    };
+    assert_eq!(resolution, expected);
+    assert!(resolution.cells_to_create().is_empty());
+}
+
+/// Test that the AppHash only depends on the bundle contents, not its encoding
+#[tokio::test]
+async fn app_hash_survives_roundtrip() {
+    let (bundle, _) = app_bundle_fixture(DnaModifiersOpt::none()).await;
+    let hash = bundle.app_hash().await;
+
+    let decoded = AppBundle::decode(&bundle.encode().unwrap()).unwrap();
+    assert_eq!(decoded.app_hash().await, hash);
+
+    let (other, _) = app_bundle_fixture(DnaModifiersOpt::none()).await;
+    assert_ne!(other.app_hash().await, hash);
+}
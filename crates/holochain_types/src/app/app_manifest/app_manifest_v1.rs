@@ -121,18 +121,14 @@ pub enum CellProvisioning {
     /// Always create a new Cell when installing this App
     Create { deferred: bool },
 
-    /*
-        TODO: implement
+    /// Require that a Cell is already installed which matches the DNA installed_hash
+    /// spec, and which has the same Agent as this App.
+    /// If no such Cell exists, *app installation fails*.
+    UseExisting { deferred: bool },
 
-        /// Require that a Cell is already installed which matches the DNA installed_hash
-        /// spec, and which has an Agent that's associated with this App's agent
-        /// via DPKI. If no such Cell exists, *app installation fails*.
-        UseExisting { deferred: bool },
+    /// Try `UseExisting`, and if that fails, fallback to `Create`
+    CreateIfNotExists { deferred: bool },
 
-        /// Try `UseExisting`, and if that fails, fallback to `Create`
-        CreateIfNotExists { deferred: bool },
-
-    */
     /// Install or locate the DNA, but never create a Cell for this DNA.
     /// Only allow clones to be created from the DNA specified.
     /// This case requires `clone_limit > 0`, otherwise no Cells will ever be created.
@@ -146,20 +142,20 @@ impl Default for CellProvisioning {
 }
 
 impl AppManifestV1 {
-    /// Update the network seed for all DNAs used in Create-provisioned Cells.
-    /// Cells with other provisioning strategies are not affected.
-    ///
-    // TODO: it probably makes sense to do this for CreateIfNotExists cells
-    // too, in the Create case, but we would have to do that during installation
-    // rather than simply updating the manifest. Let's hold off on that until
-    // we know we need it, since this way is substantially simpler.
+    /// Update the network seed for all DNAs used in Create-provisioned Cells,
+    /// including CreateIfNotExists Cells, which then only use an existing Cell
+    /// with the same network seed.
+    /// UseExisting Cells are not affected.
     pub fn set_network_seed(&mut self, network_seed: NetworkSeed) {
         for mut role in self.roles.iter_mut() {
             // Only update the network seed for roles for which it makes sense to do so
             match role.provisioning.clone().unwrap_or_default() {
-                CellProvisioning::Create { .. } | CellProvisioning::CloneOnly => {
+                CellProvisioning::Create { .. }
+                | CellProvisioning::CreateIfNotExists { .. }
+                | CellProvisioning::CloneOnly => {
                     role.dna.modifiers.network_seed = Some(network_seed.clone());
                 }
+                CellProvisioning::UseExisting { .. } => {}
             }
         }
     }
@@ -196,28 +192,30 @@ impl AppManifestV1 {
                             modifiers,
                             installed_hash,
                         },
-                        // CellProvisioning::UseExisting { deferred } => {
-                        //     AppRoleManifestValidated::UseExisting {
-                        //         deferred,
-                        //         clone_limit,
-                        //         installed_hash: Self::require(
-                        //             installed_hash,
-                        //             "roles.dna.installed_hash",
-                        //         )?,
-                        //     }
-                        // }
-                        // CellProvisioning::CreateIfNotExists { deferred } => {
-                        //     AppRoleManifestValidated::CreateIfNotExists {
-                        //         deferred,
-                        //         clone_limit,
-                        //         location: Self::require(location, "roles.dna.(path|url)")?,
-                        //         installed_hash: Self::require(
-                        //             installed_hash,
-                        //             "roles.dna.installed_hash",
-                        //         )?,
-                        //         modifiers,
-                        //     }
-                        // }
+                        CellProvisioning::UseExisting { deferred } => {
+                            AppRoleManifestValidated::UseExisting {
+                                deferred,
+                                clone_limit,
+                                location,
+                                installed_hash: Self::require(
+                                    installed_hash,
+                                    "roles.dna.installed_hash",
+                                )?,
+                                modifiers,
+                            }
+                        }
+                        CellProvisioning::CreateIfNotExists { deferred } => {
+                            AppRoleManifestValidated::CreateIfNotExists {
+                                deferred,
+                                clone_limit,
+                                location: Self::require(location, "roles.dna.(path|url)")?,
+                                installed_hash: Self::require(
+                                    installed_hash,
+                                    "roles.dna.installed_hash",
+                                )?,
+                                modifiers,
+                            }
+                        }
                         CellProvisioning::CloneOnly => AppRoleManifestValidated::CloneOnly {
                             clone_limit,
                             location: Self::require(location, "roles.dna.(path|url)")?,
@@ -353,19 +351,19 @@ roles:
         manifest.roles = vec![
             AppRoleManifest::arbitrary(&mut u).unwrap(),
             AppRoleManifest::arbitrary(&mut u).unwrap(),
-            // AppRoleManifest::arbitrary(&mut u).unwrap(),
-            // AppRoleManifest::arbitrary(&mut u).unwrap(),
+            AppRoleManifest::arbitrary(&mut u).unwrap(),
+            AppRoleManifest::arbitrary(&mut u).unwrap(),
         ];
         manifest.roles[0].provisioning = Some(CellProvisioning::Create { deferred: false });
         manifest.roles[1].provisioning = Some(CellProvisioning::Create { deferred: false });
-        // manifest.roles[2].provisioning = Some(CellProvisioning::UseExisting { deferred: false });
-        // manifest.roles[3].provisioning =
-        //     Some(CellProvisioning::CreateIfNotExists { deferred: false });
+        manifest.roles[2].provisioning = Some(CellProvisioning::UseExisting { deferred: false });
+        manifest.roles[3].provisioning =
+            Some(CellProvisioning::CreateIfNotExists { deferred: false });
 
         let network_seed = NetworkSeed::from("blabla");
         manifest.set_network_seed(network_seed.clone());
 
-        // - The Create roles have the network seed rewritten.
+        // - The Create and CreateIfNotExists roles have the network seed rewritten.
         assert_eq!(
             manifest.roles[0].dna.modifiers.network_seed.as_ref(),
             Some(&network_seed)
@@ -374,15 +372,15 @@ roles:
             manifest.roles[1].dna.modifiers.network_seed.as_ref(),
             Some(&network_seed)
         );
-        // assert_eq!(
-        //     manifest.roles[3].dna.modifiers.network_seed.as_ref(),
-        //     Some(&network_seed)
-        // );
-
-        // // - The others do not.
-        // assert_ne!(
-        //     manifest.roles[2].dna.modifiers.network_seed.as_ref(),
-        //     Some(&network_seed)
-        // );
+        assert_eq!(
+            manifest.roles[3].dna.modifiers.network_seed.as_ref(),
+            Some(&network_seed)
+        );
+
+        // - The others do not.
+        assert_ne!(
+            manifest.roles[2].dna.modifiers.network_seed.as_ref(),
+            Some(&network_seed)
+        );
     }
 }
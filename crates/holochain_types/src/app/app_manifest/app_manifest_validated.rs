@@ -60,9 +60,15 @@ pub enum AppRoleManifestValidated {
     /// Require that a Cell is already installed with a specified DNA hash,
     /// and which has an Agent that's associated with this App's agent
     /// via DPKI. If no such Cell exists, *app installation fails*.
+    ///
+    /// The Cell's DNA is the one with the installed hash, with the modifiers
+    /// applied. The location is only needed to apply modifiers when the DNA
+    /// isn't already in the DNA store.
     UseExisting {
         clone_limit: u32,
         deferred: bool,
+        location: Option<DnaLocation>,
+        modifiers: DnaModifiersOpt,
         installed_hash: DnaHashB64,
     },
     /// Try `UseExisting`, and if that fails, fallback to `Create`
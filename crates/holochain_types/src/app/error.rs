@@ -26,6 +26,15 @@ pub enum AppError {
     #[error("Tried to access missing role name: '{0}'")]
     RoleNameMissing(RoleName),
 
+    #[error("Tried to provision role '{0}', which is not deferred")]
+    RoleNotDeferred(RoleName),
+
+    #[error("Tried to provision role '{0}', which is already being provisioned")]
+    RoleAlreadyProvisioning(RoleName),
+
+    #[error("Tried to migrate role '{0}', which has no provisioned cell")]
+    RoleNotProvisioned(RoleName),
+
     #[error("Tried to install app '{0}' which contains duplicate role names. The following role names have duplicates: {1:?}")]
     DuplicateRoleNames(InstalledAppId, Vec<RoleName>),
 }
@@ -47,6 +47,54 @@ pub trait ChainHeadCoordinator {
     ) -> ChcResult<HashMap<EntryHash, Entry>>;
 }
 
+/// Record-level operations which every CHC over [`SignedActionHashed`] gets
+/// for free, built on top of the lower-level action and entry methods.
+#[async_trait::async_trait]
+pub trait ChainHeadCoordinatorExt:
+    ChainHeadCoordinator<Item = SignedActionHashed> + Send + Sync
+{
+    /// Add records to the CHC: the entries are stored first, so that the
+    /// actions are never visible without their entry data.
+    async fn add_records(&self, records: Vec<Record>) -> ChcResult<()> {
+        let (actions, entries): (Vec<_>, Vec<_>) = records
+            .into_iter()
+            .map(|r| {
+                let (action, entry) = r.into_inner();
+                (action, entry.into_option())
+            })
+            .unzip();
+        let entries: Vec<_> = entries
+            .into_iter()
+            .flatten()
+            .map(EntryHashed::from_content_sync)
+            .collect();
+        if !entries.is_empty() {
+            self.add_entries(entries).await?;
+        }
+        self.add_actions(actions).await
+    }
+
+    /// Get the full records (actions along with any entries) beyond the given hash.
+    async fn get_record_data(&self, since_hash: Option<ActionHash>) -> ChcResult<Vec<Record>> {
+        let actions = self.get_actions_since_hash(since_hash).await?;
+        let entry_hashes: HashSet<&EntryHash> = actions
+            .iter()
+            .filter_map(|a| a.hashed.entry_hash())
+            .collect();
+        let entries = if entry_hashes.is_empty() {
+            HashMap::new()
+        } else {
+            self.get_entries(entry_hashes).await?
+        };
+        records_from_actions_and_entries(actions, entries)
+    }
+}
+
+impl<C> ChainHeadCoordinatorExt for C where
+    C: ?Sized + ChainHeadCoordinator<Item = SignedActionHashed> + Send + Sync
+{
+}
+
 /// Assemble records from a list of Actions and a map of Entries
 pub fn records_from_actions_and_entries(
     actions: Vec<SignedActionHashed>,
@@ -86,6 +134,12 @@ pub enum ChcError {
 
     #[error("The CHC service is unreachable: {0}")]
     ServiceUnreachable(String),
+
+    #[error("The CHC service responded with an unexpected status {0}: {1}")]
+    UnexpectedResponse(u16, String),
+
+    #[error("The CHC namespace {0} is not a valid URL: {1}")]
+    InvalidNamespace(String, String),
 }
 
 #[allow(missing_docs)]
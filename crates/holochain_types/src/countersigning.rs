@@ -0,0 +1,42 @@
+//! Types for countersigning sessions
+
+use crate::prelude::*;
+
+/// A countersigning session which an agent has joined, as persisted in the
+/// authored database until the session is resolved.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
+pub struct CountersigningSessionState {
+    /// The agent taking part in the session.
+    pub author: AgentPubKey,
+    /// The preflight request the agent accepted to join the session.
+    pub preflight_request: PreflightRequest,
+    /// The hash of the countersigned entry, once the agent has committed it.
+    pub entry_hash: Option<EntryHash>,
+    /// The hash of the agent's action for the countersigned entry,
+    /// once the agent has committed it.
+    pub action_hash: Option<ActionHash>,
+}
+
+impl CountersigningSessionState {
+    /// The time at which the session ends.
+    pub fn expires_at(&self) -> Timestamp {
+        *self.preflight_request.session_times.end()
+    }
+
+    /// Has the session ended?
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at() < now
+    }
+}
+
+/// How a countersigning session was resolved.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CountersigningSessionResolution {
+    /// All signers committed their actions for the session, so the agent's
+    /// action is published and the agent's chain carries on from it.
+    Completed,
+    /// The session did not complete, so the agent's action for the session
+    /// is removed from its chain without ever being published.
+    Abandoned,
+}
@@ -20,6 +20,7 @@ pub mod autonomic;
 pub mod chain;
 pub mod chc;
 pub mod combinators;
+pub mod countersigning;
 pub mod db;
 pub mod db_cache;
 pub mod dht_op;
@@ -37,6 +38,7 @@ pub mod share;
 pub mod signal;
 #[warn(missing_docs)]
 pub mod sql;
+pub mod warrant;
 pub mod wasmer_types;
 pub mod web_app;
 pub mod zome_types;
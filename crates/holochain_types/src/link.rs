@@ -31,6 +31,104 @@ pub struct WireLinkKey {
     pub type_query: LinkTypeFilter,
     /// Optionally specify a tag for more specific queries.
     pub tag: Option<LinkTag>,
+    /// Only links created after this time.
+    #[serde(default)]
+    pub after: Option<Timestamp>,
+    /// Only links created before this time.
+    #[serde(default)]
+    pub before: Option<Timestamp>,
+    /// Only links created by this agent.
+    #[serde(default)]
+    pub author: Option<AgentPubKey>,
+    /// The most live links to return, in [`LinkCursor`] order.
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Only links after this position in [`LinkCursor`] order.
+    #[serde(default)]
+    pub cursor: Option<LinkCursor>,
+}
+
+impl WireLinkKey {
+    /// A key for all the links on a base with the given types and tag prefix.
+    pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
+        Self {
+            base,
+            type_query,
+            tag,
+            after: None,
+            before: None,
+            author: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+impl From<GetLinksInput> for WireLinkKey {
+    fn from(input: GetLinksInput) -> Self {
+        let GetLinksInput {
+            base_address,
+            link_type,
+            tag_prefix,
+            after,
+            before,
+            author,
+            limit,
+            cursor,
+        } = input;
+        Self {
+            base: base_address,
+            type_query: link_type,
+            tag: tag_prefix,
+            after,
+            before,
+            author,
+            limit,
+            cursor,
+        }
+    }
+}
+
+impl From<LinkQuery> for WireLinkKey {
+    fn from(query: LinkQuery) -> Self {
+        let LinkQuery {
+            base,
+            link_type,
+            tag_prefix,
+            after,
+            before,
+            author,
+        } = query;
+        Self {
+            base,
+            type_query: link_type,
+            tag: tag_prefix,
+            after,
+            before,
+            author,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+/// An authority's answer to a count links request.
+///
+/// Besides the count, it says what it knows about the create link actions
+/// the requester asked about, which are the requester's own links that may
+/// not have been published yet. That lets the requester add them to the
+/// count without counting any link twice.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
+pub struct CountLinksResponse {
+    /// The number of create link actions which match the query
+    /// and haven't been deleted.
+    pub count: usize,
+    /// The requested create link actions which the authority holds,
+    /// whether or not they have been deleted.
+    pub held: Vec<ActionHash>,
+    /// The requested create link actions which the authority knows
+    /// to have been deleted.
+    pub deleted: Vec<ActionHash>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
@@ -16,6 +16,7 @@ pub use crate::autonomic::*;
 pub use crate::chain::*;
 pub use crate::chc::*;
 pub use crate::combinators::*;
+pub use crate::countersigning::*;
 pub use crate::db::*;
 pub use crate::dht_op::error::*;
 pub use crate::dht_op::*;
@@ -29,6 +30,7 @@ pub use crate::rate_limit::*;
 pub use crate::record::error::*;
 pub use crate::record::*;
 pub use crate::signal::*;
+pub use crate::warrant::*;
 pub use crate::wasmer_types::*;
 
 #[cfg(feature = "fixturators")]
@@ -36,6 +36,9 @@ pub enum SystemSignal {
     Test(String),
     /// A countersigning session has successfully completed.
     SuccessfulCountersigning(holo_hash::EntryHash),
+    /// A countersigning session did not complete and the countersigned
+    /// action has been removed from the agent's chain.
+    AbandonedCountersigning(holo_hash::EntryHash),
 }
 
 /// Create a test signal
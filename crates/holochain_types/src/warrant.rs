@@ -0,0 +1,94 @@
+//! Types for warrants
+
+use crate::prelude::*;
+use holochain_keystore::LairResult;
+use holochain_keystore::MetaLairClient;
+
+pub use holochain_zome_types::warrant::*;
+
+/// Extension trait to keep zome types minimal
+#[async_trait::async_trait]
+pub trait SignedWarrantExt {
+    /// Sign a warrant with the keys of its author.
+    #[allow(clippy::new_ret_no_self)]
+    async fn sign(keystore: &MetaLairClient, warrant: Warrant) -> LairResult<SignedWarrant>;
+
+    /// Check that the warrant was signed by its author and that the action
+    /// it carries was signed by the warranted agent.
+    async fn is_valid(&self) -> bool;
+
+    /// The hash of the action this warrant is about.
+    fn action_hash(&self) -> ActionHash;
+}
+
+#[async_trait::async_trait]
+impl SignedWarrantExt for SignedWarrant {
+    async fn sign(keystore: &MetaLairClient, warrant: Warrant) -> LairResult<SignedWarrant> {
+        let signature = warrant.author.sign(keystore, &warrant).await?;
+        Ok(SignedWarrant::new(warrant, signature))
+    }
+
+    async fn is_valid(&self) -> bool {
+        let warrant = self.warrant();
+        let action = warrant.action();
+        warrant
+            .author
+            .verify_signature(self.signature(), warrant.clone())
+            .await
+            && action
+                .action()
+                .author()
+                .verify_signature(action.signature(), action.action())
+                .await
+    }
+
+    fn action_hash(&self) -> ActionHash {
+        ActionHash::with_data_sync(self.warrant().action())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn warrant_signatures_are_checked() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let warrantee = AgentPubKey::new_random(&keystore).await.unwrap();
+        let warrantor = AgentPubKey::new_random(&keystore).await.unwrap();
+        let someone_else = AgentPubKey::new_random(&keystore).await.unwrap();
+
+        let action = Action::Dna(Dna {
+            author: warrantee.clone(),
+            timestamp: Timestamp::now(),
+            hash: DnaHash::from_raw_36(vec![0; 36]),
+        });
+        let signature = warrantee.sign(&keystore, &action).await.unwrap();
+        let warrant = Warrant::new(
+            WarrantProof::InvalidAction {
+                action: SignedAction(action.clone(), signature),
+                validation_type: ValidationType::Sys,
+            },
+            warrantor,
+            Timestamp::now(),
+        );
+
+        let signed = SignedWarrant::sign(&keystore, warrant).await.unwrap();
+        assert!(signed.is_valid().await);
+        assert_eq!(signed.action_hash(), ActionHash::with_data_sync(&action));
+
+        // A warrant claiming to come from someone else doesn't check out.
+        let mut forged = signed.clone();
+        forged.warrant.author = someone_else;
+        assert!(!forged.is_valid().await);
+
+        // Nor does a warrant for an action the warrantee never signed.
+        let mut forged = signed;
+        forged.warrant.proof = WarrantProof::InvalidAction {
+            action: SignedAction(action, forged.signature.clone()),
+            validation_type: ValidationType::Sys,
+        };
+        assert!(!forged.is_valid().await);
+    }
+}
@@ -19,6 +19,10 @@ pub enum WebsocketError {
     RespTimeout,
     #[error("The websocket connection has shutdown")]
     Shutdown,
+    #[error(transparent)]
+    Tls(#[from] tokio_rustls::rustls::Error),
+    #[error("Invalid TLS config: {0}")]
+    TlsConfig(String),
 }
 
 pub type WebsocketResult<T> = Result<T, WebsocketError>;
@@ -83,8 +83,11 @@ use std::sync::Arc;
 use holochain_serialized_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::client::IntoClientRequest;
 use url2::Url2;
+use util::addr_to_url;
 use util::url_to_addr;
+use util::AsyncStream;
 use websocket::Websocket;
 
 mod websocket_config;
@@ -105,10 +108,14 @@ pub use websocket_receiver::*;
 
 mod websocket;
 
+pub mod tls;
+
 mod util;
 
 #[instrument(skip(config))]
 /// Create a new external websocket connection.
+/// If [`WebsocketConfig::tls_client`] is set the connection is made over TLS,
+/// in which case the host of the url must match the server's certificate.
 pub async fn connect(
     url: Url2,
     config: Arc<WebsocketConfig>,
@@ -119,20 +126,67 @@ pub async fn connect(
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let remote_addr = addr_to_url(socket.peer_addr()?, config.scheme);
+    let socket: Box<dyn AsyncStream> = match &config.tls_client {
+        Some(tls) => {
+            let host = url.host_str().unwrap_or_default();
+            let server_name = tokio_rustls::rustls::ServerName::try_from(host)
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+            Box::new(
+                tokio_rustls::TlsConnector::from(tls.clone())
+                    .connect(server_name, socket)
+                    .await?,
+            )
+        }
+        None => Box::new(socket),
+    };
+    client_handshake(url, socket, remote_addr, config).await
+}
+
+#[cfg(unix)]
+#[instrument(skip(config, path))]
+/// Create a new websocket connection over the unix domain socket at this path.
+pub async fn connect_unix(
+    path: impl AsRef<std::path::Path>,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let socket = tokio::net::UnixStream::connect(path.as_ref()).await?;
+    let remote_addr = util::path_to_url(path.as_ref());
+    // The websocket handshake still needs a url to request,
+    // but there is only one server on the other end of the socket.
+    let url = url2::url2!("{}://localhost", config.scheme);
+    client_handshake(url, Box::new(socket), remote_addr, config).await
+}
+
+async fn client_handshake(
+    url: Url2,
+    socket: Box<dyn AsyncStream>,
+    remote_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    if let Some(origin) = &config.origin {
+        request.headers_mut().insert(
+            tungstenite::http::header::ORIGIN,
+            origin
+                .parse()
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        );
+    }
+    let (socket, _) =
+        tokio_tungstenite::client_async_with_config(request, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     tracing::debug!("Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
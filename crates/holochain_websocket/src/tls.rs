@@ -0,0 +1,105 @@
+//! Helpers for building the TLS configs used by [`WebsocketConfig`](crate::WebsocketConfig)
+//! from PEM encoded files.
+
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::Certificate;
+use tokio_rustls::rustls::ClientConfig;
+use tokio_rustls::rustls::PrivateKey;
+use tokio_rustls::rustls::RootCertStore;
+use tokio_rustls::rustls::ServerConfig;
+
+use crate::WebsocketError;
+use crate::WebsocketResult;
+
+/// Create the TLS config for a listener from the certificate chain and
+/// private key of the server.
+///
+/// If `client_ca_path` is set then clients must present a certificate
+/// signed by one of the authorities in that file, otherwise the TLS
+/// handshake fails and the connection is dropped.
+pub fn server_config(
+    cert_path: &Path,
+    key_path: &Path,
+    client_ca_path: Option<&Path>,
+) -> WebsocketResult<Arc<ServerConfig>> {
+    let certs = load_certs(cert_path)?;
+    let key = load_private_key(key_path)?;
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let config = match client_ca_path {
+        Some(client_ca_path) => builder
+            .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(load_roots(
+                client_ca_path,
+            )?))
+            .with_single_cert(certs, key)?,
+        None => builder.with_no_client_auth().with_single_cert(certs, key)?,
+    };
+    Ok(Arc::new(config))
+}
+
+/// Create the TLS config for connecting to a listener whose certificate is
+/// signed by one of the authorities in `ca_path`.
+///
+/// The client certificate chain and private key are needed if the listener
+/// requires clients to authenticate.
+pub fn client_config(
+    ca_path: &Path,
+    client_cert: Option<(&Path, &Path)>,
+) -> WebsocketResult<Arc<ClientConfig>> {
+    let builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(load_roots(ca_path)?);
+    let config = match client_cert {
+        Some((cert_path, key_path)) => {
+            builder.with_single_cert(load_certs(cert_path)?, load_private_key(key_path)?)?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    Ok(Arc::new(config))
+}
+
+/// Load all the certificates in a PEM file.
+pub fn load_certs(path: &Path) -> WebsocketResult<Vec<Certificate>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let certs: Vec<_> = rustls_pemfile::certs(&mut reader)?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certs.is_empty() {
+        return Err(WebsocketError::TlsConfig(format!(
+            "No certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Load the first private key in a PEM file.
+pub fn load_private_key(path: &Path) -> WebsocketResult<PrivateKey> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    while let Some(item) = rustls_pemfile::read_one(&mut reader)? {
+        match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => (),
+        }
+    }
+    Err(WebsocketError::TlsConfig(format!(
+        "No private key found in {}",
+        path.display()
+    )))
+}
+
+fn load_roots(path: &Path) -> WebsocketResult<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots
+            .add(&cert)
+            .map_err(|e| WebsocketError::TlsConfig(format!("{}: {}", path.display(), e)))?;
+    }
+    Ok(roots)
+}
@@ -6,7 +6,19 @@ use url2::{url2, Url2};
 
 use std::io::{Error, ErrorKind, Result};
 
-pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+/// A byte stream a websocket can run over,
+/// i.e. tcp, tls over tcp or a unix domain socket.
+pub(crate) trait AsyncStream:
+    tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
+{
+}
+
+impl<T> AsyncStream for T where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
+{
+}
+
+pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<Box<dyn AsyncStream>>;
 
 /// Amount of time to spend waiting for channels to empty before forcing them to close.
 pub(crate) const CLOSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
@@ -16,6 +28,12 @@ pub(crate) fn addr_to_url(a: SocketAddr, scheme: &str) -> Url2 {
     url2!("{}://{}", scheme, a)
 }
 
+/// internal helper to convert unix domain socket paths to urls
+#[cfg(unix)]
+pub(crate) fn path_to_url(path: &std::path::Path) -> Url2 {
+    url2!("unix://{}", path.display())
+}
+
 /// internal helper convert urls to socket addrs for binding / connection
 pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> Result<SocketAddr> {
     if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
@@ -1,5 +1,8 @@
 //! defines a builder-style config struct for setting up websockets
 
+use std::collections::HashSet;
+use std::sync::Arc;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -26,6 +29,28 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// TLS config for listeners. If set, incoming connections must
+    /// complete a TLS handshake, including presenting a trusted client
+    /// certificate if the config verifies clients. [default = None]
+    pub tls_server: Option<Arc<tokio_rustls::rustls::ServerConfig>>,
+
+    /// TLS config for connecting to a listener which uses TLS. [default = None]
+    pub tls_client: Option<Arc<tokio_rustls::rustls::ClientConfig>>,
+
+    /// Permissions to set on the socket file of a unix domain socket
+    /// listener, e.g. `0o600` to only allow the owner to connect.
+    /// [default = None, i.e. use the process umask]
+    pub unix_socket_mode: Option<u32>,
+
+    /// The web origins which listeners accept connections from, checked
+    /// against the `Origin` header of the websocket handshake.
+    /// [default = AllowedOrigins::Any]
+    pub allowed_origins: AllowedOrigins,
+
+    /// The `Origin` header to send when connecting to a listener.
+    /// [default = None]
+    pub origin: Option<String>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +63,11 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            tls_server: None,
+            tls_client: None,
+            unix_socket_mode: None,
+            allowed_origins: AllowedOrigins::Any,
+            origin: None,
         }
     }
 }
@@ -78,6 +108,76 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    /// Also sets the scheme to "wss".
+    pub fn tls_server(mut self, config: Arc<tokio_rustls::rustls::ServerConfig>) -> Self {
+        self.tls_server = Some(config);
+        self.scheme = "wss";
+        self
+    }
+
+    /// Builder-style setter.
+    /// Also sets the scheme to "wss".
+    pub fn tls_client(mut self, config: Arc<tokio_rustls::rustls::ClientConfig>) -> Self {
+        self.tls_client = Some(config);
+        self.scheme = "wss";
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn unix_socket_mode(mut self, mode: u32) -> Self {
+        self.unix_socket_mode = Some(mode);
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn allowed_origins(mut self, allowed_origins: AllowedOrigins) -> Self {
+        self.allowed_origins = allowed_origins;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+}
+
+/// The web origins which a listener accepts connections from.
+///
+/// Browsers set the `Origin` header on websocket handshakes to the origin
+/// of the page which opened the connection, so this stops web pages which
+/// aren't trusted from connecting.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedOrigins {
+    /// Accept connections from any origin, or with no `Origin` header,
+    /// e.g. from clients which aren't browsers.
+    #[default]
+    Any,
+    /// Only accept connections whose `Origin` header is one of these,
+    /// e.g. `http://localhost:8888`. Connections without an `Origin`
+    /// header are refused.
+    Origins(HashSet<String>),
+}
+
+impl AllowedOrigins {
+    /// Is a connection with this `Origin` header allowed?
+    pub fn is_allowed(&self, origin: Option<&str>) -> bool {
+        match self {
+            AllowedOrigins::Any => true,
+            AllowedOrigins::Origins(origins) => origin
+                .map(|origin| origins.contains(origin))
+                .unwrap_or(false),
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for AllowedOrigins {
+    fn from_iter<T: IntoIterator<Item = S>>(origins: T) -> Self {
+        AllowedOrigins::Origins(origins.into_iter().map(Into::into).collect())
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
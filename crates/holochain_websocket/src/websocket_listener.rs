@@ -4,15 +4,24 @@ use futures::TryStreamExt;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::handshake::server::ErrorResponse;
+use tungstenite::handshake::server::Request;
+use tungstenite::handshake::server::Response;
+use tungstenite::http;
 
 use url2::Url2;
 
 use crate::util::addr_to_url;
+#[cfg(unix)]
+use crate::util::path_to_url;
 use crate::util::url_to_addr;
+use crate::util::AsyncStream;
 use crate::websocket::Websocket;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
@@ -106,6 +115,35 @@ impl WebsocketListener {
     )> {
         websocket_bind(addr, config).await
     }
+    /// Bind to a unix domain socket at this path to accept incoming connections.
+    /// A socket file left at the path by a previous listener is replaced.
+    /// The permissions of the socket file are set from
+    /// [`WebsocketConfig::unix_socket_mode`] if it is set.
+    #[cfg(unix)]
+    pub async fn bind_unix(
+        path: impl AsRef<std::path::Path>,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<Self> {
+        let (handle, stream) = Self::bind_unix_with_handle(path, config).await?;
+        Ok(Self {
+            handle,
+            stream: stream.boxed(),
+        })
+    }
+
+    /// Same as [`WebsocketListener::bind_unix`] but gives you a [`ListenerHandle`] to shutdown
+    /// the listener and any open connections.
+    #[cfg(unix)]
+    pub async fn bind_unix_with_handle(
+        path: impl AsRef<std::path::Path>,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        unix_bind(path.as_ref().to_path_buf(), config).await
+    }
+
     /// Shutdown the listener stream.
     pub fn close(self) {
         self.handle.close()
@@ -202,8 +240,100 @@ async fn websocket_bind(
     socket.set_nonblocking(true)?;
     let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
     let listener = tokio::net::TcpListener::from_std(socket)?;
-    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener)
+        .map_ok({
+            let scheme = config.scheme;
+            move |socket| {
+                let remote_addr = socket.peer_addr().map(|addr| addr_to_url(addr, scheme));
+                (Box::new(socket) as Box<dyn AsyncStream>, remote_addr)
+            }
+        })
+        .boxed();
 
+    Ok(listen(listener_stream, local_addr, config))
+}
+
+#[cfg(unix)]
+async fn unix_bind(
+    path: PathBuf,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    // A socket file left behind by a previous run stops us binding,
+    // but any other kind of file is not ours to remove, and neither is
+    // a socket something is still listening on.
+    if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+        if metadata.file_type().is_socket() {
+            if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+                return Err(Error::new(
+                    ErrorKind::AddrInUse,
+                    format!("Something is already listening on {}", path.display()),
+                )
+                .into());
+            }
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    // Bind in a directory only we can enter, so nobody can connect before
+    // the socket has its mode, then move the socket into place.
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Socket path has no file name"))?;
+    let private_dir = path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        nanoid::nanoid!()
+    ));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+    let bind = || {
+        let private_path = private_dir.join(file_name);
+        let listener = tokio::net::UnixListener::bind(&private_path)?;
+        if let Some(mode) = config.unix_socket_mode {
+            std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(mode))?;
+        }
+        std::fs::rename(&private_path, &path)?;
+        std::io::Result::Ok(listener)
+    };
+    let listener = bind();
+    let _ = std::fs::remove_dir_all(&private_dir);
+    let listener = listener?;
+    let local_addr = path_to_url(&std::fs::canonicalize(&path)?);
+    let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener)
+        .map_ok({
+            let local_addr = local_addr.clone();
+            move |socket| {
+                (
+                    Box::new(socket) as Box<dyn AsyncStream>,
+                    Ok(local_addr.clone()),
+                )
+            }
+        })
+        .boxed();
+
+    Ok(listen(listener_stream, local_addr, config))
+}
+
+/// Incoming raw sockets along with the address of the remote end.
+type RawSocketStream =
+    BoxStream<'static, std::io::Result<(Box<dyn AsyncStream>, std::io::Result<Url2>)>>;
+
+fn listen(
+    listener_stream: RawSocketStream,
+    local_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> (
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+) {
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
 
@@ -212,7 +342,7 @@ async fn websocket_bind(
         .map_ok({
             let config = config.clone();
             let valve = valve.clone();
-            move |socket_result| connect(config.clone(), socket_result, valve.clone())
+            move |(socket, remote_addr)| connect(config.clone(), socket, remote_addr, valve.clone())
         })
         .try_buffer_unordered(config.max_pending_connections);
     tracing::debug!(sever_listening_on = ?local_addr);
@@ -224,25 +354,51 @@ async fn websocket_bind(
         config,
         local_addr,
     };
-    Ok((listener_handle, stream))
+    (listener_handle, stream)
 }
 
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: Box<dyn AsyncStream>,
+    remote_addr: std::io::Result<Url2>,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
+    let remote_addr = remote_addr?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %remote_addr,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
+    let socket: Box<dyn AsyncStream> = match &config.tls_server {
+        Some(tls) => Box::new(
+            tokio_rustls::TlsAcceptor::from(tls.clone())
+                .accept(socket)
+                .await?,
+        ),
+        None => socket,
+    };
+    let allowed_origins = config.allowed_origins.clone();
+    let check_origin = move |request: &Request, response: Response| {
+        let origin = request
+            .headers()
+            .get(http::header::ORIGIN)
+            .and_then(|origin| origin.to_str().ok());
+        if allowed_origins.is_allowed(origin) {
+            Ok(response)
+        } else {
+            tracing::warn!(?origin, "Refused websocket connection from origin");
+            let mut response = ErrorResponse::new(Some("Origin not allowed".to_string()));
+            *response.status_mut() = http::StatusCode::FORBIDDEN;
+            Err(response)
+        }
+    };
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
         socket,
+        check_origin,
         Some(tungstenite::protocol::WebSocketConfig {
             max_send_queue: Some(config.max_send_queue),
             max_message_size: Some(config.max_message_size),
@@ -253,5 +409,5 @@ async fn connect(
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
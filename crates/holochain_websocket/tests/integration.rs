@@ -3,6 +3,9 @@ use std::sync::Arc;
 use futures::StreamExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::connect;
+#[cfg(unix)]
+use holochain_websocket::connect_unix;
+use holochain_websocket::tls;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
 use holochain_websocket::WebsocketConfig;
@@ -452,3 +455,236 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+/// Respond to every request on every connection with an echo.
+fn echo_server(
+    mut listener: impl futures::stream::Stream<Item = ListenerItem> + Unpin + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::task::spawn(async move {
+        while let Some(connection) = listener.next().await {
+            // Failed handshakes show up as errors but don't stop the listener.
+            let (_, mut receiver) = match connection {
+                Ok(pair) => pair,
+                Err(_) => continue,
+            };
+            tokio::task::spawn(async move {
+                while let Some((msg, resp)) = receiver.next().await {
+                    let msg: TestString = msg.try_into().unwrap();
+                    resp.respond(TestString(format!("echo: {}", msg.0)).try_into().unwrap())
+                        .await
+                        .unwrap();
+                }
+            });
+        }
+    })
+}
+
+/// A certificate authority along with a server and client certificate it signed,
+/// written out as PEM files.
+struct TestCerts {
+    dir: tempfile::TempDir,
+}
+
+impl TestCerts {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ca_params = rcgen::CertificateParams::new(vec![]);
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca = rcgen::Certificate::from_params(ca_params).unwrap();
+        std::fs::write(dir.path().join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+        for name in ["server", "client"] {
+            let cert = rcgen::Certificate::from_params(rcgen::CertificateParams::new(vec![
+                "localhost".to_string(),
+            ]))
+            .unwrap();
+            std::fs::write(
+                dir.path().join(format!("{}.pem", name)),
+                cert.serialize_pem_with_signer(&ca).unwrap(),
+            )
+            .unwrap();
+            std::fs::write(
+                dir.path().join(format!("{}.key", name)),
+                cert.serialize_private_key_pem(),
+            )
+            .unwrap();
+        }
+        Self { dir }
+    }
+
+    fn path(&self, file: &str) -> std::path::PathBuf {
+        self.dir.path().join(file)
+    }
+
+    async fn server(
+        &self,
+    ) -> (
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    ) {
+        let tls = tls::server_config(
+            &self.path("server.pem"),
+            &self.path("server.key"),
+            Some(&self.path("ca.pem")),
+        )
+        .unwrap();
+        WebsocketListener::bind_with_handle(
+            url2!("wss://127.0.0.1:0"),
+            Arc::new(WebsocketConfig::default().tls_server(tls)),
+        )
+        .await
+        .unwrap()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tls_client_with_certificate_can_connect() {
+    holochain_trace::test_run().ok();
+    let certs = TestCerts::new();
+    let (handle, listener) = certs.server().await;
+    let _s_jh = echo_server(Box::pin(listener));
+
+    let tls = tls::client_config(
+        &certs.path("ca.pem"),
+        Some((&certs.path("client.pem"), &certs.path("client.key"))),
+    )
+    .unwrap();
+    let port = handle.local_addr().port().unwrap();
+    let (mut sender, _) = connect(
+        url2!("wss://localhost:{}", port),
+        Arc::new(WebsocketConfig::default().tls_client(tls)),
+    )
+    .await
+    .unwrap();
+
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "echo: Hey from client");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tls_client_without_certificate_is_rejected() {
+    holochain_trace::test_run().ok();
+    let certs = TestCerts::new();
+    let (handle, listener) = certs.server().await;
+    let _s_jh = echo_server(Box::pin(listener));
+    let port = handle.local_addr().port().unwrap();
+
+    // Plain websocket.
+    assert!(connect(
+        url2!("ws://localhost:{}", port),
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .is_err());
+
+    // TLS without a client certificate.
+    let tls = tls::client_config(&certs.path("ca.pem"), None).unwrap();
+    assert!(connect(
+        url2!("wss://localhost:{}", port),
+        Arc::new(WebsocketConfig::default().tls_client(tls)),
+    )
+    .await
+    .is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_connect_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+
+    holochain_trace::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("admin.sock");
+    // A socket left behind by a previous listener is replaced.
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    let (_handle, listener) = WebsocketListener::bind_unix_with_handle(
+        &path,
+        Arc::new(WebsocketConfig::default().unix_socket_mode(0o600)),
+    )
+    .await
+    .unwrap();
+    let _s_jh = echo_server(Box::pin(listener));
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+
+    let (mut sender, _) = connect_unix(&path, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "echo: Hey from client");
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn unix_socket_in_use_is_not_replaced() {
+    holochain_trace::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("admin.sock");
+
+    let (_handle, listener) =
+        WebsocketListener::bind_unix_with_handle(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    let _s_jh = echo_server(Box::pin(listener));
+
+    let err = WebsocketListener::bind_unix_with_handle(&path, Arc::new(WebsocketConfig::default()))
+        .await
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WebsocketError::Io(e) if e.kind() == std::io::ErrorKind::AddrInUse
+    ));
+
+    // The first listener can still be reached, and nothing was left behind.
+    let (mut sender, _) = connect_unix(&path, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "echo: Hey from client");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_allowed_origins_can_connect() {
+    holochain_trace::test_run().ok();
+    let (handle, listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(
+            WebsocketConfig::default()
+                .allowed_origins(["http://localhost:8888"].into_iter().collect()),
+        ),
+    )
+    .await
+    .unwrap();
+    let _s_jh = echo_server(Box::pin(listener));
+    let port = handle.local_addr().port().unwrap();
+    let connect_from = |origin: Option<&str>| {
+        let config = match origin {
+            Some(origin) => WebsocketConfig::default().origin(origin),
+            None => WebsocketConfig::default(),
+        };
+        connect(url2!("ws://127.0.0.1:{}", port), Arc::new(config))
+    };
+
+    assert!(connect_from(None).await.is_err());
+    assert!(connect_from(Some("http://localhost:9999")).await.is_err());
+
+    let (mut sender, _) = connect_from(Some("http://localhost:8888")).await.unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "echo: Hey from client");
+}
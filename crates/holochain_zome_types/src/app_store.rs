@@ -0,0 +1,21 @@
+//! Types for the app store contract.
+//!
+//! An app store is a DNA from which the conductor can fetch published app and
+//! DNA bundles by hash. As with DPKI, the conductor only knows about the zome
+//! and function names defined here, so any DNA implementing this interface can
+//! be used as the conductor's app store.
+//!
+//! Bundles are passed around as their encoded bytes. The conductor decodes them
+//! and checks them against the requested hash, so the store does not need to be
+//! trusted to return the right content.
+
+/// The name of the zome which implements the app store contract.
+pub const APP_STORE_ZOME_NAME: &str = "app_store";
+
+/// Get an encoded app bundle. Takes an [`AppHash`](holo_hash::AppHash),
+/// returns `Option<Bytes>`.
+pub const APP_STORE_GET_APP_BUNDLE_FN: &str = "get_app_bundle";
+
+/// Get an encoded DNA bundle. Takes a [`DnaHash`](holo_hash::DnaHash),
+/// returns `Option<Bytes>`.
+pub const APP_STORE_GET_DNA_BUNDLE_FN: &str = "get_dna_bundle";
@@ -1,4 +1,5 @@
 use crate::CellId;
+use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
 use holo_hash::DhtOpHash;
 use holo_hash::DnaHash;
@@ -29,6 +30,11 @@ pub enum CellBlockReason {
     App(Vec<u8>),
     /// Invalid validation result.
     InvalidOp(DhtOpHash),
+    /// Another agent issued a warrant against the action with this hash.
+    Warrant(ActionHash),
+    /// This agent issued a warrant against the action with this hash,
+    /// which turned out to be valid.
+    FalseWarrant(ActionHash),
     /// Some bad cryptography.
     BadCrypto,
 }
@@ -27,7 +27,10 @@
 mod grant;
 pub use grant::*;
 
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 pub use holochain_integrity_types::capability::*;
+use holochain_integrity_types::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::CellId;
@@ -41,3 +44,119 @@ pub struct GrantZomeCallCapabilityPayload {
     /// signing for as well as access level, secret and assignees.
     pub cap_grant: ZomeCallCapGrant,
 }
+
+/// Filter for listing the capability grants on the local source chain.
+///
+/// The default filter lists every grant which is still live, i.e. has not
+/// been updated or deleted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapGrantQuery {
+    /// Only list grants with exactly this tag.
+    pub tag: Option<String>,
+    /// Only list grants which give access to this zome function.
+    pub function: Option<GrantedFunction>,
+    /// Also list grants which have been revoked by an update or delete.
+    pub include_revoked: bool,
+}
+
+impl CapGrantQuery {
+    /// Create a filter which lists all live grants.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list grants with exactly this tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Only list grants which give access to this zome function.
+    pub fn function(mut self, function: GrantedFunction) -> Self {
+        self.function = Some(function);
+        self
+    }
+
+    /// Also list grants which have been revoked.
+    pub fn include_revoked(mut self, include_revoked: bool) -> Self {
+        self.include_revoked = include_revoked;
+        self
+    }
+
+    /// Check if a grant matches the tag and function of this filter.
+    pub fn check(&self, grant: &ZomeCallCapGrant) -> bool {
+        self.tag.as_ref().map_or(true, |tag| *tag == grant.tag)
+            && self
+                .function
+                .as_ref()
+                .map_or(true, |function| match &grant.functions {
+                    GrantedFunctions::All => true,
+                    GrantedFunctions::Listed(functions) => functions.contains(function),
+                })
+    }
+}
+
+/// A capability grant committed to the local source chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapGrantInfo {
+    /// The grant itself.
+    pub cap_grant: ZomeCallCapGrant,
+    /// The hash of the action which committed the grant.
+    /// This is the hash to pass to `update_cap_grant` or `delete_cap_grant`.
+    pub action_hash: ActionHash,
+    /// When the grant was committed.
+    pub created_at: Timestamp,
+    /// When the grant was revoked by an update or delete, if it has been.
+    /// A revoked grant no longer authorizes any zome calls.
+    pub revoked_at: Option<Timestamp>,
+}
+
+/// Filter for listing the capability claims on the local source chain.
+///
+/// Claims which have been deleted are never listed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapClaimQuery {
+    /// Only list claims with exactly this tag.
+    pub tag: Option<String>,
+    /// Only list claims on grants made by this agent.
+    pub grantor: Option<AgentPubKey>,
+}
+
+impl CapClaimQuery {
+    /// Create a filter which lists all claims.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list claims with exactly this tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Only list claims on grants made by this agent.
+    pub fn grantor(mut self, grantor: AgentPubKey) -> Self {
+        self.grantor = Some(grantor);
+        self
+    }
+
+    /// Check if a claim matches this filter.
+    pub fn check(&self, claim: &CapClaim) -> bool {
+        self.tag.as_ref().map_or(true, |tag| *tag == claim.tag)
+            && self
+                .grantor
+                .as_ref()
+                .map_or(true, |grantor| *grantor == claim.grantor)
+    }
+}
+
+/// The capability which authorized the current zome call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityInfo {
+    /// The agent who made the call.
+    pub provenance: AgentPubKey,
+    /// The grant on the local source chain which authorized the call.
+    /// This is `None` when the call was made by the chain author, who
+    /// needs no grant to call their own zome functions.
+    pub grant: Option<CapGrantInfo>,
+}
@@ -0,0 +1,94 @@
+//! Types for the DPKI contract.
+//!
+//! A DPKI service is a DNA which keeps track of the lifecycle of agent keys.
+//! The conductor talks to it through the functions of a single zome, whose
+//! names and payloads are defined here, so that any DNA implementing this
+//! interface can be used as the conductor's DPKI service.
+
+use holo_hash::AgentPubKey;
+use holochain_integrity_types::Timestamp;
+
+/// The name of the zome which implements the DPKI contract.
+pub const DPKI_ZOME_NAME: &str = "dpki";
+
+/// Get the [`KeyState`] of an [`AgentPubKey`].
+pub const DPKI_KEY_STATE_FN: &str = "key_state";
+
+/// Register a new [`AgentPubKey`]. Takes the key, returns `()`.
+pub const DPKI_REGISTER_KEY_FN: &str = "register_key";
+
+/// Atomically replace a key with a new one. Takes an [`UpdateKeyInput`], returns `()`.
+pub const DPKI_UPDATE_KEY_FN: &str = "update_key";
+
+/// Revoke a key without replacing it. Takes the key, returns `()`.
+pub const DPKI_REVOKE_KEY_FN: &str = "revoke_key";
+
+/// The state of an agent key, as recorded by DPKI
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum KeyState {
+    /// The key was never registered
+    NotFound,
+    /// The key was registered and is still in use
+    Valid {
+        /// When the key was registered
+        registered_at: Timestamp,
+    },
+    /// The key was registered, and later revoked or replaced by another key
+    Invalidated {
+        /// When the key was registered
+        registered_at: Timestamp,
+        /// When the key was revoked or replaced
+        invalidated_at: Timestamp,
+    },
+}
+
+impl KeyState {
+    /// Whether an action authored with this key at the given time is valid:
+    /// the key must have been registered at or before that time, and not yet
+    /// invalidated.
+    pub fn is_valid_at(&self, timestamp: Timestamp) -> bool {
+        match self {
+            KeyState::NotFound => false,
+            KeyState::Valid { registered_at } => *registered_at <= timestamp,
+            KeyState::Invalidated {
+                registered_at,
+                invalidated_at,
+            } => *registered_at <= timestamp && timestamp < *invalidated_at,
+        }
+    }
+}
+
+/// Input to [`DPKI_UPDATE_KEY_FN`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UpdateKeyInput {
+    /// The key being replaced
+    pub old_key: AgentPubKey,
+    /// The key replacing it
+    pub new_key: AgentPubKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_state_validity_window() {
+        let t = |micros| Timestamp::from_micros(micros);
+        assert!(!KeyState::NotFound.is_valid_at(t(10)));
+
+        let valid = KeyState::Valid {
+            registered_at: t(10),
+        };
+        assert!(!valid.is_valid_at(t(9)));
+        assert!(valid.is_valid_at(t(10)));
+        assert!(valid.is_valid_at(t(1000)));
+
+        let invalidated = KeyState::Invalidated {
+            registered_at: t(10),
+            invalidated_at: t(20),
+        };
+        assert!(!invalidated.is_valid_at(t(9)));
+        assert!(invalidated.is_valid_at(t(19)));
+        assert!(!invalidated.is_valid_at(t(20)));
+    }
+}
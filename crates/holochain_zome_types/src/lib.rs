@@ -13,6 +13,7 @@
 pub mod action;
 #[allow(missing_docs)]
 pub mod agent_activity;
+pub mod app_store;
 #[allow(missing_docs)]
 pub mod block;
 pub mod bytes;
@@ -26,6 +27,7 @@ pub mod countersigning;
 #[allow(missing_docs)]
 pub mod crdt;
 pub mod dna_def;
+pub mod dpki;
 pub mod entry;
 #[allow(missing_docs)]
 pub mod entry_def;
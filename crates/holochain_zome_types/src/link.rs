@@ -89,6 +89,23 @@ pub struct GetLinksInput {
     /// The link types to include in this get.
     pub link_type: LinkTypeFilter,
     pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Only include links created after this time.
+    #[serde(default)]
+    pub after: Option<crate::Timestamp>,
+    /// Only include links created before this time.
+    #[serde(default)]
+    pub before: Option<crate::Timestamp>,
+    /// Only include links created by this agent.
+    #[serde(default)]
+    pub author: Option<holo_hash::AgentPubKey>,
+    /// The most links to return.
+    /// Links are ordered by [`LinkCursor`], oldest first.
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Only include links which come after this position,
+    /// usually the last link of the previous page.
+    #[serde(default)]
+    pub cursor: Option<LinkCursor>,
 }
 
 impl GetLinksInput {
@@ -101,6 +118,135 @@ impl GetLinksInput {
             base_address,
             link_type,
             tag_prefix,
+            after: None,
+            before: None,
+            author: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    /// Only include links created after this time.
+    pub fn after(mut self, after: crate::Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only include links created before this time.
+    pub fn before(mut self, before: crate::Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Only include links created by this agent.
+    pub fn author(mut self, author: holo_hash::AgentPubKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Return at most this many links.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Continue from a previous page of links.
+    pub fn cursor(mut self, cursor: LinkCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+/// A query for the number of live links on a base, see `count_links`.
+///
+/// Unlike [`GetLinksInput`] there is no limit or cursor, every matching link
+/// which hasn't been deleted is counted.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct LinkQuery {
+    /// The base the links are on.
+    pub base: holo_hash::AnyLinkableHash,
+    /// The link types to include in this query.
+    pub link_type: LinkTypeFilter,
+    /// Only count links whose tag starts with this prefix.
+    #[serde(default)]
+    pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Only count links created after this time.
+    #[serde(default)]
+    pub after: Option<crate::Timestamp>,
+    /// Only count links created before this time.
+    #[serde(default)]
+    pub before: Option<crate::Timestamp>,
+    /// Only count links created by this agent.
+    #[serde(default)]
+    pub author: Option<holo_hash::AgentPubKey>,
+}
+
+impl LinkQuery {
+    pub fn new(base: impl Into<holo_hash::AnyLinkableHash>, link_type: LinkTypeFilter) -> Self {
+        Self {
+            base: base.into(),
+            link_type,
+            tag_prefix: None,
+            after: None,
+            before: None,
+            author: None,
+        }
+    }
+
+    /// Only count links whose tag starts with this prefix.
+    pub fn tag_prefix(mut self, tag_prefix: crate::link::LinkTag) -> Self {
+        self.tag_prefix = Some(tag_prefix);
+        self
+    }
+
+    /// Only count links created after this time.
+    pub fn after(mut self, after: crate::Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only count links created before this time.
+    pub fn before(mut self, before: crate::Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Only count links created by this agent.
+    pub fn author(mut self, author: holo_hash::AgentPubKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+}
+
+/// A position in a list of links, which are ordered by when they were created
+/// and then by the hash of their create action.
+///
+/// Pass the cursor of the last link in a page of results as
+/// [`GetLinksInput::cursor`] to get the next page.
+#[derive(
+    Debug,
+    PartialOrd,
+    Ord,
+    Clone,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    SerializedBytes,
+)]
+pub struct LinkCursor {
+    /// When the link was created.
+    pub timestamp: crate::Timestamp,
+    /// The hash of the link's create action.
+    pub create_link_hash: ActionHash,
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        Self {
+            timestamp: link.timestamp,
+            create_link_hash: link.create_link_hash.clone(),
         }
     }
 }
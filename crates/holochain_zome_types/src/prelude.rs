@@ -3,6 +3,7 @@
 pub use crate::action::conversions::*;
 pub use crate::action::*;
 pub use crate::agent_activity::*;
+pub use crate::app_store::*;
 pub use crate::block::*;
 pub use crate::bytes::*;
 pub use crate::call::*;
@@ -12,6 +13,7 @@ pub use crate::chain::*;
 pub use crate::countersigning::*;
 pub use crate::crdt::*;
 pub use crate::dna_def::*;
+pub use crate::dpki::*;
 pub use crate::entry::*;
 pub use crate::entry_def::*;
 pub use crate::genesis::*;
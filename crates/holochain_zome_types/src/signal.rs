@@ -19,6 +19,11 @@ impl AppSignal {
     pub fn into_inner(self) -> crate::ExternIO {
         self.0
     }
+
+    /// Access the inner type by reference
+    pub fn as_inner(&self) -> &crate::ExternIO {
+        &self.0
+    }
 }
 
 /// Remote signal many agents without waiting for responses.
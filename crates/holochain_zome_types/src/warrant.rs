@@ -1,6 +1,99 @@
 //! Types for warrants
+//!
+//! A warrant is a signed claim, made by a validator, that another agent has
+//! authored an invalid action. It carries enough proof that any recipient can
+//! check it for themselves, and it is published to the agent activity
+//! authorities of the warranted agent so that anyone looking up that agent's
+//! activity will find it.
+
+use crate::record::SignedAction;
+use crate::signature::Signature;
+use holo_hash::AgentPubKey;
+use holochain_integrity_types::Timestamp;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+/// The kind of validation which found an action to be invalid.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub enum ValidationType {
+    /// The action failed system validation.
+    Sys,
+    /// The action failed the app's validation callback.
+    App,
+}
+
+/// The proof that the warranted agent has broken the rules of the network.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub enum WarrantProof {
+    /// An action which failed validation, along with its author's signature.
+    /// Any recipient can re-run validation on this action to check the claim.
+    InvalidAction {
+        /// The offending action, as signed by its author.
+        action: SignedAction,
+        /// Which kind of validation rejected the action.
+        validation_type: ValidationType,
+    },
+}
+
+/// A claim by one agent that another agent has authored invalid data.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct Warrant {
+    /// The proof of wrongdoing.
+    pub proof: WarrantProof,
+    /// The agent who issued this warrant.
+    pub author: AgentPubKey,
+    /// When the warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+impl Warrant {
+    /// Constructor
+    pub fn new(proof: WarrantProof, author: AgentPubKey, timestamp: Timestamp) -> Self {
+        Self {
+            proof,
+            author,
+            timestamp,
+        }
+    }
+
+    /// The agent this warrant is against.
+    pub fn warrantee(&self) -> &AgentPubKey {
+        match &self.proof {
+            WarrantProof::InvalidAction { action, .. } => action.action().author(),
+        }
+    }
+
+    /// The signed action this warrant is about.
+    pub fn action(&self) -> &SignedAction {
+        match &self.proof {
+            WarrantProof::InvalidAction { action, .. } => action,
+        }
+    }
+}
+
+/// A [`Warrant`] signed by its author.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct SignedWarrant {
+    /// The warrant.
+    pub warrant: Warrant,
+    /// The warrant author's signature of the warrant.
+    pub signature: Signature,
+}
+
+impl SignedWarrant {
+    /// Constructor
+    pub fn new(warrant: Warrant, signature: Signature) -> Self {
+        Self { warrant, signature }
+    }
+
+    /// Accessor for the warrant
+    pub fn warrant(&self) -> &Warrant {
+        &self.warrant
+    }
+
+    /// Accessor for the signature
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+}
@@ -54,14 +54,14 @@ wasm_io_types! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the capability claims on the local source chain.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List the capability grants on the local source chain.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<zt::capability::CapGrantInfo>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability which authorized the current zome call.
+    fn capability_info (()) -> zt::capability::CapabilityInfo;
 
     // Returns ActionHash of the newly created record.
     fn create (zt::entry::CreateInput) -> holo_hash::ActionHash;
@@ -89,11 +89,17 @@ wasm_io_types! {
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
 
+    // Get the metadata for an entry or action without the record itself.
+    fn get_meta (Vec<zt::entry::GetInput>) -> Vec<zt::metadata::MetadataSet>;
+
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
 
+    // Count the live links matching a query without fetching them.
+    fn count_links (zt::link::LinkQuery) -> usize;
+
     // Attempt to get a live entry from the cascade.
     fn get (Vec<zt::entry::GetInput>) -> Vec<Option<zt::record::Record>>;
 
@@ -126,7 +132,7 @@ wasm_io_types! {
     // Schedule a schedulable function if it is not already.
     fn schedule (String) -> ();
 
-    // @todo
+    // Suspend the zome call, up to the conductor's maximum sleep.
     fn sleep (core::time::Duration) -> ();
 
     // @todo
@@ -3,9 +3,11 @@
 
 use std::net::SocketAddr;
 use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
 use kitsune_p2p_types::codec::rmp_decode;
 use kitsune_p2p_types::codec::rmp_encode;
+use rate_limit::RateLimiter;
 use store::Store;
 use warp::{hyper::body::Bytes, Filter};
 
@@ -18,8 +20,16 @@ mod now;
 mod proxy_list;
 mod put;
 mod random;
+mod rate_limit;
+mod stats;
 mod store;
 
+pub use rate_limit::RateLimit;
+pub use stats::{RequestStats, Stats};
+#[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+pub use store::SqliteStore;
+pub use store::{MemoryStore, SpaceStats, StoreBackend};
+
 /// No reason to accept a peer data bigger then 1KB.
 // TODO: Maybe even that's too high?
 const SIZE_LIMIT: u64 = 1024;
@@ -45,23 +55,82 @@ pub async fn run_with_prune_freq(
     proxy_list: Vec<String>,
     prune_frequency: std::time::Duration,
 ) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
-    let store = Store::new(proxy_list);
+    run_with_config(
+        addr,
+        BootstrapConfig {
+            proxy_list,
+            prune_frequency,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Configuration for a bootstrap server.
+#[derive(Clone, Debug)]
+pub struct BootstrapConfig {
+    /// Proxy server addresses returned by the `proxy_list` call.
+    pub proxy_list: Vec<String>,
+    /// How often expired agent info is pruned from the store.
+    pub prune_frequency: std::time::Duration,
+    /// Where agent info is stored.
+    pub store: Arc<dyn StoreBackend>,
+    /// Limit how many requests each source ip can make.
+    /// Requests are not limited if this is `None`.
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            proxy_list: Vec::new(),
+            prune_frequency: PRUNE_EXPIRED_FREQ,
+            store: Arc::new(MemoryStore::default()),
+            rate_limit: None,
+        }
+    }
+}
+
+/// Run a bootstrap server with the given config.
+pub async fn run_with_config(
+    addr: impl Into<SocketAddr> + 'static,
+    config: BootstrapConfig,
+) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
+    let BootstrapConfig {
+        proxy_list,
+        prune_frequency,
+        store,
+        rate_limit,
+    } = config;
+    let store = Store::with_backend(store, proxy_list);
+    let limiter = rate_limit.map(|limit| Arc::new(RateLimiter::new(limit)));
 
     {
         let store = store.clone();
+        let limiter = limiter.clone();
         tokio::task::spawn(async move {
             loop {
                 tokio::time::sleep(prune_frequency).await;
-                store.prune();
+                if let Err(e) = store.prune() {
+                    tracing::error!(?e, "Failed to prune bootstrap store");
+                }
+                if let Some(limiter) = &limiter {
+                    limiter.prune();
+                }
             }
         });
     }
 
-    let boot = now::now()
-        .or(put::put(store.clone()))
-        .or(random::random(store.clone()))
-        .or(proxy_list::proxy_list(store.clone()))
-        .or(clear::clear(store));
+    let boot = rate_limit::rate_limit(limiter)
+        .and(
+            now::now()
+                .or(put::put(store.clone()))
+                .or(random::random(store.clone()))
+                .or(proxy_list::proxy_list(store.clone()))
+                .or(clear::clear(store.clone()))
+                .or(stats::stats(store)),
+        )
+        .recover(rate_limit::handle_rejection);
 
     let (s, r) = tokio::sync::oneshot::channel();
     let shutdown = Box::new(move || {
@@ -12,11 +12,25 @@ struct Args {
     /// multiple times
     #[clap(short, long, verbatim_doc_comment)]
     proxy: Vec<String>,
+
+    /// store agent info in a sqlite database at this
+    /// path so that it survives restarts, if not set
+    /// agent info is only kept in memory
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    #[clap(short, long, verbatim_doc_comment)]
+    database: Option<std::path::PathBuf>,
+
+    /// limit each source ip to this many requests
+    /// per minute, if not set requests are not limited
+    #[clap(short, long, verbatim_doc_comment)]
+    rate_limit_per_minute: Option<u32>,
 }
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
     let args = Args::parse();
+    // Errors, such as failing to prune the store, are logged even if RUST_LOG is unset.
+    holochain_trace::init_fmt(holochain_trace::Output::Log).ok();
 
     use std::net::ToSocketAddrs;
     let addr = args
@@ -27,7 +41,33 @@ async fn main() {
         .next()
         .unwrap();
 
-    match kitsune_p2p_bootstrap::run(addr, args.proxy).await {
+    let config = kitsune_p2p_bootstrap::BootstrapConfig {
+        proxy_list: args.proxy,
+        rate_limit: args.rate_limit_per_minute.map(|max_requests| {
+            kitsune_p2p_bootstrap::RateLimit {
+                max_requests,
+                window: std::time::Duration::from_secs(60),
+            }
+        }),
+        ..Default::default()
+    };
+
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    let config = match args.database {
+        Some(path) => match kitsune_p2p_bootstrap::SqliteStore::open(&path) {
+            Ok(store) => kitsune_p2p_bootstrap::BootstrapConfig {
+                store: std::sync::Arc::new(store),
+                ..config
+            },
+            Err(err) => {
+                eprintln!("Failed to open database {}: {}", path.display(), err);
+                return;
+            }
+        },
+        None => config,
+    };
+
+    match kitsune_p2p_bootstrap::run_with_config(addr, config).await {
         Ok((driver, addr, _shutdown)) => {
             println!("http://{}", addr);
             driver.await;
@@ -20,7 +20,7 @@ async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::R
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&peer)).map_err(|_| warp::reject())?;
     // TODO: Return rejection if agent info was invalid?
     if valid(&peer) {
-        store.put(peer);
+        store.put(peer).map_err(|_| warp::reject())?;
     }
     PUT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     let mut buf = Vec::with_capacity(1);
@@ -73,14 +73,6 @@ mod tests {
             .reply(&filter)
             .await;
         assert_eq!(res.status(), 200);
-        assert_eq!(
-            *store
-                .all()
-                .get(info.space.as_ref())
-                .unwrap()
-                .get(info.agent.as_ref())
-                .unwrap(),
-            info
-        );
+        assert_eq!(store.get(&info.space, &info.agent).unwrap().unwrap(), info);
     }
 }
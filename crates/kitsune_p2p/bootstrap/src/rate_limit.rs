@@ -0,0 +1,129 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use warp::{http::StatusCode, Filter};
+
+/// A limit on how many requests each source IP may make in a window of time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The number of requests allowed in each window.
+    pub max_requests: u32,
+    /// The length of each window.
+    pub window: Duration,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    windows: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from this ip, returning false if it is over the limit.
+    pub fn check(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut windows = self.windows.lock();
+        let (start, count) = windows.entry(ip).or_insert((now, 0));
+        if now.duration_since(*start) >= self.limit.window {
+            *start = now;
+            *count = 0;
+        }
+        *count += 1;
+        *count <= self.limit.max_requests
+    }
+
+    /// Forget about ips whose window has ended.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.windows
+            .lock()
+            .retain(|_, (start, _)| now.duration_since(*start) < self.limit.window);
+    }
+}
+
+#[derive(Debug)]
+struct RateLimited;
+
+impl warp::reject::Reject for RateLimited {}
+
+/// Reject requests from ips which are over the limit.
+/// Requests are never rejected when there is no limiter or no remote address.
+pub(crate) fn rate_limit(
+    limiter: Option<Arc<RateLimiter>>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |addr: Option<SocketAddr>| {
+            let limiter = limiter.clone();
+            async move {
+                match (limiter, addr) {
+                    (Some(limiter), Some(addr)) if !limiter.check(addr.ip()) => {
+                        Err(warp::reject::custom(RateLimited))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Turn rate limit rejections into `429 Too Many Requests` responses.
+pub(crate) async fn handle_rejection(
+    rejection: warp::Rejection,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if rejection.find::<RateLimited>().is_some() {
+        Ok(warp::reply::with_status(
+            warp::reply(),
+            StatusCode::TOO_MANY_REQUESTS,
+        ))
+    } else {
+        Err(rejection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rate_limit() {
+        let limiter = Arc::new(RateLimiter::new(RateLimit {
+            max_requests: 2,
+            window: Duration::from_secs(60),
+        }));
+        let filter = rate_limit(Some(limiter.clone()))
+            .map(warp::reply)
+            .recover(handle_rejection);
+
+        let request = |ip: [u8; 4]| {
+            warp::test::request()
+                .method("POST")
+                .remote_addr(SocketAddr::from((ip, 1234)))
+        };
+        for _ in 0..2 {
+            assert_eq!(request([1, 1, 1, 1]).reply(&filter).await.status(), 200);
+        }
+        assert_eq!(request([1, 1, 1, 1]).reply(&filter).await.status(), 429);
+        // Other ips have their own limit.
+        assert_eq!(request([2, 2, 2, 2]).reply(&filter).await.status(), 200);
+
+        // Once the window is over the ip can make requests again.
+        limiter.windows.lock().values_mut().for_each(|(start, _)| {
+            *start -= Duration::from_secs(60);
+        });
+        limiter.prune();
+        assert!(limiter.windows.lock().is_empty());
+        assert_eq!(request([1, 1, 1, 1]).reply(&filter).await.status(), 200);
+    }
+}
@@ -0,0 +1,128 @@
+use std::{collections::BTreeMap, sync::atomic::Ordering};
+
+use crate::store::{SpaceStats, Store};
+
+use super::*;
+use warp::Filter;
+
+/// Counts of each kind of request served since the server started.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RequestStats {
+    pub now: usize,
+    pub put: usize,
+    pub random: usize,
+}
+
+/// The response to `GET /stats`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Stats {
+    /// Stats for each space, keyed by the base64 encoded space.
+    pub spaces: BTreeMap<String, SpaceStats>,
+    pub requests: RequestStats,
+}
+
+pub(crate) fn stats(
+    store: Store,
+) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
+    let all = warp::path!("stats")
+        .and(with_store(store.clone()))
+        .and_then(all_stats);
+    let space = warp::path!("stats" / String)
+        .and(with_store(store))
+        .and_then(space_stats);
+    warp::get().and(all.or(space))
+}
+
+async fn all_stats(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+    let spaces = store
+        .stats()
+        .map_err(|_| warp::reject())?
+        .into_iter()
+        .map(|(space, stats)| (space.to_string(), stats))
+        .collect();
+    Ok(warp::reply::json(&Stats {
+        spaces,
+        requests: RequestStats {
+            now: NOW.load(Ordering::Relaxed),
+            put: PUT.load(Ordering::Relaxed),
+            random: RANDOM.load(Ordering::Relaxed),
+        },
+    }))
+}
+
+async fn space_stats(space: String, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+    store
+        .stats()
+        .map_err(|_| warp::reject())?
+        .into_iter()
+        .find(|(s, _)| s.to_string() == space)
+        .map(|(_, stats)| warp::reply::json(&stats))
+        .ok_or_else(warp::reject::not_found)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use fixt::prelude::*;
+    use kitsune_p2p::{agent_store::AgentInfoSigned, fixt::*, KitsuneSpace};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stats() {
+        let store = Store::new(vec![]);
+        let filter = super::stats(store.clone());
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+
+        for i in 0..4 {
+            let urls = if i % 2 == 0 {
+                vec!["fake:".into()]
+            } else {
+                fixt!(UrlList, Empty)
+            };
+            let info = AgentInfoSigned::sign(
+                space.clone(),
+                Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+                u32::MAX / 4,
+                urls,
+                i,
+                std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+                |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+            )
+            .await
+            .unwrap();
+            store.put(info).unwrap();
+        }
+        let expected = SpaceStats {
+            agents: 4,
+            reachable_agents: 2,
+            latest_signed_at_ms: Some(3),
+        };
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/stats")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let stats: Stats = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(stats.spaces.len(), 1);
+        assert_eq!(stats.spaces.get(&space.to_string()), Some(&expected));
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!("/stats/{}", space))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let stats: SpaceStats = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(stats, expected);
+
+        let res = warp::test::request()
+            .method("GET")
+            .path(&format!("/stats/{}", fixt!(KitsuneSpace, Unpredictable)))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 404);
+    }
+}
@@ -1,94 +1,132 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 
 use kitsune_p2p_types::{
     agent_info::AgentInfoSigned,
     bin_types::{KitsuneAgent, KitsuneSpace},
     bootstrap::RandomQuery,
     codec::rmp_encode,
+    KitsuneResult,
 };
-use parking_lot::RwLock;
-use rand::seq::IteratorRandom;
 
-type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
-type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
+mod memory;
+#[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+mod sqlite;
+
+pub use memory::MemoryStore;
+#[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+pub use sqlite::SqliteStore;
+
+/// Statistics about the agents a bootstrap server holds for one space.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SpaceStats {
+    /// The number of agents with unexpired agent info.
+    pub agents: u64,
+    /// The number of those agents which have at least one url,
+    /// i.e. which can be returned by `random`.
+    pub reachable_agents: u64,
+    /// The most recent time any agent info in the space was signed.
+    pub latest_signed_at_ms: Option<u64>,
+}
+
+/// A backend that stores the agent info held by a bootstrap server.
+///
+/// Times are passed in as milliseconds since the unix epoch so that the
+/// backends don't need to read the clock themselves.
+pub trait StoreBackend: std::fmt::Debug + Send + Sync + 'static {
+    /// Store an agent info, unless a more recently signed one is already
+    /// stored for the same agent in the same space.
+    fn put(&self, info: AgentInfoSigned) -> KitsuneResult<()>;
+
+    /// Get the agent info stored for an agent in a space.
+    fn get(
+        &self,
+        space: &KitsuneSpace,
+        agent: &KitsuneAgent,
+    ) -> KitsuneResult<Option<AgentInfoSigned>>;
+
+    /// Choose up to `limit` random agent infos from a space which have not
+    /// expired and which have at least one url.
+    fn random(
+        &self,
+        space: &KitsuneSpace,
+        limit: usize,
+        now_ms: u64,
+    ) -> KitsuneResult<Vec<AgentInfoSigned>>;
+
+    /// Remove all agent infos which expired before `now_ms`,
+    /// returning how many were removed.
+    fn prune(&self, now_ms: u64) -> KitsuneResult<usize>;
+
+    /// Remove all agent infos.
+    fn clear(&self) -> KitsuneResult<()>;
+
+    /// Statistics about the unexpired agent infos in each space.
+    fn stats(&self, now_ms: u64) -> KitsuneResult<BTreeMap<Arc<KitsuneSpace>, SpaceStats>>;
+}
 
 #[derive(Clone, Debug)]
-pub(crate) struct Store(Arc<RwLock<SpaceMap>>, Arc<Vec<String>>);
+pub(crate) struct Store(Arc<dyn StoreBackend>, Arc<Vec<String>>);
+
+fn now_ms() -> u64 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .expect("Bootstrap server time set before epoch")
+        .as_millis() as u64
+}
 
 impl Store {
+    #[cfg(test)]
     pub fn new(proxy_list: Vec<String>) -> Self {
-        Self(Arc::new(RwLock::new(HashMap::new())), Arc::new(proxy_list))
+        Self::with_backend(Arc::new(MemoryStore::default()), proxy_list)
+    }
+
+    pub fn with_backend(backend: Arc<dyn StoreBackend>, proxy_list: Vec<String>) -> Self {
+        Self(backend, Arc::new(proxy_list))
     }
 
     pub fn proxy_list(&self) -> Arc<Vec<String>> {
         self.1.clone()
     }
 
-    pub fn prune(&self) {
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-
-        self.0.write().retain(|_, map| {
-            map.retain(|_, info| info.expires_at_ms >= now);
-            !map.is_empty()
-        });
+    pub fn prune(&self) -> KitsuneResult<usize> {
+        self.0.prune(now_ms())
     }
 
-    pub fn put(&self, info: AgentInfoSigned) {
-        let mut lock = self.0.write();
-        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
-        match space_map.entry(info.agent.clone()) {
-            std::collections::hash_map::Entry::Occupied(mut e) => {
-                if info.signed_at_ms > e.get().signed_at_ms {
-                    e.insert(info);
-                }
-            }
-            std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(info);
-            }
-        }
+    pub fn put(&self, info: AgentInfoSigned) -> KitsuneResult<()> {
+        self.0.put(info)
     }
 
-    pub fn random(&self, query: RandomQuery) -> Vec<Vec<u8>> {
+    pub fn random(&self, query: RandomQuery) -> KitsuneResult<Vec<Vec<u8>>> {
         // TODO: Max this limit
         let limit = query.limit.0 as usize;
-        let mut rng = rand::thread_rng();
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-        self.0
-            .read()
-            .get(query.space.as_ref())
-            .map(|space| {
-                space
-                    .values()
-                    .filter_map(|i| {
-                        if i.expires_at_ms <= now {
-                            return None;
-                        }
-                        if i.url_list.is_empty() {
-                            return None;
-                        }
-                        let mut buf = Vec::new();
-                        match rmp_encode(&mut buf, i) {
-                            Ok(_) => Some(buf),
-                            Err(_) => None,
-                        }
-                    })
-                    .choose_multiple(&mut rng, limit)
+        Ok(self
+            .0
+            .random(query.space.as_ref(), limit, now_ms())?
+            .into_iter()
+            .filter_map(|i| {
+                let mut buf = Vec::new();
+                match rmp_encode(&mut buf, i) {
+                    Ok(_) => Some(buf),
+                    Err(_) => None,
+                }
             })
-            .unwrap_or_default()
+            .collect())
+    }
+
+    pub fn clear(&self) -> KitsuneResult<()> {
+        self.0.clear()
     }
 
-    pub fn clear(&self) {
-        self.0.write().clear()
+    pub fn stats(&self) -> KitsuneResult<BTreeMap<Arc<KitsuneSpace>, SpaceStats>> {
+        self.0.stats(now_ms())
     }
 
     #[cfg(test)]
-    pub fn all(&self) -> HashMap<Arc<KitsuneSpace>, HashMap<Arc<KitsuneAgent>, AgentInfoSigned>> {
-        self.0.read().clone()
+    pub fn get(
+        &self,
+        space: &KitsuneSpace,
+        agent: &KitsuneAgent,
+    ) -> KitsuneResult<Option<AgentInfoSigned>> {
+        self.0.get(space, agent)
     }
 }
@@ -0,0 +1,109 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned,
+    bin_types::{KitsuneAgent, KitsuneSpace},
+    KitsuneResult,
+};
+use parking_lot::RwLock;
+use rand::seq::IteratorRandom;
+
+use super::{SpaceStats, StoreBackend};
+
+type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
+type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
+
+/// Keeps agent info in memory. Everything is lost when the server stops.
+#[derive(Debug, Default)]
+pub struct MemoryStore(RwLock<SpaceMap>);
+
+impl StoreBackend for MemoryStore {
+    fn put(&self, info: AgentInfoSigned) -> KitsuneResult<()> {
+        let mut lock = self.0.write();
+        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
+        match space_map.entry(info.agent.clone()) {
+            std::collections::hash_map::Entry::Occupied(mut e) => {
+                if info.signed_at_ms > e.get().signed_at_ms {
+                    e.insert(info);
+                }
+            }
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(info);
+            }
+        }
+        Ok(())
+    }
+
+    fn get(
+        &self,
+        space: &KitsuneSpace,
+        agent: &KitsuneAgent,
+    ) -> KitsuneResult<Option<AgentInfoSigned>> {
+        Ok(self
+            .0
+            .read()
+            .get(space)
+            .and_then(|space| space.get(agent))
+            .cloned())
+    }
+
+    fn random(
+        &self,
+        space: &KitsuneSpace,
+        limit: usize,
+        now_ms: u64,
+    ) -> KitsuneResult<Vec<AgentInfoSigned>> {
+        let mut rng = rand::thread_rng();
+        Ok(self
+            .0
+            .read()
+            .get(space)
+            .map(|space| {
+                space
+                    .values()
+                    .filter(|i| i.expires_at_ms > now_ms && !i.url_list.is_empty())
+                    .cloned()
+                    .choose_multiple(&mut rng, limit)
+            })
+            .unwrap_or_default())
+    }
+
+    fn prune(&self, now_ms: u64) -> KitsuneResult<usize> {
+        let mut pruned = 0;
+        self.0.write().retain(|_, map| {
+            let before = map.len();
+            map.retain(|_, info| info.expires_at_ms >= now_ms);
+            pruned += before - map.len();
+            !map.is_empty()
+        });
+        Ok(pruned)
+    }
+
+    fn clear(&self) -> KitsuneResult<()> {
+        self.0.write().clear();
+        Ok(())
+    }
+
+    fn stats(&self, now_ms: u64) -> KitsuneResult<BTreeMap<Arc<KitsuneSpace>, SpaceStats>> {
+        Ok(self
+            .0
+            .read()
+            .iter()
+            .filter_map(|(space, map)| {
+                let mut stats = SpaceStats::default();
+                for info in map.values().filter(|i| i.expires_at_ms > now_ms) {
+                    stats.agents += 1;
+                    if !info.url_list.is_empty() {
+                        stats.reachable_agents += 1;
+                    }
+                    stats.latest_signed_at_ms =
+                        stats.latest_signed_at_ms.max(Some(info.signed_at_ms));
+                }
+                (stats.agents > 0).then(|| (space.clone(), stats))
+            })
+            .collect())
+    }
+}
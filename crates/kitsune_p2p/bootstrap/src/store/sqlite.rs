@@ -0,0 +1,279 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned,
+    bin_types::{KitsuneAgent, KitsuneSpace},
+    codec::{rmp_decode, rmp_encode},
+    KitsuneError, KitsuneResult,
+};
+use parking_lot::Mutex;
+use rusqlite::{named_params, Connection, OptionalExtension};
+
+use super::{SpaceStats, StoreBackend};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS agent_info (
+    space         BLOB    NOT NULL,
+    agent         BLOB    NOT NULL,
+    signed_at_ms  INTEGER NOT NULL,
+    expires_at_ms INTEGER NOT NULL,
+    has_urls      INTEGER NOT NULL,
+    blob          BLOB    NOT NULL,
+    PRIMARY KEY (space, agent)
+);
+CREATE INDEX IF NOT EXISTS agent_info_expires_at_ms ON agent_info (expires_at_ms);
+";
+
+/// Keeps agent info in a SQLite database so that it survives restarts.
+#[derive(Debug)]
+pub struct SqliteStore(Mutex<Connection>);
+
+fn err(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> KitsuneError {
+    KitsuneError::other(e)
+}
+
+impl SqliteStore {
+    /// Open the database at the given path, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> KitsuneResult<Self> {
+        Self::init(Connection::open(path).map_err(err)?)
+    }
+
+    /// Open a database which is only held in memory.
+    pub fn open_in_memory() -> KitsuneResult<Self> {
+        Self::init(Connection::open_in_memory().map_err(err)?)
+    }
+
+    fn init(conn: Connection) -> KitsuneResult<Self> {
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(err)?;
+        conn.execute_batch(SCHEMA).map_err(err)?;
+        Ok(Self(Mutex::new(conn)))
+    }
+}
+
+fn decode(blob: Vec<u8>) -> rusqlite::Result<AgentInfoSigned> {
+    rmp_decode(&mut blob.as_slice()).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Blob, Box::new(e))
+    })
+}
+
+impl StoreBackend for SqliteStore {
+    fn put(&self, info: AgentInfoSigned) -> KitsuneResult<()> {
+        let mut blob = Vec::new();
+        rmp_encode(&mut blob, &info).map_err(err)?;
+        self.0
+            .lock()
+            .execute(
+                "
+                INSERT INTO agent_info
+                (space, agent, signed_at_ms, expires_at_ms, has_urls, blob)
+                VALUES
+                (:space, :agent, :signed_at_ms, :expires_at_ms, :has_urls, :blob)
+                ON CONFLICT (space, agent) DO UPDATE SET
+                    signed_at_ms = excluded.signed_at_ms,
+                    expires_at_ms = excluded.expires_at_ms,
+                    has_urls = excluded.has_urls,
+                    blob = excluded.blob
+                WHERE excluded.signed_at_ms > agent_info.signed_at_ms
+                ",
+                named_params! {
+                    ":space": info.space.0,
+                    ":agent": info.agent.0,
+                    ":signed_at_ms": info.signed_at_ms as i64,
+                    ":expires_at_ms": info.expires_at_ms as i64,
+                    ":has_urls": !info.url_list.is_empty(),
+                    ":blob": blob,
+                },
+            )
+            .map_err(err)?;
+        Ok(())
+    }
+
+    fn get(
+        &self,
+        space: &KitsuneSpace,
+        agent: &KitsuneAgent,
+    ) -> KitsuneResult<Option<AgentInfoSigned>> {
+        self.0
+            .lock()
+            .query_row(
+                "SELECT blob FROM agent_info WHERE space = :space AND agent = :agent",
+                named_params! {
+                    ":space": space.0,
+                    ":agent": agent.0,
+                },
+                |row| decode(row.get(0)?),
+            )
+            .optional()
+            .map_err(err)
+    }
+
+    fn random(
+        &self,
+        space: &KitsuneSpace,
+        limit: usize,
+        now_ms: u64,
+    ) -> KitsuneResult<Vec<AgentInfoSigned>> {
+        let conn = self.0.lock();
+        let mut stmt = conn
+            .prepare_cached(
+                "
+                SELECT blob FROM agent_info
+                WHERE space = :space AND expires_at_ms > :now AND has_urls
+                ORDER BY RANDOM()
+                LIMIT :limit
+                ",
+            )
+            .map_err(err)?;
+        let infos = stmt
+            .query_map(
+                named_params! {
+                    ":space": space.0,
+                    ":now": now_ms as i64,
+                    ":limit": limit as i64,
+                },
+                |row| decode(row.get(0)?),
+            )
+            .map_err(err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(err)?;
+        Ok(infos)
+    }
+
+    fn prune(&self, now_ms: u64) -> KitsuneResult<usize> {
+        self.0
+            .lock()
+            .execute(
+                "DELETE FROM agent_info WHERE expires_at_ms < :now",
+                named_params! { ":now": now_ms as i64 },
+            )
+            .map_err(err)
+    }
+
+    fn clear(&self) -> KitsuneResult<()> {
+        self.0
+            .lock()
+            .execute("DELETE FROM agent_info", [])
+            .map_err(err)?;
+        Ok(())
+    }
+
+    fn stats(&self, now_ms: u64) -> KitsuneResult<BTreeMap<Arc<KitsuneSpace>, SpaceStats>> {
+        let conn = self.0.lock();
+        let mut stmt = conn
+            .prepare_cached(
+                "
+                SELECT space, COUNT(*), SUM(has_urls), MAX(signed_at_ms)
+                FROM agent_info
+                WHERE expires_at_ms > :now
+                GROUP BY space
+                ",
+            )
+            .map_err(err)?;
+        let stats = stmt
+            .query_map(named_params! { ":now": now_ms as i64 }, |row| {
+                let space: Vec<u8> = row.get(0)?;
+                let agents: i64 = row.get(1)?;
+                let reachable_agents: i64 = row.get(2)?;
+                let latest_signed_at_ms: i64 = row.get(3)?;
+                Ok((
+                    Arc::new(KitsuneSpace(space)),
+                    SpaceStats {
+                        agents: agents as u64,
+                        reachable_agents: reachable_agents as u64,
+                        latest_signed_at_ms: Some(latest_signed_at_ms as u64),
+                    },
+                ))
+            })
+            .map_err(err)?
+            .collect::<rusqlite::Result<_>>()
+            .map_err(err)?;
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+
+    async fn info(
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        signed_at_ms: u64,
+        expires_at_ms: u64,
+    ) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            space,
+            agent,
+            u32::MAX / 4,
+            vec!["fake:".into()],
+            signed_at_ms,
+            expires_at_ms,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn agent_info_survives_reopening_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bootstrap.sqlite3");
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let agent = Arc::new(fixt!(KitsuneAgent, Unpredictable));
+        let newer = info(space.clone(), agent.clone(), 10, 1_000).await;
+
+        {
+            let store = SqliteStore::open(&path).unwrap();
+            store.put(newer.clone()).unwrap();
+            // An older agent info doesn't replace a newer one.
+            store
+                .put(info(space.clone(), agent.clone(), 5, 2_000).await)
+                .unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.get(&space, &agent).unwrap(), Some(newer.clone()));
+        assert_eq!(store.random(&space, 10, 500).unwrap(), vec![newer]);
+        assert_eq!(
+            store.stats(500).unwrap().get(&space),
+            Some(&SpaceStats {
+                agents: 1,
+                reachable_agents: 1,
+                latest_signed_at_ms: Some(10),
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn prune_removes_expired_agent_info() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        for expires_at_ms in [100, 200, 300] {
+            store
+                .put(
+                    info(
+                        space.clone(),
+                        Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+                        0,
+                        expires_at_ms,
+                    )
+                    .await,
+                )
+                .unwrap();
+        }
+
+        // Expired agent info is hidden even before it's pruned.
+        assert_eq!(store.random(&space, 10, 150).unwrap().len(), 2);
+        assert_eq!(store.stats(150).unwrap().get(&space).unwrap().agents, 2);
+
+        assert_eq!(store.prune(250).unwrap(), 2);
+        assert_eq!(store.random(&space, 10, 0).unwrap().len(), 1);
+        assert_eq!(store.prune(250).unwrap(), 0);
+
+        store.clear().unwrap();
+        assert!(store.stats(0).unwrap().is_empty());
+    }
+}
@@ -2,7 +2,9 @@ use crate::Loc;
 use kitsune_p2p_dht_arc::DhtArc;
 use kitsune_p2p_timestamp::Timestamp;
 
-use crate::spacetime::{SpaceSegment, SpacetimeQuantumCoords, TimeSegment, Topology};
+use crate::spacetime::{
+    Quantum, SpaceQuantum, SpaceSegment, SpacetimeQuantumCoords, TimeSegment, Topology,
+};
 
 /// The cross product of a space segment and at time segment forms a Region.
 /// Hence, these two segments are the coordinates which define a Region of spacetime.
@@ -87,6 +89,29 @@ impl RegionBounds {
         self.arc_interval().contains(x) && self.time_range().contains(t)
     }
 
+    /// The smallest set of non-overlapping [`RegionCoords`] which exactly
+    /// covers these bounds, after rounding the bounds outward to whole quanta.
+    /// The location bounds may wrap around the end of the DHT.
+    pub fn to_region_coords(&self, topo: &Topology) -> Vec<RegionCoords> {
+        let (x0, x1) = (topo.space_quantum(self.x.0), topo.space_quantum(self.x.1));
+        let (t0, t1) = (topo.time_quantum(self.t.0), topo.time_quantum(self.t.1));
+        if t0 > t1 {
+            return vec![];
+        }
+        let spaces = if x0 <= x1 {
+            SpaceSegment::cover(x0, x1)
+        } else {
+            let mut spaces = SpaceSegment::cover(x0, SpaceQuantum::max_value(topo));
+            spaces.extend(SpaceSegment::cover(0.into(), x1));
+            spaces
+        };
+        let times = TimeSegment::cover(t0, t1);
+        spaces
+            .into_iter()
+            .flat_map(|space| times.iter().map(move |&time| RegionCoords { space, time }))
+            .collect()
+    }
+
     /// Just the primitive underlying numbers. For diagnostics.
     pub fn to_primitive(&self) -> ((u32, u32), (i64, i64)) {
         (
@@ -125,6 +150,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn region_bounds_to_region_coords() {
+        let topo = Topology::unit_zero();
+        let ts = |t0: i64, t1: i64| (Timestamp::from_micros(t0), Timestamp::from_micros(t1));
+
+        // Already aligned
+        assert_eq!(
+            RegionBounds::new((8u32, 15u32), ts(4, 7)).to_region_coords(&topo),
+            vec![RegionCoords::new(
+                SpaceSegment::new(3, 1),
+                TimeSegment::new(2, 1)
+            )]
+        );
+
+        // 3..=9 is covered by 3, 4..=7, 8..=9
+        assert_eq!(
+            RegionBounds::new((3u32, 9u32), ts(0, 0)).to_region_coords(&topo),
+            vec![
+                RegionCoords::new(SpaceSegment::new(0, 3), TimeSegment::new(0, 0)),
+                RegionCoords::new(SpaceSegment::new(2, 1), TimeSegment::new(0, 0)),
+                RegionCoords::new(SpaceSegment::new(1, 4), TimeSegment::new(0, 0)),
+            ]
+        );
+
+        // Wrapping around the end of space
+        assert_eq!(
+            RegionBounds::new((u32::MAX - 1, 1), ts(0, 1)).to_region_coords(&topo),
+            vec![
+                RegionCoords::new(SpaceSegment::new(1, u32::MAX / 2), TimeSegment::new(1, 0)),
+                RegionCoords::new(SpaceSegment::new(1, 0), TimeSegment::new(1, 0)),
+            ]
+        );
+    }
+
     #[test]
     fn test_quadrisect() {
         assert_eq!(
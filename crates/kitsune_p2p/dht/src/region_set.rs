@@ -9,8 +9,10 @@
 //! In the future we may have other schemes.
 
 mod ltcs;
+mod region_tree;
 
 pub use ltcs::*;
+pub use region_tree::*;
 
 use crate::{error::GossipResult, spacetime::*};
 
@@ -44,20 +46,26 @@ impl<D: RegionDataConstraints> RegionSet<D> {
 
     /// The RegionSet can be used to answer questions about more regions than
     /// just the ones specified: If a larger region is queried, and this set contains
-    /// a set of regions which over that larger region, then the larger region
-    /// can be dynamically constructed.
+    /// a set of regions which cover that larger region, then the larger region
+    /// can be dynamically constructed. Returns None if it can't be.
     ///
     /// This allows agents with differently computed RegionSets to still engage
     /// in gossip without needing to recompute regions.
-    pub fn query(&self, _bounds: &RegionBounds) -> ! {
-        unimplemented!("only implement after trying naive database-only approach")
+    ///
+    /// To answer queries about any region, use a [`RegionTree`].
+    pub fn query(&self, topo: &Topology, bounds: &RegionBounds) -> Option<D> {
+        match self {
+            Self::Ltcs(set) => set.query(topo, bounds),
+        }
     }
 
     /// In order for this RegionSet to be queryable, new data needs to be
     /// integrated into it to avoid needing to recompute it from the database
     /// on each query.
-    pub fn update(&self, _c: SpacetimeQuantumCoords, _d: D) -> ! {
-        unimplemented!("only implement after trying naive database-only approach")
+    pub fn update(&mut self, topo: &Topology, c: SpacetimeQuantumCoords, d: D) {
+        match self {
+            Self::Ltcs(set) => set.update(topo, c, d),
+        }
     }
 
     /// Find a set of Regions which represents the intersection of the two
@@ -192,6 +200,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query_and_update() {
+        let topo = Topology::unit_zero();
+        let arq = Arq::new(8, 0u32.into(), 4.into()).to_bounds(&topo);
+        let mut store = OpStore::new(topo.clone(), GossipParams::zero());
+        store.integrate_ops(op_grid(&topo, &arq, 10..20).into_iter());
+
+        let coords = RegionCoordSetLtcs::new(
+            TelescopingTimes::new(TimeQuantum::from(20)),
+            ArqBoundsSet::single(arq),
+        );
+        let mut rset: RegionSet = RegionSetLtcs::from_store(&store, coords).into();
+
+        // Two adjacent space segments over all time can be combined
+        let all_time = (Timestamp::from_micros(0), Timestamp::from_micros(15));
+        let bounds = RegionBounds::new((0u32, 511u32), all_time);
+        assert_eq!(
+            rset.query(&topo, &bounds),
+            Some(store.query_region_data(&RegionCoords::new(
+                SpaceSegment::new(9, 0),
+                TimeSegment::new(4, 0)
+            )))
+        );
+
+        // Part of a region can't be answered
+        let bounds = RegionBounds::new((0u32, 127u32), all_time);
+        assert_eq!(rset.query(&topo, &bounds), None);
+
+        let op = OpData::fake(Loc::from(300u32), Timestamp::from_micros(3), 7);
+        store.integrate_op(op.clone());
+        rset.update(&topo, op.coords(&topo), op.region_data());
+        let bounds = RegionBounds::new((256u32, 511u32), all_time);
+        assert_eq!(
+            rset.query(&topo, &bounds),
+            Some(store.query_region_data(&RegionCoords::new(
+                SpaceSegment::new(8, 1),
+                TimeSegment::new(4, 0)
+            )))
+        );
+    }
+
     #[test]
     fn test_rectify() {
         let topo = Topology::unit_zero();
@@ -8,7 +8,7 @@ use crate::{
 };
 use derivative::Derivative;
 
-use super::{Region, RegionCoords, RegionData, RegionDataConstraints};
+use super::{Region, RegionBounds, RegionCoords, RegionData, RegionDataConstraints};
 
 /// A compact representation of a set of [`RegionCoords`].
 /// The [`TelescopingTimes`] generates all relevant [`TimeSegment`]s, and the
@@ -180,6 +180,37 @@ impl<D: RegionDataConstraints> RegionSetLtcs<D> {
             .map(|((ia, ix, it), coords)| Region::new(coords, self.data[ia][ix][it].clone()))
     }
 
+    /// Add data to every region in the set which contains the given quantum.
+    pub fn update(&mut self, topo: &Topology, coords: SpacetimeQuantumCoords, data: D) {
+        for ((ia, ix, it), region) in self.coords.region_coords_flat() {
+            if region.contains(topo, &coords) {
+                self.data[ia][ix][it] += data.clone();
+            }
+        }
+    }
+
+    /// Get the data for an arbitrary region by combining the regions in this
+    /// set which make it up. Returns None if the regions in this set don't
+    /// exactly cover the queried region.
+    pub fn query(&self, topo: &Topology, bounds: &RegionBounds) -> Option<D> {
+        let mut total = D::zero();
+        for target in bounds.to_region_coords(topo) {
+            let mut quanta = 0u128;
+            for region in self.regions() {
+                if region.coords.space.is_within(topo, &target.space)
+                    && region.coords.time.is_within(topo, &target.time)
+                {
+                    quanta += num_quanta(&region.coords);
+                    total += region.data;
+                }
+            }
+            if quanta != num_quanta(&target) {
+                return None;
+            }
+        }
+        Some(total)
+    }
+
     /// Reshape the two region sets so that both match, omitting or merging
     /// regions as needed
     pub fn rectify(&mut self, other: &mut Self) -> GossipResult<()> {
@@ -241,6 +272,10 @@ impl<D: RegionDataConstraints> RegionSetLtcs<D> {
     }
 }
 
+fn num_quanta(coords: &RegionCoords) -> u128 {
+    coords.space.num_quanta() as u128 * coords.time.num_quanta() as u128
+}
+
 #[cfg(feature = "test_utils")]
 impl<D: RegionDataConstraints> RegionSetLtcs<D> {
     /// Query the specified OpStore for each coord in the set, constructing
@@ -0,0 +1,331 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+use crate::{
+    op::OpRegion,
+    region::{RegionBounds, RegionCoords, RegionData, RegionDataConstraints},
+    spacetime::*,
+};
+
+use super::{RegionCoordSetLtcs, RegionSetLtcs};
+
+/// The default number of regions a [`RegionTree`] will cache before the cache
+/// is cleared and rebuilt on demand.
+pub const DEFAULT_MAX_CACHED_REGIONS: usize = 100_000;
+
+/// An in-memory index of the [`RegionData`] of every spacetime quantum which
+/// contains ops, kept up to date as ops are integrated, so that the data for
+/// any region can be found without going back to the op store.
+///
+/// The data for regions larger than a quantum is computed when first queried,
+/// by combining the data of its four subregions if they are already known,
+/// or else by summing the quanta it contains. Those results are cached, and
+/// the cached regions are updated in place as new data is added.
+pub struct RegionTree<D: RegionDataConstraints = RegionData> {
+    topo: Topology,
+    /// The data for each nonempty quantum, ordered by time first so that
+    /// a time range can be scanned efficiently
+    quanta: BTreeMap<(TimeQuantum, SpaceQuantum), D>,
+    cache: Mutex<RegionCache<D>>,
+    max_cached_regions: usize,
+}
+
+struct RegionCache<D> {
+    regions: HashMap<RegionCoords, D>,
+    /// The distinct (space, time) powers of the cached regions. For each
+    /// of these there is at most one region containing any given quantum,
+    /// which makes it cheap to find the cached regions affected by new data.
+    powers: BTreeSet<(u8, u8)>,
+}
+
+impl<D> Default for RegionCache<D> {
+    fn default() -> Self {
+        Self {
+            regions: HashMap::new(),
+            powers: BTreeSet::new(),
+        }
+    }
+}
+
+impl<D: RegionDataConstraints> Clone for RegionCache<D> {
+    fn clone(&self) -> Self {
+        Self {
+            regions: self.regions.clone(),
+            powers: self.powers.clone(),
+        }
+    }
+}
+
+impl<D: RegionDataConstraints> Clone for RegionTree<D> {
+    fn clone(&self) -> Self {
+        Self {
+            topo: self.topo.clone(),
+            quanta: self.quanta.clone(),
+            cache: Mutex::new(self.cache.lock().unwrap().clone()),
+            max_cached_regions: self.max_cached_regions,
+        }
+    }
+}
+
+impl<D: RegionDataConstraints> std::fmt::Debug for RegionTree<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegionTree")
+            .field("quanta", &self.quanta.len())
+            .field("cached_regions", &self.cache.lock().unwrap().regions.len())
+            .finish()
+    }
+}
+
+impl<D: RegionDataConstraints> RegionTree<D> {
+    /// An empty tree
+    pub fn new(topo: Topology) -> Self {
+        Self {
+            topo,
+            quanta: BTreeMap::new(),
+            cache: Mutex::new(RegionCache::default()),
+            max_cached_regions: DEFAULT_MAX_CACHED_REGIONS,
+        }
+    }
+
+    /// Build a tree from a collection of ops
+    pub fn from_ops<'a, O: OpRegion<D> + 'a>(
+        topo: Topology,
+        ops: impl IntoIterator<Item = &'a O>,
+    ) -> Self {
+        let mut tree = Self::new(topo);
+        for op in ops {
+            tree.add_op(op);
+        }
+        tree
+    }
+
+    /// Set the number of regions to cache before the cache gets cleared.
+    pub fn with_max_cached_regions(mut self, max_cached_regions: usize) -> Self {
+        self.max_cached_regions = max_cached_regions;
+        self
+    }
+
+    /// The topology used to map ops to quanta
+    pub fn topo(&self) -> &Topology {
+        &self.topo
+    }
+
+    /// Add the data for an op to the quantum which contains it.
+    pub fn add_op<O: OpRegion<D>>(&mut self, op: &O) {
+        self.add(op.coords(&self.topo), op.region_data())
+    }
+
+    /// Add some data to a quantum, updating all cached regions which contain it.
+    pub fn add(&mut self, coords: SpacetimeQuantumCoords, data: D) {
+        let space = coords.space.normalized(&self.topo);
+        let time = coords.time;
+        let cache = self.cache.get_mut().unwrap();
+        for &(ps, pt) in cache.powers.iter() {
+            let region = RegionCoords::new(
+                SpaceSegment::new(ps, shr(space.inner(), ps)),
+                TimeSegment::new(pt, shr(time.inner(), pt)),
+            );
+            if let Some(d) = cache.regions.get_mut(&region) {
+                *d += data.clone();
+            }
+        }
+        *self.quanta.entry((time, space)).or_insert_with(D::zero) += data;
+    }
+
+    /// The data for a single region
+    pub fn query_coords(&self, coords: &RegionCoords) -> D {
+        let mut cache = self.cache.lock().unwrap();
+        self.region_data(&mut cache, coords)
+    }
+
+    /// The data for an arbitrary region, found by combining the data for the
+    /// regions which make it up. See [`RegionBounds::to_region_coords`].
+    pub fn query(&self, bounds: &RegionBounds) -> D {
+        let mut cache = self.cache.lock().unwrap();
+        bounds
+            .to_region_coords(&self.topo)
+            .iter()
+            .fold(D::zero(), |d, c| d + self.region_data(&mut cache, c))
+    }
+
+    /// Generate the data for each coord in the set.
+    pub fn region_set(&self, coords: RegionCoordSetLtcs) -> RegionSetLtcs<D> {
+        let mut cache = self.cache.lock().unwrap();
+        coords.into_region_set_infallible(|(_, c)| self.region_data(&mut cache, &c))
+    }
+
+    /// The number of regions currently cached
+    pub fn num_cached_regions(&self) -> usize {
+        self.cache.lock().unwrap().regions.len()
+    }
+
+    /// Forget all cached regions. They will be recomputed as needed.
+    pub fn clear_cache(&self) {
+        *self.cache.lock().unwrap() = RegionCache::default();
+    }
+
+    fn region_data(&self, cache: &mut RegionCache<D>, coords: &RegionCoords) -> D {
+        // Space segments can be specified with offsets beyond the end of space,
+        // so normalize them to match the regions updated by `add`.
+        let coords = &RegionCoords::new(
+            SpaceSegment::new(
+                coords.space.power,
+                shr(
+                    coords.space.quantum_bounds(&self.topo).0.inner(),
+                    coords.space.power,
+                ),
+            ),
+            coords.time,
+        );
+        if coords.space.power == 0 && coords.time.power == 0 {
+            let key = (
+                coords.time.offset.to_quantum(0),
+                coords.space.offset.to_quantum(0),
+            );
+            return self.quanta.get(&key).cloned().unwrap_or_else(D::zero);
+        }
+        if let Some(d) = cache.regions.get(coords) {
+            return d.clone();
+        }
+        let from_subregions = coords.quadrisect().and_then(|subregions| {
+            subregions.iter().try_fold(D::zero(), |d, c| {
+                cache.regions.get(c).map(|cd| d + cd.clone())
+            })
+        });
+        let data = from_subregions.unwrap_or_else(|| self.scan(coords));
+        if cache.regions.len() >= self.max_cached_regions {
+            *cache = RegionCache::default();
+        }
+        cache.powers.insert((coords.space.power, coords.time.power));
+        cache.regions.insert(*coords, data.clone());
+        data
+    }
+
+    /// Sum the data of all quanta in the region
+    fn scan(&self, coords: &RegionCoords) -> D {
+        let (t0, t1) = coords.time.quantum_bounds(&self.topo);
+        self.quanta
+            .range((t0, SpaceQuantum::from(0))..=(t1, SpaceQuantum::from(u32::MAX)))
+            // Both the quanta and the coords are normalized, so the segment
+            // contains the quantum exactly when their offsets line up
+            .filter(|((_, x), _)| shr(x.inner(), coords.space.power) == *coords.space.offset)
+            .fold(D::zero(), |d, (_, qd)| d + qd.clone())
+    }
+}
+
+/// Shift right, allowing for shifts of the full width of the u32
+fn shr(n: u32, power: u8) -> u32 {
+    n.checked_shr(power.into()).unwrap_or(0)
+}
+
+#[cfg(test)]
+#[cfg(feature = "test_utils")]
+mod tests {
+    use kitsune_p2p_timestamp::Timestamp;
+    use num_traits::Zero;
+    use proptest::prelude::*;
+
+    use crate::{
+        op::Loc,
+        persistence::AccessOpStore,
+        test_utils::{OpData, OpStore},
+        ArqBounds,
+    };
+
+    use super::*;
+
+    fn ops(coords: &[(u32, i64)]) -> Vec<std::sync::Arc<OpData>> {
+        coords
+            .iter()
+            .map(|&(x, t)| OpData::fake(Loc::from(x), Timestamp::from_micros(t), 10))
+            .collect()
+    }
+
+    #[test]
+    fn cached_regions_are_updated_incrementally() {
+        let topo = Topology::unit_zero();
+        let mut tree: RegionTree = RegionTree::new(topo);
+        let region = RegionCoords::new(SpaceSegment::new(4, 0), TimeSegment::new(4, 0));
+
+        assert_eq!(tree.query_coords(&region).count, 0);
+        assert_eq!(tree.num_cached_regions(), 1);
+
+        for op in ops(&[(1, 1), (15, 15), (16, 1)]) {
+            tree.add_op(op.as_ref());
+        }
+        // The third op is outside the region
+        assert_eq!(tree.query_coords(&region).count, 2);
+
+        // The parent region is built from its cached children
+        let parent = RegionCoords::new(SpaceSegment::new(5, 0), TimeSegment::new(5, 0));
+        for sub in parent.quadrisect().unwrap() {
+            tree.query_coords(&sub);
+        }
+        assert_eq!(tree.query_coords(&parent).count, 3);
+
+        tree.clear_cache();
+        assert_eq!(tree.num_cached_regions(), 0);
+        assert_eq!(tree.query_coords(&parent).count, 3);
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let topo = Topology::unit_zero();
+        let tree: RegionTree = RegionTree::new(topo).with_max_cached_regions(2);
+        for i in 0..3 {
+            tree.query_coords(&RegionCoords::new(
+                SpaceSegment::new(1, i),
+                TimeSegment::new(1, 0),
+            ));
+        }
+        assert_eq!(tree.num_cached_regions(), 1);
+    }
+
+    #[test]
+    fn region_set_matches_op_store() {
+        let topo = Topology::standard_zero();
+        let mut store = OpStore::new(topo.clone(), GossipParams::zero());
+        // This arq wraps around the end of space
+        let arq = ArqBounds::new(14, 60.into(), 8.into());
+        let ops: Vec<_> = (0..500u32)
+            .map(|i| {
+                OpData::fake(
+                    Loc::from(i.wrapping_mul(2_654_435_761)),
+                    TimeQuantum::from(i % 37).to_timestamp_bounds(&topo).0,
+                    i,
+                )
+            })
+            .collect();
+        store.integrate_ops(ops.into_iter());
+
+        let coords = RegionCoordSetLtcs::new(
+            TelescopingTimes::new(TimeQuantum::from(40)),
+            crate::arq::ArqBoundsSet::single(arq),
+        );
+        assert_eq!(
+            store.region_tree().region_set(coords.clone()),
+            RegionSetLtcs::from_store(&store, coords)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn query_matches_scan(
+            coords in proptest::collection::vec((any::<u32>(), 0..1000i64), 0..50),
+            x in any::<(u32, u32)>(),
+            t in (0..1000i64, 0..1000i64),
+        ) {
+            let topo = Topology::unit_zero();
+            let ops = ops(&coords);
+            let tree = RegionTree::from_ops(topo, ops.iter().map(|o| o.as_ref()));
+            let bounds = RegionBounds::new(x, (Timestamp::from_micros(t.0), Timestamp::from_micros(t.1)));
+            let expected = ops
+                .iter()
+                .filter(|o| t.0 <= t.1 && bounds.contains(&o.loc, &o.timestamp))
+                .fold(RegionData::zero(), |d, o| d + o.region_data());
+            prop_assert_eq!(tree.query(&bounds), expected);
+        }
+    }
+}
@@ -172,6 +172,31 @@ impl<O: Offset> Segment<O> {
         }
     }
 
+    /// Does this segment lie entirely within the other segment?
+    pub fn is_within(&self, topo: &Topology, other: &Self) -> bool {
+        // Segments are either nested or disjoint, so it's enough to check
+        // that this segment is no larger and that one of its quanta is contained.
+        self.power <= other.power && other.contains_quantum(topo, self.quantum_bounds(topo).0)
+    }
+
+    /// The smallest set of segments which exactly covers the inclusive range
+    /// of quanta from `lo` to `hi`. The range must not wrap.
+    pub fn cover(lo: O::Quantum, hi: O::Quantum) -> Vec<Self> {
+        let mut segments = Vec::new();
+        let mut lo = lo.inner() as u64;
+        let hi = hi.inner() as u64;
+        while lo <= hi {
+            // The largest aligned segment starting at `lo` which doesn't pass `hi`
+            let mut power = (lo.trailing_zeros() as u8).min(32);
+            while lo + 2u64.pow(power.into()) - 1 > hi {
+                power -= 1;
+            }
+            segments.push(Segment::new(power, O::from((lo >> power) as u32)));
+            lo += 2u64.pow(power.into());
+        }
+        segments
+    }
+
     /// Split a segment in half
     pub fn bisect(&self) -> Option<[Self; 2]> {
         if self.power == 0 {
@@ -1,7 +1,7 @@
 use crate::{
     op::OpRegion,
     persistence::AccessOpStore,
-    prelude::{RegionCoords, RegionSet, RegionSetLtcs},
+    prelude::{RegionCoords, RegionTree},
     region::{RegionData, RegionDataConstraints},
     spacetime::{GossipParams, Topology},
 };
@@ -15,7 +15,7 @@ use super::op_data::OpData;
 pub struct OpStore<O: OpRegion<D> = OpData, D: RegionDataConstraints = RegionData> {
     pub(crate) topo: Topology,
     pub(crate) ops: BTreeSet<Arc<O>>,
-    pub(crate) _region_set: RegionSet<D>,
+    pub(crate) region_tree: RegionTree<D>,
     pub(crate) gossip_params: GossipParams,
 }
 
@@ -23,12 +23,17 @@ impl<D: RegionDataConstraints, O: OpRegion<D>> OpStore<O, D> {
     /// Construct an empty store
     pub fn new(topo: Topology, gossip_params: GossipParams) -> Self {
         Self {
+            region_tree: RegionTree::new(topo.clone()),
             topo,
             ops: Default::default(),
-            _region_set: RegionSetLtcs::empty().into(),
             gossip_params,
         }
     }
+
+    /// The region tree, which is kept up to date as ops are integrated
+    pub fn region_tree(&self) -> &RegionTree<D> {
+        &self.region_tree
+    }
 }
 
 impl<D: RegionDataConstraints, O: OpRegion<D>> AccessOpStore<O, D> for OpStore<O, D> {
@@ -37,7 +42,7 @@ impl<D: RegionDataConstraints, O: OpRegion<D>> AccessOpStore<O, D> for OpStore<O
         let (x0, x1) = region.x;
         let (t0, t1) = region.t;
         let op0 = O::bound(t0, x0);
-        let op1 = O::bound(t1, x0);
+        let op1 = O::bound(t1, x1);
         self.ops
             .range((Bound::Included(op0), Bound::Included(op1)))
             .filter(|o| x0 <= o.loc() && o.loc() <= x1)
@@ -62,9 +67,9 @@ impl<D: RegionDataConstraints, O: OpRegion<D>> AccessOpStore<O, D> for OpStore<O
     }
 
     fn integrate_ops<Ops: Clone + Iterator<Item = Arc<O>>>(&mut self, ops: Ops) {
-        // for op in ops.clone() {
-        //     self.region_set.add(op.region_tuple(self.region_set.topo()));
-        // }
+        for op in ops.clone() {
+            self.region_tree.add_op(op.as_ref());
+        }
         self.ops.extend(ops);
     }
 
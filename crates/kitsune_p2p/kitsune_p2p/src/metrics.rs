@@ -16,11 +16,46 @@ use kitsune_p2p_timestamp::Timestamp;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
 
 use num_traits::*;
+use once_cell::sync::Lazy;
+use prometheus::HistogramVec;
+use prometheus::IntCounterVec;
 
 /// how long historical metric records should be kept
 /// (currently set to 1 week)
 const HISTORICAL_RECORD_EXPIRE_DURATION_MICROS: i64 = 1000 * 1000 * 60 * 60 * 24 * 7;
 
+static GOSSIP_ROUNDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    prometheus::register_int_counter_vec!(
+        "kitsune_gossip_rounds_total",
+        "Gossip rounds which were initiated, accepted, succeeded or failed",
+        &["gossip_type", "event"]
+    )
+    .expect("Metric names are unique")
+});
+
+static GOSSIP_ROUND_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    prometheus::register_histogram_vec!(
+        "kitsune_gossip_round_duration_seconds",
+        "Duration of completed gossip rounds",
+        &["gossip_type", "outcome"],
+        vec![0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0]
+    )
+    .expect("Metric names are unique")
+});
+
+fn gossip_type_label(gossip_type: GossipModuleType) -> &'static str {
+    match gossip_type {
+        GossipModuleType::ShardedRecent => "recent",
+        GossipModuleType::ShardedHistorical => "historical",
+    }
+}
+
+fn count_gossip_round(gossip_type: GossipModuleType, event: &str) {
+    GOSSIP_ROUNDS
+        .with_label_values(&[gossip_type_label(gossip_type), event])
+        .inc();
+}
+
 /// Running average that prioritizes memory and cpu efficiency
 /// over strict accuracy.
 /// For metrics where we can't afford the memory of tracking samples
@@ -442,6 +477,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        count_gossip_round(gossip_type, "initiate");
         for agent_info in remote_agent_list {
             let history = self
                 .agent_history
@@ -465,6 +501,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        count_gossip_round(gossip_type, "accept");
         for agent_info in remote_agent_list {
             let history = self
                 .agent_history
@@ -488,6 +525,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        count_gossip_round(gossip_type, "success");
         let mut should_dec_force_initiates = false;
 
         for agent_info in remote_agent_list {
@@ -523,6 +561,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        count_gossip_round(gossip_type, "error");
         for agent_info in remote_agent_list {
             let history = self
                 .agent_history
@@ -573,7 +612,14 @@ impl Metrics {
         let history = self.node_history.entry(node.clone()).or_default();
         let r = history.current_round.take();
         if let Some(r) = r {
-            history.completed_rounds.push_back(r.completed(error))
+            let r = r.completed(error);
+            GOSSIP_ROUND_DURATION
+                .with_label_values(&[
+                    gossip_type_label(r.gossip_type),
+                    if error { "error" } else { "success" },
+                ])
+                .observe(r.duration().as_secs_f64());
+            history.completed_rounds.push_back(r)
         }
     }
 